@@ -0,0 +1,24 @@
+//! Streaming transcription worker for platforms without WhisperKit
+//!
+//! Speaks the same stdin/stdout protocol as whisperkit-worker; see
+//! `services::streaming_worker`.
+//!
+//! Usage: streaming-worker stream [--replay <script.json>] [--models-dir <dir>]
+
+use private_transcript::services::streaming_worker::{self, WorkerOptions};
+
+fn main() {
+    let result = WorkerOptions::parse(std::env::args().skip(1)).and_then(|options| {
+        let engine = options.engine()?;
+        eprintln!("[StreamingWorker] Starting streaming mode...");
+        streaming_worker::run(std::io::stdin(), std::io::stdout(), engine)
+    });
+
+    match result {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("[StreamingWorker] {}", e);
+            std::process::exit(1);
+        }
+    }
+}
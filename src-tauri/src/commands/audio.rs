@@ -1,5 +1,7 @@
-use crate::services::audio;
+use crate::services::{audio, encryption};
 use crate::utils::IntoTauriResult;
+use std::path::Path;
+use tauri::ipc::Response;
 use tauri::AppHandle;
 
 #[tauri::command]
@@ -20,3 +22,24 @@ pub async fn get_audio_path(app: AppHandle, session_id: String) -> Result<String
         .await
         .into_tauri_result()
 }
+
+/// Read a recording for playback, decrypting it if needed. Only files in the
+/// app's audio directory can be read.
+#[tauri::command]
+pub async fn read_audio_file(app: AppHandle, audio_path: String) -> Result<Response, String> {
+    let audio_dir = audio::get_audio_dir(&app)
+        .and_then(|dir| dir.canonicalize().map_err(Into::into))
+        .into_tauri_result()?;
+    let path = Path::new(&audio_path)
+        .canonicalize()
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    if !path.starts_with(&audio_dir) {
+        return Err("Audio file is outside the recordings directory".to_string());
+    }
+
+    let data = tokio::task::spawn_blocking(move || encryption::read_audio(&path))
+        .await
+        .map_err(|e| e.to_string())?
+        .into_tauri_result()?;
+    Ok(Response::new(data))
+}
@@ -0,0 +1,17 @@
+use crate::models::{AuditEntry, AuditLogQuery, AuditVerification};
+use crate::services::{audit, database};
+use crate::utils::IntoTauriResult;
+
+/// List audit log entries matching the filter, newest first
+#[tauri::command]
+pub async fn query_audit_log(query: AuditLogQuery) -> Result<Vec<AuditEntry>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    audit::query(pool, &query).await.into_tauri_result()
+}
+
+/// Walk the whole hash chain and report the first entry that does not match
+#[tauri::command]
+pub async fn verify_audit_log() -> Result<AuditVerification, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    audit::verify(pool).await.into_tauri_result()
+}
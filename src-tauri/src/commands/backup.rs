@@ -0,0 +1,21 @@
+use crate::models::{BackupInfo, RestoreBackupRequest, RestoreSummary};
+use crate::services::backup;
+use crate::utils::IntoTauriResult;
+use tauri::AppHandle;
+
+/// Write a backup archive of the whole library to the exports directory
+#[tauri::command]
+pub async fn create_backup(app: AppHandle, include_settings: bool) -> Result<BackupInfo, String> {
+    backup::create_backup(&app, include_settings)
+        .await
+        .into_tauri_result()
+}
+
+/// Restore a backup archive, merging it into the library or replacing it
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    request: RestoreBackupRequest,
+) -> Result<RestoreSummary, String> {
+    backup::restore_backup(&app, &request).await.into_tauri_result()
+}
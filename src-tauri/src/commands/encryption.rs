@@ -0,0 +1,48 @@
+use crate::models::EncryptionStatus;
+use crate::services::encryption;
+use crate::utils::IntoTauriResult;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn get_encryption_status(app: AppHandle) -> EncryptionStatus {
+    encryption::status(&app)
+}
+
+/// Unlock an encrypted store at startup and open the database
+#[tauri::command]
+pub async fn unlock_database(app: AppHandle, passphrase: String) -> Result<(), String> {
+    encryption::unlock(&app, &passphrase)
+        .await
+        .into_tauri_result()
+}
+
+/// Turn on encryption, encrypting the existing database and recordings
+#[tauri::command]
+pub async fn set_encryption_passphrase(app: AppHandle, passphrase: String) -> Result<(), String> {
+    encryption::set_passphrase(&app, &passphrase)
+        .await
+        .into_tauri_result()
+}
+
+/// Re-encrypt the database and recordings under a new passphrase
+#[tauri::command]
+pub async fn change_encryption_passphrase(
+    app: AppHandle,
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), String> {
+    encryption::change_passphrase(&app, &current_passphrase, &new_passphrase)
+        .await
+        .into_tauri_result()
+}
+
+/// Turn off encryption, decrypting the database and recordings
+#[tauri::command]
+pub async fn remove_encryption_passphrase(
+    app: AppHandle,
+    current_passphrase: String,
+) -> Result<(), String> {
+    encryption::remove_passphrase(&app, &current_passphrase)
+        .await
+        .into_tauri_result()
+}
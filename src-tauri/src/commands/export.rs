@@ -2,17 +2,40 @@ use crate::services::export;
 use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
 
+/// Audit an export that wrote `path` when it came from a session. If the
+/// audit entry can't be recorded the file is deleted, so no session leaves
+/// the app unrecorded.
+async fn audited(session_id: Option<String>, format: &str, path: String) -> anyhow::Result<String> {
+    if let Some(session_id) = session_id {
+        if let Err(e) = export::record_export(&session_id, format, &path).await {
+            if let Err(remove_err) = tokio::fs::remove_file(&path).await {
+                eprintln!(
+                    "Warning: Could not delete unaudited export {}: {}",
+                    path, remove_err
+                );
+            }
+            return Err(e);
+        }
+    }
+    Ok(path)
+}
+
 #[tauri::command]
 pub async fn export_markdown(
     _app: AppHandle,
     content: String,
     filename: String,
+    session_id: Option<String>,
 ) -> Result<String, String> {
-    export::export_markdown(&content, &filename)
+    let path = export::export_markdown(&content, &filename)
+        .await
+        .into_tauri_result()?;
+    audited(session_id, "markdown", path)
         .await
         .into_tauri_result()
 }
 
+/// `tags` are the user's defaults; the session's own tags are added first
 #[tauri::command]
 pub async fn export_to_obsidian(
     _app: AppHandle,
@@ -20,8 +43,15 @@ pub async fn export_to_obsidian(
     filename: String,
     vault_path: String,
     tags: Vec<String>,
+    session_id: Option<String>,
 ) -> Result<String, String> {
-    export::export_to_obsidian(&content, &filename, &vault_path, tags)
+    let tags = export::obsidian_tags(session_id.as_deref(), tags)
+        .await
+        .into_tauri_result()?;
+    let path = export::export_to_obsidian(&content, &filename, &vault_path, tags)
+        .await
+        .into_tauri_result()?;
+    audited(session_id, "obsidian", path)
         .await
         .into_tauri_result()
 }
@@ -31,10 +61,12 @@ pub async fn export_pdf(
     _app: AppHandle,
     content: String,
     filename: String,
+    session_id: Option<String>,
 ) -> Result<String, String> {
-    export::export_pdf(&content, &filename)
+    let path = export::export_pdf(&content, &filename)
         .await
-        .into_tauri_result()
+        .into_tauri_result()?;
+    audited(session_id, "pdf", path).await.into_tauri_result()
 }
 
 #[tauri::command]
@@ -42,8 +74,25 @@ pub async fn export_docx(
     _app: AppHandle,
     content: String,
     filename: String,
+    session_id: Option<String>,
 ) -> Result<String, String> {
-    export::export_docx(&content, &filename)
+    let path = export::export_docx(&content, &filename)
         .await
-        .into_tauri_result()
+        .into_tauri_result()?;
+    audited(session_id, "docx", path).await.into_tauri_result()
+}
+
+/// `content` is the SRT or VTT text the frontend built from the segments
+#[tauri::command]
+pub async fn export_subtitles(
+    _app: AppHandle,
+    content: String,
+    filename: String,
+    format: String,
+    session_id: Option<String>,
+) -> Result<String, String> {
+    let path = export::export_subtitles(&content, &filename, &format)
+        .await
+        .into_tauri_result()?;
+    audited(session_id, &format, path).await.into_tauri_result()
 }
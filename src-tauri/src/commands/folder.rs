@@ -1,5 +1,5 @@
 use crate::models::{CreateFolderRequest, Folder, UpdateFolderRequest};
-use crate::services::database;
+use crate::services::{database, folders};
 use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
 
@@ -28,3 +28,31 @@ pub async fn update_folder(app: AppHandle, request: UpdateFolderRequest) -> Resu
 pub async fn delete_folder(app: AppHandle, id: String) -> Result<(), String> {
     database::delete_folder(&app, &id).await.into_tauri_result()
 }
+
+/// Nest a folder under another one, or move it to the top level with no parent
+#[tauri::command]
+pub async fn move_folder(id: String, parent_id: Option<String>) -> Result<Folder, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    folders::move_folder(
+        pool,
+        &id,
+        parent_id.as_deref(),
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+    .into_tauri_result()
+}
+
+/// Folders whose metadata field has the given value, e.g. every case with one
+/// docket number. Searches all workspaces when none is given.
+#[tauri::command]
+pub async fn find_folders_by_metadata(
+    workspace_id: Option<String>,
+    field: String,
+    value: serde_json::Value,
+) -> Result<Vec<Folder>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    folders::find_by_metadata(pool, workspace_id.as_deref(), &field, &value)
+        .await
+        .into_tauri_result()
+}
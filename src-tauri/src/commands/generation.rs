@@ -1,5 +1,6 @@
 use crate::models::OllamaStatus;
-use crate::services::llm;
+use crate::services::cancellation::{self, JobKind};
+use crate::services::{database, llm};
 use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
 
@@ -23,9 +24,27 @@ pub async fn generate_note_streaming(
     transcript: String,
     template_id: String,
 ) -> Result<String, String> {
-    llm::generate_note_streaming(&app, &session_id, &transcript, &template_id)
-        .await
-        .into_tauri_result()
+    let guard = cancellation::register(JobKind::Generation, &session_id);
+    let result =
+        llm::generate_note_streaming(&app, &session_id, &transcript, &template_id, guard.token())
+            .await;
+
+    if let Err(ref e) = result {
+        if cancellation::is_cancelled_error(e) {
+            println!("[Generation] Cancelled for session: {}", session_id);
+            if let Err(e) = database::reset_status_after_cancel(&app, &session_id).await {
+                println!("[Generation] Failed to reset session status: {}", e);
+            }
+        }
+    }
+
+    result.into_tauri_result()
+}
+
+/// Stop a running note generation. Returns false if none is running for the session.
+#[tauri::command]
+pub async fn cancel_generation(session_id: String) -> Result<bool, String> {
+    Ok(cancellation::cancel(JobKind::Generation, &session_id))
 }
 
 #[tauri::command]
@@ -0,0 +1,45 @@
+use crate::models::{EnqueueJobRequest, IntegrityIssue, IntegrityReport, RepairReport};
+use crate::services::{audio, database, integrity, jobs};
+use crate::utils::IntoTauriResult;
+use tauri::AppHandle;
+
+/// Look for orphaned or missing audio and stale search chunks. Changes
+/// nothing; review the report, then pass the issues to fix to `repair_library`.
+#[tauri::command]
+pub async fn check_library_integrity(app: AppHandle) -> Result<IntegrityReport, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let audio_dir = audio::get_audio_dir(&app).into_tauri_result()?;
+    integrity::check(pool, &audio_dir, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+/// Fix reviewed issues from `check_library_integrity`. Sessions whose chunks
+/// were stale are queued for indexing again.
+#[tauri::command]
+pub async fn repair_library(
+    app: AppHandle,
+    issues: Vec<IntegrityIssue>,
+) -> Result<RepairReport, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let audio_dir = audio::get_audio_dir(&app).into_tauri_result()?;
+    let mut report = integrity::repair(pool, &audio_dir, &issues, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()?;
+
+    for session_id in &report.reindex {
+        let request = EnqueueJobRequest {
+            session_id: session_id.clone(),
+            kind: jobs::JobType::Index.as_str().to_string(),
+            template_id: None,
+            chain: None,
+            priority: None,
+        };
+        if let Err(e) = jobs::enqueue(&app, &request).await {
+            report
+                .errors
+                .push(format!("Couldn't queue indexing for {}: {}", session_id, e));
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,40 @@
+use crate::models::{EnqueueJobRequest, Job};
+use crate::services::{database, jobs};
+use crate::utils::IntoTauriResult;
+use tauri::{AppHandle, Emitter};
+
+/// Queue a transcription, generation or indexing job for a session.
+/// With `chain` set, the following steps are queued as each one finishes.
+#[tauri::command]
+pub async fn enqueue_job(app: AppHandle, request: EnqueueJobRequest) -> Result<Job, String> {
+    jobs::enqueue(&app, &request).await.into_tauri_result()
+}
+
+/// List jobs, optionally filtered by status
+#[tauri::command]
+pub async fn list_jobs(status: Option<String>) -> Result<Vec<Job>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    jobs::list_jobs(pool, status.as_deref())
+        .await
+        .into_tauri_result()
+}
+
+/// Change the priority of a queued job (higher runs first)
+#[tauri::command]
+pub async fn set_job_priority(app: AppHandle, job_id: String, priority: i64) -> Result<Job, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let job = jobs::set_priority(pool, &job_id, priority)
+        .await
+        .into_tauri_result()?;
+    let _ = app.emit("job-updated", &job);
+    Ok(job)
+}
+
+/// Cancel a queued or running job
+#[tauri::command]
+pub async fn cancel_job(app: AppHandle, job_id: String) -> Result<Job, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let job = jobs::cancel_job(pool, &job_id).await.into_tauri_result()?;
+    let _ = app.emit("job-updated", &job);
+    Ok(job)
+}
@@ -1,13 +1,22 @@
 pub mod audio;
+pub mod audit;
+pub mod backup;
 pub mod chat;
+pub mod encryption;
 pub mod export;
 pub mod folder;
 pub mod generation;
+pub mod integrity;
+pub mod jobs;
 pub mod models;
+pub mod retention;
 pub mod session;
 pub mod settings;
 pub mod streaming_transcription;
 pub mod system_audio;
+pub mod tag;
 pub mod template;
 pub mod transcription;
+pub mod trash;
 pub mod workspace;
+pub mod workspace_type;
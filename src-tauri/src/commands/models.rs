@@ -2,7 +2,7 @@ use crate::services::local_llm;
 use crate::services::model_manager::{
     get_all_models, get_llm_models, get_whisper_models, ModelInfo, ModelManager,
 };
-use crate::services::whisper;
+use crate::services::whisper::{self, LoadedWhisperModel};
 use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
 
@@ -79,9 +79,9 @@ pub fn is_whisper_model_loaded() -> bool {
     whisper::is_model_loaded()
 }
 
-/// Get the currently loaded Whisper model ID
+/// Get the currently loaded Whisper model and the backend it runs on
 #[tauri::command]
-pub fn get_loaded_whisper_model() -> Option<String> {
+pub fn get_loaded_whisper_model() -> Option<LoadedWhisperModel> {
     whisper::get_loaded_model()
 }
 
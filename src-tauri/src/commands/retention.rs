@@ -0,0 +1,52 @@
+use crate::models::{
+    RetentionCandidate, RetentionLogEntry, RetentionPolicy, SetRetentionPolicyRequest,
+};
+use crate::services::{database, retention};
+use crate::utils::IntoTauriResult;
+
+/// List every workspace and folder retention policy
+#[tauri::command]
+pub async fn get_retention_policies() -> Result<Vec<RetentionPolicy>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    retention::list_policies(pool).await.into_tauri_result()
+}
+
+/// Create or replace the retention policy for a workspace or folder
+#[tauri::command]
+pub async fn set_retention_policy(
+    request: SetRetentionPolicyRequest,
+) -> Result<RetentionPolicy, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    retention::set_policy(pool, &request, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn delete_retention_policy(id: String) -> Result<(), String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    retention::delete_policy(pool, &id).await.into_tauri_result()
+}
+
+/// Dry run: list the data the sweeper would purge right now
+#[tauri::command]
+pub async fn preview_retention(
+    workspace_id: Option<String>,
+) -> Result<Vec<RetentionCandidate>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    retention::find_due(pool, chrono::Utc::now().timestamp(), workspace_id.as_deref())
+        .await
+        .into_tauri_result()
+}
+
+/// Purges carried out under retention policies, most recent first
+#[tauri::command]
+pub async fn get_retention_log(
+    workspace_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<RetentionLogEntry>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    retention::list_log(pool, workspace_id.as_deref(), limit.unwrap_or(100))
+        .await
+        .into_tauri_result()
+}
@@ -1,5 +1,8 @@
-use crate::models::{CreateSessionRequest, Session, UpdateSessionRequest};
-use crate::services::{database, rag};
+use crate::models::{
+    CreateSessionRequest, Session, SessionListQuery, SessionPage, SessionSearchResult,
+    UpdateSessionRequest,
+};
+use crate::services::{database, folders, rag, search, session_list};
 use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
 
@@ -13,16 +16,29 @@ pub async fn create_session(
         .into_tauri_result()
 }
 
+/// A folder's sessions, optionally only those carrying all of `tags`
 #[tauri::command]
-pub async fn get_sessions(app: AppHandle, folder_id: String) -> Result<Vec<Session>, String> {
-    database::get_sessions(&app, &folder_id)
+pub async fn get_sessions(
+    app: AppHandle,
+    folder_id: String,
+    tags: Option<Vec<String>>,
+) -> Result<Vec<Session>, String> {
+    database::get_sessions(&app, &folder_id, &tags.unwrap_or_default())
         .await
         .into_tauri_result()
 }
 
+/// A page of a folder's sessions as summaries, sorted and filtered. Pass
+/// `nextCursor` back as `cursor` for the page after.
+#[tauri::command]
+pub async fn list_sessions(query: SessionListQuery) -> Result<SessionPage, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    session_list::list(pool, &query).await.into_tauri_result()
+}
+
 #[tauri::command]
 pub async fn get_session(app: AppHandle, id: String) -> Result<Session, String> {
-    database::get_session(&app, &id).await.into_tauri_result()
+    database::view_session(&app, &id).await.into_tauri_result()
 }
 
 #[tauri::command]
@@ -51,6 +67,15 @@ pub async fn update_session(
     Ok(session)
 }
 
+/// File a session under another folder
+#[tauri::command]
+pub async fn move_session(id: String, folder_id: String) -> Result<Session, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    folders::move_session(pool, &id, &folder_id, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
 #[tauri::command]
 pub async fn delete_session(app: AppHandle, id: String) -> Result<(), String> {
     database::delete_session(&app, &id)
@@ -58,13 +83,30 @@ pub async fn delete_session(app: AppHandle, id: String) -> Result<(), String> {
         .into_tauri_result()
 }
 
+/// Full-text search over titles, transcripts and notes. Supports "phrases",
+/// prefix*, AND / OR / NOT and parentheses; best matches first. `tags`
+/// limits results to sessions carrying all of them, in any folder, and
+/// with an empty query lists those sessions.
 #[tauri::command]
 pub async fn search_sessions(
-    app: AppHandle,
     query: String,
+    tags: Option<Vec<String>>,
     limit: Option<i32>,
-) -> Result<Vec<Session>, String> {
-    database::search_sessions(&app, &query, limit.unwrap_or(50))
+) -> Result<Vec<SessionSearchResult>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    search::search_sessions(pool, &query, &tags.unwrap_or_default(), limit.unwrap_or(50))
+        .await
+        .into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn rename_speaker(
+    app: AppHandle,
+    session_id: String,
+    old_name: String,
+    new_name: String,
+) -> Result<Session, String> {
+    database::rename_speaker(&app, &session_id, &old_name, &new_name)
         .await
         .into_tauri_result()
 }
@@ -1,6 +1,9 @@
 //! Tauri commands for streaming transcription
 
-use crate::services::streaming_transcription::{self, LiveTranscriptionConfig};
+use crate::models::{InterruptedSession, Session};
+use crate::services::streaming_transcription::{self, LiveTranscriptionConfig, StreamingStatus};
+use crate::services::{audio, database, encryption, live_sessions, rag};
+use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
 
 /// Start the streaming transcription worker
@@ -34,6 +37,8 @@ pub async fn start_live_transcription(
 ) -> Result<(), String> {
     println!("[Command] start_live_transcription: session={}", sessionId);
     let config = config.unwrap_or_default();
+    // Re-encryption refuses to start during a session, so wait for one running
+    let _lock = encryption::REKEY_LOCK.read().await;
     streaming_transcription::start_session(&app, &sessionId, &config).map_err(|e| e.to_string())
 }
 
@@ -74,10 +79,10 @@ pub fn is_streaming_worker_running() -> bool {
     streaming_transcription::is_worker_running()
 }
 
-/// Get the current state of the streaming worker
+/// Get the state of the streaming workers and every active live session
 #[tauri::command]
-pub fn get_streaming_worker_state() -> String {
-    format!("{:?}", streaming_transcription::get_state())
+pub fn get_streaming_worker_state() -> StreamingStatus {
+    streaming_transcription::get_status()
 }
 
 /// Ensure the streaming worker is running, restarting if necessary
@@ -87,3 +92,95 @@ pub async fn ensure_streaming_worker_running(app: AppHandle) -> Result<(), Strin
     println!("[Command] ensure_streaming_worker_running");
     streaming_transcription::ensure_worker_running(&app).map_err(|e| e.to_string())
 }
+
+/// Create the session for a recording about to be live-transcribed, so its
+/// confirmed segments are saved as they arrive
+#[tauri::command]
+pub async fn begin_live_session(
+    session_id: String,
+    folder_id: String,
+    title: Option<String>,
+) -> Result<(), String> {
+    let _lock = encryption::REKEY_LOCK.read().await;
+    let pool = database::get_pool().into_tauri_result()?;
+    live_sessions::begin(
+        pool,
+        &session_id,
+        &folder_id,
+        title.as_deref(),
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+    .into_tauri_result()
+}
+
+/// Attach the saved recording to a live session and end its live state
+#[tauri::command]
+pub async fn finish_live_session(
+    app: AppHandle,
+    session_id: String,
+    audio_path: String,
+    audio_duration: Option<i64>,
+) -> Result<Session, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    live_sessions::finish(
+        pool,
+        &session_id,
+        &audio_path,
+        audio_duration,
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+    .into_tauri_result()?;
+    database::get_session(&app, &session_id)
+        .await
+        .into_tauri_result()
+}
+
+/// Live sessions left behind by a crash or a stopped worker, to offer for
+/// recovery
+#[tauri::command]
+pub async fn get_interrupted_sessions(app: AppHandle) -> Result<Vec<InterruptedSession>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let audio_dir = audio::get_audio_dir(&app).into_tauri_result()?;
+    let active = streaming_transcription::active_session_ids();
+    live_sessions::interrupted(pool, &audio_dir, &active)
+        .await
+        .into_tauri_result()
+}
+
+/// Keep an interrupted session with the transcript saved before it stopped
+#[tauri::command]
+pub async fn recover_live_session(app: AppHandle, session_id: String) -> Result<Session, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let audio_dir = audio::get_audio_dir(&app).into_tauri_result()?;
+    live_sessions::recover(
+        pool,
+        &audio_dir,
+        &session_id,
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+    .into_tauri_result()?;
+
+    if let Err(e) = rag::index_session(pool, &session_id).await {
+        println!(
+            "[Command] Failed to index recovered session {}: {}",
+            session_id, e
+        );
+    }
+    database::get_session(&app, &session_id)
+        .await
+        .into_tauri_result()
+}
+
+/// Delete a live session and any partial recording, for a discarded or
+/// unwanted interrupted recording
+#[tauri::command]
+pub async fn discard_live_session(app: AppHandle, session_id: String) -> Result<(), String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let audio_dir = audio::get_audio_dir(&app).into_tauri_result()?;
+    live_sessions::discard(pool, &audio_dir, &session_id)
+        .await
+        .into_tauri_result()
+}
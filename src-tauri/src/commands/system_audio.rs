@@ -22,8 +22,10 @@ pub fn start_system_recording(
 
 /// Stop system audio recording
 #[tauri::command]
-pub fn stop_system_recording() -> Result<String, String> {
-    crate::services::system_audio::stop_recording().into_tauri_result()
+pub async fn stop_system_recording() -> Result<String, String> {
+    crate::services::system_audio::stop_recording()
+        .await
+        .into_tauri_result()
 }
 
 /// Get current recording status
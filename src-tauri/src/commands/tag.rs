@@ -0,0 +1,49 @@
+use crate::models::{Session, Tag};
+use crate::services::{database, tags};
+use crate::utils::IntoTauriResult;
+
+#[tauri::command]
+pub async fn get_tags() -> Result<Vec<Tag>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    tags::list(pool).await.into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn create_tag(name: String) -> Result<Tag, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    tags::create(pool, &name, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn rename_tag(id: String, name: String) -> Result<Tag, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    tags::rename(pool, &id, &name, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+/// Delete a tag, taking it off every session
+#[tauri::command]
+pub async fn delete_tag(id: String) -> Result<(), String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    tags::delete(pool, &id).await.into_tauri_result()
+}
+
+/// Tag a session by name, creating the tag if it's new
+#[tauri::command]
+pub async fn add_session_tag(session_id: String, name: String) -> Result<Session, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    tags::add_to_session(pool, &session_id, &name, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn remove_session_tag(session_id: String, name: String) -> Result<Session, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    tags::remove_from_session(pool, &session_id, &name)
+        .await
+        .into_tauri_result()
+}
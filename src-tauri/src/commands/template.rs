@@ -1,4 +1,4 @@
-use crate::models::Template;
+use crate::models::{CreateTemplateRequest, Template};
 use crate::services::database;
 use crate::utils::IntoTauriResult;
 use tauri::AppHandle;
@@ -17,3 +17,18 @@ pub async fn get_templates(
 pub async fn get_template(app: AppHandle, id: String) -> Result<Template, String> {
     database::get_template(&app, &id).await.into_tauri_result()
 }
+
+#[tauri::command]
+pub async fn create_template(
+    app: AppHandle,
+    request: CreateTemplateRequest,
+) -> Result<Template, String> {
+    database::create_template(&app, request)
+        .await
+        .into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn delete_template(app: AppHandle, id: String) -> Result<(), String> {
+    database::delete_template(&app, &id).await.into_tauri_result()
+}
@@ -1,9 +1,12 @@
-use crate::models::TranscriptionProgress;
-use crate::services::whisper;
+use crate::models::{LowConfidenceSpan, TranscriptSegment, TranscriptionProgress};
+use crate::services::cancellation::{self, JobKind};
+use crate::services::whisper::{ProgressListener, TranscriptionProgressEvent};
+use crate::services::{confidence, database, whisper};
 use crate::utils::IntoTauriResult;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::AppHandle;
 
 /// Track active transcription progress
@@ -23,6 +26,9 @@ pub fn update_progress(session_id: &str, progress: f32, status: &str) {
             session_id: session_id.to_string(),
             progress,
             status: status.to_string(),
+            processed_seconds: None,
+            total_seconds: None,
+            eta_seconds: None,
         },
     );
     println!(
@@ -31,6 +37,22 @@ pub fn update_progress(session_id: &str, progress: f32, status: &str) {
     );
 }
 
+/// Record a detailed progress event from the whisper service
+fn record_progress(event: &TranscriptionProgressEvent) {
+    let mut map = TRANSCRIPTION_PROGRESS.lock();
+    map.insert(
+        event.session_id.clone(),
+        TranscriptionProgress {
+            session_id: event.session_id.clone(),
+            progress: event.progress,
+            status: event.status.clone(),
+            processed_seconds: event.processed_seconds,
+            total_seconds: event.total_seconds,
+            eta_seconds: event.eta_seconds,
+        },
+    );
+}
+
 /// Clear transcription progress when done
 pub fn clear_progress(session_id: &str) {
     println!(
@@ -78,11 +100,17 @@ pub async fn transcribe_audio(
         file_size
     );
 
-    // WhisperKit auto-downloads models, so we can transcribe directly
-    // Set a default model if none is loaded (for UI compatibility)
+    // Load the configured backend's default model if none is loaded
     if !whisper::is_model_loaded() {
-        println!("[Transcription] Setting default WhisperKit model...");
-        whisper::load_model(&app, "whisperkit-base")
+        let backend = whisper::configured_backend(&app).await.map_err(|e| {
+            update_progress(&sessionId, 0.0, "error");
+            e.to_string()
+        })?;
+        println!(
+            "[Transcription] Loading default {} model...",
+            backend.as_str()
+        );
+        whisper::load_model(&app, backend.default_model_id())
             .await
             .map_err(|e| {
                 let err = format!("Failed to set model: {}", e);
@@ -93,8 +121,13 @@ pub async fn transcribe_audio(
     }
 
     update_progress(&sessionId, 10.0, "transcribing");
-    println!("[Transcription] Starting WhisperKit transcription...");
-    let result = whisper::transcribe(&app, &sessionId, &audioPath).await;
+    println!("[Transcription] Starting transcription...");
+    let listener: ProgressListener = Arc::new(record_progress);
+    let guard = cancellation::register(JobKind::Transcription, &sessionId);
+    let result =
+        whisper::transcribe_and_save(&app, &sessionId, &audioPath, listener, guard.token())
+            .await
+            .map(|session| session.transcript.unwrap_or_default());
 
     match &result {
         Ok(transcript) => {
@@ -108,11 +141,19 @@ pub async fn transcribe_audio(
             );
             update_progress(&sessionId, 100.0, "complete");
         }
+        Err(e) if cancellation::is_cancelled_error(e) => {
+            println!("[Transcription] Cancelled for session: {}", sessionId);
+            update_progress(&sessionId, 0.0, "cancelled");
+            if let Err(e) = database::reset_status_after_cancel(&app, &sessionId).await {
+                println!("[Transcription] Failed to reset session status: {}", e);
+            }
+        }
         Err(e) => {
             println!("[Transcription] ERROR during transcription: {}", e);
             update_progress(&sessionId, 0.0, "error");
         }
     }
+    drop(guard);
 
     // Clean up progress tracking after a short delay
     let session_id_clone = sessionId.clone();
@@ -124,6 +165,12 @@ pub async fn transcribe_audio(
     result.into_tauri_result()
 }
 
+/// Stop a running transcription. Returns false if none is running for the session.
+#[tauri::command]
+pub async fn cancel_transcription(session_id: String) -> Result<bool, String> {
+    Ok(cancellation::cancel(JobKind::Transcription, &session_id))
+}
+
 #[tauri::command]
 pub async fn get_transcription_progress(
     _app: AppHandle,
@@ -137,9 +184,35 @@ pub async fn get_transcription_progress(
             session_id,
             progress: 0.0,
             status: "pending".to_string(),
+            processed_seconds: None,
+            total_seconds: None,
+            eta_seconds: None,
         }))
 }
 
+/// Get spans of low-confidence words in a session transcript for human review
+#[tauri::command]
+pub async fn get_low_confidence_spans(
+    app: AppHandle,
+    session_id: String,
+    threshold: Option<f32>,
+) -> Result<Vec<LowConfidenceSpan>, String> {
+    let session = database::get_session(&app, &session_id)
+        .await
+        .into_tauri_result()?;
+
+    let segments: Vec<TranscriptSegment> = match session.transcript_segments {
+        Some(ref json) => serde_json::from_str(json)
+            .map_err(|e| format!("Failed to parse transcript segments: {}", e))?,
+        None => return Ok(Vec::new()),
+    };
+
+    Ok(confidence::find_low_confidence_spans(
+        &segments,
+        threshold.unwrap_or(confidence::DEFAULT_CONFIDENCE_THRESHOLD),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -418,12 +491,16 @@ mod tests {
             session_id: "test".to_string(),
             progress: 50.0,
             status: "transcribing".to_string(),
+            processed_seconds: Some(30.0),
+            total_seconds: Some(60.0),
+            eta_seconds: Some(12.0),
         };
 
         let cloned = progress.clone();
         assert_eq!(cloned.session_id, progress.session_id);
         assert_eq!(cloned.progress, progress.progress);
         assert_eq!(cloned.status, progress.status);
+        assert_eq!(cloned.eta_seconds, progress.eta_seconds);
     }
 
     #[test]
@@ -432,6 +509,9 @@ mod tests {
             session_id: "test-session".to_string(),
             progress: 75.5,
             status: "transcribing".to_string(),
+            processed_seconds: None,
+            total_seconds: None,
+            eta_seconds: None,
         };
 
         let json = serde_json::to_string(&progress).unwrap();
@@ -448,6 +528,7 @@ mod tests {
         assert_eq!(progress.session_id, "test");
         assert_eq!(progress.progress, 50.0);
         assert_eq!(progress.status, "complete");
+        assert!(progress.eta_seconds.is_none());
     }
 
     // ============================================================================
@@ -0,0 +1,38 @@
+use crate::models::TrashItem;
+use crate::services::{database, trash};
+use crate::utils::IntoTauriResult;
+
+/// List trashed sessions and folders, optionally for one workspace
+#[tauri::command]
+pub async fn list_trash(workspace_id: Option<String>) -> Result<Vec<TrashItem>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    let retention_days = trash::retention_days(pool).await.into_tauri_result()?;
+    trash::list_trash(pool, workspace_id.as_deref(), retention_days)
+        .await
+        .into_tauri_result()
+}
+
+/// Move a trashed session back to its folder
+#[tauri::command]
+pub async fn restore_session(id: String) -> Result<(), String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    trash::restore_session(pool, &id, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+/// Move a trashed folder and its sessions back
+#[tauri::command]
+pub async fn restore_folder(id: String) -> Result<(), String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    trash::restore_folder(pool, &id, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+/// Permanently delete everything in the trash. Returns the number of items removed.
+#[tauri::command]
+pub async fn empty_trash() -> Result<usize, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    trash::empty_trash(pool).await.into_tauri_result()
+}
@@ -0,0 +1,37 @@
+use crate::models::{CreateWorkspaceTypeRequest, UpdateWorkspaceTypeRequest, WorkspaceType};
+use crate::services::{database, workspace_types};
+use crate::utils::IntoTauriResult;
+
+#[tauri::command]
+pub async fn get_workspace_types() -> Result<Vec<WorkspaceType>, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    workspace_types::list(pool).await.into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn create_workspace_type(
+    request: CreateWorkspaceTypeRequest,
+) -> Result<WorkspaceType, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    workspace_types::create(pool, &request, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+/// Change a type's labels, default template or metadata schema; an empty
+/// string clears an optional field
+#[tauri::command]
+pub async fn update_workspace_type(
+    request: UpdateWorkspaceTypeRequest,
+) -> Result<WorkspaceType, String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    workspace_types::update(pool, &request, chrono::Utc::now().timestamp())
+        .await
+        .into_tauri_result()
+}
+
+#[tauri::command]
+pub async fn delete_workspace_type(id: String) -> Result<(), String> {
+    let pool = database::get_pool().into_tauri_result()?;
+    workspace_types::delete(pool, &id).await.into_tauri_result()
+}
@@ -0,0 +1,4 @@
+-- Revert migration 002: Transcript Chunks
+
+DROP TABLE IF EXISTS session_indexing_status;
+DROP TABLE IF EXISTS transcript_chunks;
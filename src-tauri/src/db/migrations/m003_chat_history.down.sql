@@ -0,0 +1,4 @@
+-- Revert migration 003: Chat History
+
+DROP TABLE IF EXISTS chat_messages;
+DROP TABLE IF EXISTS chat_conversations;
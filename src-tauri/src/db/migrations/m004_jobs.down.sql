@@ -0,0 +1,3 @@
+-- Revert migration 004: Jobs
+
+DROP TABLE IF EXISTS jobs;
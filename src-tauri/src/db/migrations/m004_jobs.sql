@@ -0,0 +1,33 @@
+-- Migration 004: Background Job Queue
+-- Persists transcription, note generation and RAG indexing work so it
+-- survives restarts and can run unattended
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('transcribe', 'generate', 'index')),
+    status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'completed', 'failed', 'cancelled')),
+    -- Higher runs first
+    priority INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    -- Template for generate jobs (and for the generate job chained after a transcription)
+    template_id TEXT,
+    -- When set, a finished job enqueues the next step: transcribe -> generate -> index
+    chain INTEGER NOT NULL DEFAULT 0,
+    progress REAL NOT NULL DEFAULT 0,
+    error TEXT,
+    -- Unix timestamp before which a retried job won't be picked up
+    run_after INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    started_at INTEGER,
+    finished_at INTEGER,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+-- Index for picking the next queued job
+CREATE INDEX IF NOT EXISTS idx_jobs_queue ON jobs(status, priority DESC, created_at);
+
+-- Index for looking up a session's jobs
+CREATE INDEX IF NOT EXISTS idx_jobs_session ON jobs(session_id);
@@ -0,0 +1,6 @@
+-- Revert migration 005: Session Search
+
+DROP TRIGGER IF EXISTS sessions_fts_update;
+DROP TRIGGER IF EXISTS sessions_fts_delete;
+DROP TRIGGER IF EXISTS sessions_fts_insert;
+DROP TABLE IF EXISTS sessions_fts;
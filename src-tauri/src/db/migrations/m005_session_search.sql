@@ -0,0 +1,33 @@
+-- Migration 005: Full-Text Session Search
+-- FTS5 index over session titles, transcripts and notes, kept in sync with
+-- the sessions table by triggers. Uses sessions as external content, so the
+-- index stores only tokens, not a second copy of every transcript.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS sessions_fts USING fts5(
+    title,
+    transcript,
+    generated_note,
+    content = 'sessions',
+    content_rowid = 'rowid',
+    tokenize = 'porter unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_insert AFTER INSERT ON sessions BEGIN
+    INSERT INTO sessions_fts (rowid, title, transcript, generated_note)
+    VALUES (new.rowid, new.title, new.transcript, new.generated_note);
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_delete AFTER DELETE ON sessions BEGIN
+    INSERT INTO sessions_fts (sessions_fts, rowid, title, transcript, generated_note)
+    VALUES ('delete', old.rowid, old.title, old.transcript, old.generated_note);
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_update AFTER UPDATE OF title, transcript, generated_note ON sessions BEGIN
+    INSERT INTO sessions_fts (sessions_fts, rowid, title, transcript, generated_note)
+    VALUES ('delete', old.rowid, old.title, old.transcript, old.generated_note);
+    INSERT INTO sessions_fts (rowid, title, transcript, generated_note)
+    VALUES (new.rowid, new.title, new.transcript, new.generated_note);
+END;
+
+-- Index sessions that existed before this migration
+INSERT INTO sessions_fts (sessions_fts) VALUES ('rebuild');
@@ -0,0 +1,8 @@
+-- Revert migration 006: Trash
+-- Trashed rows become visible again
+
+DROP INDEX IF EXISTS idx_folders_deleted;
+DROP INDEX IF EXISTS idx_sessions_deleted;
+
+ALTER TABLE folders DROP COLUMN deleted_at;
+ALTER TABLE sessions DROP COLUMN deleted_at;
@@ -0,0 +1,8 @@
+-- Migration 006: Trash
+-- Deleting a session or folder sets deleted_at; rows are purged later
+
+ALTER TABLE sessions ADD COLUMN deleted_at INTEGER;
+ALTER TABLE folders ADD COLUMN deleted_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_deleted ON sessions(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_folders_deleted ON folders(deleted_at);
@@ -0,0 +1,15 @@
+-- Revert migration 007: Retention policies
+-- Drops the purge record along with the policies
+
+DROP INDEX IF EXISTS idx_retention_log_session;
+DROP INDEX IF EXISTS idx_retention_log_purged;
+DROP TABLE IF EXISTS retention_log;
+
+ALTER TABLE sessions DROP COLUMN chunks_purged_at;
+ALTER TABLE sessions DROP COLUMN note_purged_at;
+ALTER TABLE sessions DROP COLUMN transcript_purged_at;
+ALTER TABLE sessions DROP COLUMN audio_purged_at;
+
+DROP INDEX IF EXISTS idx_retention_policies_folder;
+DROP INDEX IF EXISTS idx_retention_policies_workspace;
+DROP TABLE IF EXISTS retention_policies;
@@ -0,0 +1,44 @@
+-- Migration 007: Retention policies
+-- Per-workspace or per-folder limits on how long each kind of session data
+-- is kept, and a permanent record of everything purged under them
+
+CREATE TABLE IF NOT EXISTS retention_policies (
+    id TEXT PRIMARY KEY,
+    workspace_id TEXT,
+    folder_id TEXT,
+    -- Days after recording; NULL keeps that data indefinitely
+    audio_days INTEGER,
+    transcript_days INTEGER,
+    note_days INTEGER,
+    chunks_days INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    CHECK ((workspace_id IS NULL) != (folder_id IS NULL)),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (folder_id) REFERENCES folders(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_retention_policies_workspace ON retention_policies(workspace_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_retention_policies_folder ON retention_policies(folder_id);
+
+-- Set when the data is purged so it is never purged (or logged) twice
+ALTER TABLE sessions ADD COLUMN audio_purged_at INTEGER;
+ALTER TABLE sessions ADD COLUMN transcript_purged_at INTEGER;
+ALTER TABLE sessions ADD COLUMN note_purged_at INTEGER;
+ALTER TABLE sessions ADD COLUMN chunks_purged_at INTEGER;
+
+-- No foreign keys: entries must outlive the sessions and policies they describe
+CREATE TABLE IF NOT EXISTS retention_log (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    folder_id TEXT NOT NULL,
+    workspace_id TEXT NOT NULL,
+    policy_id TEXT NOT NULL,
+    data_type TEXT NOT NULL CHECK (data_type IN ('audio', 'transcript', 'note', 'chunks')),
+    retention_days INTEGER NOT NULL,
+    session_created_at INTEGER NOT NULL,
+    purged_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_retention_log_purged ON retention_log(purged_at);
+CREATE INDEX IF NOT EXISTS idx_retention_log_session ON retention_log(session_id);
@@ -0,0 +1,46 @@
+-- Revert migration 009: Workspace types
+-- Restores the fixed CHECK list. Workspaces and templates of custom types
+-- become general ones.
+
+CREATE TABLE workspaces_old (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    workspace_type TEXT NOT NULL CHECK (workspace_type IN ('therapy', 'legal', 'research', 'general')),
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    is_active INTEGER DEFAULT 1
+);
+INSERT INTO workspaces_old (id, name, workspace_type, created_at, updated_at, is_active)
+    SELECT id, name,
+        CASE WHEN workspace_type IN ('therapy', 'legal', 'research', 'general') THEN workspace_type ELSE 'general' END,
+        created_at, updated_at, is_active
+    FROM workspaces;
+DROP INDEX IF EXISTS idx_workspaces_type;
+DROP TABLE workspaces;
+ALTER TABLE workspaces_old RENAME TO workspaces;
+
+CREATE TABLE templates_old (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    workspace_type TEXT NOT NULL CHECK (workspace_type IN ('therapy', 'legal', 'research', 'general')),
+    description TEXT,
+    prompt TEXT NOT NULL,
+    output_format TEXT,
+    is_default INTEGER DEFAULT 0,
+    is_system INTEGER DEFAULT 1,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+INSERT INTO templates_old (id, name, workspace_type, description, prompt, output_format, is_default, is_system, created_at, updated_at)
+    SELECT id, name,
+        CASE WHEN workspace_type IN ('therapy', 'legal', 'research', 'general') THEN workspace_type ELSE 'general' END,
+        description, prompt, output_format,
+        -- A custom type's default would compete with the general default
+        CASE WHEN workspace_type IN ('therapy', 'legal', 'research', 'general') THEN is_default ELSE 0 END,
+        is_system, created_at, updated_at
+    FROM templates;
+DROP TABLE templates;
+ALTER TABLE templates_old RENAME TO templates;
+CREATE INDEX IF NOT EXISTS idx_templates_workspace_type ON templates(workspace_type);
+
+DROP TABLE IF EXISTS workspace_types;
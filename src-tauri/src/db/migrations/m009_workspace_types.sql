@@ -0,0 +1,71 @@
+-- Migration 009: Workspace types
+-- Workspace types become rows instead of a fixed CHECK list, so teams can add
+-- their own. workspaces and templates are rebuilt to reference the new table;
+-- the runner applies this with foreign keys off, as SQLite's rebuild requires.
+
+CREATE TABLE IF NOT EXISTS workspace_types (
+    -- Slug stored in workspaces.workspace_type and templates.workspace_type
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    -- What a folder is called in this type: Client, Case, Project
+    folder_label TEXT NOT NULL,
+    description TEXT,
+    icon TEXT,
+    default_template_id TEXT,
+    -- JSON schema for folder metadata
+    metadata_schema TEXT,
+    is_system INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (default_template_id) REFERENCES templates(id) ON DELETE SET NULL
+);
+
+INSERT OR IGNORE INTO workspace_types (id, name, folder_label, description, icon, is_system, created_at, updated_at) VALUES
+    ('therapy', 'Therapy', 'Client', 'For therapists, counselors, and psychologists', '🧠', 1, strftime('%s', 'now'), strftime('%s', 'now')),
+    ('legal', 'Legal', 'Case', 'For attorneys, paralegals, and legal teams', '⚖️', 1, strftime('%s', 'now'), strftime('%s', 'now')),
+    ('research', 'Research', 'Project', 'For qualitative researchers and academics', '🔬', 1, strftime('%s', 'now'), strftime('%s', 'now')),
+    ('general', 'General', 'Folder', 'For meetings, consultants, and general use', '📝', 1, strftime('%s', 'now'), strftime('%s', 'now'));
+
+CREATE TABLE workspaces_new (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    workspace_type TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    is_active INTEGER DEFAULT 1,
+    FOREIGN KEY (workspace_type) REFERENCES workspace_types(id)
+);
+INSERT INTO workspaces_new (id, name, workspace_type, created_at, updated_at, is_active)
+    SELECT id, name, workspace_type, created_at, updated_at, is_active FROM workspaces;
+DROP TABLE workspaces;
+ALTER TABLE workspaces_new RENAME TO workspaces;
+
+CREATE TABLE templates_new (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    workspace_type TEXT NOT NULL,
+    description TEXT,
+    prompt TEXT NOT NULL,
+    output_format TEXT,
+    is_default INTEGER DEFAULT 0,
+    is_system INTEGER DEFAULT 1,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (workspace_type) REFERENCES workspace_types(id)
+);
+INSERT INTO templates_new (id, name, workspace_type, description, prompt, output_format, is_default, is_system, created_at, updated_at)
+    SELECT id, name, workspace_type, description, prompt, output_format, is_default, is_system, created_at, updated_at FROM templates;
+DROP TABLE templates;
+ALTER TABLE templates_new RENAME TO templates;
+
+CREATE INDEX IF NOT EXISTS idx_templates_workspace_type ON templates(workspace_type);
+CREATE INDEX IF NOT EXISTS idx_workspaces_type ON workspaces(workspace_type);
+
+-- Built-in templates are only inserted on first launch, after migrations;
+-- existing libraries already have them
+UPDATE workspace_types SET default_template_id = (
+    SELECT id FROM templates
+    WHERE templates.workspace_type = workspace_types.id AND is_default = 1
+    ORDER BY is_system DESC, created_at
+    LIMIT 1
+);
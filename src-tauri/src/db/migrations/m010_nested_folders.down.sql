@@ -0,0 +1,25 @@
+-- Revert migration 010: Nested folders
+-- SQLite can't drop a foreign key column, so folders is rebuilt. Every
+-- folder becomes a top-level folder again.
+
+DROP INDEX IF EXISTS idx_folders_parent;
+
+CREATE TABLE folders_old (
+    id TEXT PRIMARY KEY,
+    workspace_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    metadata TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    is_active INTEGER DEFAULT 1,
+    deleted_at INTEGER,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
+);
+INSERT INTO folders_old (id, workspace_id, name, metadata, created_at, updated_at, is_active, deleted_at)
+    SELECT id, workspace_id, name, metadata, created_at, updated_at, is_active, deleted_at
+    FROM folders;
+DROP TABLE folders;
+ALTER TABLE folders_old RENAME TO folders;
+
+CREATE INDEX IF NOT EXISTS idx_folders_workspace ON folders(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_folders_deleted ON folders(deleted_at);
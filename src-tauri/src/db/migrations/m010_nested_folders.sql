@@ -0,0 +1,7 @@
+-- Migration 010: Nested folders
+-- A folder can sit inside another folder of the same workspace, such as
+-- Case → Depositions → Witness. NULL parent_id means a top-level folder.
+
+ALTER TABLE folders ADD COLUMN parent_id TEXT REFERENCES folders(id);
+
+CREATE INDEX IF NOT EXISTS idx_folders_parent ON folders(parent_id);
@@ -0,0 +1,10 @@
+-- Revert migration 011: clear the seeded schemas unless they were edited
+
+UPDATE workspace_types SET metadata_schema = NULL
+WHERE id = 'therapy' AND metadata_schema = '{"type":"object","properties":{"date_of_birth":{"type":"string","title":"Date of birth","format":"date"},"diagnosis_codes":{"type":"string","title":"Diagnosis codes"}}}';
+
+UPDATE workspace_types SET metadata_schema = NULL
+WHERE id = 'legal' AND metadata_schema = '{"type":"object","properties":{"case_number":{"type":"string","title":"Case number"},"docket_number":{"type":"string","title":"Docket number"},"court":{"type":"string","title":"Court"}}}';
+
+UPDATE workspace_types SET metadata_schema = NULL
+WHERE id = 'research' AND metadata_schema = '{"type":"object","properties":{"irb_protocol_id":{"type":"string","title":"IRB protocol ID"}}}';
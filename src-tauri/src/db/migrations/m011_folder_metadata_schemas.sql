@@ -0,0 +1,13 @@
+-- Migration 011: Folder metadata schemas for the built-in workspace types
+-- Folder metadata is now checked against its workspace type's schema. These
+-- only describe the common fields; other fields are still allowed, so
+-- existing metadata stays valid. Schemas a user already set are kept.
+
+UPDATE workspace_types SET metadata_schema = '{"type":"object","properties":{"date_of_birth":{"type":"string","title":"Date of birth","format":"date"},"diagnosis_codes":{"type":"string","title":"Diagnosis codes"}}}'
+WHERE id = 'therapy' AND metadata_schema IS NULL;
+
+UPDATE workspace_types SET metadata_schema = '{"type":"object","properties":{"case_number":{"type":"string","title":"Case number"},"docket_number":{"type":"string","title":"Docket number"},"court":{"type":"string","title":"Court"}}}'
+WHERE id = 'legal' AND metadata_schema IS NULL;
+
+UPDATE workspace_types SET metadata_schema = '{"type":"object","properties":{"irb_protocol_id":{"type":"string","title":"IRB protocol ID"}}}'
+WHERE id = 'research' AND metadata_schema IS NULL;
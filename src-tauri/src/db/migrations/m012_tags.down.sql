@@ -0,0 +1,5 @@
+-- Revert migration 012: Session tags
+
+DROP INDEX IF EXISTS idx_session_tags_tag;
+DROP TABLE IF EXISTS session_tags;
+DROP TABLE IF EXISTS tags;
@@ -0,0 +1,4 @@
+-- Revert migration 013: Live sessions
+
+DROP INDEX IF EXISTS idx_sessions_live;
+ALTER TABLE sessions DROP COLUMN live_started_at;
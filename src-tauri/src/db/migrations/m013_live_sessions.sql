@@ -0,0 +1,9 @@
+-- Migration 013: Live sessions
+-- A session recorded with live transcription is created when recording
+-- starts, and confirmed segments are written to it as they arrive. It stays
+-- live until the recording is saved, so a session still live when the app
+-- starts was interrupted and can be recovered.
+
+ALTER TABLE sessions ADD COLUMN live_started_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_live ON sessions(live_started_at);
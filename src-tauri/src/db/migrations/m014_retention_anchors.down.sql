@@ -0,0 +1,6 @@
+-- Revert migration 014: Retention anchors
+
+DROP TRIGGER IF EXISTS sessions_note_completed;
+DROP TRIGGER IF EXISTS sessions_transcript_completed;
+ALTER TABLE sessions DROP COLUMN note_completed_at;
+ALTER TABLE sessions DROP COLUMN transcript_completed_at;
@@ -0,0 +1,24 @@
+-- Migration 014: Retention anchors
+-- Retention limits count from when each kind of data was finished: the
+-- transcript (and the RAG chunks built from it) from when it was last
+-- saved, and the note and recording from when the note was last saved, so
+-- "30 days after the note is signed" holds for a note finished weeks after
+-- recording. Sessions without a timestamp fall back to when they were
+-- recorded.
+
+ALTER TABLE sessions ADD COLUMN transcript_completed_at INTEGER;
+ALTER TABLE sessions ADD COLUMN note_completed_at INTEGER;
+
+-- Existing data was last saved no later than the session's last change
+UPDATE sessions SET transcript_completed_at = updated_at WHERE transcript IS NOT NULL;
+UPDATE sessions SET note_completed_at = updated_at WHERE generated_note IS NOT NULL;
+
+CREATE TRIGGER IF NOT EXISTS sessions_transcript_completed AFTER UPDATE OF transcript ON sessions
+WHEN new.transcript IS NOT NULL AND new.transcript IS NOT old.transcript BEGIN
+    UPDATE sessions SET transcript_completed_at = new.updated_at WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_note_completed AFTER UPDATE OF generated_note ON sessions
+WHEN new.generated_note IS NOT NULL AND new.generated_note IS NOT old.generated_note BEGIN
+    UPDATE sessions SET note_completed_at = new.updated_at WHERE id = new.id;
+END;
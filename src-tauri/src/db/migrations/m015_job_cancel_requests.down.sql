@@ -0,0 +1,3 @@
+-- Revert migration 015: Job cancel requests
+
+ALTER TABLE jobs DROP COLUMN cancel_requested;
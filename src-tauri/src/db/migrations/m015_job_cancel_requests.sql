@@ -0,0 +1,6 @@
+-- Migration 015: Job cancel requests
+-- Cancelling a running job signals its cancellation token, but the job only
+-- registers one once it starts work. The request is recorded on the job so
+-- a job cancelled before then stops as soon as it registers.
+
+ALTER TABLE jobs ADD COLUMN cancel_requested INTEGER NOT NULL DEFAULT 0;
@@ -1,12 +1,18 @@
-use anyhow::Result;
-use sqlx::sqlite::SqlitePool;
-use sqlx::Row;
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use sqlx::{Connection, Row};
 
 /// Represents a database migration
 struct Migration {
     version: i32,
     name: &'static str,
     sql: &'static str,
+    /// Reverts `sql`. Migrations without one can't be rolled back.
+    down: Option<&'static str>,
+    /// Drops and recreates tables that others reference. Runs with foreign
+    /// keys off, then checks that no references were left dangling.
+    rebuilds_tables: bool,
 }
 
 /// All migrations in order
@@ -15,19 +21,117 @@ const MIGRATIONS: &[Migration] = &[
         version: 1,
         name: "initial_schema",
         sql: include_str!("m001_initial_schema.sql"),
+        down: None,
+        rebuilds_tables: false,
     },
     Migration {
         version: 2,
         name: "transcript_chunks",
         sql: include_str!("m002_transcript_chunks.sql"),
+        down: Some(include_str!("m002_transcript_chunks.down.sql")),
+        rebuilds_tables: false,
     },
     Migration {
         version: 3,
         name: "chat_history",
         sql: include_str!("m003_chat_history.sql"),
+        down: Some(include_str!("m003_chat_history.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 4,
+        name: "jobs",
+        sql: include_str!("m004_jobs.sql"),
+        down: Some(include_str!("m004_jobs.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 5,
+        name: "session_search",
+        sql: include_str!("m005_session_search.sql"),
+        down: Some(include_str!("m005_session_search.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 6,
+        name: "trash",
+        sql: include_str!("m006_trash.sql"),
+        down: Some(include_str!("m006_trash.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 7,
+        name: "retention",
+        sql: include_str!("m007_retention.sql"),
+        down: Some(include_str!("m007_retention.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 8,
+        name: "audit_log",
+        sql: include_str!("m008_audit_log.sql"),
+        down: Some(include_str!("m008_audit_log.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 9,
+        name: "workspace_types",
+        sql: include_str!("m009_workspace_types.sql"),
+        down: Some(include_str!("m009_workspace_types.down.sql")),
+        rebuilds_tables: true,
+    },
+    Migration {
+        version: 10,
+        name: "nested_folders",
+        sql: include_str!("m010_nested_folders.sql"),
+        down: Some(include_str!("m010_nested_folders.down.sql")),
+        rebuilds_tables: true,
+    },
+    Migration {
+        version: 11,
+        name: "folder_metadata_schemas",
+        sql: include_str!("m011_folder_metadata_schemas.sql"),
+        down: Some(include_str!("m011_folder_metadata_schemas.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 12,
+        name: "tags",
+        sql: include_str!("m012_tags.sql"),
+        down: Some(include_str!("m012_tags.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 13,
+        name: "live_sessions",
+        sql: include_str!("m013_live_sessions.sql"),
+        down: Some(include_str!("m013_live_sessions.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 14,
+        name: "retention_anchors",
+        sql: include_str!("m014_retention_anchors.sql"),
+        down: Some(include_str!("m014_retention_anchors.down.sql")),
+        rebuilds_tables: false,
+    },
+    Migration {
+        version: 15,
+        name: "job_cancel_requests",
+        sql: include_str!("m015_job_cancel_requests.sql"),
+        down: Some(include_str!("m015_job_cancel_requests.down.sql")),
+        rebuilds_tables: false,
     },
 ];
 
+/// SHA-256 of a migration's SQL, ignoring line-ending differences
+fn checksum(sql: &str) -> String {
+    Sha256::digest(sql.replace("\r\n", "\n").as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 /// Ensures the _migrations table exists
 async fn ensure_migrations_table(pool: &SqlitePool) -> Result<()> {
     sqlx::query(
@@ -35,17 +139,35 @@ async fn ensure_migrations_table(pool: &SqlitePool) -> Result<()> {
         CREATE TABLE IF NOT EXISTS _migrations (
             version INTEGER PRIMARY KEY,
             name TEXT NOT NULL,
-            applied_at INTEGER NOT NULL
+            applied_at INTEGER NOT NULL,
+            checksum TEXT
         )
         "#,
     )
     .execute(pool)
     .await?;
+
+    // Tables created before checksums were tracked
+    let has_checksum: i32 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pragma_table_info('_migrations') WHERE name = 'checksum'",
+    )
+    .fetch_one(pool)
+    .await?;
+    if has_checksum == 0 {
+        sqlx::query("ALTER TABLE _migrations ADD COLUMN checksum TEXT")
+            .execute(pool)
+            .await?;
+    }
     Ok(())
 }
 
+/// Schema version this build migrates to
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
 /// Gets the current schema version (0 if no migrations applied)
-async fn get_current_version(pool: &SqlitePool) -> Result<i32> {
+pub async fn get_current_version(pool: &SqlitePool) -> Result<i32> {
     let result: Option<(i32,)> =
         sqlx::query_as("SELECT COALESCE(MAX(version), 0) FROM _migrations")
             .fetch_optional(pool)
@@ -54,14 +176,52 @@ async fn get_current_version(pool: &SqlitePool) -> Result<i32> {
 }
 
 /// Records that a migration was applied
-async fn record_migration(pool: &SqlitePool, version: i32, name: &str) -> Result<()> {
+async fn record_migration(conn: &mut SqliteConnection, migration: &Migration) -> Result<()> {
     let now = chrono::Utc::now().timestamp();
-    sqlx::query("INSERT INTO _migrations (version, name, applied_at) VALUES (?, ?, ?)")
-        .bind(version)
-        .bind(name)
+    sqlx::query("INSERT INTO _migrations (version, name, applied_at, checksum) VALUES (?, ?, ?, ?)")
+        .bind(migration.version)
+        .bind(migration.name)
         .bind(now)
-        .execute(pool)
+        .bind(checksum(migration.sql))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Check that applied migrations haven't been edited since they ran.
+/// Records from before checksums were tracked get the current checksum.
+async fn verify_checksums(pool: &SqlitePool, migrations: &[Migration]) -> Result<()> {
+    let rows = sqlx::query("SELECT version, checksum FROM _migrations ORDER BY version")
+        .fetch_all(pool)
         .await?;
+
+    for row in rows {
+        let version: i32 = row.get("version");
+        let stored: Option<String> = row.get("checksum");
+        // Applied by a newer build; nothing to compare against
+        let Some(migration) = migrations.iter().find(|m| m.version == version) else {
+            continue;
+        };
+        let expected = checksum(migration.sql);
+
+        match stored {
+            Some(stored) if stored != expected => {
+                return Err(anyhow!(
+                    "Migration {} ({}) has been modified since it was applied",
+                    migration.version,
+                    migration.name
+                ));
+            }
+            Some(_) => {}
+            None => {
+                sqlx::query("UPDATE _migrations SET checksum = ? WHERE version = ?")
+                    .bind(&expected)
+                    .bind(version)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+    }
     Ok(())
 }
 
@@ -86,10 +246,212 @@ async fn table_exists(pool: &SqlitePool, table_name: &str) -> Result<bool> {
     Ok(result.0 > 0)
 }
 
+/// Whether a statement is a `CREATE TRIGGER` whose `BEGIN ... END` body is
+/// still open. `CASE ... END` inside the body is counted too.
+fn in_trigger_body(words: &[String]) -> bool {
+    let is_trigger = match words {
+        [create, trigger, ..] if create == "CREATE" && trigger == "TRIGGER" => true,
+        [create, temp, trigger, ..] => {
+            create == "CREATE" && (temp == "TEMP" || temp == "TEMPORARY") && trigger == "TRIGGER"
+        }
+        _ => false,
+    };
+    if !is_trigger {
+        return false;
+    }
+
+    let mut depth = 0;
+    for word in words {
+        match word.as_str() {
+            "BEGIN" | "CASE" => depth += 1,
+            "END" => depth -= 1,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+/// Split migration SQL into statements.
+///
+/// A semicolon ends a statement unless it is inside a string literal, a
+/// quoted identifier, a comment or a trigger body. Comments are dropped.
+fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    // Upper-cased keywords and identifiers of the current statement
+    let mut words: Vec<String> = Vec::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Literals and quoted identifiers; a doubled quote is an escape
+            '\'' | '"' | '`' => {
+                current.push(c);
+                while let Some(q) = chars.next() {
+                    current.push(q);
+                    if q == c {
+                        if chars.peek() == Some(&c) {
+                            current.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '[' => {
+                current.push(c);
+                for q in chars.by_ref() {
+                    current.push(q);
+                    if q == ']' {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                while chars.peek().is_some_and(|&n| n != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+                current.push(' ');
+            }
+            ';' if !in_trigger_body(&words) => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+                words.clear();
+            }
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(&n) = chars.peek() {
+                    if !(n.is_ascii_alphanumeric() || n == '_' || n == '$') {
+                        break;
+                    }
+                    word.push(n);
+                    chars.next();
+                }
+                current.push_str(&word);
+                words.push(word.to_ascii_uppercase());
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+/// Run every statement of a script on one connection
+async fn execute_script(conn: &mut SqliteConnection, sql: &str) -> Result<()> {
+    for (idx, statement) in split_statements(sql).iter().enumerate() {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to execute SQL statement {}: {}\nError: {}",
+                    idx,
+                    statement.chars().take(100).collect::<String>(),
+                    e
+                )
+            })?;
+    }
+    Ok(())
+}
+
+/// Turn foreign key enforcement on or off for a table-rebuilding migration.
+/// SQLite ignores this pragma inside a transaction, so it is set around it.
+async fn set_foreign_keys(
+    conn: &mut SqliteConnection,
+    migration: &Migration,
+    enabled: bool,
+) -> Result<()> {
+    if migration.rebuilds_tables {
+        let value = if enabled { "ON" } else { "OFF" };
+        sqlx::query(&format!("PRAGMA foreign_keys = {}", value))
+            .execute(conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Fail if a table-rebuilding script left rows pointing at missing parents
+async fn check_foreign_keys(conn: &mut SqliteConnection, migration: &Migration) -> Result<()> {
+    if !migration.rebuilds_tables {
+        return Ok(());
+    }
+    let violations = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(&mut *conn)
+        .await?;
+    if let Some(row) = violations.first() {
+        return Err(anyhow!(
+            "{} foreign key violation(s), first in table {}",
+            violations.len(),
+            row.get::<String, _>(0)
+        ));
+    }
+    Ok(())
+}
+
+/// Apply one migration and record it, all in a single transaction
+async fn apply_migration(pool: &SqlitePool, migration: &Migration) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    set_foreign_keys(&mut conn, migration, false).await?;
+    let result: Result<()> = async {
+        let mut tx = conn.begin().await?;
+        execute_script(&mut tx, migration.sql).await?;
+        check_foreign_keys(&mut tx, migration).await?;
+        record_migration(&mut tx, migration).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+    set_foreign_keys(&mut conn, migration, true).await?;
+    result
+}
+
+/// Revert one migration with its down script and remove its record, all in
+/// a single transaction
+async fn revert_migration(pool: &SqlitePool, migration: &Migration, down: &str) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    set_foreign_keys(&mut conn, migration, false).await?;
+    let result: Result<()> = async {
+        let mut tx = conn.begin().await?;
+        execute_script(&mut tx, down).await?;
+        check_foreign_keys(&mut tx, migration).await?;
+        sqlx::query("DELETE FROM _migrations WHERE version = ?")
+            .bind(migration.version)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+    set_foreign_keys(&mut conn, migration, true).await?;
+    result
+}
+
 /// Runs all pending migrations
 pub async fn run_pending_migrations(pool: &SqlitePool) -> Result<()> {
+    run_migrations(pool, MIGRATIONS).await
+}
+
+async fn run_migrations(pool: &SqlitePool, migrations: &[Migration]) -> Result<()> {
     // Ensure the migrations tracking table exists
     ensure_migrations_table(pool).await?;
+    verify_checksums(pool, migrations).await?;
 
     let current_version = get_current_version(pool).await?;
     let is_fresh = is_fresh_database(pool).await?;
@@ -100,13 +462,13 @@ pub async fn run_pending_migrations(pool: &SqlitePool) -> Result<()> {
         // Check if workspaces table exists (key table from migration 1)
         if table_exists(pool, "workspaces").await? {
             println!("[migrations] Detected existing database, marking migration 1 as already applied");
-            record_migration(pool, 1, "initial_schema").await?;
+            record_migration(&mut *pool.acquire().await?, &migrations[0]).await?;
             return Ok(());
         }
     }
 
     // Apply pending migrations
-    for migration in MIGRATIONS {
+    for migration in migrations {
         if migration.version > current_version {
             println!(
                 "[migrations] Applying migration {} ({})",
@@ -114,46 +476,14 @@ pub async fn run_pending_migrations(pool: &SqlitePool) -> Result<()> {
                 migration.name
             );
 
-            // Execute the migration SQL
-            // Split by semicolons and execute each statement
-            for (idx, statement) in migration.sql.split(';').enumerate() {
-                let trimmed = statement.trim();
-                // Skip empty statements
-                if trimmed.is_empty() {
-                    continue;
-                }
-                // Extract non-comment content to check if there's actual SQL
-                let non_comment: String = trimmed
-                    .lines()
-                    .filter(|line| !line.trim().starts_with("--"))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                // Skip if it's only comments
-                if non_comment.trim().is_empty() {
-                    continue;
-                }
-                #[cfg(test)]
-                println!(
-                    "[debug] Executing statement {}: {}...",
-                    idx,
-                    non_comment.chars().take(60).collect::<String>()
-                );
-
-                sqlx::query(trimmed)
-                    .execute(pool)
-                    .await
-                    .map_err(|e| {
-                        anyhow::anyhow!(
-                            "Failed to execute SQL statement {}: {}\nError: {}",
-                            idx,
-                            non_comment.chars().take(100).collect::<String>(),
-                            e
-                        )
-                    })?;
-            }
-
-            // Record the migration
-            record_migration(pool, migration.version, migration.name).await?;
+            apply_migration(pool, migration).await.map_err(|e| {
+                anyhow!(
+                    "Migration {} ({}) failed and was rolled back: {}",
+                    migration.version,
+                    migration.name,
+                    e
+                )
+            })?;
 
             println!("[migrations] Migration {} applied successfully", migration.version);
         }
@@ -162,6 +492,63 @@ pub async fn run_pending_migrations(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
+/// Roll the schema back to `target_version` by running the down scripts of
+/// every newer applied migration, newest first. Each step is its own
+/// transaction. Nothing is reverted if any step lacks a down script.
+#[allow(dead_code)]
+pub async fn rollback_to_version(pool: &SqlitePool, target_version: i32) -> Result<()> {
+    rollback_migrations(pool, MIGRATIONS, target_version).await
+}
+
+async fn rollback_migrations(
+    pool: &SqlitePool,
+    migrations: &[Migration],
+    target_version: i32,
+) -> Result<()> {
+    ensure_migrations_table(pool).await?;
+    verify_checksums(pool, migrations).await?;
+
+    let applied: Vec<i32> =
+        sqlx::query_scalar("SELECT version FROM _migrations WHERE version > ? ORDER BY version DESC")
+            .bind(target_version)
+            .fetch_all(pool)
+            .await?;
+
+    let mut steps = Vec::with_capacity(applied.len());
+    for version in applied {
+        let migration = migrations
+            .iter()
+            .find(|m| m.version == version)
+            .ok_or_else(|| anyhow!("Migration {} is not known to this version of the app", version))?;
+        let down = migration.down.ok_or_else(|| {
+            anyhow!(
+                "Migration {} ({}) has no down script and can't be rolled back",
+                migration.version,
+                migration.name
+            )
+        })?;
+        steps.push((migration, down));
+    }
+
+    for (migration, down) in steps {
+        println!(
+            "[migrations] Reverting migration {} ({})",
+            migration.version,
+            migration.name
+        );
+        revert_migration(pool, migration, down).await.map_err(|e| {
+            anyhow!(
+                "Reverting migration {} ({}) failed: {}",
+                migration.version,
+                migration.name,
+                e
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
 /// Gets a list of applied migrations for debugging
 #[allow(dead_code)]
 pub async fn get_applied_migrations(pool: &SqlitePool) -> Result<Vec<(i32, String, i64)>> {
@@ -186,26 +573,18 @@ pub async fn get_applied_migrations(pool: &SqlitePool) -> Result<Vec<(i32, Strin
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sqlx::sqlite::SqlitePoolOptions;
-
-    async fn create_test_pool() -> SqlitePool {
-        SqlitePoolOptions::new()
-            .max_connections(1)
-            .connect("sqlite::memory:")
-            .await
-            .expect("Failed to create test pool")
-    }
+    use crate::db::test_support::empty_pool;
 
     #[tokio::test]
     async fn test_fresh_migration() {
-        let pool = create_test_pool().await;
+        let pool = empty_pool().await;
 
         // Run migrations on fresh database
         run_pending_migrations(&pool).await.unwrap();
 
         // Verify migrations were recorded
         let version = get_current_version(&pool).await.unwrap();
-        assert_eq!(version, 3); // Now we have 3 migrations
+        assert_eq!(version, 15); // Now we have 15 migrations
 
         // Verify migration 1 tables were created
         assert!(table_exists(&pool, "workspaces").await.unwrap());
@@ -221,28 +600,91 @@ mod tests {
         // Verify migration 3 tables (chat history)
         assert!(table_exists(&pool, "chat_conversations").await.unwrap());
         assert!(table_exists(&pool, "chat_messages").await.unwrap());
+
+        // Verify migration 4 tables (job queue)
+        assert!(table_exists(&pool, "jobs").await.unwrap());
+
+        // Verify migration 5 tables (full-text search)
+        assert!(table_exists(&pool, "sessions_fts").await.unwrap());
+
+        // Verify migration 6 columns (trash)
+        sqlx::query("SELECT deleted_at FROM sessions UNION ALL SELECT deleted_at FROM folders")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+        // Verify migration 7 tables (retention)
+        assert!(table_exists(&pool, "retention_policies").await.unwrap());
+        assert!(table_exists(&pool, "retention_log").await.unwrap());
+
+        // Verify migration 8 tables (audit log)
+        assert!(table_exists(&pool, "audit_log").await.unwrap());
+
+        // Verify migration 9 tables (workspace types)
+        assert!(table_exists(&pool, "workspace_types").await.unwrap());
+
+        // Verify migration 12 tables (tags)
+        assert!(table_exists(&pool, "tags").await.unwrap());
+        assert!(table_exists(&pool, "session_tags").await.unwrap());
+
+        // Verify migration 13 columns (live sessions)
+        sqlx::query("SELECT live_started_at FROM sessions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+        // Verify migration 14 columns (retention anchors)
+        sqlx::query("SELECT transcript_completed_at, note_completed_at FROM sessions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+        // Verify migration 15 columns (job cancel requests)
+        sqlx::query("SELECT cancel_requested FROM jobs")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
     async fn test_skip_applied_migrations() {
-        let pool = create_test_pool().await;
+        let pool = empty_pool().await;
 
         // Run migrations twice
         run_pending_migrations(&pool).await.unwrap();
         run_pending_migrations(&pool).await.unwrap();
 
-        // Should still be at version 3, not error
+        // Should still be at the latest version, not error
         let version = get_current_version(&pool).await.unwrap();
-        assert_eq!(version, 3);
+        assert_eq!(version, latest_version());
 
-        // Check all 3 migration records exist
+        // Check a record exists for every migration
         let migrations = get_applied_migrations(&pool).await.unwrap();
-        assert_eq!(migrations.len(), 3);
+        assert_eq!(migrations.len(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_split_statements_keeps_trigger_bodies() {
+        let sql = r#"
+-- A comment; with a semicolon
+CREATE TABLE a (id INTEGER);
+CREATE TRIGGER a_insert AFTER INSERT ON a BEGIN
+    INSERT INTO b VALUES (new.id);
+    INSERT INTO c VALUES (new.id);
+END;
+CREATE INDEX idx_a ON a(id);
+"#;
+        let statements = split_statements(sql);
+
+        assert_eq!(statements.len(), 3);
+        assert!(statements[1].starts_with("CREATE TRIGGER"));
+        assert!(statements[1].ends_with("END"));
+        assert!(statements[1].contains("INSERT INTO c"));
     }
 
     #[tokio::test]
     async fn test_existing_database_detection() {
-        let pool = create_test_pool().await;
+        let pool = empty_pool().await;
 
         // Manually create a table that would exist in pre-migration database
         sqlx::query("CREATE TABLE workspaces (id TEXT PRIMARY KEY)")
@@ -257,4 +699,264 @@ mod tests {
         let version = get_current_version(&pool).await.unwrap();
         assert_eq!(version, 1);
     }
+
+    #[test]
+    fn test_split_statements_respects_literals_and_comments() {
+        let sql = r#"
+INSERT INTO t VALUES ('a;b', 'it''s; fine'); /* block; comment */
+INSERT INTO "odd;name" VALUES (1); -- trailing; comment
+CREATE TRIGGER t_ins AFTER INSERT ON t BEGIN
+    UPDATE t SET v = CASE WHEN new.v > 0 THEN 'pos;' ELSE 'neg' END;
+    DELETE FROM u;
+END;
+"#;
+        let statements = split_statements(sql);
+
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "INSERT INTO t VALUES ('a;b', 'it''s; fine')");
+        assert_eq!(statements[1], r#"INSERT INTO "odd;name" VALUES (1)"#);
+        assert!(statements[2].contains("DELETE FROM u;"));
+        assert!(statements[2].ends_with("END"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_is_rolled_back() {
+        let pool = empty_pool().await;
+        let migrations = [
+            Migration {
+                version: 1,
+                name: "first",
+                sql: "CREATE TABLE a (id INTEGER);",
+                down: None,
+                rebuilds_tables: false,
+            },
+            Migration {
+                version: 2,
+                name: "broken",
+                sql: "CREATE TABLE b (id INTEGER); INSERT INTO missing VALUES (1);",
+                down: None,
+                rebuilds_tables: false,
+            },
+        ];
+
+        let err = run_migrations(&pool, &migrations).await.unwrap_err();
+        assert!(err.to_string().contains("rolled back"));
+
+        assert!(table_exists(&pool, "a").await.unwrap());
+        assert!(!table_exists(&pool, "b").await.unwrap());
+        assert_eq!(get_current_version(&pool).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_modified_migration_is_detected() {
+        let pool = empty_pool().await;
+        let original = [Migration {
+            version: 1,
+            name: "first",
+            sql: "CREATE TABLE a (id INTEGER);",
+            down: None,
+            rebuilds_tables: false,
+        }];
+        run_migrations(&pool, &original).await.unwrap();
+
+        let edited = [Migration {
+            version: 1,
+            name: "first",
+            sql: "CREATE TABLE a (id INTEGER, name TEXT);",
+            down: None,
+            rebuilds_tables: false,
+        }];
+        let err = run_migrations(&pool, &edited).await.unwrap_err();
+        assert!(err.to_string().contains("modified"));
+
+        // Line endings alone don't count as an edit
+        assert_eq!(
+            checksum("CREATE TABLE a (id INTEGER);\r\n"),
+            checksum("CREATE TABLE a (id INTEGER);\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checksums_backfilled_for_old_records() {
+        let pool = empty_pool().await;
+        sqlx::query("CREATE TABLE _migrations (version INTEGER PRIMARY KEY, name TEXT NOT NULL, applied_at INTEGER NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let migrations = [Migration {
+            version: 1,
+            name: "first",
+            sql: "CREATE TABLE a (id INTEGER);",
+            down: None,
+            rebuilds_tables: false,
+        }];
+        sqlx::query("CREATE TABLE a (id INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO _migrations (version, name, applied_at) VALUES (1, 'first', 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        run_migrations(&pool, &migrations).await.unwrap();
+
+        let stored: Option<String> =
+            sqlx::query_scalar("SELECT checksum FROM _migrations WHERE version = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, Some(checksum(migrations[0].sql)));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_version() {
+        let pool = empty_pool().await;
+        run_pending_migrations(&pool).await.unwrap();
+
+        rollback_to_version(&pool, 4).await.unwrap();
+        assert_eq!(get_current_version(&pool).await.unwrap(), 4);
+        assert!(!table_exists(&pool, "sessions_fts").await.unwrap());
+        assert!(sqlx::query("SELECT deleted_at FROM sessions")
+            .fetch_all(&pool)
+            .await
+            .is_err());
+
+        // Migration 1 has no down script, so nothing is reverted
+        assert!(rollback_to_version(&pool, 0).await.is_err());
+        assert_eq!(get_current_version(&pool).await.unwrap(), 4);
+        assert!(table_exists(&pool, "jobs").await.unwrap());
+
+        // Rolled back migrations apply again
+        run_pending_migrations(&pool).await.unwrap();
+        assert_eq!(get_current_version(&pool).await.unwrap(), 15);
+        assert!(table_exists(&pool, "sessions_fts").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_workspace_types_rebuild_keeps_rows() {
+        let pool = empty_pool().await;
+        run_migrations(&pool, &MIGRATIONS[..8]).await.unwrap();
+
+        sqlx::query("INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Practice', 'therapy', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO templates (id, name, workspace_type, prompt, is_default, created_at, updated_at) VALUES ('t', 'SOAP', 'therapy', 'p', 1, 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f', 'ws', 'Client', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO retention_policies (id, workspace_id, audio_days, created_at, updated_at) VALUES ('p', 'ws', 30, 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        run_pending_migrations(&pool).await.unwrap();
+
+        // Dropping the old workspaces table must not cascade to policies
+        let policies: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM retention_policies")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(policies, 1);
+        let default_template: Option<String> =
+            sqlx::query_scalar("SELECT default_template_id FROM workspace_types WHERE id = 'therapy'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(default_template.as_deref(), Some("t"));
+
+        // Custom types are allowed, unknown ones are not
+        sqlx::query("INSERT INTO workspace_types (id, name, folder_label, created_at, updated_at) VALUES ('coaching', 'Coaching', 'Coachee', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws2', 'Coaching', 'coaching', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(sqlx::query("INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws3', 'Bad', 'missing', 0, 0)")
+            .execute(&pool)
+            .await
+            .is_err());
+
+        // Reverting turns the custom workspace into a general one
+        rollback_to_version(&pool, 8).await.unwrap();
+        let workspace_type: String =
+            sqlx::query_scalar("SELECT workspace_type FROM workspaces WHERE id = 'ws2'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(workspace_type, "general");
+        assert!(!table_exists(&pool, "workspace_types").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_nested_folders_rollback_flattens() {
+        let pool = empty_pool().await;
+        run_pending_migrations(&pool).await.unwrap();
+
+        for sql in [
+            "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Firm', 'legal', 0, 0)",
+            "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('case', 'ws', 'Case', 0, 0)",
+            "INSERT INTO folders (id, workspace_id, parent_id, name, created_at, updated_at) VALUES ('depositions', 'ws', 'case', 'Depositions', 0, 0)",
+            "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES ('s', 'depositions', 'a.wav', 0, 0)",
+            "INSERT INTO retention_policies (id, folder_id, audio_days, created_at, updated_at) VALUES ('p', 'case', 30, 0, 0)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        assert!(sqlx::query("INSERT INTO folders (id, workspace_id, parent_id, name, created_at, updated_at) VALUES ('x', 'ws', 'missing', 'X', 0, 0)")
+            .execute(&pool)
+            .await
+            .is_err());
+
+        // Rebuilding folders must not cascade to its policies
+        rollback_to_version(&pool, 9).await.unwrap();
+        let counts: (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM folders), (SELECT COUNT(*) FROM sessions), (SELECT COUNT(*) FROM retention_policies)",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(counts, (2, 1, 1));
+        assert!(sqlx::query("SELECT parent_id FROM folders")
+            .fetch_all(&pool)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_seeded_metadata_schemas_rollback() {
+        let pool = empty_pool().await;
+        run_pending_migrations(&pool).await.unwrap();
+
+        let schemas: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, metadata_schema FROM workspace_types WHERE metadata_schema IS NOT NULL ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let ids: Vec<&str> = schemas.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["legal", "research", "therapy"]);
+        for (_, schema) in &schemas {
+            crate::services::folder_metadata::MetadataSchema::parse(schema).unwrap();
+        }
+
+        // An edited schema survives the rollback
+        sqlx::query("UPDATE workspace_types SET metadata_schema = '{\"type\":\"object\"}' WHERE id = 'legal'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        rollback_to_version(&pool, 10).await.unwrap();
+        let remaining: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM workspace_types WHERE metadata_schema IS NOT NULL")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(remaining, ["legal"]);
+    }
 }
@@ -1,2 +1,4 @@
 // Database module
 pub mod migrations;
+#[cfg(test)]
+pub mod test_support;
@@ -0,0 +1,34 @@
+//! Database fixtures shared by the test modules
+
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+
+use super::migrations;
+
+/// An empty in-memory database. Limited to one connection, since every
+/// connection to `sqlite::memory:` opens a database of its own.
+pub async fn empty_pool() -> SqlitePool {
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create test pool")
+}
+
+/// An in-memory database with every migration applied
+pub async fn migrated_pool() -> SqlitePool {
+    let pool = empty_pool().await;
+    migrations::run_pending_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
+    pool
+}
+
+/// Run fixture statements in order
+pub async fn seed(pool: &SqlitePool, statements: &[&str]) {
+    for sql in statements {
+        sqlx::query(sql)
+            .execute(pool)
+            .await
+            .expect("Failed to seed test data");
+    }
+}
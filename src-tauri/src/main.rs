@@ -16,15 +16,35 @@ fn main() {
         .setup(|app| {
             // Set app data directory for embeddings service
             if let Ok(app_data_dir) = app.path().app_data_dir() {
+                // A passphrase change cut short leaves the store inconsistent until settled
+                if let Err(e) = services::encryption::recover_rekey(&app_data_dir) {
+                    eprintln!("Failed to recover an interrupted passphrase change: {}", e);
+                }
+                // Decrypted recordings for external tools go here, never to the system temp dir
+                if let Err(e) = services::encryption::init_decrypted_dir(&app_data_dir) {
+                    eprintln!("Failed to set up decrypted audio directory: {}", e);
+                }
                 services::embeddings::set_app_data_dir(app_data_dir);
             }
 
             // Initialize database on startup
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                // An encrypted database stays closed until the user unlocks it
+                if services::encryption::is_enabled(&app_handle) {
+                    println!("[Startup] Database is encrypted, waiting for passphrase");
+                    return;
+                }
+
                 if let Err(e) = services::database::init_database(&app_handle).await {
                     eprintln!("Failed to initialize database: {}", e);
+                    return;
                 }
+
+                // Resume queued background jobs
+                services::jobs::start(app_handle.clone());
+                services::trash::start(app_handle.clone());
+                services::retention::start(app_handle);
             });
             Ok(())
         })
@@ -34,31 +54,84 @@ fn main() {
             commands::workspace::get_workspaces,
             commands::workspace::update_workspace,
             commands::workspace::delete_workspace,
+            // Workspace type commands
+            commands::workspace_type::get_workspace_types,
+            commands::workspace_type::create_workspace_type,
+            commands::workspace_type::update_workspace_type,
+            commands::workspace_type::delete_workspace_type,
             // Folder commands
             commands::folder::create_folder,
             commands::folder::get_folders,
             commands::folder::update_folder,
             commands::folder::delete_folder,
+            commands::folder::move_folder,
+            commands::folder::find_folders_by_metadata,
             // Session commands
             commands::session::create_session,
             commands::session::get_sessions,
+            commands::session::list_sessions,
             commands::session::get_session,
             commands::session::update_session,
+            commands::session::move_session,
             commands::session::delete_session,
             commands::session::search_sessions,
+            commands::session::rename_speaker,
+            commands::tag::get_tags,
+            commands::tag::create_tag,
+            commands::tag::rename_tag,
+            commands::tag::delete_tag,
+            commands::tag::add_session_tag,
+            commands::tag::remove_session_tag,
+            // Trash commands
+            commands::trash::list_trash,
+            commands::trash::restore_session,
+            commands::trash::restore_folder,
+            commands::trash::empty_trash,
+            // Library integrity commands
+            commands::integrity::check_library_integrity,
+            commands::integrity::repair_library,
+            // Backup commands
+            commands::backup::create_backup,
+            commands::backup::restore_backup,
+            // Audit commands
+            commands::audit::query_audit_log,
+            commands::audit::verify_audit_log,
+            // Retention commands
+            commands::retention::get_retention_policies,
+            commands::retention::set_retention_policy,
+            commands::retention::delete_retention_policy,
+            commands::retention::preview_retention,
+            commands::retention::get_retention_log,
             // Template commands
             commands::template::get_templates,
             commands::template::get_template,
+            commands::template::create_template,
+            commands::template::delete_template,
             // Transcription commands
             commands::transcription::transcribe_audio,
             commands::transcription::get_transcription_progress,
+            commands::transcription::get_low_confidence_spans,
+            commands::transcription::cancel_transcription,
             // Generation commands
             commands::generation::generate_note,
             commands::generation::generate_note_streaming,
+            commands::generation::cancel_generation,
             commands::generation::check_ollama_status,
+            // Job queue commands
+            commands::jobs::enqueue_job,
+            commands::jobs::list_jobs,
+            commands::jobs::set_job_priority,
+            commands::jobs::cancel_job,
             // Audio commands
             commands::audio::save_audio_file,
             commands::audio::get_audio_path,
+            commands::audio::read_audio_file,
+            // Encryption commands
+            commands::encryption::get_encryption_status,
+            commands::encryption::unlock_database,
+            commands::encryption::set_encryption_passphrase,
+            commands::encryption::change_encryption_passphrase,
+            commands::encryption::remove_encryption_passphrase,
             // System audio commands
             commands::system_audio::get_audio_devices,
             commands::system_audio::start_system_recording,
@@ -76,11 +149,17 @@ fn main() {
             commands::streaming_transcription::is_streaming_worker_running,
             commands::streaming_transcription::get_streaming_worker_state,
             commands::streaming_transcription::ensure_streaming_worker_running,
+            commands::streaming_transcription::begin_live_session,
+            commands::streaming_transcription::finish_live_session,
+            commands::streaming_transcription::get_interrupted_sessions,
+            commands::streaming_transcription::recover_live_session,
+            commands::streaming_transcription::discard_live_session,
             // Export commands
             commands::export::export_markdown,
             commands::export::export_pdf,
             commands::export::export_docx,
             commands::export::export_to_obsidian,
+            commands::export::export_subtitles,
             // Settings commands
             commands::settings::get_settings,
             commands::settings::update_settings,
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// An entry in the append-only audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Position in the hash chain
+    pub seq: i64,
+    pub id: String,
+    pub occurred_at: i64,
+    /// OS account the app ran under, or "system" for background work
+    pub actor: String,
+    /// "view", "update", "export", "delete", "restore", "purge" or "cloud_llm"
+    pub action: String,
+    /// "session", "folder" or "transcript"
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    /// JSON with action-specific details
+    pub details: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// Filters for listing audit entries; all are optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQuery {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub action: Option<String>,
+    /// Only entries at or after this time
+    pub since: Option<i64>,
+    /// Only entries before this time
+    pub until: Option<i64>,
+    /// Only entries older than this position, for paging
+    pub before_seq: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Result of checking the audit log's hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub entries_checked: i64,
+    /// First entry that does not match the chain
+    pub first_invalid_seq: Option<i64>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Describes a backup archive; stored in it as `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: i64,
+    /// Schema version of the database snapshot
+    pub schema_version: i32,
+    /// The snapshot and recordings are encrypted with the library's passphrase
+    pub encrypted: bool,
+    pub includes_settings: bool,
+    pub session_count: i64,
+    pub files: Vec<BackupFile>,
+}
+
+/// A file in a backup archive with its checksum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// A backup that was written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    pub manifest: BackupManifest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupRequest {
+    pub path: String,
+    /// "merge" adds the backup to the current library, "replace" swaps it in
+    pub mode: String,
+    /// Apply the settings stored in the backup, if it has any
+    pub restore_settings: Option<bool>,
+    /// Required when the backup is encrypted
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    pub mode: String,
+    /// Sessions added (merge) or now in the library (replace)
+    pub sessions_restored: i64,
+    pub audio_files_restored: usize,
+}
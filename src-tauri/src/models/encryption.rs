@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether encryption at rest is on, and whether the store has been unlocked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
@@ -5,11 +5,14 @@ use serde::{Deserialize, Serialize};
 pub struct Folder {
     pub id: String,
     pub workspace_id: String,
+    /// Enclosing folder, `None` at the top level
+    pub parent_id: Option<String>,
     pub name: String,
     pub metadata: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
     pub is_active: bool,
+    /// Sessions in this folder and all its subfolders
     #[serde(default)]
     pub session_count: i64,
 }
@@ -20,6 +23,8 @@ pub struct CreateFolderRequest {
     pub workspace_id: String,
     pub name: String,
     pub metadata: Option<String>,
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Something in the library that is out of step with the rest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    /// "orphaned_audio", "missing_audio", "orphaned_chunks", "stale_chunks"
+    /// or "stale_index"
+    pub kind: String,
+    pub session_id: Option<String>,
+    /// The audio file concerned, for audio issues
+    pub path: Option<String>,
+    /// What is wrong, for display
+    pub description: String,
+    /// What repairing will do, for display
+    pub repair: String,
+}
+
+/// Result of checking the library for inconsistencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub checked_at: i64,
+    pub sessions_checked: i64,
+    pub audio_files_checked: i64,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// What a library repair did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    pub repaired: Vec<IntegrityIssue>,
+    /// Reviewed issues that were no longer found, or would now be repaired
+    /// differently, and so were left alone
+    pub skipped: i64,
+    /// Sessions whose transcripts need indexing again
+    pub reindex: Vec<String>,
+    pub errors: Vec<String>,
+}
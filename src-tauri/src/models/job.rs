@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// A unit of background work on a session, stored in the `jobs` table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    pub session_id: String,
+    /// transcribe, generate or index
+    pub kind: String,
+    /// queued, running, completed, failed or cancelled
+    pub status: String,
+    pub priority: i64,
+    pub attempts: i64,
+    pub max_attempts: i64,
+    pub template_id: Option<String>,
+    pub chain: bool,
+    /// Percentage between 0 and 100
+    pub progress: f64,
+    pub error: Option<String>,
+    pub run_after: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnqueueJobRequest {
+    pub session_id: String,
+    pub kind: String,
+    pub template_id: Option<String>,
+    /// Run the following steps once this job finishes
+    pub chain: Option<bool>,
+    pub priority: Option<i64>,
+}
@@ -1,11 +1,33 @@
+mod audit;
+mod backup;
+mod encryption;
 mod folder;
+mod integrity;
+mod job;
+mod retention;
+mod search;
 mod session;
 mod settings;
+mod tag;
 mod template;
+mod transcript;
+mod trash;
 mod workspace;
+mod workspace_type;
 
+pub use audit::*;
+pub use backup::*;
+pub use encryption::*;
 pub use folder::*;
+pub use integrity::*;
+pub use job::*;
+pub use retention::*;
+pub use search::*;
 pub use session::*;
 pub use settings::*;
+pub use tag::*;
 pub use template::*;
+pub use transcript::*;
+pub use trash::*;
 pub use workspace::*;
+pub use workspace_type::*;
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// How long each kind of session data is kept in a workspace or folder.
+/// Limits are days after the session was recorded; None keeps the data
+/// indefinitely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub id: String,
+    /// Set for a workspace policy
+    pub workspace_id: Option<String>,
+    /// Set for a folder policy, which overrides its workspace's
+    pub folder_id: Option<String>,
+    pub audio_days: Option<i64>,
+    pub transcript_days: Option<i64>,
+    pub note_days: Option<i64>,
+    pub chunks_days: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Create or replace the policy for a workspace or a folder (exactly one)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRetentionPolicyRequest {
+    pub workspace_id: Option<String>,
+    pub folder_id: Option<String>,
+    pub audio_days: Option<i64>,
+    pub transcript_days: Option<i64>,
+    pub note_days: Option<i64>,
+    pub chunks_days: Option<i64>,
+}
+
+/// Session data that is due to be purged under a policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidate {
+    pub session_id: String,
+    pub session_title: Option<String>,
+    pub folder_id: String,
+    pub workspace_id: String,
+    pub policy_id: String,
+    /// "audio", "transcript", "note" or "chunks"
+    pub data_type: String,
+    pub retention_days: i64,
+    pub session_created_at: i64,
+    /// When the data was finished; its age counts from here
+    pub anchored_at: i64,
+    /// When the data became due for purging
+    pub due_at: i64,
+}
+
+/// A purge carried out under a retention policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionLogEntry {
+    pub id: String,
+    pub session_id: String,
+    pub folder_id: String,
+    pub workspace_id: String,
+    pub policy_id: String,
+    pub data_type: String,
+    pub retention_days: i64,
+    pub session_created_at: i64,
+    pub purged_at: i64,
+}
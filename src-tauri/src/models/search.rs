@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use super::Session;
+
+/// A session matching a full-text search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchResult {
+    #[serde(flatten)]
+    pub session: Session,
+    /// Relevance from bm25; higher is better
+    pub score: f64,
+    /// Fields containing a match: `title`, `transcript` and/or `note`
+    pub matched_fields: Vec<String>,
+    /// Highlighted context for each matched field
+    pub snippets: Vec<SearchSnippet>,
+}
+
+/// Context around a match in one field
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSnippet {
+    pub field: String,
+    pub parts: Vec<SnippetPart>,
+}
+
+/// A run of snippet text, highlighted when it matched the query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlighted: bool,
+}
@@ -17,6 +17,84 @@ pub struct Session {
     pub error_message: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Names of the session's tags, alphabetically
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A session for lists: everything but the transcript, segments and note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub id: String,
+    pub folder_id: String,
+    pub title: Option<String>,
+    pub audio_duration: Option<i64>,
+    pub template_id: Option<String>,
+    pub status: String,
+    pub error_message: Option<String>,
+    /// The start of the transcript, if there is one
+    pub transcript_preview: Option<String>,
+    pub has_note: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Names of the session's tags, alphabetically
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Which of a folder's sessions to list, and in what order. Filters are
+/// optional and combine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionListQuery {
+    pub folder_id: String,
+    /// "created" (the default), "duration", "title" or "status"
+    pub sort: Option<String>,
+    /// "asc" or "desc"; newest, longest and A to Z come first by default
+    pub order: Option<String>,
+    pub status: Option<String>,
+    /// Only sessions created at or after this time
+    pub since: Option<i64>,
+    /// Only sessions created before this time
+    pub until: Option<i64>,
+    pub template_id: Option<String>,
+    /// Only sessions with (true) or without (false) a generated note
+    pub has_note: Option<bool>,
+    /// Only sessions carrying all of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// One page of a session listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPage {
+    pub sessions: Vec<SessionSummary>,
+    /// Pass back as `cursor` for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// A live-transcribed session whose recording was never saved, because the
+/// app or the worker stopped mid-recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedSession {
+    pub id: String,
+    pub folder_id: String,
+    pub title: Option<String>,
+    pub started_at: i64,
+    /// Confirmed segments saved before the interruption
+    pub segment_count: i64,
+    /// End of the last saved segment, in seconds from the start of recording
+    pub transcribed_seconds: f64,
+    /// The start of the saved transcript
+    pub transcript_preview: Option<String>,
+    /// Path of a partial recording found in the audio folder, if any
+    pub audio_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,4 +122,10 @@ pub struct TranscriptionProgress {
     pub session_id: String,
     pub progress: f32,
     pub status: String,
+    #[serde(default)]
+    pub processed_seconds: Option<f64>,
+    #[serde(default)]
+    pub total_seconds: Option<f64>,
+    #[serde(default)]
+    pub eta_seconds: Option<f64>,
 }
@@ -1,3 +1,5 @@
+use crate::services::transcription_backend::TranscriptionBackendKind;
+use crate::services::trash::DEFAULT_RETENTION_DAYS;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +19,10 @@ pub struct AppSettings {
     // Bundled model settings
     pub bundled_whisper_model: Option<String>,
     pub bundled_llm_model: Option<String>,
+    // Transcription engine: "whisperkit" | "whispercpp"
+    pub transcription_backend: String,
+    /// Days a deleted session or folder stays in the trash
+    pub trash_retention_days: i64,
 }
 
 impl Default for AppSettings {
@@ -35,6 +41,10 @@ impl Default for AppSettings {
             auto_save: true,
             bundled_whisper_model: Some("whisper-base".to_string()),
             bundled_llm_model: Some("llama-3.2-3b".to_string()),
+            transcription_backend: TranscriptionBackendKind::platform_default()
+                .as_str()
+                .to_string(),
+            trash_retention_days: DEFAULT_RETENTION_DAYS,
         }
     }
 }
@@ -55,6 +65,8 @@ pub struct UpdateSettingsRequest {
     pub auto_save: Option<bool>,
     pub bundled_whisper_model: Option<String>,
     pub bundled_llm_model: Option<String>,
+    pub transcription_backend: Option<String>,
+    pub trash_retention_days: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
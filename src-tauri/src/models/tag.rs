@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A label for sessions, such as "intake" or "needs review", shared by all
+/// workspaces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    /// Live sessions carrying the tag
+    #[serde(default)]
+    pub session_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
@@ -14,3 +14,14 @@ pub struct Template {
     pub created_at: i64,
     pub updated_at: i64,
 }
+
+/// A user-written template; built-in ones come from `templates::insert_default_templates`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    pub workspace_type: String,
+    pub description: Option<String>,
+    pub prompt: String,
+    pub output_format: Option<String>,
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// A timestamped span of a transcript, stored as JSON in `sessions.transcript_segments`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    /// Start time in seconds from the beginning of the audio
+    pub start: f64,
+    /// End time in seconds from the beginning of the audio
+    pub end: f64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Average token log-probability reported by the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f32>,
+    /// Word-level timings, when the backend produced them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<TranscriptWord>>,
+}
+
+/// A single word with its timing and model confidence
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptWord {
+    pub text: String,
+    /// Start time in seconds from the beginning of the audio
+    pub start: f64,
+    /// End time in seconds from the beginning of the audio
+    pub end: f64,
+    /// Model confidence between 0 and 1
+    pub probability: f32,
+}
+
+/// Join segment texts into the flat transcript stored in `sessions.transcript`.
+/// When more than one speaker is labelled, each speaker turn starts a new
+/// `Speaker: text` paragraph so note generation knows who said what.
+pub fn transcript_from_segments(segments: &[TranscriptSegment]) -> String {
+    let mut speakers: Vec<&str> = segments.iter().filter_map(|s| s.speaker.as_deref()).collect();
+    speakers.sort_unstable();
+    speakers.dedup();
+
+    if speakers.len() <= 1 {
+        return segments
+            .iter()
+            .map(|s| s.text.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
+    let mut turns: Vec<(Option<&str>, Vec<&str>)> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((speaker, texts)) if *speaker == segment.speaker.as_deref() => texts.push(text),
+            _ => turns.push((segment.speaker.as_deref(), vec![text])),
+        }
+    }
+
+    turns
+        .into_iter()
+        .map(|(speaker, texts)| match speaker {
+            Some(speaker) => format!("{}: {}", speaker, texts.join(" ")),
+            None => texts.join(" "),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// A run of consecutive words the model was unsure about
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LowConfidenceSpan {
+    /// Index into the session's transcript segments
+    pub segment_index: usize,
+    /// Index of the first word in the segment (None when the segment has no word timings)
+    pub word_start_index: Option<usize>,
+    /// Index one past the last word in the segment
+    pub word_end_index: Option<usize>,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    /// Lowest probability within the span
+    pub min_probability: f32,
+}
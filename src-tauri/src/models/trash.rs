@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// A trashed session or folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    /// "session" or "folder"
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    pub workspace_id: String,
+    /// Folder a trashed session belongs to
+    pub folder_id: Option<String>,
+    pub folder_name: Option<String>,
+    /// Sessions that go with a trashed folder
+    pub session_count: i64,
+    pub deleted_at: i64,
+    /// When the item will be permanently deleted
+    pub purge_at: i64,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// A kind of workspace: the four built-in ones or one a team added
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceType {
+    /// Slug stored in `Workspace::workspace_type` and `Template::workspace_type`
+    pub id: String,
+    pub name: String,
+    /// What a folder is called in this type, singular: "Client", "Case"
+    pub folder_label: String,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub default_template_id: Option<String>,
+    /// JSON schema for folder metadata
+    pub metadata_schema: Option<String>,
+    pub is_system: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkspaceTypeRequest {
+    pub name: String,
+    pub folder_label: String,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub default_template_id: Option<String>,
+    pub metadata_schema: Option<String>,
+}
+
+/// Fields left as None are unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceTypeRequest {
+    pub id: String,
+    pub name: Option<String>,
+    pub folder_label: Option<String>,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub default_template_id: Option<String>,
+    pub metadata_schema: Option<String>,
+}
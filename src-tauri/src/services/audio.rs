@@ -1,22 +1,25 @@
 use anyhow::{anyhow, Result};
 use rubato::{FftFixedIn, Resampler};
-use std::path::PathBuf;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use symphonia::core::audio::SampleBuffer;
 use symphonia::core::codecs::DecoderOptions;
 use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use tauri::AppHandle;
 use tauri::Manager;
 
+use super::encryption;
+
 // Target sample rate for Whisper (16kHz mono)
-// These are used by decode_audio_to_whisper_format which will be called by WhisperKit
+// These are used by decode_audio_to_whisper_format for the whisper.cpp backend
 #[allow(dead_code)]
 const TARGET_SAMPLE_RATE: u32 = 16000;
 
 /// Get the audio directory for storing recordings
-fn get_audio_dir(app: &AppHandle) -> Result<PathBuf> {
+pub(crate) fn get_audio_dir(app: &AppHandle) -> Result<PathBuf> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -45,7 +48,11 @@ pub async fn save_audio_file(
     let file_path = audio_dir.join(&filename);
     println!("[Audio]   file_path: {:?}", file_path);
 
-    tokio::fs::write(&file_path, audio_data).await?;
+    // Encrypted when a passphrase is set, under keys that can't change meanwhile
+    {
+        let _lock = encryption::REKEY_LOCK.read().await;
+        encryption::write_audio(&file_path, audio_data)?;
+    }
 
     let saved_path = file_path.to_string_lossy().to_string();
     println!("[Audio]   saved to: {}", saved_path);
@@ -73,24 +80,131 @@ pub async fn get_audio_path(app: &AppHandle, session_id: &str) -> Result<String>
     Err(anyhow!("Audio file not found for session {}", session_id))
 }
 
+/// Open an audio file for Symphonia. Encrypted recordings are decrypted into memory.
+fn open_media_source(audio_path: &str) -> Result<MediaSourceStream> {
+    let path = Path::new(audio_path);
+    let source: Box<dyn MediaSource> = if encryption::is_encrypted_file(path)
+        .map_err(|e| anyhow!("Failed to open audio file: {}", e))?
+    {
+        Box::new(Cursor::new(encryption::read_audio(path)?))
+    } else {
+        Box::new(
+            std::fs::File::open(path).map_err(|e| anyhow!("Failed to open audio file: {}", e))?,
+        )
+    };
+    Ok(MediaSourceStream::new(source, Default::default()))
+}
+
+/// Get the duration of an audio file in seconds from its container metadata,
+/// without decoding the audio
+pub fn probe_duration_secs(audio_path: &str) -> Result<f64> {
+    let mss = open_media_source(audio_path)?;
+
+    let mut hint = Hint::new();
+    if let Some(ext) = std::path::Path::new(audio_path)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| anyhow!("Failed to probe audio format: {}", e))?;
+
+    let track = probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow!("No audio track found"))?;
+
+    let params = &track.codec_params;
+    let n_frames = params
+        .n_frames
+        .ok_or_else(|| anyhow!("Audio duration unknown"))?;
+
+    if let Some(time_base) = params.time_base {
+        let time = time_base.calc_time(n_frames);
+        return Ok(time.seconds as f64 + time.frac);
+    }
+
+    let sample_rate = params
+        .sample_rate
+        .ok_or_else(|| anyhow!("Audio sample rate unknown"))?;
+    Ok(n_frames as f64 / sample_rate as f64)
+}
+
+/// Fix the sizes in the header of a WAV file whose writer never finished it,
+/// e.g. a recording cut short by a crash, so the audio written so far can be
+/// played. Anything that isn't a plain WAV file is left alone. Returns
+/// whether the file was changed.
+pub fn repair_wav_header(path: &Path) -> Result<bool> {
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    let len = file.metadata()?.len();
+    let mut header = [0u8; 12];
+    if len < 12 || file.read_exact(&mut header).is_err() {
+        return Ok(false);
+    }
+    if &header[..4] != b"RIFF" || &header[8..] != b"WAVE" {
+        return Ok(false);
+    }
+
+    let mut offset = 12u64;
+    let mut block_align = 1u64;
+    while offset + 8 <= len {
+        let mut chunk = [0u8; 8];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut chunk)?;
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+
+        if &chunk[..4] == b"fmt " && size >= 16 {
+            let mut fmt = [0u8; 16];
+            file.read_exact(&mut fmt)?;
+            block_align = u16::from_le_bytes([fmt[12], fmt[13]]).max(1) as u64;
+        } else if &chunk[..4] == b"data" {
+            let available = len - offset - 8;
+            // A size that fits was written by a writer that finished
+            if size != 0 && size <= available {
+                return Ok(false);
+            }
+            // Drop a partly written frame at the end
+            let data_len = (available - available % block_align).min(u32::MAX as u64);
+            let end = offset + 8 + data_len;
+            file.seek(SeekFrom::Start(4))?;
+            file.write_all(&((end - 8) as u32).to_le_bytes())?;
+            file.seek(SeekFrom::Start(offset + 4))?;
+            file.write_all(&(data_len as u32).to_le_bytes())?;
+            file.set_len(end)?;
+            file.sync_all()?;
+            return Ok(true);
+        }
+        offset += 8 + size + size % 2;
+    }
+    Ok(false)
+}
+
 /// Decode any audio file and convert to f32 samples at 16kHz mono
 /// This replaces ffmpeg for audio conversion - pure Rust implementation
-/// Currently used by WhisperKit subprocess for audio preprocessing
+/// Used by the whisper.cpp backend (unused on macOS, where WhisperKit decodes itself)
 #[allow(dead_code)]
 pub fn decode_audio_to_whisper_format(audio_path: &str) -> Result<Vec<f32>> {
     println!("[Audio] decode_audio_to_whisper_format() called for: {}", audio_path);
 
-    let file = std::fs::File::open(audio_path)
-        .map_err(|e| {
-            println!("[Audio] ERROR: Failed to open audio file: {}", e);
-            anyhow!("Failed to open audio file: {}", e)
-        })?;
+    // Decrypts encrypted recordings transparently
+    let mss = open_media_source(audio_path).map_err(|e| {
+        println!("[Audio] ERROR: {}", e);
+        e
+    })?;
 
-    let file_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let file_size = mss.byte_len().unwrap_or(0);
     println!("[Audio] File opened, size: {} bytes", file_size);
 
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
-
     // Create a hint to help format detection
     let mut hint = Hint::new();
     if let Some(ext) = std::path::Path::new(audio_path).extension() {
@@ -236,6 +350,12 @@ pub fn decode_audio_to_whisper_format(audio_path: &str) -> Result<Vec<f32>> {
     Ok(all_samples)
 }
 
+/// Duration in seconds of samples from `decode_audio_to_whisper_format`
+#[allow(dead_code)]
+pub fn whisper_samples_duration_secs(samples: &[f32]) -> f64 {
+    samples.len() as f64 / TARGET_SAMPLE_RATE as f64
+}
+
 /// Resample audio using rubato (high quality resampling)
 #[allow(dead_code)]
 fn resample_audio(samples: &[f32], source_rate: usize, target_rate: usize) -> Result<Vec<f32>> {
@@ -318,6 +438,78 @@ mod tests {
         assert!(error.contains("Failed to open audio file"));
     }
 
+    #[test]
+    fn test_probe_duration_of_wav() {
+        // 1.5 seconds of 16-bit mono silence at 16kHz
+        let sample_rate = 16000u32;
+        let samples = 24000u32;
+        let data_len = samples * 2;
+
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(wav.len() + data_len as usize, 0);
+
+        let temp_path = std::env::temp_dir().join("probe_duration_test.wav");
+        std::fs::write(&temp_path, &wav).unwrap();
+
+        let duration = probe_duration_secs(temp_path.to_str().unwrap()).unwrap();
+        assert!((duration - 1.5).abs() < 0.01, "Expected 1.5s, got {}", duration);
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_repair_wav_header_of_unfinished_recording() {
+        // Header as written when recording starts: sizes not filled in yet
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&0u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&32000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&0u32.to_le_bytes());
+        // 1 second of audio and half a sample
+        wav.resize(wav.len() + 32001, 0);
+
+        let temp_path = std::env::temp_dir().join("repair_wav_header_test.wav");
+        std::fs::write(&temp_path, &wav).unwrap();
+
+        assert!(repair_wav_header(&temp_path).unwrap());
+        let repaired = std::fs::read(&temp_path).unwrap();
+        assert_eq!(repaired.len(), 44 + 32000);
+        assert_eq!(&repaired[4..8], &(36 + 32000u32).to_le_bytes());
+        assert_eq!(&repaired[40..44], &32000u32.to_le_bytes());
+        let duration = probe_duration_secs(temp_path.to_str().unwrap()).unwrap();
+        assert!((duration - 1.0).abs() < 0.01, "Expected 1s, got {}", duration);
+
+        // A finished file is left as it is
+        assert!(!repair_wav_header(&temp_path).unwrap());
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_probe_duration_nonexistent_file() {
+        assert!(probe_duration_secs("/nonexistent/path/audio.wav").is_err());
+    }
+
     #[tokio::test]
     async fn test_get_audio_path_nonexistent() {
         // Create a temporary mock that would fail
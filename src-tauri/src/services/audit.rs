@@ -0,0 +1,502 @@
+//! Tamper-evident audit log
+//!
+//! Records who viewed, changed, exported or deleted session data, and when
+//! session content was sent to a cloud LLM. Entries are only ever appended
+//! (triggers in migration 008 reject updates and deletes), and each entry's
+//! hash covers its fields and the previous entry's hash, so editing or
+//! removing an entry breaks the chain from that point on. Verification can't
+//! detect entries cut from the end of the log.
+//!
+//! Changes are recorded in the same transaction as the change itself (see
+//! [`record_in`]), so one is never committed without the other.
+//!
+//! Details never include transcript or note content.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnection, SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::models::{AuditEntry, AuditLogQuery, AuditVerification};
+
+/// `prev_hash` of the first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Actor for work the app does on its own, like purges
+pub const SYSTEM_ACTOR: &str = "system";
+const DEFAULT_QUERY_LIMIT: i64 = 200;
+
+/// Appends read the chain head and write after it, so they run one at a time
+static APPEND_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// Permission to append, from [`lock`]. Take it before beginning a
+/// transaction that records with [`record_in`], so that no other append can
+/// wait on the transaction while it waits on the lock.
+pub struct AppendGuard {
+    _lock: tokio::sync::MutexGuard<'static, ()>,
+}
+
+/// Wait for any other append to finish
+pub async fn lock() -> AppendGuard {
+    AppendGuard {
+        _lock: APPEND_LOCK.lock().await,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    View,
+    Update,
+    Export,
+    Delete,
+    Restore,
+    Purge,
+    CloudLlm,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::View => "view",
+            AuditAction::Update => "update",
+            AuditAction::Export => "export",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::CloudLlm => "cloud_llm",
+        }
+    }
+}
+
+/// Something to record. Build with [`AuditEvent::session`] or
+/// [`AuditEvent::new`], then add details.
+#[derive(Debug, Clone)]
+pub struct AuditEvent<'a> {
+    pub action: AuditAction,
+    pub entity_type: &'a str,
+    pub entity_id: Option<&'a str>,
+    pub details: Option<Value>,
+}
+
+impl<'a> AuditEvent<'a> {
+    pub fn new(action: AuditAction, entity_type: &'a str, entity_id: Option<&'a str>) -> Self {
+        Self {
+            action,
+            entity_type,
+            entity_id,
+            details: None,
+        }
+    }
+
+    pub fn session(action: AuditAction, id: &'a str) -> Self {
+        Self::new(action, "session", Some(id))
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// The OS account the app is running under
+pub fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|user| !user.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Hash of an entry's fields chained to the previous entry. Fields are
+/// JSON-encoded together so no two different entries hash the same input.
+#[allow(clippy::too_many_arguments)]
+fn entry_hash(
+    prev_hash: &str,
+    id: &str,
+    occurred_at: i64,
+    actor: &str,
+    action: &str,
+    entity_type: &str,
+    entity_id: Option<&str>,
+    details: Option<&str>,
+) -> String {
+    let fields = serde_json::json!([
+        prev_hash,
+        id,
+        occurred_at,
+        actor,
+        action,
+        entity_type,
+        entity_id,
+        details
+    ]);
+    Sha256::digest(fields.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn entry_from_row(row: SqliteRow) -> AuditEntry {
+    AuditEntry {
+        seq: row.get("seq"),
+        id: row.get("id"),
+        occurred_at: row.get("occurred_at"),
+        actor: row.get("actor"),
+        action: row.get("action"),
+        entity_type: row.get("entity_type"),
+        entity_id: row.get("entity_id"),
+        details: row.get("details"),
+        prev_hash: row.get("prev_hash"),
+        hash: row.get("hash"),
+    }
+}
+
+const ENTRY_COLUMNS: &str =
+    "seq, id, occurred_at, actor, action, entity_type, entity_id, details, prev_hash, hash";
+
+/// Append an entry at `occurred_at` on behalf of `actor`, inside the caller's
+/// transaction so it commits or rolls back with the change it records
+async fn append(
+    conn: &mut SqliteConnection,
+    _guard: &AppendGuard,
+    actor: &str,
+    event: AuditEvent<'_>,
+    occurred_at: i64,
+) -> Result<AuditEntry> {
+    let prev_hash: String =
+        sqlx::query_scalar("SELECT hash FROM audit_log ORDER BY seq DESC LIMIT 1")
+            .fetch_optional(&mut *conn)
+            .await?
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+    let id = Uuid::new_v4().to_string();
+    let action = event.action.as_str();
+    let details = event.details.as_ref().map(Value::to_string);
+    let hash = entry_hash(
+        &prev_hash,
+        &id,
+        occurred_at,
+        actor,
+        action,
+        event.entity_type,
+        event.entity_id,
+        details.as_deref(),
+    );
+
+    let row = sqlx::query(&format!(
+        r#"
+        INSERT INTO audit_log (id, occurred_at, actor, action, entity_type, entity_id, details, prev_hash, hash)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING {}
+        "#,
+        ENTRY_COLUMNS
+    ))
+    .bind(&id)
+    .bind(occurred_at)
+    .bind(actor)
+    .bind(action)
+    .bind(event.entity_type)
+    .bind(event.entity_id)
+    .bind(&details)
+    .bind(&prev_hash)
+    .bind(&hash)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(entry_from_row(row))
+}
+
+/// Append an entry at `occurred_at` on behalf of `actor`, in a transaction of its own
+pub async fn record_at(
+    pool: &SqlitePool,
+    actor: &str,
+    event: AuditEvent<'_>,
+    occurred_at: i64,
+) -> Result<AuditEntry> {
+    let guard = lock().await;
+    let mut tx = pool.begin().await?;
+    let entry = append(&mut *tx, &guard, actor, event, occurred_at).await?;
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Append an entry for the person using the app
+pub async fn record(pool: &SqlitePool, event: AuditEvent<'_>) -> Result<AuditEntry> {
+    record_at(pool, &local_user(), event, chrono::Utc::now().timestamp()).await
+}
+
+/// Append an entry for work the app did on its own
+pub async fn record_system(pool: &SqlitePool, event: AuditEvent<'_>) -> Result<AuditEntry> {
+    record_at(pool, SYSTEM_ACTOR, event, chrono::Utc::now().timestamp()).await
+}
+
+/// Append an entry for the person using the app inside the caller's transaction
+pub async fn record_in(
+    conn: &mut SqliteConnection,
+    guard: &AppendGuard,
+    event: AuditEvent<'_>,
+) -> Result<AuditEntry> {
+    let now = chrono::Utc::now().timestamp();
+    append(conn, guard, &local_user(), event, now).await
+}
+
+/// Append an entry for work the app did on its own inside the caller's transaction
+pub async fn record_system_in(
+    conn: &mut SqliteConnection,
+    guard: &AppendGuard,
+    event: AuditEvent<'_>,
+) -> Result<AuditEntry> {
+    let now = chrono::Utc::now().timestamp();
+    append(conn, guard, SYSTEM_ACTOR, event, now).await
+}
+
+/// Entries matching `filter`, newest first
+pub async fn query(pool: &SqlitePool, filter: &AuditLogQuery) -> Result<Vec<AuditEntry>> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {}
+        FROM audit_log
+        WHERE (?1 IS NULL OR entity_type = ?1)
+          AND (?2 IS NULL OR entity_id = ?2)
+          AND (?3 IS NULL OR action = ?3)
+          AND (?4 IS NULL OR occurred_at >= ?4)
+          AND (?5 IS NULL OR occurred_at < ?5)
+          AND (?6 IS NULL OR seq < ?6)
+        ORDER BY seq DESC
+        LIMIT ?7
+        "#,
+        ENTRY_COLUMNS
+    ))
+    .bind(&filter.entity_type)
+    .bind(&filter.entity_id)
+    .bind(&filter.action)
+    .bind(filter.since)
+    .bind(filter.until)
+    .bind(filter.before_seq)
+    .bind(filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT).max(1))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(entry_from_row).collect())
+}
+
+/// Walk the whole chain, recomputing every hash
+pub async fn verify(pool: &SqlitePool) -> Result<AuditVerification> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM audit_log ORDER BY seq",
+        ENTRY_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut checked = 0;
+    for entry in rows.into_iter().map(entry_from_row) {
+        let computed = entry_hash(
+            &entry.prev_hash,
+            &entry.id,
+            entry.occurred_at,
+            &entry.actor,
+            &entry.action,
+            &entry.entity_type,
+            entry.entity_id.as_deref(),
+            entry.details.as_deref(),
+        );
+
+        let error = if entry.prev_hash != expected_prev {
+            Some("does not follow the previous entry; entries were removed or reordered")
+        } else if entry.hash != computed {
+            Some("does not match its hash; the entry was modified")
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            return Ok(AuditVerification {
+                valid: false,
+                entries_checked: checked,
+                first_invalid_seq: Some(entry.seq),
+                error: Some(format!("Entry {} {}", entry.seq, error)),
+            });
+        }
+
+        expected_prev = entry.hash;
+        checked += 1;
+    }
+
+    Ok(AuditVerification {
+        valid: true,
+        entries_checked: checked,
+        first_invalid_seq: None,
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::migrated_pool;
+
+    async fn seed(pool: &SqlitePool) {
+        record_at(
+            pool,
+            "alice",
+            AuditEvent::session(AuditAction::View, "s1"),
+            10,
+        )
+        .await
+        .unwrap();
+        record_at(
+            pool,
+            "alice",
+            AuditEvent::session(AuditAction::Export, "s1")
+                .with_details(serde_json::json!({ "format": "pdf" })),
+            20,
+        )
+        .await
+        .unwrap();
+        record_at(
+            pool,
+            SYSTEM_ACTOR,
+            AuditEvent::session(AuditAction::Purge, "s2"),
+            30,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_chained() {
+        let pool = migrated_pool().await;
+        seed(&pool).await;
+
+        let entries = query(&pool, &AuditLogQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[2].hash);
+        assert_eq!(entries[0].prev_hash, entries[1].hash);
+        assert_eq!(entries[1].details.as_deref(), Some(r#"{"format":"pdf"}"#));
+
+        let verification = verify(&pool).await.unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.entries_checked, 3);
+    }
+
+    #[tokio::test]
+    async fn test_query_filters() {
+        let pool = migrated_pool().await;
+        seed(&pool).await;
+
+        let by_session = query(
+            &pool,
+            &AuditLogQuery {
+                entity_id: Some("s1".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(by_session.len(), 2);
+
+        let exports = query(
+            &pool,
+            &AuditLogQuery {
+                action: Some("export".to_string()),
+                since: Some(20),
+                until: Some(30),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(exports.len(), 1);
+
+        let page = query(
+            &pool,
+            &AuditLogQuery {
+                before_seq: Some(by_session[0].seq),
+                limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].seq, by_session[1].seq);
+    }
+
+    #[tokio::test]
+    async fn test_entries_roll_back_with_their_transaction() {
+        let pool = migrated_pool().await;
+        seed(&pool).await;
+
+        let guard = lock().await;
+        let mut tx = pool.begin().await.unwrap();
+        record_in(
+            &mut *tx,
+            &guard,
+            AuditEvent::session(AuditAction::Delete, "s1"),
+        )
+        .await
+        .unwrap();
+        tx.rollback().await.unwrap();
+        drop(guard);
+
+        let entries = query(&pool, &AuditLogQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(verify(&pool).await.unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_log_is_append_only() {
+        let pool = migrated_pool().await;
+        seed(&pool).await;
+
+        assert!(sqlx::query("UPDATE audit_log SET actor = 'mallory'")
+            .execute(&pool)
+            .await
+            .is_err());
+        assert!(sqlx::query("DELETE FROM audit_log")
+            .execute(&pool)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_tampering() {
+        let pool = migrated_pool().await;
+        seed(&pool).await;
+
+        // Someone with direct file access drops the triggers and edits an entry
+        sqlx::query("DROP TRIGGER audit_log_no_update")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE audit_log SET actor = 'mallory' WHERE seq = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let verification = verify(&pool).await.unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.entries_checked, 1);
+        assert_eq!(verification.first_invalid_seq, Some(2));
+
+        // Removing an entry from the middle breaks the link after it
+        let pool = migrated_pool().await;
+        seed(&pool).await;
+        sqlx::query("DROP TRIGGER audit_log_no_delete")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM audit_log WHERE seq = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let verification = verify(&pool).await.unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.first_invalid_seq, Some(3));
+    }
+}
@@ -0,0 +1,1188 @@
+//! Library backup and restore
+//!
+//! A backup is a zip archive holding a `manifest.json`, a snapshot of the
+//! database taken with SQLite's online backup API, every recording under
+//! `audio/` and, for an encrypted library, its `encryption.json`. Files are
+//! archived as they are on disk, so an encrypted library gives an encrypted
+//! backup that needs its passphrase to restore. The manifest lists a SHA-256
+//! checksum for every file, and all of them are checked before anything is
+//! restored.
+
+use anyhow::{anyhow, Result};
+use libsqlite3_sys as ffi;
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use sqlx::{ConnectOptions, Connection, Row};
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::audit::{self, AppendGuard, AuditAction, AuditEvent};
+use super::encryption::{self, EncryptionConfig, Keys};
+use super::{audio, database, export, jobs};
+use crate::db::migrations;
+use crate::models::{
+    BackupFile, BackupInfo, BackupManifest, RestoreBackupRequest, RestoreSummary,
+};
+
+/// Bumped when the archive layout changes
+const BACKUP_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const DATABASE_FILE: &str = "library.db";
+const ENCRYPTION_FILE: &str = "encryption.json";
+const AUDIO_DIR: &str = "audio";
+
+/// Tables copied when merging, parents before children. Workspace types and
+/// templates refer to each other, so foreign keys are checked at commit.
+/// Jobs are not restored.
+const MERGE_TABLES: [&str; 13] = [
+    "workspace_types",
+    "workspaces",
+    "templates",
+    "folders",
+    "retention_policies",
+    "sessions",
+    "transcript_chunks",
+    "session_indexing_status",
+    "chat_conversations",
+    "chat_messages",
+    "retention_log",
+    "tags",
+    "session_tags",
+];
+
+/// How a backup is brought into the library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Add the backup's rows; rows whose ids already exist are kept
+    Merge,
+    /// Swap the whole library for the backup
+    Replace,
+}
+
+impl RestoreMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "merge" => Ok(RestoreMode::Merge),
+            "replace" => Ok(RestoreMode::Replace),
+            other => Err(anyhow!("Unknown restore mode: {}", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RestoreMode::Merge => "merge",
+            RestoreMode::Replace => "replace",
+        }
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// A scratch directory in the app data directory, removed on drop
+struct StagingDir(PathBuf);
+
+impl StagingDir {
+    fn new(parent: &Path, purpose: &str) -> Result<Self> {
+        let dir = parent.join(format!("{}-{}", purpose, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self(dir))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| anyhow!("Failed to get app data dir: {}", e))
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| anyhow!("Path is not valid UTF-8: {:?}", path))
+}
+
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| anyhow!("Backup task failed: {}", e))?
+}
+
+// ============================================================================
+// Database snapshot
+// ============================================================================
+
+fn sqlite_error(rc: i32) -> String {
+    // SAFETY: sqlite3_errstr returns a static, NUL-terminated string
+    unsafe { CStr::from_ptr(ffi::sqlite3_errstr(rc)) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Copy the database to `dest` with SQLite's online backup API, which gives a
+/// consistent snapshot while the app keeps running. The copy uses the same
+/// keys, as SQLCipher requires.
+pub async fn snapshot_database(pool: &SqlitePool, dest: &Path, keys: Option<&Keys>) -> Result<()> {
+    let mut source = pool.acquire().await?;
+    let mut target = database::connect_options(path_str(dest)?, keys)?
+        .connect()
+        .await?;
+
+    {
+        let mut source_handle = source.lock_handle().await?;
+        let mut target_handle = target.lock_handle().await?;
+        let main = c"main";
+
+        // SAFETY: both handles are locked away from their worker threads until
+        // the guards drop, and the backup object is finished before that
+        let rc = unsafe {
+            let backup = ffi::sqlite3_backup_init(
+                target_handle.as_raw_handle().as_ptr(),
+                main.as_ptr(),
+                source_handle.as_raw_handle().as_ptr(),
+                main.as_ptr(),
+            );
+            if backup.is_null() {
+                return Err(anyhow!("Failed to start database backup"));
+            }
+            let rc = ffi::sqlite3_backup_step(backup, -1);
+            ffi::sqlite3_backup_finish(backup);
+            rc
+        };
+        if rc != ffi::SQLITE_DONE {
+            return Err(anyhow!("Database backup failed: {}", sqlite_error(rc)));
+        }
+    }
+
+    target.close().await?;
+    Ok(())
+}
+
+/// Point each session's audio path at its file in the archive
+/// (`audio/<name>`) and list the recordings to archive: everything in the
+/// audio directory, plus recordings stored elsewhere, prefixed with their
+/// session id so names stay unique. Returns the files and the session count.
+async fn prepare_backup_snapshot(
+    snapshot: &SqlitePool,
+    audio_dir: &Path,
+    include_settings: bool,
+) -> Result<(Vec<(String, PathBuf)>, i64)> {
+    let mut files = Vec::new();
+    if audio_dir.exists() {
+        for entry in std::fs::read_dir(audio_dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension().is_some_and(|e| e == "tmp") {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                files.push((format!("{}/{}", AUDIO_DIR, name), path.clone()));
+            }
+        }
+    }
+
+    let rows = sqlx::query("SELECT id, audio_path FROM sessions")
+        .fetch_all(snapshot)
+        .await?;
+    for row in &rows {
+        let id: String = row.get("id");
+        let audio_path: String = row.get("audio_path");
+        let path = Path::new(&audio_path);
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        // Missing recordings keep their old path so the session still restores
+        if !path.is_file() {
+            continue;
+        }
+
+        let archived = if path.parent() == Some(audio_dir) {
+            format!("{}/{}", AUDIO_DIR, name)
+        } else {
+            let archived = format!("{}/{}-{}", AUDIO_DIR, id, name);
+            files.push((archived.clone(), path.to_path_buf()));
+            archived
+        };
+        sqlx::query("UPDATE sessions SET audio_path = ? WHERE id = ?")
+            .bind(&archived)
+            .bind(&id)
+            .execute(snapshot)
+            .await?;
+    }
+
+    if !include_settings {
+        sqlx::query("DELETE FROM settings").execute(snapshot).await?;
+    }
+
+    Ok((files, rows.len() as i64))
+}
+
+/// Hard-link each file into the staging directory, or copy it where links
+/// aren't possible. Recordings are only ever replaced by rename, so a link
+/// keeps the snapshotted version however the original changes later.
+fn stage_files(files: Vec<(String, PathBuf)>, staging: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let dir = staging.join(AUDIO_DIR);
+    std::fs::create_dir_all(&dir)?;
+    files
+        .into_iter()
+        .enumerate()
+        .map(|(i, (archived, path))| {
+            let staged = dir.join(i.to_string());
+            if std::fs::hard_link(&path, &staged).is_err() {
+                std::fs::copy(&path, &staged)?;
+            }
+            Ok((archived, staged))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Archive
+// ============================================================================
+
+/// Copy `input` to `output`, returning the byte count and SHA-256
+fn copy_hashed(input: &mut impl Read, output: &mut impl Write) -> io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let n = input.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        output.write_all(&buf[..n])?;
+        size += n as u64;
+    }
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Write each `(archive path, source file)` followed by the manifest with
+/// their checksums. The archive is written under a temporary name first so a
+/// failed backup never leaves a partial file behind.
+fn write_archive(
+    dest: &Path,
+    mut manifest: BackupManifest,
+    files: &[(String, PathBuf)],
+) -> Result<BackupManifest> {
+    let tmp = dest.with_extension("zip.tmp");
+
+    let result = (|| {
+        let mut zip = ZipWriter::new(BufWriter::new(File::create(&tmp)?));
+        for (name, source) in files {
+            // Recordings are often encrypted or compressed already
+            let method = if name == DATABASE_FILE {
+                CompressionMethod::Deflated
+            } else {
+                CompressionMethod::Stored
+            };
+            let options = FileOptions::default()
+                .compression_method(method)
+                .large_file(true);
+            zip.start_file(name.as_str(), options)?;
+            let (size, sha256) = copy_hashed(&mut BufReader::new(File::open(source)?), &mut zip)?;
+            manifest.files.push(BackupFile {
+                path: name.clone(),
+                size,
+                sha256,
+            });
+        }
+
+        zip.start_file(MANIFEST_FILE, FileOptions::default())?;
+        zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        zip.finish()?
+            .into_inner()
+            .map_err(|e| anyhow!("Failed to flush backup: {}", e))?
+            .sync_all()?;
+        std::fs::rename(&tmp, dest)?;
+        Ok(manifest)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn open_archive(path: &Path) -> Result<(ZipArchive<BufReader<File>>, BackupManifest)> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(path)?))
+        .map_err(|e| anyhow!("Not a backup archive: {}", e))?;
+    let manifest = read_manifest_entry(&mut archive)?;
+    Ok((archive, manifest))
+}
+
+fn read_manifest_entry(archive: &mut ZipArchive<impl Read + Seek>) -> Result<BackupManifest> {
+    let entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| anyhow!("Not a backup archive: the manifest is missing"))?;
+    let manifest: BackupManifest = serde_json::from_reader(entry)
+        .map_err(|e| anyhow!("The backup manifest is invalid: {}", e))?;
+
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(anyhow!(
+            "This backup was made by a newer version of the app"
+        ));
+    }
+    if !manifest.files.iter().any(|f| f.path == DATABASE_FILE) {
+        return Err(anyhow!("The backup has no database"));
+    }
+    Ok(manifest)
+}
+
+/// Read a backup's manifest without extracting anything
+pub fn read_manifest(path: &Path) -> Result<BackupManifest> {
+    open_archive(path).map(|(_, manifest)| manifest)
+}
+
+/// Where an archive entry is extracted to, relative to the staging
+/// directory. Anything that could land outside it is rejected.
+fn entry_destination(name: &str) -> Option<PathBuf> {
+    match name {
+        DATABASE_FILE | ENCRYPTION_FILE => Some(PathBuf::from(name)),
+        _ => {
+            let file = name.strip_prefix(AUDIO_DIR)?.strip_prefix('/')?;
+            let valid = !file.is_empty()
+                && file != "."
+                && file != ".."
+                && !file.contains(['/', '\\', ':']);
+            valid.then(|| Path::new(AUDIO_DIR).join(file))
+        }
+    }
+}
+
+/// Extract every file listed in the manifest into `dest`, checking each
+/// against its size and checksum
+pub fn extract_archive(path: &Path, dest: &Path) -> Result<BackupManifest> {
+    let (mut archive, manifest) = open_archive(path)?;
+    std::fs::create_dir_all(dest.join(AUDIO_DIR))?;
+
+    for file in &manifest.files {
+        let relative = entry_destination(&file.path)
+            .ok_or_else(|| anyhow!("The backup contains an unexpected file: {}", file.path))?;
+        let mut entry = archive
+            .by_name(&file.path)
+            .map_err(|_| anyhow!("The backup is incomplete: {} is missing", file.path))?;
+
+        let mut out = BufWriter::new(File::create(dest.join(relative))?);
+        let (size, sha256) = copy_hashed(&mut entry, &mut out)?;
+        out.flush()?;
+
+        if size != file.size || sha256 != file.sha256 {
+            return Err(anyhow!(
+                "The backup is damaged: {} does not match its checksum",
+                file.path
+            ));
+        }
+    }
+
+    Ok(manifest)
+}
+
+// ============================================================================
+// Backup
+// ============================================================================
+
+/// Write a backup of the whole library to the exports directory
+pub async fn create_backup(app: &AppHandle, include_settings: bool) -> Result<BackupInfo> {
+    // Keeps the keys and files from changing while they're snapshotted. The
+    // archive is written from the staged copies after it's released.
+    let lock = encryption::REKEY_LOCK.write().await;
+
+    let pool = database::get_pool()?;
+    let data_dir = app_data_dir(app)?;
+    let audio_dir = audio::get_audio_dir(app)?;
+    let keys = encryption::current_keys();
+
+    let staging = StagingDir::new(&data_dir, "backup")?;
+    let snapshot_path = staging.path().join(DATABASE_FILE);
+    snapshot_database(pool, &snapshot_path, keys.as_deref()).await?;
+
+    let snapshot = database::open_pool(path_str(&snapshot_path)?, keys.as_deref()).await?;
+    let prepared = prepare_backup_snapshot(&snapshot, &audio_dir, include_settings).await;
+    let schema_version = migrations::get_current_version(&snapshot).await;
+    snapshot.close().await;
+    let (audio_files, session_count) = prepared?;
+
+    let mut files = vec![(DATABASE_FILE.to_string(), snapshot_path)];
+    if keys.is_some() {
+        let config = encryption::load_config(&data_dir)?
+            .ok_or_else(|| anyhow!("Encryption settings are missing"))?;
+        let config_path = staging.path().join(ENCRYPTION_FILE);
+        std::fs::write(&config_path, serde_json::to_vec_pretty(&config)?)?;
+        files.push((ENCRYPTION_FILE.to_string(), config_path));
+    }
+    {
+        let staging = staging.path().to_path_buf();
+        files.extend(run_blocking(move || stage_files(audio_files, &staging)).await?);
+    }
+    drop(lock);
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: app.package_info().version.to_string(),
+        created_at: now(),
+        schema_version: schema_version?,
+        encrypted: keys.is_some(),
+        includes_settings: include_settings,
+        session_count,
+        files: Vec::new(),
+    };
+    let dest = export::get_exports_dir()?.join(format!(
+        "private-transcript-backup-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    let manifest = {
+        let dest = dest.clone();
+        run_blocking(move || write_archive(&dest, manifest, &files)).await?
+    };
+    println!(
+        "[Backup] Wrote {} session(s) and {} file(s) to {:?}",
+        manifest.session_count,
+        manifest.files.len(),
+        dest
+    );
+
+    Ok(BackupInfo {
+        path: dest.to_string_lossy().to_string(),
+        manifest,
+    })
+}
+
+// ============================================================================
+// Restore
+// ============================================================================
+
+/// Keys for an encrypted backup, from the passphrase it was made with
+async fn unlock_backup(staging: &Path, passphrase: Option<&str>) -> Result<Keys> {
+    let passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| anyhow!("This backup is encrypted. Enter the passphrase it was made with."))?
+        .to_string();
+    let config: EncryptionConfig = std::fs::read(staging.join(ENCRYPTION_FILE))
+        .map_err(anyhow::Error::from)
+        .and_then(|json| Ok(serde_json::from_slice(&json)?))
+        .map_err(|e| anyhow!("The backup's encryption settings are unreadable: {}", e))?;
+
+    encryption::run_blocking(move || encryption::open_config(&passphrase, &config)).await
+}
+
+/// Make an extracted snapshot fit this library: bring its schema up to date,
+/// point sessions at this library's audio directory and drop queued jobs.
+/// Returns the number of sessions in it.
+async fn prepare_restored_snapshot(snapshot: &SqlitePool, audio_dir: &Path) -> Result<i64> {
+    if migrations::get_current_version(snapshot).await? > migrations::latest_version() {
+        return Err(anyhow!(
+            "This backup was made by a newer version of the app"
+        ));
+    }
+    migrations::run_pending_migrations(snapshot).await?;
+
+    let prefix = format!(
+        "{}{}",
+        path_str(audio_dir)?,
+        std::path::MAIN_SEPARATOR
+    );
+    sqlx::query("UPDATE sessions SET audio_path = ? || substr(audio_path, ?) WHERE audio_path LIKE ?")
+        .bind(prefix)
+        .bind(AUDIO_DIR.len() as i64 + 2)
+        .bind(format!("{}/%", AUDIO_DIR))
+        .execute(snapshot)
+        .await?;
+    sqlx::query("DELETE FROM jobs").execute(snapshot).await?;
+
+    Ok(sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+        .fetch_one(snapshot)
+        .await?)
+}
+
+/// Overwrite the snapshot's settings with the library's current ones
+async fn keep_current_settings(live: &SqlitePool, snapshot: &SqlitePool) -> Result<()> {
+    let rows: Vec<(String, String, i64)> =
+        sqlx::query_as("SELECT key, value, updated_at FROM settings")
+            .fetch_all(live)
+            .await?;
+
+    let mut tx = snapshot.begin().await?;
+    sqlx::query("DELETE FROM settings").execute(&mut *tx).await?;
+    for (key, value, updated_at) in rows {
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)")
+            .bind(key)
+            .bind(value)
+            .bind(updated_at)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Replace the restored library's audit log with the library's current one,
+/// so a restore can't rewind what was recorded, and append `event` to it
+async fn keep_current_audit_log(
+    live: &SqlitePool,
+    restored: &SqlitePool,
+    guard: &AppendGuard,
+    event: AuditEvent<'_>,
+) -> Result<()> {
+    let rows = sqlx::query(
+        "SELECT seq, id, occurred_at, actor, action, entity_type, entity_id, details, prev_hash, hash FROM audit_log ORDER BY seq",
+    )
+    .fetch_all(live)
+    .await?;
+
+    let mut tx = restored.begin().await?;
+    // The log is append-only, so its triggers come off while it's replaced
+    let triggers: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND tbl_name = 'audit_log'",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    for (name, _) in &triggers {
+        sqlx::query(&format!("DROP TRIGGER \"{}\"", name.replace('"', "\"\"")))
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query("DELETE FROM audit_log")
+        .execute(&mut *tx)
+        .await?;
+    for row in rows {
+        sqlx::query(
+            "INSERT INTO audit_log (seq, id, occurred_at, actor, action, entity_type, entity_id, details, prev_hash, hash) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(row.get::<i64, _>("seq"))
+        .bind(row.get::<String, _>("id"))
+        .bind(row.get::<i64, _>("occurred_at"))
+        .bind(row.get::<String, _>("actor"))
+        .bind(row.get::<String, _>("action"))
+        .bind(row.get::<String, _>("entity_type"))
+        .bind(row.get::<Option<String>, _>("entity_id"))
+        .bind(row.get::<Option<String>, _>("details"))
+        .bind(row.get::<String, _>("prev_hash"))
+        .bind(row.get::<String, _>("hash"))
+        .execute(&mut *tx)
+        .await?;
+    }
+    for (_, sql) in &triggers {
+        sqlx::query(sql).execute(&mut *tx).await?;
+    }
+
+    audit::record_in(&mut *tx, guard, event).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+fn restore_event(mode: RestoreMode, sessions: i64) -> AuditEvent<'static> {
+    AuditEvent::new(AuditAction::Restore, "library", None).with_details(serde_json::json!({
+        "mode": mode.as_str(),
+        "sessions": sessions,
+    }))
+}
+
+/// The library's id for the backed-up tag in `column`. A backed-up tag whose
+/// name the library already uses was skipped, so that tag stands in for it.
+fn local_tag_id(column: &str) -> String {
+    format!(
+        r#"(
+    SELECT m.id
+    FROM main.tags m
+    JOIN backup.tags b ON b.name = m.name
+    WHERE b.id = {column}
+)"#
+    )
+}
+
+/// The library's id for the backed-up template in `column`. Built-in
+/// templates get new ids on every install, so those are matched to the local
+/// copy by name.
+fn local_template_id(column: &str) -> String {
+    format!(
+        r#"CASE
+    WHEN {column} IN (SELECT id FROM backup.templates WHERE is_system = 1) THEN (
+        SELECT m.id
+        FROM main.templates m
+        JOIN backup.templates b ON b.name = m.name AND b.workspace_type = m.workspace_type
+        WHERE b.id = {column} AND m.is_system = 1
+        LIMIT 1
+    )
+    ELSE {column}
+END"#
+    )
+}
+
+/// Copy the rows of the snapshot attached as `backup` into the library.
+/// Returns the number of sessions added.
+async fn merge_attached(
+    conn: &mut SqliteConnection,
+    guard: &AppendGuard,
+    restore_settings: bool,
+) -> Result<i64> {
+    let mut tx = conn.begin().await?;
+    sqlx::query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *tx)
+        .await?;
+    let before: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM main.sessions")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    for table in MERGE_TABLES {
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info(?, 'main')")
+                .bind(table)
+                .fetch_all(&mut *tx)
+                .await?;
+        let values = columns
+            .iter()
+            .map(|c| match (table, c.as_str()) {
+                ("sessions", "template_id") | ("workspace_types", "default_template_id") => {
+                    local_template_id(c)
+                }
+                ("session_tags", "tag_id") => local_tag_id(c),
+                _ => format!("\"{}\"", c.replace('"', "\"\"")),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let columns = columns
+            .iter()
+            .map(|c| format!("\"{}\"", c.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let filter = if table == "templates" {
+            " WHERE is_system = 0"
+        } else {
+            ""
+        };
+
+        sqlx::query(&format!(
+            "INSERT OR IGNORE INTO main.{table} ({columns}) SELECT {values} FROM backup.{table}{filter}"
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to merge {}: {}", table, e))?;
+    }
+
+    if restore_settings {
+        // "WHERE true" keeps the upsert's ON CONFLICT from parsing as a join
+        sqlx::query(
+            "INSERT INTO main.settings (key, value, updated_at) SELECT key, value, updated_at FROM backup.settings WHERE true ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let after: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM main.sessions")
+        .fetch_one(&mut *tx)
+        .await?;
+    let event = restore_event(RestoreMode::Merge, after - before);
+    audit::record_in(&mut *tx, guard, event).await?;
+    tx.commit().await?;
+    Ok(after - before)
+}
+
+/// Add a prepared snapshot's rows to the library, keeping existing rows when
+/// ids collide, and audit the restore. Returns the number of sessions added.
+async fn merge_snapshot(
+    pool: &SqlitePool,
+    snapshot: &Path,
+    keys: Option<&Keys>,
+    restore_settings: bool,
+) -> Result<i64> {
+    let guard = audit::lock().await;
+    let mut conn = pool.acquire().await?;
+    let key = keys.map_or_else(|| "''".to_string(), |k| k.database_key_pragma());
+    sqlx::query(&format!(
+        "ATTACH DATABASE '{}' AS backup KEY {}",
+        path_str(snapshot)?.replace('\'', "''"),
+        key
+    ))
+    .execute(&mut *conn)
+    .await?;
+
+    let result = merge_attached(&mut conn, &guard, restore_settings).await;
+    let detached = sqlx::query("DETACH DATABASE backup")
+        .execute(&mut *conn)
+        .await;
+
+    let added = result?;
+    detached?;
+    Ok(added)
+}
+
+/// Move files from `from` into `to`, skipping names that already exist.
+/// Returns how many were moved.
+fn move_new_files(from: &Path, to: &Path) -> Result<usize> {
+    let mut moved = 0;
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let target = to.join(name);
+        if path.is_file() && !target.exists() {
+            std::fs::rename(&path, &target)?;
+            moved += 1;
+        }
+    }
+    Ok(moved)
+}
+
+/// Swap `replacement` in for `current`, moving the old directory to `previous`
+fn swap_dirs(current: &Path, replacement: &Path, previous: &Path) -> Result<()> {
+    std::fs::rename(current, previous)?;
+    if let Err(e) = std::fs::rename(replacement, current) {
+        std::fs::rename(previous, current)?;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Restore a backup archive into the library
+pub async fn restore_backup(
+    app: &AppHandle,
+    request: &RestoreBackupRequest,
+) -> Result<RestoreSummary> {
+    let mode = RestoreMode::parse(&request.mode)?;
+    let _lock = encryption::REKEY_LOCK.write().await;
+
+    // Running jobs hold the current pool, which is closed during a replace
+    let pool = database::get_pool()?;
+    if mode == RestoreMode::Replace && !jobs::list_jobs(pool, Some("running")).await?.is_empty() {
+        return Err(anyhow!(
+            "Wait for background jobs to finish before replacing the library"
+        ));
+    }
+
+    let data_dir = app_data_dir(app)?;
+    let audio_dir = audio::get_audio_dir(app)?;
+    let staging = StagingDir::new(&data_dir, "restore")?;
+
+    let manifest = {
+        let (archive, dest) = (PathBuf::from(&request.path), staging.path().to_path_buf());
+        run_blocking(move || extract_archive(&archive, &dest)).await?
+    };
+    println!(
+        "[Backup] Restoring backup from {} ({}, {} session(s))",
+        request.path,
+        mode.as_str(),
+        manifest.session_count
+    );
+
+    let backup_keys = if manifest.encrypted {
+        Some(Arc::new(
+            unlock_backup(staging.path(), request.passphrase.as_deref()).await?,
+        ))
+    } else {
+        None
+    };
+    let current_keys = encryption::current_keys();
+    let restore_settings = request.restore_settings.unwrap_or(false) && manifest.includes_settings;
+
+    let snapshot_path = staging.path().join(DATABASE_FILE);
+    let snapshot = database::open_pool(path_str(&snapshot_path)?, backup_keys.as_deref()).await?;
+    let prepared = async {
+        let count = prepare_restored_snapshot(&snapshot, &audio_dir).await?;
+        if mode == RestoreMode::Replace && !restore_settings {
+            keep_current_settings(pool, &snapshot).await?;
+        }
+        Ok::<_, anyhow::Error>(count)
+    }
+    .await;
+    snapshot.close().await;
+    let session_count = prepared?;
+
+    // Recordings go under the library's keys
+    let staged_audio = staging.path().join(AUDIO_DIR);
+    {
+        let (dir, from, to) = (staged_audio.clone(), backup_keys.clone(), current_keys.clone());
+        run_blocking(move || encryption::reencrypt_audio_dir(&dir, from.as_deref(), to.as_deref()))
+            .await?;
+    }
+
+    let (sessions_restored, audio_files_restored) = match mode {
+        RestoreMode::Merge => {
+            let added =
+                merge_snapshot(pool, &snapshot_path, backup_keys.as_deref(), restore_settings)
+                    .await?;
+            (added, move_new_files(&staged_audio, &audio_dir)?)
+        }
+        RestoreMode::Replace => {
+            let restored_path = staging.path().join("restored.db");
+            let restored = path_str(&restored_path)?;
+            database::export_database(
+                path_str(&snapshot_path)?,
+                backup_keys.as_deref(),
+                restored,
+                current_keys.as_deref(),
+            )
+            .await?;
+
+            // Appends wait until the swap, so none land in the old log after it's copied
+            let guard = audit::lock().await;
+            let restored_pool = database::open_pool(restored, current_keys.as_deref()).await?;
+            let kept = keep_current_audit_log(
+                pool,
+                &restored_pool,
+                &guard,
+                restore_event(mode, session_count),
+            )
+            .await;
+            restored_pool.close().await;
+            kept?;
+
+            let audio_count = std::fs::read_dir(&staged_audio)?.count();
+            // The old recordings end up in the staging directory and go with it
+            let previous_audio = staging.path().join("previous-audio");
+            database::replace_database(app, restored, || {
+                swap_dirs(&audio_dir, &staged_audio, &previous_audio)
+            })
+            .await?;
+            drop(guard);
+            (session_count, audio_count)
+        }
+    };
+
+    println!(
+        "[Backup] Restored {} session(s) and {} recording(s)",
+        sessions_restored, audio_files_restored
+    );
+    Ok(RestoreSummary {
+        mode: mode.as_str().to_string(),
+        sessions_restored,
+        audio_files_restored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pt-backup-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn create_library(dir: &Path) -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(
+                database::connect_options(path_str(&dir.join("library.db")).unwrap(), None)
+                    .unwrap(),
+            )
+            .await
+            .expect("Failed to create test pool");
+
+        migrations::run_pending_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    async fn seed(pool: &SqlitePool, audio_path: &Path, template_id: &str) {
+        for sql in [
+            "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Test', 'general', 0, 0)",
+            "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('folder', 'ws', 'Test', 0, 0)",
+            "INSERT INTO settings (key, value, updated_at) VALUES ('theme', 'dark', 0)",
+            // A custom type whose default is a custom template of that type
+            "INSERT INTO workspace_types (id, name, folder_label, created_at, updated_at) VALUES ('coaching', 'Coaching', 'Coachee', 0, 0)",
+            "INSERT INTO templates (id, name, workspace_type, prompt, is_system, created_at, updated_at) VALUES ('goals', 'Goals', 'coaching', 'p', 0, 0, 0)",
+            "UPDATE workspace_types SET default_template_id = 'goals' WHERE id = 'coaching'",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO templates (id, name, workspace_type, prompt, is_system, created_at, updated_at) VALUES (?, 'SOAP Note', 'general', 'p', 1, 0, 0)",
+        )
+        .bind(template_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, title, audio_path, transcript, template_id, created_at, updated_at) VALUES ('s1', 'folder', 'Intake', ?, 'Discussed sleep', ?, 0, 0)",
+        )
+        .bind(path_str(audio_path).unwrap())
+        .bind(template_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn manifest() -> BackupManifest {
+        BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            app_version: "test".to_string(),
+            created_at: 0,
+            schema_version: migrations::latest_version(),
+            encrypted: false,
+            includes_settings: true,
+            session_count: 0,
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_archive_roundtrip_checks_files() {
+        let dir = temp_dir("roundtrip");
+        std::fs::write(dir.join("db"), b"database").unwrap();
+        std::fs::write(dir.join("a.wav"), b"audio").unwrap();
+        let files = vec![
+            (DATABASE_FILE.to_string(), dir.join("db")),
+            ("audio/a.wav".to_string(), dir.join("a.wav")),
+        ];
+
+        let archive = dir.join("backup.zip");
+        let written = write_archive(&archive, manifest(), &files).unwrap();
+        assert_eq!(written.files.len(), 2);
+        assert_eq!(read_manifest(&archive).unwrap().files[1].path, "audio/a.wav");
+
+        let out = dir.join("out");
+        extract_archive(&archive, &out).unwrap();
+        assert_eq!(std::fs::read(out.join("library.db")).unwrap(), b"database");
+        assert_eq!(std::fs::read(out.join("audio").join("a.wav")).unwrap(), b"audio");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_damaged_archive_is_rejected() {
+        let dir = temp_dir("damaged");
+        let archive = dir.join("backup.zip");
+
+        let mut bad = manifest();
+        bad.files.push(BackupFile {
+            path: DATABASE_FILE.to_string(),
+            size: 8,
+            sha256: "0".repeat(64),
+        });
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        zip.start_file(DATABASE_FILE, FileOptions::default()).unwrap();
+        zip.write_all(b"database").unwrap();
+        zip.start_file(MANIFEST_FILE, FileOptions::default()).unwrap();
+        zip.write_all(&serde_json::to_vec(&bad).unwrap()).unwrap();
+        zip.finish().unwrap();
+
+        let err = extract_archive(&archive, &dir.join("out")).unwrap_err();
+        assert!(err.to_string().contains("damaged"));
+
+        std::fs::write(&archive, b"not a zip").unwrap();
+        assert!(read_manifest(&archive).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_entry_destination_stays_in_staging() {
+        assert_eq!(
+            entry_destination("audio/a.wav"),
+            Some(Path::new("audio").join("a.wav"))
+        );
+        assert_eq!(entry_destination("library.db"), Some(PathBuf::from("library.db")));
+        assert_eq!(entry_destination("audio/../library.db"), None);
+        assert_eq!(entry_destination("audio/.."), None);
+        assert_eq!(entry_destination("audio/"), None);
+        assert_eq!(entry_destination("../evil"), None);
+        assert_eq!(entry_destination("audiox/a.wav"), None);
+        assert_eq!(entry_destination("notes.txt"), None);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_merge_into_another_library() {
+        let source_dir = temp_dir("source");
+        let source_audio = source_dir.join("audio");
+        std::fs::create_dir_all(&source_audio).unwrap();
+        let recording = source_audio.join("s1.wav");
+        std::fs::write(&recording, b"audio").unwrap();
+
+        let source = create_library(&source_dir).await;
+        seed(&source, &recording, "source-template").await;
+        for sql in [
+            "INSERT INTO tags (id, name, created_at, updated_at) VALUES ('source-intake', 'Intake', 0, 0)",
+            "INSERT INTO session_tags (session_id, tag_id, created_at) VALUES ('s1', 'source-intake', 0)",
+        ] {
+            sqlx::query(sql).execute(&source).await.unwrap();
+        }
+
+        // Back up without settings
+        let snapshot_path = source_dir.join("snapshot.db");
+        snapshot_database(&source, &snapshot_path, None).await.unwrap();
+        let snapshot = database::open_pool(path_str(&snapshot_path).unwrap(), None)
+            .await
+            .unwrap();
+        let (files, sessions) = prepare_backup_snapshot(&snapshot, &source_audio, false)
+            .await
+            .unwrap();
+        assert_eq!(sessions, 1);
+        assert_eq!(files, vec![("audio/s1.wav".to_string(), recording.clone())]);
+        let archived_path: String = sqlx::query_scalar("SELECT audio_path FROM sessions")
+            .fetch_one(&snapshot)
+            .await
+            .unwrap();
+        assert_eq!(archived_path, "audio/s1.wav");
+
+        // Restore into a library with its own copy of the built-in template
+        // and a tag of the same name
+        let target_dir = temp_dir("target");
+        let target_audio = target_dir.join("audio");
+        let target = create_library(&target_dir).await;
+        for sql in [
+            "INSERT INTO templates (id, name, workspace_type, prompt, is_system, created_at, updated_at) VALUES ('target-template', 'SOAP Note', 'general', 'p', 1, 0, 0)",
+            "INSERT INTO tags (id, name, created_at, updated_at) VALUES ('target-intake', 'intake', 0, 0)",
+        ] {
+            sqlx::query(sql).execute(&target).await.unwrap();
+        }
+
+        assert_eq!(prepare_restored_snapshot(&snapshot, &target_audio).await.unwrap(), 1);
+        assert_eq!(
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM settings")
+                .fetch_one(&snapshot)
+                .await
+                .unwrap(),
+            0
+        );
+        snapshot.close().await;
+
+        let added = merge_snapshot(&target, &snapshot_path, None, true).await.unwrap();
+        assert_eq!(added, 1);
+
+        let row = sqlx::query("SELECT audio_path, template_id FROM sessions WHERE id = 's1'")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert_eq!(
+            row.get::<String, _>("audio_path"),
+            target_audio.join("s1.wav").to_string_lossy()
+        );
+        assert_eq!(row.get::<String, _>("template_id"), "target-template");
+        let default_template: Option<String> = sqlx::query_scalar(
+            "SELECT default_template_id FROM workspace_types WHERE id = 'coaching'",
+        )
+        .fetch_one(&target)
+        .await
+        .unwrap();
+        assert_eq!(default_template.as_deref(), Some("goals"));
+        let tag_id: String =
+            sqlx::query_scalar("SELECT tag_id FROM session_tags WHERE session_id = 's1'")
+                .fetch_one(&target)
+                .await
+                .unwrap();
+        assert_eq!(tag_id, "target-intake");
+
+        // The merged session is searchable
+        let hits: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM sessions_fts WHERE sessions_fts MATCH 'sleep'")
+                .fetch_one(&target)
+                .await
+                .unwrap();
+        assert_eq!(hits, 1);
+
+        // Merging again adds nothing
+        assert_eq!(merge_snapshot(&target, &snapshot_path, None, true).await.unwrap(), 0);
+
+        std::fs::remove_dir_all(&source_dir).unwrap();
+        std::fs::remove_dir_all(&target_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replace_keeps_the_current_audit_log() {
+        let live_dir = temp_dir("live");
+        let restored_dir = temp_dir("restored");
+        let live = create_library(&live_dir).await;
+        let restored = create_library(&restored_dir).await;
+        audit::record(&live, AuditEvent::session(AuditAction::View, "s1"))
+            .await
+            .unwrap();
+        audit::record(&live, AuditEvent::session(AuditAction::Delete, "s1"))
+            .await
+            .unwrap();
+        audit::record(&restored, AuditEvent::session(AuditAction::View, "old"))
+            .await
+            .unwrap();
+
+        let guard = audit::lock().await;
+        keep_current_audit_log(
+            &live,
+            &restored,
+            &guard,
+            restore_event(RestoreMode::Replace, 1),
+        )
+        .await
+        .unwrap();
+        drop(guard);
+
+        let actions: Vec<String> = sqlx::query_scalar("SELECT action FROM audit_log ORDER BY seq")
+            .fetch_all(&restored)
+            .await
+            .unwrap();
+        assert_eq!(actions, ["view", "delete", "restore"]);
+        assert!(audit::verify(&restored).await.unwrap().valid);
+        // Still append-only afterwards
+        assert!(sqlx::query("DELETE FROM audit_log")
+            .execute(&restored)
+            .await
+            .is_err());
+
+        live.close().await;
+        restored.close().await;
+        std::fs::remove_dir_all(&live_dir).unwrap();
+        std::fs::remove_dir_all(&restored_dir).unwrap();
+    }
+
+    #[test]
+    fn test_move_new_files_keeps_existing() {
+        let from = temp_dir("from");
+        let to = temp_dir("to");
+        std::fs::write(from.join("a.wav"), b"new").unwrap();
+        std::fs::write(from.join("b.wav"), b"new").unwrap();
+        std::fs::write(to.join("a.wav"), b"old").unwrap();
+
+        assert_eq!(move_new_files(&from, &to).unwrap(), 1);
+        assert_eq!(std::fs::read(to.join("a.wav")).unwrap(), b"old");
+        assert_eq!(std::fs::read(to.join("b.wav")).unwrap(), b"new");
+
+        std::fs::remove_dir_all(&from).unwrap();
+        std::fs::remove_dir_all(&to).unwrap();
+    }
+
+    #[test]
+    fn test_staged_files_keep_the_snapshotted_version() {
+        let audio_dir = temp_dir("audio");
+        let staging = temp_dir("staging");
+        let recording = audio_dir.join("a.wav");
+        std::fs::write(&recording, b"before").unwrap();
+
+        let staged = stage_files(
+            vec![("audio/a.wav".to_string(), recording.clone())],
+            &staging,
+        )
+        .unwrap();
+
+        // A passphrase change replaces the recording by rename
+        let replacement = audio_dir.join("a.wav.rekey");
+        std::fs::write(&replacement, b"after").unwrap();
+        std::fs::rename(&replacement, &recording).unwrap();
+
+        assert_eq!(staged[0].0, "audio/a.wav");
+        assert_eq!(std::fs::read(&staged[0].1).unwrap(), b"before");
+
+        std::fs::remove_dir_all(&audio_dir).unwrap();
+        std::fs::remove_dir_all(&staging).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+//! Cancellation tokens for long-running session jobs
+//!
+//! Transcription and note generation register a token keyed by session id
+//! while they run. The `cancel_*` commands flip the token; the running job
+//! polls it, stops its subprocess or sampling loop, and returns [`Cancelled`].
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Kind of job a token belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    Transcription,
+    Generation,
+}
+
+/// Error returned by a job that stopped because it was cancelled
+#[derive(Debug, thiserror::Error)]
+#[error("Cancelled by user")]
+pub struct Cancelled;
+
+/// Whether an error chain was caused by cancellation
+pub fn is_cancelled_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|e| e.is::<Cancelled>())
+}
+
+/// Shared flag polled by a running job
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Return `Err(Cancelled)` if the token has been cancelled
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.is_cancelled() {
+            Err(Cancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Active tokens keyed by job kind and session id
+static TOKENS: Lazy<Mutex<HashMap<(JobKind, String), CancellationToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Keeps a token registered while a job runs and unregisters it on drop
+pub struct CancellationGuard {
+    kind: JobKind,
+    session_id: String,
+    token: CancellationToken,
+}
+
+impl CancellationGuard {
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl Drop for CancellationGuard {
+    fn drop(&mut self) {
+        let mut tokens = TOKENS.lock();
+        // Only remove our own token; a newer job for the same session may have replaced it
+        let key = (self.kind, self.session_id.clone());
+        if let Some(current) = tokens.get(&key) {
+            if Arc::ptr_eq(&current.0, &self.token.0) {
+                tokens.remove(&key);
+            }
+        }
+    }
+}
+
+/// Register a fresh token for a job
+pub fn register(kind: JobKind, session_id: &str) -> CancellationGuard {
+    let token = CancellationToken::new();
+    TOKENS
+        .lock()
+        .insert((kind, session_id.to_string()), token.clone());
+
+    CancellationGuard {
+        kind,
+        session_id: session_id.to_string(),
+        token,
+    }
+}
+
+/// Cancel a running job. Returns false if no such job is running.
+pub fn cancel(kind: JobKind, session_id: &str) -> bool {
+    match TOKENS.lock().get(&(kind, session_id.to_string())) {
+        Some(token) => {
+            println!(
+                "[Cancellation] Cancelling {:?} for session {}",
+                kind, session_id
+            );
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether a job is currently registered
+pub fn is_running(kind: JobKind, session_id: &str) -> bool {
+    TOKENS.lock().contains_key(&(kind, session_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_registered_job() {
+        let guard = register(JobKind::Transcription, "cancel-test-1");
+        let token = guard.token();
+
+        assert!(!token.is_cancelled());
+        assert!(cancel(JobKind::Transcription, "cancel-test-1"));
+        assert!(token.is_cancelled());
+        assert!(is_cancelled_error(&token.check().unwrap_err()));
+    }
+
+    #[test]
+    fn test_cancelled_error_found_in_source_chain() {
+        #[derive(Debug, thiserror::Error)]
+        #[error("Backend failed")]
+        struct BackendError(#[source] Cancelled);
+
+        let error = anyhow::Error::from(BackendError(Cancelled));
+        assert!(is_cancelled_error(&error));
+        assert!(is_cancelled_error(&error.context("Transcription failed")));
+        assert!(!is_cancelled_error(&anyhow::anyhow!("Backend failed")));
+    }
+
+    #[test]
+    fn test_cancel_unknown_job() {
+        assert!(!cancel(JobKind::Generation, "cancel-test-missing"));
+    }
+
+    #[test]
+    fn test_guard_unregisters_on_drop() {
+        {
+            let _guard = register(JobKind::Generation, "cancel-test-2");
+            assert!(is_running(JobKind::Generation, "cancel-test-2"));
+        }
+        assert!(!is_running(JobKind::Generation, "cancel-test-2"));
+    }
+
+    #[test]
+    fn test_kinds_are_independent() {
+        let guard = register(JobKind::Transcription, "cancel-test-3");
+        assert!(!cancel(JobKind::Generation, "cancel-test-3"));
+        assert!(!guard.token().is_cancelled());
+    }
+
+    #[test]
+    fn test_stale_guard_keeps_newer_token() {
+        let old = register(JobKind::Transcription, "cancel-test-4");
+        let new = register(JobKind::Transcription, "cancel-test-4");
+        drop(old);
+
+        assert!(is_running(JobKind::Transcription, "cancel-test-4"));
+        assert!(cancel(JobKind::Transcription, "cancel-test-4"));
+        assert!(new.token().is_cancelled());
+    }
+}
@@ -263,6 +263,24 @@ pub fn chunk_transcript_segments(
     chunks
 }
 
+/// Chunk a session's transcript the way it is indexed: from the segments
+/// when there are any, since they carry speakers, otherwise the plain text.
+/// `None` when the session has no transcript.
+pub fn chunk_session(
+    session_id: &str,
+    transcript: Option<&str>,
+    transcript_segments: Option<&str>,
+    config: &ChunkingConfig,
+) -> Option<Vec<TranscriptChunk>> {
+    match (transcript_segments, transcript) {
+        (Some(segments_json), _) => {
+            Some(chunk_transcript_segments(session_id, segments_json, config))
+        }
+        (None, Some(text)) => Some(chunk_transcript(session_id, text, config)),
+        (None, None) => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -381,4 +399,24 @@ mod tests {
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0].text, "Just plain text here.");
     }
+
+    #[test]
+    fn test_chunk_timestamped_segments() {
+        let config = ChunkingConfig {
+            min_chunk_size: 10,
+            ..Default::default()
+        };
+
+        // Segments produced by batch transcription carry timing data
+        let segments_json = r#"[
+            {"start": 0.0, "end": 2.5, "text": "Hello, how are you?", "avgLogprob": -0.2},
+            {"start": 2.5, "end": 4.0, "text": "I'm doing well, thanks!"}
+        ]"#;
+
+        let chunks = chunk_transcript_segments("test-session", segments_json, &config);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, "Hello, how are you? I'm doing well, thanks!");
+        assert!(chunks[0].speaker.is_none());
+    }
 }
@@ -0,0 +1,164 @@
+//! Low-confidence span detection for human transcript review
+
+use crate::models::{LowConfidenceSpan, TranscriptSegment};
+
+/// Words below this probability are flagged for review by default
+pub const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// Find runs of words whose probability falls below `threshold`.
+///
+/// Segments without word timings fall back to their average log-probability,
+/// so transcripts from older backends still get segment-level highlights.
+pub fn find_low_confidence_spans(
+    segments: &[TranscriptSegment],
+    threshold: f32,
+) -> Vec<LowConfidenceSpan> {
+    let mut spans = Vec::new();
+
+    for (segment_index, segment) in segments.iter().enumerate() {
+        let words = match segment.words {
+            Some(ref words) if !words.is_empty() => words,
+            _ => {
+                if let Some(avg_logprob) = segment.avg_logprob {
+                    let probability = avg_logprob.exp();
+                    if probability < threshold {
+                        spans.push(LowConfidenceSpan {
+                            segment_index,
+                            word_start_index: None,
+                            word_end_index: None,
+                            start: segment.start,
+                            end: segment.end,
+                            text: segment.text.trim().to_string(),
+                            min_probability: probability,
+                        });
+                    }
+                }
+                continue;
+            }
+        };
+
+        let mut current: Option<LowConfidenceSpan> = None;
+
+        for (word_index, word) in words.iter().enumerate() {
+            if word.probability >= threshold {
+                if let Some(span) = current.take() {
+                    spans.push(span);
+                }
+                continue;
+            }
+
+            match current {
+                Some(ref mut span) => {
+                    span.word_end_index = Some(word_index + 1);
+                    span.end = word.end;
+                    span.text.push(' ');
+                    span.text.push_str(&word.text);
+                    span.min_probability = span.min_probability.min(word.probability);
+                }
+                None => {
+                    current = Some(LowConfidenceSpan {
+                        segment_index,
+                        word_start_index: Some(word_index),
+                        word_end_index: Some(word_index + 1),
+                        start: word.start,
+                        end: word.end,
+                        text: word.text.clone(),
+                        min_probability: word.probability,
+                    });
+                }
+            }
+        }
+
+        if let Some(span) = current {
+            spans.push(span);
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TranscriptWord;
+
+    fn word(text: &str, start: f64, probability: f32) -> TranscriptWord {
+        TranscriptWord {
+            text: text.to_string(),
+            start,
+            end: start + 0.3,
+            probability,
+        }
+    }
+
+    fn segment(words: Option<Vec<TranscriptWord>>, avg_logprob: Option<f32>) -> TranscriptSegment {
+        TranscriptSegment {
+            start: 0.0,
+            end: 2.0,
+            text: "The plaintiff's counsel objected".to_string(),
+            speaker: None,
+            avg_logprob,
+            words,
+        }
+    }
+
+    #[test]
+    fn test_merges_consecutive_low_confidence_words() {
+        let segments = vec![segment(
+            Some(vec![
+                word("The", 0.0, 0.95),
+                word("plaintiff's", 0.3, 0.3),
+                word("counsel", 0.6, 0.4),
+                word("objected", 0.9, 0.9),
+            ]),
+            None,
+        )];
+
+        let spans = find_low_confidence_spans(&segments, DEFAULT_CONFIDENCE_THRESHOLD);
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "plaintiff's counsel");
+        assert_eq!(spans[0].word_start_index, Some(1));
+        assert_eq!(spans[0].word_end_index, Some(3));
+        assert_eq!(spans[0].start, 0.3);
+        assert!((spans[0].end - 0.9).abs() < 1e-9);
+        assert_eq!(spans[0].min_probability, 0.3);
+    }
+
+    #[test]
+    fn test_separate_spans_within_segment() {
+        let segments = vec![segment(
+            Some(vec![
+                word("a", 0.0, 0.1),
+                word("b", 0.3, 0.9),
+                word("c", 0.6, 0.2),
+            ]),
+            None,
+        )];
+
+        let spans = find_low_confidence_spans(&segments, 0.5);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[1].word_start_index, Some(2));
+    }
+
+    #[test]
+    fn test_segment_level_fallback() {
+        let segments = vec![
+            segment(None, Some(-2.0)), // exp(-2.0) ~ 0.135
+            segment(None, Some(-0.1)), // exp(-0.1) ~ 0.905
+            segment(None, None),
+        ];
+
+        let spans = find_low_confidence_spans(&segments, 0.5);
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].segment_index, 0);
+        assert!(spans[0].word_start_index.is_none());
+    }
+
+    #[test]
+    fn test_confident_transcript_has_no_spans() {
+        let segments = vec![segment(Some(vec![word("Fine", 0.0, 0.99)]), Some(-0.01))];
+        assert!(find_low_confidence_spans(&segments, 0.5).is_empty());
+    }
+}
@@ -1,16 +1,28 @@
 use crate::db::migrations;
 use crate::models::*;
+use crate::services::audit::{self, AuditAction, AuditEvent};
+use crate::services::encryption::{self, Keys};
+use crate::services::folders;
+use crate::services::search;
+use crate::services::tags;
+use crate::services::transcription_backend::TranscriptionBackendKind;
+use crate::services::trash;
+use crate::services::workspace_types;
 use crate::templates;
 use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
-use sqlx::Row;
+use sqlx::{ConnectOptions, Connection, Row};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::OnceLock;
 use tauri::AppHandle;
 use tauri::Manager;
 use uuid::Uuid;
 
-static DB_POOL: OnceLock<SqlitePool> = OnceLock::new();
+/// The open pool. Replaced when the database is re-keyed; each pool is leaked
+/// so callers can keep handing out `&'static` references.
+static DB_POOL: Lazy<RwLock<Option<&'static SqlitePool>>> = Lazy::new(|| RwLock::new(None));
 
 fn get_db_path(app: &AppHandle) -> Result<String> {
     let app_data_dir = app
@@ -18,20 +30,46 @@ fn get_db_path(app: &AppHandle) -> Result<String> {
         .app_data_dir()
         .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?;
     std::fs::create_dir_all(&app_data_dir)?;
-    let db_path = app_data_dir.join("private_transcript.db");
-    Ok(db_path.to_string_lossy().to_string())
+    Ok(db_path_in(&app_data_dir).to_string_lossy().to_string())
 }
 
-pub async fn init_database(app: &AppHandle) -> Result<()> {
-    let db_path = get_db_path(app)?;
+/// The database file in the app data directory
+pub(crate) fn db_path_in(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("private_transcript.db")
+}
+
+/// Where `rekey_database` writes the re-encrypted copy of the database
+pub(crate) fn rekeyed_copy_path(db_path: &str) -> String {
+    format!("{}.rekey", db_path)
+}
 
+/// Connection options, keyed for SQLCipher when encryption is enabled
+pub(crate) fn connect_options(db_path: &str, keys: Option<&Keys>) -> Result<SqliteConnectOptions> {
     let options = SqliteConnectOptions::from_str(&format!("sqlite:{}?mode=rwc", db_path))?
         .create_if_missing(true);
 
-    let pool = SqlitePoolOptions::new()
+    // sqlx always sends `key` before any other pragma, as SQLCipher requires
+    Ok(match keys {
+        Some(keys) => options.pragma("key", keys.database_key_pragma()),
+        None => options,
+    })
+}
+
+pub(crate) async fn open_pool(db_path: &str, keys: Option<&Keys>) -> Result<SqlitePool> {
+    Ok(SqlitePoolOptions::new()
         .max_connections(5)
-        .connect_with(options)
-        .await?;
+        .connect_with(connect_options(db_path, keys)?)
+        .await?)
+}
+
+fn install_pool(pool: SqlitePool) {
+    *DB_POOL.write() = Some(Box::leak(Box::new(pool)));
+}
+
+pub async fn init_database(app: &AppHandle) -> Result<()> {
+    let db_path = get_db_path(app)?;
+    let keys = encryption::current_keys();
+    let pool = open_pool(&db_path, keys.as_deref()).await?;
 
     // Run database migrations
     migrations::run_pending_migrations(&pool).await?;
@@ -45,15 +83,197 @@ pub async fn init_database(app: &AppHandle) -> Result<()> {
         templates::insert_default_templates(&pool).await?;
     }
 
-    DB_POOL.set(pool).ok();
+    install_pool(pool);
     Ok(())
 }
 
 /// Get the database pool - public for RAG and other services
 pub fn get_pool() -> Result<&'static SqlitePool> {
-    DB_POOL
-        .get()
-        .ok_or_else(|| anyhow::anyhow!("Database not initialized"))
+    let pool = *DB_POOL.read();
+    pool.ok_or_else(|| anyhow::anyhow!("Database not initialized"))
+}
+
+/// Whether the database has been opened (it stays closed until an encrypted store is unlocked)
+pub fn is_initialized() -> bool {
+    DB_POOL.read().is_some()
+}
+
+/// Rewrite the database under new keys (None = plaintext) into a copy beside
+/// it with `sqlcipher_export`, then run `commit`. Once `commit` succeeds the
+/// copy is moved into place and reopened under the new keys; if the export or
+/// `commit` fails the copy is dropped and the old file reopened.
+pub async fn rekey_database(
+    app: &AppHandle,
+    new_keys: Option<&Keys>,
+    commit: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let db_path = get_db_path(app)?;
+    let tmp_path = rekeyed_copy_path(&db_path);
+    let old_keys = encryption::current_keys();
+
+    // Close the live pool so nothing is written to the old file during the export
+    let old_pool = DB_POOL.write().take();
+    if let Some(pool) = old_pool {
+        pool.close().await;
+    }
+
+    let prepared = async {
+        export_database(&db_path, old_keys.as_deref(), &tmp_path, new_keys).await?;
+        commit()
+    }
+    .await;
+    if let Err(e) = prepared {
+        let _ = std::fs::remove_file(&tmp_path);
+        return reopen_after_failure(&db_path, old_keys.as_deref(), e).await;
+    }
+
+    // Committed, so there is no going back to the old file. The copy already
+    // opened under the new keys when its search index was rebuilt.
+    if let Err(e) = install_database_file(&db_path, &tmp_path) {
+        return Err(anyhow::anyhow!(
+            "Failed to move the re-keyed database into place; restart the app to finish: {}",
+            e
+        ));
+    }
+    let pool = open_pool(&db_path, new_keys).await.map_err(|e| {
+        anyhow::anyhow!(
+            "The database was re-keyed but could not be reopened; restart the app: {}",
+            e
+        )
+    })?;
+    install_pool(pool);
+    println!("[Database] Re-keyed database");
+    Ok(())
+}
+
+/// Reopen the old database after a swap failed with `error`. If it won't
+/// open either, both errors are reported.
+async fn reopen_after_failure(
+    db_path: &str,
+    keys: Option<&Keys>,
+    error: anyhow::Error,
+) -> Result<()> {
+    match open_pool(db_path, keys).await {
+        Ok(pool) => {
+            install_pool(pool);
+            Err(error)
+        }
+        Err(reopen) => Err(anyhow::anyhow!(
+            "{} (and the database could not be reopened: {})",
+            error,
+            reopen
+        )),
+    }
+}
+
+/// Check a database file opens under `keys` before it is swapped in
+async fn check_opens(path: &str, keys: Option<&Keys>) -> Result<()> {
+    let pool = open_pool(path, keys).await?;
+    let checked = sqlx::query("SELECT COUNT(*) FROM sqlite_master")
+        .fetch_one(&pool)
+        .await;
+    pool.close().await;
+    checked?;
+    Ok(())
+}
+
+/// Move a prepared database file over the current one, dropping the old
+/// file's write-ahead log
+pub(crate) fn install_database_file(db_path: &str, new_path: &str) -> Result<()> {
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", db_path, suffix));
+    }
+    std::fs::rename(new_path, db_path)?;
+    Ok(())
+}
+
+/// Copy a database into a new file under different keys (None = plaintext)
+/// with `sqlcipher_export`. The copy renumbers the sessions' rowids, which
+/// the search index refers to, so the index is rebuilt in the new file.
+pub(crate) async fn export_database(
+    src_path: &str,
+    src_keys: Option<&Keys>,
+    dest_path: &str,
+    dest_keys: Option<&Keys>,
+) -> Result<()> {
+    let _ = std::fs::remove_file(dest_path);
+
+    let mut conn = connect_options(src_path, src_keys)?.connect().await?;
+    let key = dest_keys.map_or_else(|| "''".to_string(), |k| k.database_key_pragma());
+    sqlx::query(&format!(
+        "ATTACH DATABASE '{}' AS exported KEY {}",
+        dest_path.replace('\'', "''"),
+        key
+    ))
+    .execute(&mut conn)
+    .await?;
+    sqlx::query("SELECT sqlcipher_export('exported')")
+        .execute(&mut conn)
+        .await?;
+    sqlx::query("DETACH DATABASE exported")
+        .execute(&mut conn)
+        .await?;
+    conn.close().await?;
+
+    let exported = open_pool(dest_path, dest_keys).await?;
+    let rebuilt = search::rebuild_index(&exported).await;
+    exported.close().await;
+    rebuilt
+}
+
+/// Move `new_path` into place as the database file. `commit` runs once it is
+/// there; if it fails the old file is put back.
+fn swap_database_file(
+    db_path: &str,
+    new_path: &str,
+    commit: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let backup_path = format!("{}.bak", db_path);
+
+    std::fs::rename(db_path, &backup_path)?;
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", db_path, suffix));
+    }
+    std::fs::rename(new_path, db_path)?;
+
+    if let Err(e) = commit() {
+        std::fs::rename(&backup_path, db_path)?;
+        return Err(e);
+    }
+    std::fs::remove_file(&backup_path)?;
+    Ok(())
+}
+
+/// Replace the database with a prepared file, already encrypted under the
+/// current keys, and reopen it. `commit` runs once the new file is in place;
+/// if it fails the old file is put back.
+pub async fn replace_database(
+    app: &AppHandle,
+    new_path: &str,
+    commit: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let db_path = get_db_path(app)?;
+    let keys = encryption::current_keys();
+    check_opens(new_path, keys.as_deref()).await?;
+
+    let old_pool = DB_POOL.write().take();
+    if let Some(pool) = old_pool {
+        pool.close().await;
+    }
+
+    // On failure the old file is back in place
+    if let Err(e) = swap_database_file(&db_path, new_path, commit) {
+        return reopen_after_failure(&db_path, keys.as_deref(), e).await;
+    }
+    let pool = open_pool(&db_path, keys.as_deref()).await.map_err(|e| {
+        anyhow::anyhow!(
+            "The database was replaced but could not be reopened; restart the app: {}",
+            e
+        )
+    })?;
+    install_pool(pool);
+    println!("[Database] Replaced database");
+    Ok(())
 }
 
 fn now() -> i64 {
@@ -71,10 +291,11 @@ fn workspace_from_row(row: SqliteRow) -> Workspace {
     }
 }
 
-fn folder_from_row(row: SqliteRow) -> Folder {
+pub(crate) fn folder_from_row(row: SqliteRow) -> Folder {
     Folder {
         id: row.get("id"),
         workspace_id: row.get("workspace_id"),
+        parent_id: row.get("parent_id"),
         name: row.get("name"),
         metadata: row.get("metadata"),
         created_at: row.get("created_at"),
@@ -84,7 +305,36 @@ fn folder_from_row(row: SqliteRow) -> Folder {
     }
 }
 
-fn session_from_row(row: SqliteRow) -> Session {
+/// A session's tag names as a JSON array. The sessions table must be
+/// aliased `s`.
+macro_rules! tag_list_column {
+    () => {
+        "(SELECT json_group_array(t.name) FROM session_tags st JOIN tags t ON t.id = st.tag_id WHERE st.session_id = s.id) AS tag_list"
+    };
+}
+
+/// What `session_from_row` reads: a session with its tag names
+pub(crate) const SESSION_COLUMNS: &str = concat!("s.*, ", tag_list_column!());
+
+/// What `session_summary_from_row` reads: a session without its transcript,
+/// segments and note, which can run to megabytes each
+pub(crate) const SESSION_SUMMARY_COLUMNS: &str = concat!(
+    "s.id, s.folder_id, s.title, s.audio_duration, s.template_id, s.status, s.error_message, ",
+    "substr(s.transcript, 1, 200) AS transcript_preview, ",
+    "COALESCE(s.generated_note, '') != '' AS has_note, s.created_at, s.updated_at, ",
+    tag_list_column!()
+);
+
+fn tags_from_row(row: &SqliteRow) -> Vec<String> {
+    let tag_list: String = row.get("tag_list");
+    let mut tags: Vec<String> = serde_json::from_str(&tag_list).unwrap_or_default();
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags
+}
+
+pub(crate) fn session_from_row(row: SqliteRow) -> Session {
+    let tags = tags_from_row(&row);
+
     Session {
         id: row.get("id"),
         folder_id: row.get("folder_id"),
@@ -100,9 +350,48 @@ fn session_from_row(row: SqliteRow) -> Session {
         error_message: row.get("error_message"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
+        tags,
+    }
+}
+
+/// A session as listed, without its transcript, segments and note. Content is
+/// only shown through `view_session`, so each time it's read is audited.
+pub(crate) fn without_content(mut session: Session) -> Session {
+    session.transcript = None;
+    session.transcript_segments = None;
+    session.generated_note = None;
+    session
+}
+
+pub(crate) fn session_summary_from_row(row: SqliteRow) -> SessionSummary {
+    SessionSummary {
+        id: row.get("id"),
+        folder_id: row.get("folder_id"),
+        title: row.get("title"),
+        audio_duration: row.get("audio_duration"),
+        template_id: row.get("template_id"),
+        status: row.get("status"),
+        error_message: row.get("error_message"),
+        transcript_preview: row.get("transcript_preview"),
+        has_note: row.get("has_note"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        tags: tags_from_row(&row),
     }
 }
 
+/// Read one session, live or trashed
+pub(crate) async fn fetch_session(pool: &SqlitePool, id: &str) -> Result<Session> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM sessions s WHERE s.id = ?",
+        SESSION_COLUMNS
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+    Ok(session_from_row(row))
+}
+
 fn template_from_row(row: SqliteRow) -> Template {
     Template {
         id: row.get("id"),
@@ -127,6 +416,9 @@ pub async fn create_workspace(
     let id = Uuid::new_v4().to_string();
     let now = now();
 
+    // Fails with the type's name rather than a foreign key error
+    workspace_types::get(pool, &request.workspace_type).await?;
+
     sqlx::query(
         "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at, is_active) VALUES (?, ?, ?, ?, ?, 1)",
     )
@@ -197,128 +489,21 @@ pub async fn delete_workspace(_app: &AppHandle, id: &str) -> Result<()> {
 
 // Folder operations
 pub async fn create_folder(_app: &AppHandle, request: CreateFolderRequest) -> Result<Folder> {
-    let pool = get_pool()?;
-    let id = Uuid::new_v4().to_string();
-    let now = now();
-
-    sqlx::query(
-        "INSERT INTO folders (id, workspace_id, name, metadata, created_at, updated_at, is_active) VALUES (?, ?, ?, ?, ?, ?, 1)",
-    )
-    .bind(&id)
-    .bind(&request.workspace_id)
-    .bind(&request.name)
-    .bind(&request.metadata)
-    .bind(now)
-    .bind(now)
-    .execute(pool)
-    .await?;
-
-    Ok(Folder {
-        id,
-        workspace_id: request.workspace_id,
-        name: request.name,
-        metadata: request.metadata,
-        created_at: now,
-        updated_at: now,
-        is_active: true,
-        session_count: 0,
-    })
+    folders::create(get_pool()?, &request, now()).await
 }
 
 pub async fn get_folders(_app: &AppHandle, workspace_id: &str) -> Result<Vec<Folder>> {
-    let pool = get_pool()?;
-    let rows = sqlx::query(
-        r#"
-        SELECT
-            f.id, f.workspace_id, f.name, f.metadata, f.created_at, f.updated_at,
-            f.is_active,
-            COALESCE((SELECT COUNT(*) FROM sessions s WHERE s.folder_id = f.id), 0) as session_count
-        FROM folders f
-        WHERE f.workspace_id = ? AND f.is_active = 1
-        ORDER BY f.created_at DESC
-        "#
-    )
-    .bind(workspace_id)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(rows.into_iter().map(folder_from_row).collect())
+    folders::list(get_pool()?, workspace_id).await
 }
 
 pub async fn update_folder(_app: &AppHandle, request: UpdateFolderRequest) -> Result<Folder> {
-    let pool = get_pool()?;
-    let now = now();
-
-    if let Some(name) = &request.name {
-        sqlx::query("UPDATE folders SET name = ?, updated_at = ? WHERE id = ?")
-            .bind(name)
-            .bind(now)
-            .bind(&request.id)
-            .execute(pool)
-            .await?;
-    }
-
-    if let Some(metadata) = &request.metadata {
-        sqlx::query("UPDATE folders SET metadata = ?, updated_at = ? WHERE id = ?")
-            .bind(metadata)
-            .bind(now)
-            .bind(&request.id)
-            .execute(pool)
-            .await?;
-    }
-
-    let row = sqlx::query(
-        r#"
-        SELECT
-            f.id, f.workspace_id, f.name, f.metadata, f.created_at, f.updated_at,
-            f.is_active,
-            COALESCE((SELECT COUNT(*) FROM sessions s WHERE s.folder_id = f.id), 0) as session_count
-        FROM folders f
-        WHERE f.id = ?
-        "#
-    )
-    .bind(&request.id)
-    .fetch_one(pool)
-    .await?;
-
-    Ok(folder_from_row(row))
+    folders::update(get_pool()?, &request, now()).await
 }
 
-pub async fn delete_folder(app: &AppHandle, id: &str) -> Result<()> {
+/// Move a folder, its subfolders and their sessions to the trash
+pub async fn delete_folder(_app: &AppHandle, id: &str) -> Result<()> {
     println!("[Database] delete_folder called with id: {}", id);
-    let pool = get_pool()?;
-
-    // First, get all sessions in this folder to delete their audio files
-    let sessions = get_sessions(app, id).await?;
-    println!("[Database] Found {} sessions in folder to delete", sessions.len());
-
-    // Delete audio files for all sessions
-    for session in &sessions {
-        if let Err(e) = std::fs::remove_file(&session.audio_path) {
-            // Log but don't fail if file doesn't exist or can't be deleted
-            eprintln!(
-                "Warning: Could not delete audio file {}: {}",
-                session.audio_path, e
-            );
-        }
-    }
-
-    // Delete all sessions in this folder
-    let delete_result = sqlx::query("DELETE FROM sessions WHERE folder_id = ?")
-        .bind(id)
-        .execute(pool)
-        .await?;
-    println!("[Database] Deleted {} session records", delete_result.rows_affected());
-
-    // Soft-delete the folder
-    let update_result = sqlx::query("UPDATE folders SET is_active = 0, updated_at = ? WHERE id = ?")
-        .bind(now())
-        .bind(id)
-        .execute(pool)
-        .await?;
-    println!("[Database] Soft-deleted folder, rows affected: {}", update_result.rows_affected());
-
-    Ok(())
+    trash::trash_folder(get_pool()?, id, now()).await
 }
 
 // Session operations
@@ -355,28 +540,52 @@ pub async fn create_session(_app: &AppHandle, request: CreateSessionRequest) ->
         error_message: None,
         created_at: now,
         updated_at: now,
+        tags: Vec::new(),
     })
 }
 
-pub async fn get_sessions(_app: &AppHandle, folder_id: &str) -> Result<Vec<Session>> {
+/// A folder's sessions, newest first, limited to those carrying every one of
+/// `tag_names` when any are given. Content is left out; open a session with
+/// `view_session`.
+pub async fn get_sessions(
+    _app: &AppHandle,
+    folder_id: &str,
+    tag_names: &[String],
+) -> Result<Vec<Session>> {
     let pool = get_pool()?;
-    let rows = sqlx::query(
-        "SELECT * FROM sessions WHERE folder_id = ? ORDER BY created_at DESC"
-    )
-    .bind(folder_id)
-    .fetch_all(pool)
-    .await?;
+    let tag_names = tags::normalize(tag_names);
+    let tag_filter = if tag_names.is_empty() {
+        String::new()
+    } else {
+        format!(" AND {}", tags::filter_sql(&tag_names))
+    };
+
+    let sql = format!(
+        "SELECT {} FROM sessions s WHERE s.folder_id = ? AND s.deleted_at IS NULL{} ORDER BY s.created_at DESC",
+        SESSION_COLUMNS, tag_filter
+    );
+    let mut query = sqlx::query(&sql).bind(folder_id);
+    for name in &tag_names {
+        query = query.bind(name);
+    }
+    let rows = query.fetch_all(pool).await?;
 
-    Ok(rows.into_iter().map(session_from_row).collect())
+    Ok(rows
+        .into_iter()
+        .map(|row| without_content(session_from_row(row)))
+        .collect())
 }
 
 pub async fn get_session(_app: &AppHandle, id: &str) -> Result<Session> {
-    let pool = get_pool()?;
-    let row = sqlx::query("SELECT * FROM sessions WHERE id = ?")
-        .bind(id)
-        .fetch_one(pool)
-        .await?;
-    Ok(session_from_row(row))
+    fetch_session(get_pool()?, id).await
+}
+
+/// Read a session for display, recording the view in the audit log. Reads
+/// the app makes for its own work use `get_session` instead.
+pub async fn view_session(app: &AppHandle, id: &str) -> Result<Session> {
+    let session = get_session(app, id).await?;
+    audit::record(get_pool()?, AuditEvent::session(AuditAction::View, id)).await?;
+    Ok(session)
 }
 
 pub async fn update_session(_app: &AppHandle, request: UpdateSessionRequest) -> Result<Session> {
@@ -428,73 +637,135 @@ pub async fn update_session(_app: &AppHandle, request: UpdateSessionRequest) ->
             .await?;
     }
 
-    let row = sqlx::query("SELECT * FROM sessions WHERE id = ?")
-        .bind(&request.id)
-        .fetch_one(pool)
-        .await?;
+    let session = fetch_session(pool, &request.id).await?;
+
+    // Status changes are workflow, not edits, so only content is audited
+    let edited: Vec<&str> = [
+        ("title", request.title.is_some()),
+        ("transcript", request.transcript.is_some()),
+        ("generatedNote", request.generated_note.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(field, changed)| changed.then_some(field))
+    .collect();
+    if !edited.is_empty() {
+        let event = AuditEvent::session(AuditAction::Update, &request.id)
+            .with_details(serde_json::json!({ "fields": edited }));
+        audit::record(pool, event).await?;
+    }
 
-    Ok(session_from_row(row))
+    Ok(session)
 }
 
-pub async fn delete_session(app: &AppHandle, id: &str) -> Result<()> {
+/// Store timestamped segments for a session, deriving the flat transcript from them
+pub async fn save_transcript_segments(
+    _app: &AppHandle,
+    session_id: &str,
+    segments: &[TranscriptSegment],
+) -> Result<Session> {
     let pool = get_pool()?;
+    let now = now();
 
-    // First, get the session to retrieve the audio path
-    let session = get_session(app, id).await?;
+    let segments_json = serde_json::to_string(segments)?;
+    let transcript = transcript_from_segments(segments);
 
-    // Delete the audio file
-    if let Err(e) = std::fs::remove_file(&session.audio_path) {
-        // Log but don't fail if file doesn't exist or can't be deleted
-        eprintln!(
-            "Warning: Could not delete audio file {}: {}",
-            session.audio_path, e
-        );
-    }
+    sqlx::query(
+        "UPDATE sessions SET transcript = ?, transcript_segments = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&transcript)
+    .bind(&segments_json)
+    .bind(now)
+    .bind(session_id)
+    .execute(pool)
+    .await?;
 
-    // Delete the database record
-    sqlx::query("DELETE FROM sessions WHERE id = ?")
-        .bind(id)
-        .execute(pool)
-        .await?;
+    let event = AuditEvent::session(AuditAction::Update, session_id)
+        .with_details(serde_json::json!({ "fields": ["transcript", "transcriptSegments"] }));
+    audit::record(pool, event).await?;
 
-    Ok(())
+    fetch_session(pool, session_id).await
 }
 
-/// Search sessions across all folders by title, transcript, or generated notes
-pub async fn search_sessions(_app: &AppHandle, query: &str, limit: i32) -> Result<Vec<Session>> {
+/// Reset a session's status after a cancelled job: `complete` if it already
+/// has a transcript, otherwise back to `pending`
+pub async fn reset_status_after_cancel(_app: &AppHandle, session_id: &str) -> Result<Session> {
     let pool = get_pool()?;
 
-    // Use LIKE for basic search across title, transcript, and generated_note
-    // The % wildcards allow matching anywhere in the string
-    let search_pattern = format!("%{}%", query);
-
-    let rows = sqlx::query(
-        r#"
-        SELECT * FROM sessions
-        WHERE title LIKE ?
-           OR transcript LIKE ?
-           OR generated_note LIKE ?
-        ORDER BY updated_at DESC
-        LIMIT ?
-        "#
+    sqlx::query(
+        "UPDATE sessions SET status = CASE WHEN COALESCE(transcript, '') = '' THEN 'pending' ELSE 'complete' END, error_message = NULL, updated_at = ? WHERE id = ?",
     )
-    .bind(&search_pattern)
-    .bind(&search_pattern)
-    .bind(&search_pattern)
-    .bind(limit)
-    .fetch_all(pool)
+    .bind(now())
+    .bind(session_id)
+    .execute(pool)
     .await?;
 
-    Ok(rows.into_iter().map(session_from_row).collect())
+    fetch_session(pool, session_id).await
+}
+
+/// Rename a speaker label across a session's segments, transcript and RAG chunks
+pub async fn rename_speaker(
+    app: &AppHandle,
+    session_id: &str,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Session> {
+    let pool = get_pool()?;
+
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(anyhow::anyhow!("Speaker name cannot be empty"));
+    }
+
+    let session = get_session(app, session_id).await?;
+    let segments_json = session
+        .transcript_segments
+        .ok_or_else(|| anyhow::anyhow!("Session has no transcript segments"))?;
+    let mut segments: Vec<TranscriptSegment> = serde_json::from_str(&segments_json)?;
+
+    let mut renamed = 0;
+    for segment in segments.iter_mut() {
+        if segment.speaker.as_deref() == Some(old_name) {
+            segment.speaker = Some(new_name.to_string());
+            renamed += 1;
+        }
+    }
+    if renamed == 0 {
+        return Err(anyhow::anyhow!("Speaker not found: {}", old_name));
+    }
+
+    sqlx::query("UPDATE transcript_chunks SET speaker = ?, updated_at = ? WHERE session_id = ? AND speaker = ?")
+        .bind(new_name)
+        .bind(now())
+        .bind(session_id)
+        .bind(old_name)
+        .execute(pool)
+        .await?;
+
+    save_transcript_segments(app, session_id, &segments).await
+}
+
+/// Move a session to the trash
+pub async fn delete_session(_app: &AppHandle, id: &str) -> Result<()> {
+    trash::trash_session(get_pool()?, id, now()).await
 }
 
 // Template operations
+
+/// Templates for a workspace type, or all of them. A type's default template
+/// is included and listed first even when it belongs to another type.
 pub async fn get_templates(_app: &AppHandle, workspace_type: Option<&str>) -> Result<Vec<Template>> {
     let pool = get_pool()?;
 
     let rows = if let Some(wt) = workspace_type {
         sqlx::query(
-            "SELECT id, name, workspace_type, description, prompt, output_format, is_default, is_system, created_at, updated_at FROM templates WHERE workspace_type = ? ORDER BY is_default DESC, name ASC"
+            r#"
+            SELECT id, name, workspace_type, description, prompt, output_format, is_default, is_system, created_at, updated_at
+            FROM templates
+            WHERE workspace_type = ?1
+               OR id = (SELECT default_template_id FROM workspace_types WHERE id = ?1)
+            ORDER BY id = (SELECT default_template_id FROM workspace_types WHERE id = ?1) DESC,
+                     is_default DESC, name ASC
+            "#,
         )
         .bind(wt)
         .fetch_all(pool)
@@ -521,6 +792,59 @@ pub async fn get_template(_app: &AppHandle, id: &str) -> Result<Template> {
     Ok(template_from_row(row))
 }
 
+pub async fn create_template(app: &AppHandle, request: CreateTemplateRequest) -> Result<Template> {
+    let pool = get_pool()?;
+    workspace_types::get(pool, &request.workspace_type).await?;
+    if request.name.trim().is_empty() || request.prompt.trim().is_empty() {
+        return Err(anyhow::anyhow!("A template needs a name and a prompt"));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let now = now();
+    sqlx::query(
+        "INSERT INTO templates (id, name, workspace_type, description, prompt, output_format, is_default, is_system, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, 0, 0, ?, ?)",
+    )
+    .bind(&id)
+    .bind(request.name.trim())
+    .bind(&request.workspace_type)
+    .bind(&request.description)
+    .bind(&request.prompt)
+    .bind(&request.output_format)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    get_template(app, &id).await
+}
+
+/// Delete a user-written template. Built-in ones and templates that notes
+/// were generated with are kept.
+pub async fn delete_template(app: &AppHandle, id: &str) -> Result<()> {
+    let pool = get_pool()?;
+    let template = get_template(app, id).await?;
+    if template.is_system {
+        return Err(anyhow::anyhow!("Built-in templates can't be deleted"));
+    }
+    let used: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE template_id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    if used > 0 {
+        return Err(anyhow::anyhow!(
+            "{} was used for {} session(s) and can't be deleted",
+            template.name,
+            used
+        ));
+    }
+
+    sqlx::query("DELETE FROM templates WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 // Settings operations
 pub async fn get_settings(_app: &AppHandle) -> Result<AppSettings> {
     let pool = get_pool()?;
@@ -545,6 +869,12 @@ pub async fn get_settings(_app: &AppHandle) -> Result<AppSettings> {
             "audio_input_device" => settings.audio_input_device = Some(value),
             "export_format" => settings.export_format = value,
             "auto_save" => settings.auto_save = value == "true",
+            "transcription_backend" => settings.transcription_backend = value,
+            "trash_retention_days" => {
+                if let Ok(days) = value.parse() {
+                    settings.trash_retention_days = days;
+                }
+            }
             _ => {}
         }
     }
@@ -604,6 +934,17 @@ pub async fn update_settings(
     if let Some(v) = request.auto_save {
         upsert(pool, "auto_save", if v { "true" } else { "false" }, now).await?;
     }
+    if let Some(v) = &request.transcription_backend {
+        // Reject unknown backends before they reach the settings table
+        TranscriptionBackendKind::parse(v)?;
+        upsert(pool, "transcription_backend", v, now).await?;
+    }
+    if let Some(days) = request.trash_retention_days {
+        if days < 1 {
+            return Err(anyhow::anyhow!("Trash retention must be at least 1 day"));
+        }
+        upsert(pool, "trash_retention_days", &days.to_string(), now).await?;
+    }
 
     get_settings(_app).await
 }
@@ -611,27 +952,27 @@ pub async fn update_settings(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::migrations;
-    use sqlx::sqlite::SqlitePoolOptions;
-
-    async fn create_test_pool() -> SqlitePool {
-        let pool = SqlitePoolOptions::new()
-            .max_connections(1)
-            .connect("sqlite::memory:")
-            .await
-            .expect("Failed to create test pool");
-
-        // Run migrations
-        migrations::run_pending_migrations(&pool)
-            .await
-            .expect("Failed to run migrations");
+    use crate::db::test_support::migrated_pool;
 
-        pool
+    #[tokio::test]
+    async fn test_reopen_after_failure_reports_both_errors() {
+        let missing = std::env::temp_dir()
+            .join(format!("pt-missing-{}", Uuid::new_v4()))
+            .join("library.db");
+        let error = reopen_after_failure(
+            &missing.to_string_lossy(),
+            None,
+            anyhow::anyhow!("Swap failed"),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(error.starts_with("Swap failed (and the database could not be reopened: "));
     }
 
     #[tokio::test]
     async fn test_workspace_crud() {
-        let pool = create_test_pool().await;
+        let pool = migrated_pool().await;
 
         // Create workspace
         let id = Uuid::new_v4().to_string();
@@ -704,7 +1045,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_folder_crud() {
-        let pool = create_test_pool().await;
+        let pool = migrated_pool().await;
 
         // Create workspace first
         let workspace_id = Uuid::new_v4().to_string();
@@ -741,7 +1082,7 @@ mod tests {
         let row = sqlx::query(
             r#"
             SELECT
-                f.id, f.workspace_id, f.name, f.metadata, f.created_at, f.updated_at,
+                f.id, f.workspace_id, f.parent_id, f.name, f.metadata, f.created_at, f.updated_at,
                 f.is_active,
                 COALESCE((SELECT COUNT(*) FROM sessions s WHERE s.folder_id = f.id), 0) as session_count
             FROM folders f
@@ -762,7 +1103,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_session_crud() {
-        let pool = create_test_pool().await;
+        let pool = migrated_pool().await;
 
         // Create workspace and folder first
         let workspace_id = Uuid::new_v4().to_string();
@@ -810,13 +1151,7 @@ mod tests {
         .unwrap();
 
         // Read session
-        let row = sqlx::query("SELECT * FROM sessions WHERE id = ?")
-            .bind(&session_id)
-            .fetch_one(&pool)
-            .await
-            .unwrap();
-
-        let session = session_from_row(row);
+        let session = fetch_session(&pool, &session_id).await.unwrap();
         assert_eq!(session.id, session_id);
         assert_eq!(session.folder_id, folder_id);
         assert_eq!(session.title, Some("Test Session".to_string()));
@@ -835,20 +1170,14 @@ mod tests {
             .await
             .unwrap();
 
-        let row = sqlx::query("SELECT * FROM sessions WHERE id = ?")
-            .bind(&session_id)
-            .fetch_one(&pool)
-            .await
-            .unwrap();
-
-        let session = session_from_row(row);
+        let session = fetch_session(&pool, &session_id).await.unwrap();
         assert_eq!(session.transcript, Some("This is a test transcript.".to_string()));
         assert_eq!(session.status, "complete");
     }
 
     #[tokio::test]
     async fn test_template_crud() {
-        let pool = create_test_pool().await;
+        let pool = migrated_pool().await;
 
         // Create template
         let template_id = Uuid::new_v4().to_string();
@@ -891,7 +1220,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_settings_crud() {
-        let pool = create_test_pool().await;
+        let pool = migrated_pool().await;
         let now = chrono::Utc::now().timestamp();
 
         // Insert setting
@@ -937,7 +1266,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_session_count_in_folder() {
-        let pool = create_test_pool().await;
+        let pool = migrated_pool().await;
 
         // Create workspace and folder
         let workspace_id = Uuid::new_v4().to_string();
@@ -989,7 +1318,7 @@ mod tests {
         let row = sqlx::query(
             r#"
             SELECT
-                f.id, f.workspace_id, f.name, f.metadata, f.created_at, f.updated_at,
+                f.id, f.workspace_id, f.parent_id, f.name, f.metadata, f.created_at, f.updated_at,
                 f.is_active,
                 COALESCE((SELECT COUNT(*) FROM sessions s WHERE s.folder_id = f.id), 0) as session_count
             FROM folders f
@@ -0,0 +1,705 @@
+//! Local speaker diarization
+//!
+//! Runs after transcription: each segment gets a spectral voice embedding
+//! (log mel band statistics over 16 kHz audio), segments are clustered by
+//! cosine similarity, and clusters are labelled `Speaker 1`, `Speaker 2`, ...
+//! in order of first appearance. Clustering compares embeddings with the
+//! session mean removed, which exaggerates small differences, so clusters
+//! whose actual spectra are no further apart than the segments within them
+//! are merged back into one voice. Everything stays on-device.
+
+use anyhow::{anyhow, Result};
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+use super::audio;
+use crate::models::TranscriptSegment;
+
+/// Sample rate produced by `audio::decode_audio_to_whisper_format`
+const SAMPLE_RATE: f32 = 16000.0;
+/// 25ms analysis window
+const FRAME_LEN: usize = 400;
+/// 10ms hop between frames
+const HOP_LEN: usize = 160;
+const FFT_SIZE: usize = 512;
+const MEL_BANDS: usize = 24;
+const MEL_MIN_HZ: f32 = 80.0;
+const MEL_MAX_HZ: f32 = 7600.0;
+/// Frames quieter than this RMS are treated as silence
+const SILENCE_RMS: f32 = 1e-4;
+/// Least spread (RMS log mel energy, in nats) assumed within one voice, so
+/// that clusters of a segment or two aren't told apart by tiny differences
+const MIN_VOICE_SPREAD: f32 = 0.75;
+
+/// Configuration for diarization
+#[derive(Debug, Clone)]
+pub struct DiarizationConfig {
+    /// Upper bound on the number of speakers
+    pub max_speakers: usize,
+    /// Clusters more similar than this (cosine) are merged
+    pub similarity_threshold: f32,
+    /// Segments shorter than this are labelled from the nearest cluster
+    /// instead of taking part in clustering
+    pub min_segment_secs: f64,
+}
+
+impl Default for DiarizationConfig {
+    fn default() -> Self {
+        Self {
+            max_speakers: 8,
+            similarity_threshold: 0.4,
+            min_segment_secs: 0.8,
+        }
+    }
+}
+
+/// Label for the n-th speaker (0-based)
+pub fn speaker_label(index: usize) -> String {
+    format!("Speaker {}", index + 1)
+}
+
+/// Decode an audio file and assign speaker labels to its segments.
+/// Returns the number of speakers found.
+pub fn diarize_file(audio_path: &str, segments: &mut [TranscriptSegment]) -> Result<usize> {
+    let samples = audio::decode_audio_to_whisper_format(audio_path)?;
+    Ok(diarize_segments(
+        &samples,
+        segments,
+        &DiarizationConfig::default(),
+    ))
+}
+
+/// Run diarization on a background thread, returning the labelled segments
+pub async fn diarize_file_async(
+    audio_path: String,
+    mut segments: Vec<TranscriptSegment>,
+) -> Result<Vec<TranscriptSegment>> {
+    tokio::task::spawn_blocking(move || {
+        let speakers = diarize_file(&audio_path, &mut segments)?;
+        println!(
+            "[Diarization] Found {} speaker(s) across {} segments",
+            speakers,
+            segments.len()
+        );
+        Ok(segments)
+    })
+    .await
+    .map_err(|e| anyhow!("Task join error: {}", e))?
+}
+
+/// Assign `Speaker N` labels to segments from 16 kHz mono samples.
+/// Returns the number of speakers found.
+pub fn diarize_segments(
+    samples: &[f32],
+    segments: &mut [TranscriptSegment],
+    config: &DiarizationConfig,
+) -> usize {
+    if segments.is_empty() {
+        return 0;
+    }
+
+    let extractor = EmbeddingExtractor::new();
+    let mut embeddings: Vec<Option<Vec<f32>>> = segments
+        .iter()
+        .map(|s| extractor.embed(samples, s.start, s.end))
+        .collect();
+    let shapes: Vec<Option<Vec<f32>>> = embeddings
+        .iter()
+        .map(|e| e.as_deref().map(spectral_shape))
+        .collect();
+    normalize_embeddings(&mut embeddings);
+
+    // Only segments long enough for a stable embedding take part in clustering
+    let clusterable: Vec<usize> = (0..segments.len())
+        .filter(|&i| {
+            embeddings[i].is_some() && segments[i].end - segments[i].start >= config.min_segment_secs
+        })
+        .collect();
+
+    let clusters = if clusterable.is_empty() {
+        Clusters {
+            members: Vec::new(),
+            centroids: Vec::new(),
+        }
+    } else {
+        let weights: Vec<f32> = clusterable
+            .iter()
+            .map(|&i| (segments[i].end - segments[i].start) as f32)
+            .collect();
+        let vectors: Vec<&[f32]> = clusterable
+            .iter()
+            .map(|&i| embeddings[i].as_deref().unwrap_or(&[]))
+            .collect();
+        let shapes: Vec<&[f32]> = clusterable
+            .iter()
+            .map(|&i| shapes[i].as_deref().unwrap_or(&[]))
+            .collect();
+        let clusters = cluster(&vectors, &weights, config);
+        merge_same_voice(clusters, &vectors, &shapes, &weights)
+    };
+
+    // Map each clustered segment to its cluster
+    let mut assignment: Vec<Option<usize>> = vec![None; segments.len()];
+    for (cluster_index, members) in clusters.members.iter().enumerate() {
+        for &member in members {
+            assignment[clusterable[member]] = Some(cluster_index);
+        }
+    }
+
+    // Short segments join the nearest cluster, or inherit from their neighbour
+    for i in 0..segments.len() {
+        if assignment[i].is_some() {
+            continue;
+        }
+        assignment[i] = match embeddings[i] {
+            Some(ref embedding) if !clusters.centroids.is_empty() => {
+                nearest_centroid(embedding, &clusters.centroids)
+            }
+            _ => None,
+        }
+        .or_else(|| if i > 0 { assignment[i - 1] } else { None });
+    }
+    // A leading run without a label takes the first label that follows it
+    if let Some(first) = assignment.iter().flatten().next().copied() {
+        for slot in assignment.iter_mut() {
+            match slot {
+                Some(_) => break,
+                None => *slot = Some(first),
+            }
+        }
+    } else {
+        assignment.iter_mut().for_each(|slot| *slot = Some(0));
+    }
+
+    // Number speakers in order of first appearance
+    let mut order: Vec<usize> = Vec::new();
+    for (segment, cluster_index) in segments.iter_mut().zip(assignment.iter()) {
+        let cluster_index = cluster_index.unwrap_or(0);
+        let speaker_index = match order.iter().position(|&c| c == cluster_index) {
+            Some(pos) => pos,
+            None => {
+                order.push(cluster_index);
+                order.len() - 1
+            }
+        };
+        segment.speaker = Some(speaker_label(speaker_index));
+    }
+
+    order.len()
+}
+
+/// Computes per-segment voice embeddings from log mel band statistics
+struct EmbeddingExtractor {
+    window: Vec<f32>,
+    filterbank: Vec<Vec<(usize, f32)>>,
+}
+
+impl EmbeddingExtractor {
+    fn new() -> Self {
+        let window = (0..FRAME_LEN)
+            .map(|i| {
+                0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos()
+            })
+            .collect();
+
+        Self {
+            window,
+            filterbank: mel_filterbank(),
+        }
+    }
+
+    /// Mean and standard deviation of log mel energies over the voiced frames of a span
+    fn embed(&self, samples: &[f32], start: f64, end: f64) -> Option<Vec<f32>> {
+        let from = ((start.max(0.0) * SAMPLE_RATE as f64) as usize).min(samples.len());
+        let to = ((end.max(0.0) * SAMPLE_RATE as f64) as usize).min(samples.len());
+        if to <= from || to - from < FRAME_LEN {
+            return None;
+        }
+        let span = &samples[from..to];
+
+        let mut planner = FftPlanner::<f32>::new();
+        let fft = planner.plan_fft_forward(FFT_SIZE);
+        let mut buffer = vec![Complex::new(0.0f32, 0.0); FFT_SIZE];
+
+        let mut sums = [0.0f32; MEL_BANDS];
+        let mut sq_sums = [0.0f32; MEL_BANDS];
+        let mut frames = 0usize;
+
+        let mut offset = 0;
+        while offset + FRAME_LEN <= span.len() {
+            let frame = &span[offset..offset + FRAME_LEN];
+            offset += HOP_LEN;
+
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / FRAME_LEN as f32).sqrt();
+            if rms < SILENCE_RMS {
+                continue;
+            }
+
+            for (i, slot) in buffer.iter_mut().enumerate() {
+                *slot = if i < FRAME_LEN {
+                    Complex::new(frame[i] * self.window[i], 0.0)
+                } else {
+                    Complex::new(0.0, 0.0)
+                };
+            }
+            fft.process(&mut buffer);
+
+            for (band, filter) in self.filterbank.iter().enumerate() {
+                let energy: f32 = filter
+                    .iter()
+                    .map(|&(bin, weight)| buffer[bin].norm_sqr() * weight)
+                    .sum();
+                let log_energy = (energy + 1e-10).ln();
+                sums[band] += log_energy;
+                sq_sums[band] += log_energy * log_energy;
+            }
+            frames += 1;
+        }
+
+        if frames == 0 {
+            return None;
+        }
+
+        let n = frames as f32;
+        let mut embedding: Vec<f32> = sums.iter().map(|s| s / n).collect();
+        let stds: Vec<f32> = sums
+            .iter()
+            .zip(sq_sums.iter())
+            .map(|(s, sq)| {
+                let mean = s / n;
+                (sq / n - mean * mean).max(0.0).sqrt()
+            })
+            .collect();
+        embedding.extend(stds);
+
+        Some(embedding)
+    }
+}
+
+/// Triangular mel filters as (fft bin, weight) pairs
+fn mel_filterbank() -> Vec<Vec<(usize, f32)>> {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+
+    let mel_min = hz_to_mel(MEL_MIN_HZ);
+    let mel_max = hz_to_mel(MEL_MAX_HZ);
+    let bin_of = |hz: f32| (hz * FFT_SIZE as f32 / SAMPLE_RATE).floor() as usize;
+
+    let edges: Vec<usize> = (0..MEL_BANDS + 2)
+        .map(|i| {
+            let mel = mel_min + (mel_max - mel_min) * i as f32 / (MEL_BANDS + 1) as f32;
+            bin_of(mel_to_hz(mel))
+        })
+        .collect();
+
+    (0..MEL_BANDS)
+        .map(|band| {
+            let (lo, mid, hi) = (edges[band], edges[band + 1], edges[band + 2]);
+            let mut filter = Vec::new();
+            for bin in lo..=hi {
+                let weight = if bin <= mid {
+                    if mid == lo {
+                        1.0
+                    } else {
+                        (bin - lo) as f32 / (mid - lo) as f32
+                    }
+                } else if hi == mid {
+                    1.0
+                } else {
+                    (hi - bin) as f32 / (hi - mid) as f32
+                };
+                if weight > 0.0 {
+                    filter.push((bin, weight));
+                }
+            }
+            filter
+        })
+        .collect()
+}
+
+/// The mean log mel energies of an embedding with the overall level removed,
+/// so the same voice louder or quieter has the same shape
+fn spectral_shape(embedding: &[f32]) -> Vec<f32> {
+    let means = &embedding[..MEL_BANDS.min(embedding.len())];
+    let level = means.iter().sum::<f32>() / means.len().max(1) as f32;
+    means.iter().map(|m| m - level).collect()
+}
+
+/// Subtract the session mean so embeddings capture differences between voices
+/// rather than the recording channel, then scale to unit length
+fn normalize_embeddings(embeddings: &mut [Option<Vec<f32>>]) {
+    let present: Vec<&Vec<f32>> = embeddings.iter().flatten().collect();
+    if present.is_empty() {
+        return;
+    }
+
+    let dims = present[0].len();
+    let mut mean = vec![0.0f32; dims];
+    for embedding in &present {
+        for (m, v) in mean.iter_mut().zip(embedding.iter()) {
+            *m += v;
+        }
+    }
+    let count = present.len() as f32;
+    mean.iter_mut().for_each(|m| *m /= count);
+
+    for embedding in embeddings.iter_mut().flatten() {
+        for (v, m) in embedding.iter_mut().zip(mean.iter()) {
+            *v -= m;
+        }
+        l2_normalize(embedding);
+    }
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 1e-8 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a < 1e-8 || norm_b < 1e-8 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+fn nearest_centroid(embedding: &[f32], centroids: &[Vec<f32>]) -> Option<usize> {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, cosine_similarity(embedding, c)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+}
+
+/// Result of clustering: member indices and duration-weighted centroid per cluster
+struct Clusters {
+    members: Vec<Vec<usize>>,
+    centroids: Vec<Vec<f32>>,
+}
+
+/// Agglomerative clustering on cosine similarity between duration-weighted centroids.
+/// Merges while the closest pair is above the threshold or there are too many clusters.
+/// Similarities are kept in a matrix along with each cluster's nearest
+/// neighbour, so a merge only recomputes the merged cluster's row.
+fn cluster(vectors: &[&[f32]], weights: &[f32], config: &DiarizationConfig) -> Clusters {
+    let n = vectors.len();
+    let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut centroids: Vec<Vec<f32>> = vectors.iter().map(|v| v.to_vec()).collect();
+    let mut masses: Vec<f32> = weights.iter().map(|w| w.max(1e-3)).collect();
+    // Clusters merged into another are no longer active
+    let mut active = vec![true; n];
+    let mut remaining = n;
+
+    let mut similarity = vec![vec![0.0f32; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let s = cosine_similarity(&centroids[i], &centroids[j]);
+            similarity[i][j] = s;
+            similarity[j][i] = s;
+        }
+    }
+    let nearest_of = |i: usize, similarity: &[Vec<f32>], active: &[bool]| {
+        (0..n)
+            .filter(|&j| j != i && active[j])
+            .fold((i, f32::MIN), |best, j| {
+                if similarity[i][j] > best.1 {
+                    (j, similarity[i][j])
+                } else {
+                    best
+                }
+            })
+    };
+    let mut nearest: Vec<(usize, f32)> = (0..n)
+        .map(|i| nearest_of(i, &similarity, &active))
+        .collect();
+
+    while remaining > 1 {
+        let (mut i, mut j, mut best) = (0, 0, f32::MIN);
+        for k in (0..n).filter(|&k| active[k]) {
+            if nearest[k].1 > best {
+                (i, j, best) = (k, nearest[k].0, nearest[k].1);
+            }
+        }
+        if best < config.similarity_threshold && remaining <= config.max_speakers {
+            break;
+        }
+        // Keep the earlier cluster, so clusters stay in order of first member
+        let (i, j) = (i.min(j), i.max(j));
+
+        // Merge j into i
+        let (mass_i, mass_j) = (masses[i], masses[j]);
+        let merged: Vec<f32> = centroids[i]
+            .iter()
+            .zip(centroids[j].iter())
+            .map(|(a, b)| (a * mass_i + b * mass_j) / (mass_i + mass_j))
+            .collect();
+        centroids[i] = merged;
+        masses[i] = mass_i + mass_j;
+        let moved = std::mem::take(&mut members[j]);
+        members[i].extend(moved);
+        active[j] = false;
+        remaining -= 1;
+
+        for k in (0..n).filter(|&k| k != i && active[k]) {
+            let s = cosine_similarity(&centroids[i], &centroids[k]);
+            similarity[i][k] = s;
+            similarity[k][i] = s;
+        }
+        nearest[i] = nearest_of(i, &similarity, &active);
+        for k in (0..n).filter(|&k| k != i && active[k]) {
+            if nearest[k].0 == i || nearest[k].0 == j {
+                nearest[k] = nearest_of(k, &similarity, &active);
+            } else if similarity[k][i] > nearest[k].1 {
+                nearest[k] = (i, similarity[k][i]);
+            }
+        }
+    }
+
+    let (members, centroids) = (0..n)
+        .filter(|&i| active[i])
+        .map(|i| {
+            (
+                std::mem::take(&mut members[i]),
+                std::mem::take(&mut centroids[i]),
+            )
+        })
+        .unzip();
+    Clusters { members, centroids }
+}
+
+/// Mean spectral shape of a cluster and the RMS spread of its members around it
+fn shape_stats(members: &[usize], shapes: &[&[f32]]) -> (Vec<f32>, f32) {
+    let dims = shapes[members[0]].len();
+    let mut mean = vec![0.0f32; dims];
+    for &m in members {
+        for (acc, v) in mean.iter_mut().zip(shapes[m].iter()) {
+            *acc += v;
+        }
+    }
+    mean.iter_mut().for_each(|v| *v /= members.len() as f32);
+
+    let variance = members
+        .iter()
+        .map(|&m| {
+            shapes[m]
+                .iter()
+                .zip(mean.iter())
+                .map(|(v, mu)| (v - mu) * (v - mu))
+                .sum::<f32>()
+                / dims.max(1) as f32
+        })
+        .sum::<f32>()
+        / members.len() as f32;
+    (mean, variance.sqrt())
+}
+
+/// Merge clusters that are the same voice: their mean spectral shapes are no
+/// further apart (RMS per band) than the spread of the segments within them.
+/// Centring the embeddings turns one voice's small variations into
+/// directions, so a monologue would otherwise be split into several speakers.
+fn merge_same_voice(
+    mut clusters: Clusters,
+    vectors: &[&[f32]],
+    shapes: &[&[f32]],
+    weights: &[f32],
+) -> Clusters {
+    loop {
+        let stats: Vec<(Vec<f32>, f32)> = clusters
+            .members
+            .iter()
+            .map(|members| shape_stats(members, shapes))
+            .collect();
+
+        // The pair closest relative to its spread
+        let mut closest: Option<(usize, usize, f32)> = None;
+        for i in 0..stats.len() {
+            for j in (i + 1)..stats.len() {
+                let (mean_i, spread_i) = &stats[i];
+                let (mean_j, spread_j) = &stats[j];
+                let distance = (mean_i
+                    .iter()
+                    .zip(mean_j.iter())
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum::<f32>()
+                    / mean_i.len().max(1) as f32)
+                    .sqrt();
+                let spread = ((spread_i * spread_i + spread_j * spread_j) / 2.0)
+                    .sqrt()
+                    .max(MIN_VOICE_SPREAD);
+                let ratio = distance / spread;
+                if ratio < 1.0 && !matches!(closest, Some((_, _, best)) if best <= ratio) {
+                    closest = Some((i, j, ratio));
+                }
+            }
+        }
+        let Some((i, j, _)) = closest else {
+            return clusters;
+        };
+
+        let moved = clusters.members.remove(j);
+        clusters.centroids.remove(j);
+        clusters.members[i].extend(moved);
+        clusters.centroids[i] = weighted_centroid(&clusters.members[i], vectors, weights);
+    }
+}
+
+fn weighted_centroid(members: &[usize], vectors: &[&[f32]], weights: &[f32]) -> Vec<f32> {
+    let mut centroid = vec![0.0f32; vectors[members[0]].len()];
+    let mut mass = 0.0f32;
+    for &m in members {
+        let w = weights[m].max(1e-3);
+        for (c, v) in centroid.iter_mut().zip(vectors[m].iter()) {
+            *c += v * w;
+        }
+        mass += w;
+    }
+    centroid.iter_mut().for_each(|c| *c /= mass);
+    centroid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A crude "voice": a fundamental plus harmonics
+    fn voice(fundamental: f32, seconds: f32) -> Vec<f32> {
+        let n = (seconds * SAMPLE_RATE) as usize;
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE;
+                (1..=6)
+                    .map(|h| {
+                        (2.0 * std::f32::consts::PI * fundamental * h as f32 * t).sin()
+                            / h as f32
+                    })
+                    .sum::<f32>()
+                    * 0.2
+            })
+            .collect()
+    }
+
+    fn segment(start: f64, end: f64) -> TranscriptSegment {
+        TranscriptSegment {
+            start,
+            end,
+            text: "words".to_string(),
+            speaker: None,
+            avg_logprob: None,
+            words: None,
+        }
+    }
+
+    #[test]
+    fn test_two_alternating_speakers() {
+        let mut samples = voice(110.0, 2.0);
+        samples.extend(voice(420.0, 2.0));
+        samples.extend(voice(110.0, 2.0));
+        samples.extend(voice(420.0, 2.0));
+
+        let mut segments = vec![
+            segment(0.0, 2.0),
+            segment(2.0, 4.0),
+            segment(4.0, 6.0),
+            segment(6.0, 8.0),
+        ];
+
+        let speakers = diarize_segments(&samples, &mut segments, &DiarizationConfig::default());
+
+        assert_eq!(speakers, 2);
+        let labels: Vec<_> = segments.iter().map(|s| s.speaker.clone().unwrap()).collect();
+        assert_eq!(labels, vec!["Speaker 1", "Speaker 2", "Speaker 1", "Speaker 2"]);
+    }
+
+    #[test]
+    fn test_short_segment_takes_neighbour_label() {
+        let mut samples = voice(110.0, 3.0);
+        samples.extend(voice(420.0, 3.0));
+
+        let mut segments = vec![
+            segment(0.0, 3.0),
+            segment(3.0, 3.01), // too short to embed
+            segment(3.01, 6.0),
+        ];
+
+        diarize_segments(&samples, &mut segments, &DiarizationConfig::default());
+
+        assert_eq!(segments[1].speaker, segments[0].speaker);
+        assert_ne!(segments[2].speaker, segments[0].speaker);
+    }
+
+    #[test]
+    fn test_silence_gets_single_speaker() {
+        let samples = vec![0.0f32; 16000 * 4];
+        let mut segments = vec![segment(0.0, 2.0), segment(2.0, 4.0)];
+
+        let speakers = diarize_segments(&samples, &mut segments, &DiarizationConfig::default());
+
+        assert_eq!(speakers, 1);
+        assert!(segments.iter().all(|s| s.speaker.as_deref() == Some("Speaker 1")));
+    }
+
+    #[test]
+    fn test_max_speakers_is_respected() {
+        let mut samples = Vec::new();
+        let mut segments = Vec::new();
+        for (i, f) in [100.0, 200.0, 400.0, 800.0].iter().enumerate() {
+            samples.extend(voice(*f, 2.0));
+            segments.push(segment(i as f64 * 2.0, (i + 1) as f64 * 2.0));
+        }
+
+        let config = DiarizationConfig {
+            max_speakers: 2,
+            similarity_threshold: 1.1, // never merge on similarity alone
+            ..Default::default()
+        };
+        let speakers = diarize_segments(&samples, &mut segments, &config);
+
+        assert_eq!(speakers, 2);
+    }
+
+    #[test]
+    fn test_one_voice_over_many_segments_is_one_speaker() {
+        // One voice with a little background noise, cut into uneven segments
+        let mut samples = voice(150.0, 30.0);
+        let mut seed = 12345u32;
+        for sample in samples.iter_mut() {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            *sample += (seed as f32 / u32::MAX as f32 - 0.5) * 0.02;
+        }
+        let mut segments = Vec::new();
+        let mut start = 0.0;
+        for i in 0..14 {
+            let end = start + 1.5 + (i % 4) as f64 * 0.4;
+            segments.push(segment(start, end));
+            start = end;
+        }
+
+        let speakers = diarize_segments(&samples, &mut segments, &DiarizationConfig::default());
+
+        assert_eq!(speakers, 1);
+        assert!(segments
+            .iter()
+            .all(|s| s.speaker.as_deref() == Some("Speaker 1")));
+
+        // Two segments of it are one speaker too
+        let mut segments = vec![segment(0.0, 2.0), segment(2.0, 4.0)];
+        assert_eq!(
+            diarize_segments(&samples, &mut segments, &DiarizationConfig::default()),
+            1
+        );
+    }
+
+    #[test]
+    fn test_empty_segments() {
+        assert_eq!(
+            diarize_segments(&[0.0; 100], &mut [], &DiarizationConfig::default()),
+            0
+        );
+    }
+}
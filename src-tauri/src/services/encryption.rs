@@ -0,0 +1,1209 @@
+//! Passphrase-based encryption at rest
+//!
+//! When a passphrase is set, a 64-byte secret is derived from it with Argon2id.
+//! The first half keys SQLCipher, which encrypts every database page; the
+//! second half keys XChaCha20-Poly1305 for audio recordings. The salt, KDF
+//! parameters and a verifier live in `encryption.json` beside the database.
+//! Neither the passphrase nor the derived keys are ever written to disk.
+//!
+//! Encrypted audio files start with [`AUDIO_MAGIC`] and a stream nonce,
+//! followed by 64 KiB chunks sealed with the STREAM construction, so files
+//! can be encrypted and decrypted without holding them in memory and a
+//! truncated or reordered file fails authentication.
+//!
+//! Changing the passphrase writes re-encrypted copies beside the recordings
+//! and the database, and moves them into place only after a journal beside
+//! the config is committed, so a change cut short by a crash is finished or
+//! dropped at the next startup.
+
+use anyhow::{anyhow, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::aead::{generic_array::GenericArray, Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::RwLock;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use zeroize::Zeroizing;
+
+use super::{audio, database, jobs, retention, streaming_transcription, system_audio, trash};
+use crate::models::EncryptionStatus;
+
+/// Settings file holding the salt, KDF parameters and verifier
+const CONFIG_FILE: &str = "encryption.json";
+const CONFIG_VERSION: u32 = 1;
+
+/// Journal of a passphrase change in progress, beside the config
+const JOURNAL_FILE: &str = "encryption.journal.json";
+/// Extension of a recording's re-encrypted copy, until it is moved into place
+const REKEY_EXTENSION: &str = "rekey";
+
+/// Argon2id cost: 64 MiB, 3 passes. Takes well under a second on a laptop
+/// but makes offline guessing expensive.
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_ITERATIONS: u32 = 3;
+const KDF_PARALLELISM: u32 = 1;
+const SALT_LEN: usize = 16;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Sealed into the config so a wrong passphrase is rejected before touching data
+const VERIFIER_PLAINTEXT: &[u8] = b"private-transcript";
+
+/// Header of an encrypted audio file
+const AUDIO_MAGIC: &[u8; 8] = b"PTENCAU1";
+/// XChaCha20 nonce minus the 5 bytes STREAM uses for the counter and last-chunk flag
+const STREAM_NONCE_LEN: usize = 19;
+const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+/// Held for writing while the store's files are rewritten (re-encryption,
+/// backup and restore) so those never overlap, and for reading while a
+/// recording is saved, so it is never written under keys about to change
+pub(crate) static REKEY_LOCK: Lazy<tokio::sync::RwLock<()>> =
+    Lazy::new(|| tokio::sync::RwLock::new(()));
+
+/// App-private directory for decrypted copies of recordings (set during app init)
+static DECRYPTED_AUDIO_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Keys for the unlocked store, if encryption is enabled
+static KEYS: Lazy<RwLock<Option<Arc<Keys>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Keys derived from the passphrase. Zeroed when dropped.
+pub struct Keys {
+    database: Zeroizing<[u8; 32]>,
+    audio: Zeroizing<[u8; 32]>,
+}
+
+impl Keys {
+    /// Value for SQLCipher's `PRAGMA key`. Passing the raw key makes SQLCipher
+    /// skip its own (weaker) KDF.
+    pub fn database_key_pragma(&self) -> String {
+        let hex: String = self.database.iter().map(|b| format!("{:02X}", b)).collect();
+        format!("\"x'{}'\"", hex)
+    }
+
+    fn audio_cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(Key::from_slice(&self.audio[..]))
+    }
+}
+
+/// Contents of `encryption.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionConfig {
+    pub version: u32,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// Base64 Argon2 salt
+    pub salt: String,
+    /// Base64 nonce and ciphertext of [`VERIFIER_PLAINTEXT`] under the audio key
+    pub verifier: String,
+}
+
+/// Contents of the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RekeyJournal {
+    /// Config being moved to; None turns encryption off
+    target: Option<EncryptionConfig>,
+    /// Set once every copy is written. From then on the change is finished
+    /// rather than dropped.
+    committed: bool,
+}
+
+/// Keys for the unlocked store, or None when encryption is off or still locked
+pub fn current_keys() -> Option<Arc<Keys>> {
+    KEYS.read().clone()
+}
+
+fn set_keys(keys: Option<Arc<Keys>>) {
+    *KEYS.write() = keys;
+}
+
+// ============================================================================
+// Key derivation
+// ============================================================================
+
+fn derive_keys(passphrase: &str, config: &EncryptionConfig) -> Result<Keys> {
+    let params = Params::new(
+        config.memory_kib,
+        config.iterations,
+        config.parallelism,
+        Some(64),
+    )
+    .map_err(|e| anyhow!("Invalid key derivation parameters: {}", e))?;
+    let salt = BASE64.decode(&config.salt)?;
+
+    let mut secret = Zeroizing::new([0u8; 64]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut secret[..])
+        .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+
+    let mut database = Zeroizing::new([0u8; 32]);
+    let mut audio = Zeroizing::new([0u8; 32]);
+    database.copy_from_slice(&secret[..32]);
+    audio.copy_from_slice(&secret[32..]);
+    Ok(Keys { database, audio })
+}
+
+/// Create a config with a fresh salt and derive its keys
+fn new_config(
+    passphrase: &str,
+    memory_kib: u32,
+    iterations: u32,
+) -> Result<(EncryptionConfig, Keys)> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let mut config = EncryptionConfig {
+        version: CONFIG_VERSION,
+        memory_kib,
+        iterations,
+        parallelism: KDF_PARALLELISM,
+        salt: BASE64.encode(salt),
+        verifier: String::new(),
+    };
+    let keys = derive_keys(passphrase, &config)?;
+    config.verifier = BASE64.encode(seal(&keys, VERIFIER_PLAINTEXT)?);
+
+    Ok((config, keys))
+}
+
+/// Derive keys and check them against the verifier
+pub(crate) fn open_config(passphrase: &str, config: &EncryptionConfig) -> Result<Keys> {
+    let keys = derive_keys(passphrase, config)?;
+    let verifier = BASE64.decode(&config.verifier)?;
+    match open(&keys, &verifier) {
+        Ok(plaintext) if plaintext == VERIFIER_PLAINTEXT => Ok(keys),
+        _ => Err(anyhow!("Incorrect passphrase")),
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(anyhow!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// Seal a small buffer as nonce || ciphertext
+fn seal(keys: &Keys, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; 24];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = keys
+        .audio_cipher()
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .map_err(|_| anyhow!("Encryption failed"))?;
+    Ok([&nonce[..], &ciphertext].concat())
+}
+
+fn open(keys: &Keys, sealed: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < 24 {
+        return Err(anyhow!("Sealed data is truncated"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(24);
+    keys.audio_cipher()
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Decryption failed"))
+}
+
+// ============================================================================
+// Config file
+// ============================================================================
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| anyhow!("Failed to get app data dir: {}", e))
+}
+
+pub(crate) fn load_config(dir: &Path) -> Result<Option<EncryptionConfig>> {
+    let path = dir.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&json)?))
+}
+
+/// Write (or with None, remove) the config, replacing it atomically
+fn save_config(dir: &Path, config: Option<&EncryptionConfig>) -> Result<()> {
+    let path = dir.join(CONFIG_FILE);
+    match config {
+        Some(config) => write_json(&path, config)?,
+        None => {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn load_journal(dir: &Path) -> Result<Option<RekeyJournal>> {
+    let path = dir.join(JOURNAL_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&json)?))
+}
+
+fn save_journal(dir: &Path, journal: &RekeyJournal) -> Result<()> {
+    write_json(&dir.join(JOURNAL_FILE), journal)
+}
+
+/// Write a JSON file through a temporary sibling, synced before it is renamed
+/// into place
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid path: {:?}", path))?;
+    let tmp = path.with_file_name(format!("{}.tmp", file_name.to_string_lossy()));
+    let mut file = File::create(&tmp)?;
+    file.write_all(&serde_json::to_vec_pretty(value)?)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// ============================================================================
+// Audio files
+// ============================================================================
+
+/// Read until `buf` is full or the input ends
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypt a plaintext stream into the encrypted audio format
+fn encrypt_stream(keys: &Keys, mut input: impl Read, output: &mut impl Write) -> Result<()> {
+    let mut nonce = [0u8; STREAM_NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    output.write_all(AUDIO_MAGIC)?;
+    output.write_all(&nonce)?;
+
+    let mut encryptor =
+        EncryptorBE32::from_aead(keys.audio_cipher(), GenericArray::from_slice(&nonce));
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = read_full(&mut input, &mut buf)?;
+        // A short read marks the final chunk, which may be empty
+        if n == CHUNK_SIZE {
+            let chunk = encryptor
+                .encrypt_next(&buf[..])
+                .map_err(|_| anyhow!("Audio encryption failed"))?;
+            output.write_all(&chunk)?;
+        } else {
+            let chunk = encryptor
+                .encrypt_last(&buf[..n])
+                .map_err(|_| anyhow!("Audio encryption failed"))?;
+            output.write_all(&chunk)?;
+            break;
+        }
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
+/// Reader yielding the plaintext of an encrypted audio stream
+struct DecryptingReader<R: Read> {
+    inner: R,
+    decryptor: Option<DecryptorBE32<XChaCha20Poly1305>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> DecryptingReader<R> {
+    fn new(keys: &Keys, mut inner: R) -> Result<Self> {
+        let mut header = [0u8; AUDIO_MAGIC.len() + STREAM_NONCE_LEN];
+        if read_full(&mut inner, &mut header)? < header.len()
+            || &header[..AUDIO_MAGIC.len()] != AUDIO_MAGIC
+        {
+            return Err(anyhow!("Not an encrypted audio file"));
+        }
+        let nonce = GenericArray::from_slice(&header[AUDIO_MAGIC.len()..]);
+
+        Ok(Self {
+            inner,
+            decryptor: Some(DecryptorBE32::from_aead(keys.audio_cipher(), nonce)),
+            chunk: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// Decrypt the next chunk into `self.chunk`; false once the stream has ended
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.decryptor.is_none() {
+            return Ok(false);
+        }
+
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Audio could not be decrypted: wrong passphrase or corrupted file",
+            )
+        };
+
+        let mut buf = vec![0u8; CHUNK_SIZE + TAG_LEN];
+        let n = read_full(&mut self.inner, &mut buf)?;
+        self.chunk = if n == buf.len() {
+            let decryptor = self.decryptor.as_mut().ok_or_else(invalid)?;
+            decryptor.decrypt_next(&buf[..]).map_err(|_| invalid())?
+        } else if n >= TAG_LEN {
+            let decryptor = self.decryptor.take().ok_or_else(invalid)?;
+            decryptor.decrypt_last(&buf[..n]).map_err(|_| invalid())?
+        } else {
+            // Every stream ends with a short chunk, so this one was cut off
+            return Err(invalid());
+        };
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.chunk.len() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+        let n = out.len().min(self.chunk.len() - self.pos);
+        out[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Whether a file is in the encrypted audio format
+pub fn is_encrypted_file(path: &Path) -> Result<bool> {
+    let mut header = [0u8; AUDIO_MAGIC.len()];
+    let n = read_full(&mut File::open(path)?, &mut header)?;
+    Ok(n == header.len() && &header == AUDIO_MAGIC)
+}
+
+fn require_keys() -> Result<Arc<Keys>> {
+    current_keys().ok_or_else(|| anyhow!("Audio is encrypted. Unlock with your passphrase first."))
+}
+
+/// Read an audio file, decrypting it if it is encrypted
+pub fn read_audio(path: &Path) -> Result<Vec<u8>> {
+    if !is_encrypted_file(path)? {
+        return Ok(std::fs::read(path)?);
+    }
+
+    let keys = require_keys()?;
+    let mut reader = DecryptingReader::new(&keys, BufReader::new(File::open(path)?))?;
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Write audio bytes, encrypting them when a passphrase is set
+pub fn write_audio(path: &Path, data: &[u8]) -> Result<()> {
+    match current_keys() {
+        Some(keys) => replace_file(path, |out| encrypt_stream(&keys, data, out)),
+        None => Ok(std::fs::write(path, data)?),
+    }
+}
+
+/// Encrypt a plaintext recording in place when a passphrase is set.
+/// Used for files written by the native recorder.
+pub fn protect_audio_file(path: &Path) -> Result<()> {
+    let Some(keys) = current_keys() else {
+        return Ok(());
+    };
+    if is_encrypted_file(path)? {
+        return Ok(());
+    }
+    let input = BufReader::new(File::open(path)?);
+    replace_file(path, |out| encrypt_stream(&keys, input, out))
+}
+
+/// Write a file through a temporary sibling and rename it into place, so a
+/// failure never leaves a half-written recording behind
+fn replace_file(path: &Path, write: impl FnOnce(&mut BufWriter<File>) -> Result<()>) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid audio path: {:?}", path))?;
+    let tmp = path.with_file_name(format!("{}.tmp", file_name.to_string_lossy()));
+
+    let result = (|| {
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        write(&mut out)?;
+        out.into_inner()
+            .map_err(|e| anyhow!("Failed to flush {:?}: {}", tmp, e))?
+            .sync_all()?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Whether an encrypted file opens with the given keys
+fn opens_with(keys: &Keys, path: &Path) -> bool {
+    File::open(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|f| DecryptingReader::new(keys, BufReader::new(f)))
+        .and_then(|mut reader| reader.fill().map_err(Into::into))
+        .is_ok()
+}
+
+/// Move an audio file from one key set to another; None means plaintext.
+/// Returns whether the file was rewritten.
+fn reencrypt_audio_file(path: &Path, from: Option<&Keys>, to: Option<&Keys>) -> Result<bool> {
+    reencrypt_audio_file_to(path, path, from, to)
+}
+
+/// Write an audio file moved from one key set to another to `dest`. Returns
+/// whether it needed moving; if not, nothing is written.
+fn reencrypt_audio_file_to(
+    path: &Path,
+    dest: &Path,
+    from: Option<&Keys>,
+    to: Option<&Keys>,
+) -> Result<bool> {
+    let encrypted = is_encrypted_file(path)?;
+    if !encrypted && to.is_none() {
+        return Ok(false);
+    }
+    if encrypted && to.is_some_and(|keys| opens_with(keys, path)) {
+        return Ok(false);
+    }
+
+    let file = BufReader::new(File::open(path)?);
+    let mut input: Box<dyn Read> = if encrypted {
+        let from = from.ok_or_else(|| anyhow!("No key to decrypt {:?}", path))?;
+        Box::new(DecryptingReader::new(from, file)?)
+    } else {
+        Box::new(file)
+    };
+
+    replace_file(dest, |out| match to {
+        Some(keys) => encrypt_stream(keys, input, out),
+        None => {
+            io::copy(&mut input, out)?;
+            Ok(())
+        }
+    })?;
+    Ok(true)
+}
+
+/// Re-encrypt every file in the audio directory. If one fails, files already
+/// rewritten are moved back so the directory stays under a single key.
+pub(crate) fn reencrypt_audio_dir(
+    dir: &Path,
+    from: Option<&Keys>,
+    to: Option<&Keys>,
+) -> Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut done: Vec<PathBuf> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !is_recording(&path) {
+            continue;
+        }
+        match reencrypt_audio_file(&path, from, to) {
+            Ok(true) => done.push(path),
+            Ok(false) => {}
+            Err(e) => {
+                for path in &done {
+                    if let Err(e) = reencrypt_audio_file(path, to, from) {
+                        eprintln!("[Encryption] Failed to restore {:?}: {}", path, e);
+                    }
+                }
+                return Err(anyhow!("Failed to re-encrypt {:?}: {}", path, e));
+            }
+        }
+    }
+    Ok(done.len())
+}
+
+/// Whether a file in the audio directory is a recording, not a file being
+/// written or a re-encrypted copy
+fn is_recording(path: &Path) -> bool {
+    path.is_file()
+        && !path
+            .extension()
+            .is_some_and(|e| e == "tmp" || e == REKEY_EXTENSION)
+}
+
+/// Path of a recording's re-encrypted copy
+fn rekeyed_copy(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", REKEY_EXTENSION));
+    path.with_file_name(name)
+}
+
+/// Write a re-encrypted copy beside each recording that needs one, leaving
+/// the recordings untouched. If one fails, the copies are removed.
+fn write_rekeyed_audio(dir: &Path, from: Option<&Keys>, to: Option<&Keys>) -> Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !is_recording(&path) {
+            continue;
+        }
+        match reencrypt_audio_file_to(&path, &rekeyed_copy(&path), from, to) {
+            Ok(true) => count += 1,
+            Ok(false) => {}
+            Err(e) => {
+                if let Err(e) = remove_rekeyed_audio(dir) {
+                    eprintln!("[Encryption] Failed to remove re-encrypted copies: {}", e);
+                }
+                return Err(anyhow!("Failed to re-encrypt {:?}: {}", path, e));
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// The re-encrypted copies in the audio directory
+fn rekeyed_audio(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut copies = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == REKEY_EXTENSION) {
+            copies.push(path);
+        }
+    }
+    Ok(copies)
+}
+
+fn remove_rekeyed_audio(dir: &Path) -> Result<()> {
+    for copy in rekeyed_audio(dir)? {
+        std::fs::remove_file(copy)?;
+    }
+    Ok(())
+}
+
+/// Move each re-encrypted copy over its recording
+fn install_rekeyed_audio(dir: &Path) -> Result<()> {
+    for copy in rekeyed_audio(dir)? {
+        std::fs::rename(&copy, copy.with_extension(""))?;
+    }
+    Ok(())
+}
+
+/// Finish a passphrase change whose journal was committed, or drop one that
+/// wasn't, so the config, database and recordings agree again. Needs no
+/// keys. Returns whether a committed change was finished.
+fn settle_rekey(dir: &Path, audio_dir: &Path) -> Result<bool> {
+    let Some(journal) = load_journal(dir)? else {
+        return Ok(false);
+    };
+    let db_path = database::db_path_in(dir);
+    let db_path = db_path.to_string_lossy();
+    let db_copy = database::rekeyed_copy_path(&db_path);
+
+    if journal.committed {
+        if Path::new(&db_copy).exists() {
+            database::install_database_file(&db_path, &db_copy)?;
+        }
+        install_rekeyed_audio(audio_dir)?;
+        save_config(dir, journal.target.as_ref())?;
+    } else {
+        if Path::new(&db_copy).exists() {
+            std::fs::remove_file(&db_copy)?;
+        }
+        remove_rekeyed_audio(audio_dir)?;
+    }
+    std::fs::remove_file(dir.join(JOURNAL_FILE))?;
+    Ok(journal.committed)
+}
+
+/// Finish or drop a passphrase change cut short by a crash or kill. Runs at
+/// startup, before the store is opened.
+pub fn recover_rekey(app_data_dir: &Path) -> Result<()> {
+    if !app_data_dir.join(JOURNAL_FILE).exists() {
+        return Ok(());
+    }
+    let finished = settle_rekey(app_data_dir, &app_data_dir.join("audio"))?;
+    println!(
+        "[Encryption] {} an interrupted passphrase change",
+        if finished { "Finished" } else { "Dropped" }
+    );
+    Ok(())
+}
+
+/// Set up the directory for decrypted copies of recordings under app data.
+/// Copies are normally deleted when done with, so any found here were left by
+/// a crash or kill and are deleted.
+pub fn init_decrypted_dir(app_data_dir: &Path) -> Result<()> {
+    let dir = app_data_dir.join("decrypted");
+    prepare_decrypted_dir(&dir)?;
+    let _ = DECRYPTED_AUDIO_DIR.set(dir);
+    Ok(())
+}
+
+/// Empty (or create) a directory only the user can open
+fn prepare_decrypted_dir(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// An audio file readable by external tools: the original if it is
+/// plaintext, otherwise a private copy under app data deleted on drop
+pub struct DecryptedAudio {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl DecryptedAudio {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DecryptedAudio {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Get a plaintext path for an audio file, for tools that need to open it themselves
+pub fn decrypted_audio(path: &Path) -> Result<DecryptedAudio> {
+    if !is_encrypted_file(path)? {
+        return Ok(DecryptedAudio {
+            path: path.to_path_buf(),
+            temporary: false,
+        });
+    }
+
+    let keys = require_keys()?;
+    let dir = DECRYPTED_AUDIO_DIR
+        .get()
+        .ok_or_else(|| anyhow!("Decrypted audio directory is not set up"))?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("wav");
+    let tmp = dir.join(format!("{}.{}", uuid::Uuid::new_v4(), ext));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let decrypted = DecryptedAudio {
+        path: tmp,
+        temporary: true,
+    };
+    let mut out = BufWriter::new(options.open(decrypted.path())?);
+    let mut reader = DecryptingReader::new(&keys, BufReader::new(File::open(path)?))?;
+    io::copy(&mut reader, &mut out)?;
+    out.flush()?;
+
+    Ok(decrypted)
+}
+
+// ============================================================================
+// Passphrase management
+// ============================================================================
+
+/// Whether a passphrase has been set
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app_data_dir(app).is_ok_and(|dir| dir.join(CONFIG_FILE).exists())
+}
+
+pub fn status(app: &AppHandle) -> EncryptionStatus {
+    let enabled = is_enabled(app);
+    EncryptionStatus {
+        enabled,
+        unlocked: !enabled || current_keys().is_some(),
+    }
+}
+
+/// Run key derivation or file re-encryption off the async runtime
+pub(crate) async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| anyhow!("Encryption task failed: {}", e))?
+}
+
+/// Unlock an encrypted store at startup, then open the database and start the job queue
+pub async fn unlock(app: &AppHandle, passphrase: &str) -> Result<()> {
+    if database::is_initialized() {
+        return Ok(());
+    }
+
+    let dir = app_data_dir(app)?;
+    let config = load_config(&dir)?.ok_or_else(|| anyhow!("Encryption is not enabled"))?;
+    let passphrase = passphrase.to_string();
+    let keys = run_blocking(move || open_config(&passphrase, &config)).await?;
+
+    set_keys(Some(Arc::new(keys)));
+    if let Err(e) = database::init_database(app).await {
+        set_keys(None);
+        return Err(e);
+    }
+    println!("[Encryption] Store unlocked");
+
+    jobs::start(app.clone());
+    trash::start(app.clone());
+    retention::start(app.clone());
+    Ok(())
+}
+
+/// Turn on encryption and encrypt the existing database and recordings
+pub async fn set_passphrase(app: &AppHandle, passphrase: &str) -> Result<()> {
+    validate_passphrase(passphrase)?;
+    if is_enabled(app) {
+        return Err(anyhow!("A passphrase is already set. Change it instead."));
+    }
+
+    let passphrase = passphrase.to_string();
+    let (config, keys) =
+        run_blocking(move || new_config(&passphrase, KDF_MEMORY_KIB, KDF_ITERATIONS)).await?;
+    rekey(app, Some((config, Arc::new(keys)))).await
+}
+
+/// Re-encrypt everything under a new passphrase
+pub async fn change_passphrase(app: &AppHandle, current: &str, new: &str) -> Result<()> {
+    validate_passphrase(new)?;
+    verify_current(app, current).await?;
+
+    let new = new.to_string();
+    let (config, keys) =
+        run_blocking(move || new_config(&new, KDF_MEMORY_KIB, KDF_ITERATIONS)).await?;
+    rekey(app, Some((config, Arc::new(keys)))).await
+}
+
+/// Turn off encryption, decrypting the database and recordings
+pub async fn remove_passphrase(app: &AppHandle, current: &str) -> Result<()> {
+    verify_current(app, current).await?;
+    rekey(app, None).await
+}
+
+async fn verify_current(app: &AppHandle, passphrase: &str) -> Result<()> {
+    let dir = app_data_dir(app)?;
+    let config = load_config(&dir)?.ok_or_else(|| anyhow!("Encryption is not enabled"))?;
+    if current_keys().is_none() {
+        return Err(anyhow!("Unlock the database first"));
+    }
+    let passphrase = passphrase.to_string();
+    run_blocking(move || open_config(&passphrase, &config).map(|_| ())).await
+}
+
+/// Move the recordings and database to new keys (None = plaintext) and
+/// record the new config. The target config is journaled first, then
+/// re-encrypted copies of the recordings and database are written beside
+/// them. Committing the journal is the point of no return: the copies are
+/// moved into place after it, and until it they are simply dropped on failure.
+async fn rekey(app: &AppHandle, target: Option<(EncryptionConfig, Arc<Keys>)>) -> Result<()> {
+    let _lock = REKEY_LOCK.write().await;
+
+    // Running jobs hold the current pool, which is closed during the swap
+    let pool = database::get_pool()?;
+    if !jobs::list_jobs(pool, Some("running")).await?.is_empty() {
+        return Err(anyhow!(
+            "Wait for background jobs to finish before changing encryption"
+        ));
+    }
+    // Live sessions save segments through the pool and recordings go into
+    // the audio directory as they run
+    if system_audio::is_recording() || !streaming_transcription::active_session_ids().is_empty() {
+        return Err(anyhow!(
+            "Stop recording and live transcription before changing encryption"
+        ));
+    }
+
+    let dir = app_data_dir(app)?;
+    let audio_dir = audio::get_audio_dir(app)?;
+    let old_keys = current_keys();
+    let new_keys = target.as_ref().map(|(_, keys)| keys.clone());
+
+    let journal = RekeyJournal {
+        target: target.as_ref().map(|(config, _)| config.clone()),
+        committed: false,
+    };
+    save_journal(&dir, &journal)?;
+
+    println!("[Encryption] Re-encrypting recordings...");
+    let copied = {
+        let (audio_dir, from, to) = (audio_dir.clone(), old_keys.clone(), new_keys.clone());
+        run_blocking(move || write_rekeyed_audio(&audio_dir, from.as_deref(), to.as_deref())).await
+    };
+    let count = match copied {
+        Ok(count) => count,
+        Err(e) => {
+            if let Err(settle) = settle_rekey(&dir, &audio_dir) {
+                eprintln!(
+                    "[Encryption] Failed to drop the passphrase change: {}",
+                    settle
+                );
+            }
+            return Err(e);
+        }
+    };
+    println!("[Encryption] Re-encrypted {} recording(s)", count);
+
+    let result = database::rekey_database(app, new_keys.as_deref(), || {
+        save_journal(
+            &dir,
+            &RekeyJournal {
+                committed: true,
+                ..journal.clone()
+            },
+        )
+    })
+    .await;
+
+    // Moves the recordings into place if the journal was committed, or
+    // drops their copies if not
+    let settled = {
+        let (dir, audio_dir) = (dir.clone(), audio_dir.clone());
+        run_blocking(move || settle_rekey(&dir, &audio_dir)).await
+    };
+    let committed = match &result {
+        Ok(()) => true,
+        Err(_) => matches!(settled, Ok(true)),
+    };
+    if committed {
+        set_keys(new_keys);
+    }
+    if let Err(e) = result {
+        eprintln!("[Encryption] Database re-encryption failed: {}", e);
+        return Err(e);
+    }
+    settled.map_err(|e| {
+        anyhow!(
+            "The passphrase change will finish when the app restarts: {}",
+            e
+        )
+    })?;
+
+    println!(
+        "[Encryption] Encryption {}",
+        if target.is_some() {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::search;
+
+    /// Cheap KDF settings so tests run quickly
+    fn test_config(passphrase: &str) -> (EncryptionConfig, Keys) {
+        new_config(passphrase, 64, 1).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pt-encryption-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn encrypt_to_vec(keys: &Keys, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        encrypt_stream(keys, data, &mut out).unwrap();
+        out
+    }
+
+    fn decrypt_vec(keys: &Keys, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut reader = DecryptingReader::new(keys, data).map_err(io::Error::other)?;
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_passphrase_verification() {
+        let (config, keys) = test_config("correct horse");
+
+        let reopened = open_config("correct horse", &config).unwrap();
+        assert_eq!(reopened.database_key_pragma(), keys.database_key_pragma());
+        assert!(open_config("wrong horse", &config).is_err());
+    }
+
+    #[test]
+    fn test_database_key_pragma_is_raw_hex_key() {
+        let (_, keys) = test_config("passphrase");
+        let pragma = keys.database_key_pragma();
+
+        assert!(pragma.starts_with("\"x'") && pragma.ends_with("'\""));
+        assert_eq!(pragma.len(), 64 + 5);
+    }
+
+    #[test]
+    fn test_audio_round_trip_across_chunk_boundaries() {
+        let (_, keys) = test_config("passphrase");
+
+        for len in [0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, CHUNK_SIZE * 2 + 17] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let encrypted = encrypt_to_vec(&keys, &data);
+
+            assert!(encrypted.starts_with(AUDIO_MAGIC));
+            assert_eq!(decrypt_vec(&keys, &encrypted).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_tampered_or_truncated_audio_is_rejected() {
+        let (_, keys) = test_config("passphrase");
+        let data = vec![7u8; CHUNK_SIZE + 100];
+        let encrypted = encrypt_to_vec(&keys, &data);
+
+        let mut tampered = encrypted.clone();
+        tampered[40] ^= 1;
+        assert!(decrypt_vec(&keys, &tampered).is_err());
+
+        // Dropping the final chunk must not look like a shorter valid file
+        let truncated = &encrypted[..AUDIO_MAGIC.len() + STREAM_NONCE_LEN + CHUNK_SIZE + TAG_LEN];
+        assert!(decrypt_vec(&keys, truncated).is_err());
+
+        let (_, other) = test_config("another one");
+        assert!(decrypt_vec(&other, &encrypted).is_err());
+    }
+
+    #[test]
+    fn test_reencrypt_audio_dir() {
+        let dir = temp_dir("rekey");
+        let path = dir.join("session.wav");
+        let data = b"RIFF fake wav data".repeat(1000);
+        std::fs::write(&path, &data).unwrap();
+
+        let (_, first) = test_config("first passphrase");
+        let (_, second) = test_config("second passphrase");
+
+        assert_eq!(reencrypt_audio_dir(&dir, None, Some(&first)).unwrap(), 1);
+        assert!(is_encrypted_file(&path).unwrap());
+        assert!(opens_with(&first, &path));
+
+        assert_eq!(
+            reencrypt_audio_dir(&dir, Some(&first), Some(&second)).unwrap(),
+            1
+        );
+        assert!(opens_with(&second, &path));
+        // Already under the new key, so a retry is a no-op
+        assert_eq!(
+            reencrypt_audio_dir(&dir, Some(&first), Some(&second)).unwrap(),
+            0
+        );
+
+        assert_eq!(reencrypt_audio_dir(&dir, Some(&second), None).unwrap(), 1);
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_reencrypt_rolls_back_on_failure() {
+        let dir = temp_dir("rollback");
+        let (_, first) = test_config("first passphrase");
+        let (_, stranger) = test_config("unrelated passphrase");
+
+        // One file under the current key and one under a key we don't have
+        std::fs::write(dir.join("a.wav"), b"plain audio").unwrap();
+        assert!(reencrypt_audio_file(&dir.join("a.wav"), None, Some(&first)).unwrap());
+        let mut foreign = File::create(dir.join("b.wav")).unwrap();
+        encrypt_stream(&stranger, &b"foreign audio"[..], &mut foreign).unwrap();
+
+        let (_, second) = test_config("second passphrase");
+        assert!(reencrypt_audio_dir(&dir, Some(&first), Some(&second)).is_err());
+
+        // a.wav is back under the first key
+        assert!(opens_with(&first, &dir.join("a.wav")));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// An app data directory halfway through turning encryption on: the
+    /// journal and the re-encrypted copies are written, nothing is moved yet
+    fn half_done_rekey(name: &str, committed: bool) -> (PathBuf, EncryptionConfig, Keys) {
+        let dir = temp_dir(name);
+        let audio_dir = dir.join("audio");
+        std::fs::create_dir_all(&audio_dir).unwrap();
+        std::fs::write(audio_dir.join("a.wav"), b"plain audio").unwrap();
+        let db_path = database::db_path_in(&dir);
+        std::fs::write(&db_path, b"old database").unwrap();
+
+        let (config, keys) = test_config("new passphrase");
+        let journal = RekeyJournal {
+            target: Some(config.clone()),
+            committed,
+        };
+        save_journal(&dir, &journal).unwrap();
+        assert_eq!(
+            write_rekeyed_audio(&audio_dir, None, Some(&keys)).unwrap(),
+            1
+        );
+        std::fs::write(
+            database::rekeyed_copy_path(&db_path.to_string_lossy()),
+            b"new database",
+        )
+        .unwrap();
+        (dir, config, keys)
+    }
+
+    #[test]
+    fn test_recover_rekey_drops_an_uncommitted_change() {
+        let (dir, _, _) = half_done_rekey("uncommitted", false);
+        let audio_dir = dir.join("audio");
+
+        recover_rekey(&dir).unwrap();
+        assert_eq!(
+            std::fs::read(audio_dir.join("a.wav")).unwrap(),
+            b"plain audio"
+        );
+        assert!(rekeyed_audio(&audio_dir).unwrap().is_empty());
+        assert_eq!(
+            std::fs::read(database::db_path_in(&dir)).unwrap(),
+            b"old database"
+        );
+        assert!(load_config(&dir).unwrap().is_none());
+        assert!(load_journal(&dir).unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_recover_rekey_finishes_a_committed_change() {
+        let (dir, config, keys) = half_done_rekey("committed", true);
+        let audio_dir = dir.join("audio");
+
+        recover_rekey(&dir).unwrap();
+        assert!(opens_with(&keys, &audio_dir.join("a.wav")));
+        assert!(rekeyed_audio(&audio_dir).unwrap().is_empty());
+        assert_eq!(
+            std::fs::read(database::db_path_in(&dir)).unwrap(),
+            b"new database"
+        );
+        assert_eq!(load_config(&dir).unwrap().unwrap().salt, config.salt);
+        assert!(load_journal(&dir).unwrap().is_none());
+
+        // Nothing left to do
+        recover_rekey(&dir).unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prepare_decrypted_dir_deletes_leftovers() {
+        let dir = temp_dir("decrypted").join("decrypted");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("left-by-crash.wav"), b"plaintext audio").unwrap();
+
+        prepare_decrypted_dir(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_finds_the_same_session_after_rekey() {
+        let dir = temp_dir("search");
+        let plain = dir.join("plain.db");
+        let pool = database::open_pool(plain.to_str().unwrap(), None)
+            .await
+            .unwrap();
+        crate::db::migrations::run_pending_migrations(&pool)
+            .await
+            .unwrap();
+        for sql in [
+            "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Test', 'general', 0, 0)",
+            "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('folder', 'ws', 'Test', 0, 0)",
+            "INSERT INTO sessions (id, folder_id, title, audio_path, transcript, created_at, updated_at) VALUES ('first', 'folder', 'First', '/a.wav', 'budget review', 0, 0)",
+            "INSERT INTO sessions (id, folder_id, title, audio_path, transcript, created_at, updated_at) VALUES ('second', 'folder', 'Second', '/b.wav', 'sleep hygiene', 0, 0)",
+            // Leaves a gap in the rowids, which the export closes up
+            "DELETE FROM sessions WHERE id = 'first'",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        let found = search::search_sessions(&pool, "sleep", &[], 10)
+            .await
+            .unwrap();
+        assert_eq!(found[0].session.id, "second");
+        pool.close().await;
+
+        let (_, keys) = test_config("passphrase");
+        let encrypted = dir.join("encrypted.db");
+        database::export_database(
+            plain.to_str().unwrap(),
+            None,
+            encrypted.to_str().unwrap(),
+            Some(&keys),
+        )
+        .await
+        .unwrap();
+
+        let pool = database::open_pool(encrypted.to_str().unwrap(), Some(&keys))
+            .await
+            .unwrap();
+        let found = search::search_sessions(&pool, "sleep", &[], 10)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].session.id, "second");
+        assert!(search::search_sessions(&pool, "budget", &[], 10)
+            .await
+            .unwrap()
+            .is_empty());
+        pool.close().await;
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
 use std::path::PathBuf;
 
+use super::audit::{self, AuditAction, AuditEvent};
+use super::database;
+
 /// Get the exports directory
-fn get_exports_dir() -> Result<PathBuf> {
+pub(crate) fn get_exports_dir() -> Result<PathBuf> {
     let downloads = dirs::download_dir()
         .or_else(dirs::desktop_dir)
         .or_else(dirs::home_dir)
@@ -11,6 +14,34 @@ fn get_exports_dir() -> Result<PathBuf> {
     Ok(downloads)
 }
 
+/// Record in the audit log that a session was exported to `destination`
+pub async fn record_export(session_id: &str, format: &str, destination: &str) -> Result<()> {
+    let event = AuditEvent::session(AuditAction::Export, session_id)
+        .with_details(serde_json::json!({ "format": format, "destination": destination }));
+    audit::record(database::get_pool()?, event).await?;
+    Ok(())
+}
+
+/// Tags for an Obsidian note: the session's own tags followed by the user's
+/// default `tags`, without repeats
+pub async fn obsidian_tags(session_id: Option<&str>, tags: Vec<String>) -> Result<Vec<String>> {
+    let mut all = match session_id {
+        Some(id) => {
+            database::fetch_session(database::get_pool()?, id)
+                .await?
+                .tags
+        }
+        None => Vec::new(),
+    };
+    all.extend(tags);
+    Ok(super::tags::normalize(&all))
+}
+
+/// Obsidian tags can't contain spaces, so "needs review" becomes "needs-review"
+fn obsidian_tag(tag: &str) -> String {
+    tag.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
 /// Parse markdown content into title, transcript, and notes sections
 fn parse_content(content: &str) -> (String, String, String) {
     let mut title = String::new();
@@ -60,6 +91,23 @@ pub async fn export_markdown(content: &str, filename: &str) -> Result<String> {
     Ok(path)
 }
 
+/// Export subtitles the frontend built from the transcript segments.
+/// `format` is "srt" or "vtt".
+pub async fn export_subtitles(content: &str, filename: &str, format: &str) -> Result<String> {
+    if !matches!(format, "srt" | "vtt") {
+        return Err(anyhow::anyhow!("Unknown subtitle format: {}", format));
+    }
+    println!("[Export] Exporting {}: {}", format, filename);
+    let exports_dir = get_exports_dir()?;
+    let file_path = exports_dir.join(format!("{}.{}", filename, format));
+
+    tokio::fs::write(&file_path, content).await?;
+
+    let path = file_path.to_string_lossy().to_string();
+    println!("[Export] Subtitles exported to: {}", path);
+    Ok(path)
+}
+
 /// Export content as PDF using printpdf
 pub async fn export_pdf(content: &str, filename: &str) -> Result<String> {
     use printpdf::*;
@@ -244,7 +292,7 @@ pub async fn export_to_obsidian(
         "  - transcript".to_string()
     } else {
         tags.iter()
-            .map(|t| format!("  - {}", t))
+            .map(|t| format!("  - {}", obsidian_tag(t)))
             .collect::<Vec<_>>()
             .join("\n")
     };
@@ -305,6 +353,21 @@ mod tests {
         let _ = fs::remove_file(&path);
     }
 
+    #[tokio::test]
+    async fn test_export_subtitles() {
+        let content = "1\n00:00:00,000 --> 00:00:01,000\nHello\n";
+        let path = export_subtitles(content, "test_subtitles", "srt")
+            .await
+            .unwrap();
+        assert!(path.ends_with(".srt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        let _ = fs::remove_file(&path);
+
+        assert!(export_subtitles(content, "test_subtitles", "txt")
+            .await
+            .is_err());
+    }
+
     #[test]
     fn test_parse_content() {
         let content = "# My Title\n\n## Transcript\n\nLine 1\nLine 2\n\n## Notes\n\nNote 1\nNote 2";
@@ -559,6 +622,12 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
 
+    #[test]
+    fn test_obsidian_tag() {
+        assert_eq!(obsidian_tag("needs review"), "needs-review");
+        assert_eq!(obsidian_tag(" intake "), "intake");
+    }
+
     #[test]
     fn test_get_exports_dir() {
         let result = get_exports_dir();
@@ -0,0 +1,553 @@
+//! Folder metadata schemas
+//!
+//! A workspace type can declare what its folders record, such as a client's
+//! date of birth or a case's docket number, as a JSON schema in
+//! `workspace_types.metadata_schema`. Folder metadata is checked against it on
+//! every write, can be searched field by field (`folders::find_by_metadata`),
+//! and fills `{folder.<field>}` placeholders in note templates.
+//!
+//! Only a flat subset of JSON Schema is understood: an object whose
+//! `properties` are strings, numbers, integers or booleans, each with an
+//! optional `title`, `enum`, `format: "date"`, `minimum`, `maximum` or
+//! `maxLength`, plus the object's `required` list and `additionalProperties`.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use sqlx::sqlite::SqlitePool;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    field_type: FieldType,
+    /// `format: "date"`: a YYYY-MM-DD string
+    date: bool,
+    options: Option<Vec<Value>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    max_length: Option<u64>,
+}
+
+/// A parsed metadata schema
+#[derive(Debug, Clone)]
+pub struct MetadataSchema {
+    fields: Vec<Field>,
+    required: Vec<String>,
+    additional_properties: bool,
+}
+
+/// Field names double as JSON paths and template placeholders, so they are
+/// limited to ASCII letters, digits and underscores
+pub fn is_field_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn number(spec: &Map<String, Value>, name: &str, keyword: &str) -> Result<Option<f64>> {
+    match spec.get(keyword) {
+        None => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .map(Some)
+            .ok_or_else(|| anyhow!("Field {}: {} must be a number", name, keyword)),
+    }
+}
+
+impl Field {
+    fn parse(name: &str, spec: &Value) -> Result<Self> {
+        if !is_field_name(name) {
+            return Err(anyhow!(
+                "Metadata field names may only use letters, digits and underscores: {}",
+                name
+            ));
+        }
+        let spec = spec
+            .as_object()
+            .ok_or_else(|| anyhow!("Field {} must be described by an object", name))?;
+        let field_type = match spec.get("type").and_then(Value::as_str) {
+            Some("string") => FieldType::String,
+            Some("number") => FieldType::Number,
+            Some("integer") => FieldType::Integer,
+            Some("boolean") => FieldType::Boolean,
+            _ => {
+                return Err(anyhow!(
+                    "Field {} must be a string, number, integer or boolean",
+                    name
+                ))
+            }
+        };
+
+        let max_length = match spec.get("maxLength") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_u64()
+                    .ok_or_else(|| anyhow!("Field {}: maxLength must be a whole number", name))?,
+            ),
+        };
+
+        let mut field = Field {
+            name: name.to_string(),
+            field_type,
+            date: field_type == FieldType::String
+                && spec.get("format").and_then(Value::as_str) == Some("date"),
+            options: None,
+            minimum: number(spec, name, "minimum")?,
+            maximum: number(spec, name, "maximum")?,
+            max_length,
+        };
+        if let Some(options) = spec.get("enum") {
+            let options = options
+                .as_array()
+                .filter(|options| !options.is_empty())
+                .ok_or_else(|| anyhow!("Field {}: enum must list at least one value", name))?;
+            for option in options {
+                field
+                    .check(option)
+                    .map_err(|problem| anyhow!("Field {}: enum value {}", name, problem))?;
+            }
+            field.options = Some(options.clone());
+        }
+        Ok(field)
+    }
+
+    /// Why `value` doesn't fit this field, if it doesn't. Null counts as no
+    /// value.
+    fn check(&self, value: &Value) -> std::result::Result<(), String> {
+        if value.is_null() {
+            return Ok(());
+        }
+        let fits = match self.field_type {
+            FieldType::String => value.is_string(),
+            FieldType::Number => value.is_number(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::Boolean => value.is_boolean(),
+        };
+        if !fits {
+            let expected = match self.field_type {
+                FieldType::String => "text",
+                FieldType::Number => "a number",
+                FieldType::Integer => "a whole number",
+                FieldType::Boolean => "true or false",
+            };
+            return Err(format!("{} must be {}", self.name, expected));
+        }
+
+        if let Some(text) = value.as_str() {
+            if self.date && chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_err() {
+                return Err(format!("{} must be a date like 2024-01-31", self.name));
+            }
+            if let Some(max) = self.max_length {
+                if text.chars().count() as u64 > max {
+                    return Err(format!("{} must be at most {} characters", self.name, max));
+                }
+            }
+        }
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = self.minimum.filter(|min| n < *min) {
+                return Err(format!("{} must be at least {}", self.name, min));
+            }
+            if let Some(max) = self.maximum.filter(|max| n > *max) {
+                return Err(format!("{} must be at most {}", self.name, max));
+            }
+        }
+        if let Some(options) = &self.options {
+            if !options.contains(value) {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                return Err(format!(
+                    "{} must be one of {}",
+                    self.name,
+                    options.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MetadataSchema {
+    pub fn parse(schema: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(schema)
+            .map_err(|e| anyhow!("Metadata schema is not valid JSON: {}", e))?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| anyhow!("Metadata schema must be a JSON object"))?;
+        if object.get("type").is_some_and(|kind| kind != "object") {
+            return Err(anyhow!("Metadata schema must describe an object"));
+        }
+
+        let mut fields = Vec::new();
+        if let Some(properties) = object.get("properties") {
+            let properties = properties
+                .as_object()
+                .ok_or_else(|| anyhow!("Metadata schema properties must be an object"))?;
+            for (name, spec) in properties {
+                fields.push(Field::parse(name, spec)?);
+            }
+        }
+
+        let required: Vec<String> = match object.get("required") {
+            None => Vec::new(),
+            Some(names) => names
+                .as_array()
+                .and_then(|names| {
+                    names
+                        .iter()
+                        .map(|name| name.as_str().map(str::to_string))
+                        .collect()
+                })
+                .ok_or_else(|| anyhow!("Metadata schema required must list field names"))?,
+        };
+        if let Some(name) = required
+            .iter()
+            .find(|name| !fields.iter().any(|f| &f.name == *name))
+        {
+            return Err(anyhow!(
+                "Required field {} is not in the schema's properties",
+                name
+            ));
+        }
+
+        let additional_properties = match object.get("additionalProperties") {
+            None => true,
+            Some(Value::Bool(allowed)) => *allowed,
+            Some(_) => return Err(anyhow!("additionalProperties must be true or false")),
+        };
+
+        Ok(Self {
+            fields,
+            required,
+            additional_properties,
+        })
+    }
+
+    /// Names of the declared fields
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|f| f.name.as_str())
+    }
+
+    /// Check metadata against the schema, reporting every problem at once
+    pub fn validate(&self, metadata: &Value) -> Result<()> {
+        let object = metadata
+            .as_object()
+            .ok_or_else(|| anyhow!("Folder metadata must be a JSON object"))?;
+
+        let mut problems = Vec::new();
+        for name in &self.required {
+            let missing = match object.get(name) {
+                None | Some(Value::Null) => true,
+                Some(Value::String(text)) => text.trim().is_empty(),
+                Some(_) => false,
+            };
+            if missing {
+                problems.push(format!("{} is required", name));
+            }
+        }
+        for (name, value) in object {
+            match self.fields.iter().find(|f| &f.name == name) {
+                Some(field) => {
+                    if let Err(problem) = field.check(value) {
+                        problems.push(problem);
+                    }
+                }
+                None if !self.additional_properties => {
+                    problems.push(format!("{} is not a field of this workspace type", name))
+                }
+                None => {}
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Invalid folder metadata: {}", problems.join("; ")))
+        }
+    }
+}
+
+/// Schema of a workspace's type, if the type declares one
+pub async fn schema_for_workspace(
+    pool: &SqlitePool,
+    workspace_id: &str,
+) -> Result<Option<MetadataSchema>> {
+    let schema: Option<Option<String>> = sqlx::query_scalar(
+        r#"
+        SELECT t.metadata_schema
+        FROM workspaces w
+        JOIN workspace_types t ON t.id = w.workspace_type
+        WHERE w.id = ?
+        "#,
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match schema {
+        None => Err(anyhow!("Workspace not found: {}", workspace_id)),
+        Some(None) => Ok(None),
+        Some(Some(schema)) => MetadataSchema::parse(&schema).map(Some),
+    }
+}
+
+/// Check a folder's metadata against its workspace type's schema and return
+/// what to store. Without a schema the metadata is kept as given; with one it
+/// must be a JSON object, stored compactly, and no metadata at all counts as
+/// an empty object.
+pub async fn validate(
+    pool: &SqlitePool,
+    workspace_id: &str,
+    metadata: Option<&str>,
+) -> Result<Option<String>> {
+    let schema = match schema_for_workspace(pool, workspace_id).await? {
+        Some(schema) => schema,
+        None => return Ok(metadata.map(str::to_string)),
+    };
+
+    let value = match metadata.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(text) => serde_json::from_str(text)
+            .map_err(|e| anyhow!("Folder metadata is not valid JSON: {}", e))?,
+        None => Value::Object(Map::new()),
+    };
+    schema.validate(&value)?;
+
+    let empty = value.as_object().is_some_and(Map::is_empty);
+    Ok((!empty).then(|| value.to_string()))
+}
+
+fn variable_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Template variables for a session's folder: `name`, every field of the
+/// type's schema (empty when not filled in) and any other metadata fields
+pub async fn template_variables(
+    pool: &SqlitePool,
+    session_id: &str,
+) -> Result<BTreeMap<String, String>> {
+    let row: Option<(String, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT f.name, f.metadata, t.metadata_schema
+        FROM sessions s
+        JOIN folders f ON f.id = s.folder_id
+        JOIN workspaces w ON w.id = f.workspace_id
+        JOIN workspace_types t ON t.id = w.workspace_type
+        WHERE s.id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let mut variables = BTreeMap::new();
+    let (name, metadata, schema) = match row {
+        Some(row) => row,
+        None => return Ok(variables),
+    };
+
+    // A schema edited since is not a reason to fail note generation
+    if let Some(schema) = schema.and_then(|schema| MetadataSchema::parse(&schema).ok()) {
+        for field in schema.field_names() {
+            variables.insert(field.to_string(), String::new());
+        }
+    }
+    if let Some(Value::Object(fields)) =
+        metadata.and_then(|metadata| serde_json::from_str(&metadata).ok())
+    {
+        for (field, value) in fields.iter().filter(|(field, _)| is_field_name(field)) {
+            variables.insert(field.clone(), variable_text(value));
+        }
+    }
+    variables.insert("name".to_string(), name);
+    Ok(variables)
+}
+
+/// Replace `{folder.<field>}` placeholders; unknown fields become empty
+fn fill_folder_variables(text: &str, variables: &BTreeMap<String, String>) -> String {
+    const OPEN: &str = "{folder.";
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + OPEN.len()..];
+        match after.find('}').filter(|end| is_field_name(&after[..*end])) {
+            Some(end) => {
+                let value = variables.get(&after[..end]);
+                filled.push_str(value.map(String::as_str).unwrap_or_default());
+                rest = &after[end + 1..];
+            }
+            None => {
+                filled.push_str(OPEN);
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Build a note prompt from a template: `{transcript}` becomes the
+/// transcript and `{folder.<field>}` the folder's metadata. The transcript
+/// itself is never scanned for placeholders.
+pub fn fill_template(
+    prompt: &str,
+    transcript: &str,
+    variables: &BTreeMap<String, String>,
+) -> String {
+    prompt
+        .split("{transcript}")
+        .map(|part| fill_folder_variables(part, variables))
+        .collect::<Vec<_>>()
+        .join(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::migrated_pool;
+    use serde_json::json;
+
+    const CASE_SCHEMA: &str = r#"{
+        "type": "object",
+        "properties": {
+            "docket_number": { "type": "string", "title": "Docket number", "maxLength": 20 },
+            "filed_on": { "type": "string", "format": "date" },
+            "court": { "type": "string", "enum": ["district", "appeals"] },
+            "witnesses": { "type": "integer", "minimum": 0 },
+            "sealed": { "type": "boolean" }
+        },
+        "required": ["docket_number"],
+        "additionalProperties": false
+    }"#;
+
+    #[test]
+    fn test_parse_rejects_unsupported_schemas() {
+        assert!(MetadataSchema::parse(CASE_SCHEMA).is_ok());
+        assert!(MetadataSchema::parse(r#"{"type":"object"}"#).is_ok());
+
+        for bad in [
+            "[1, 2]",
+            r#"{"type": "array"}"#,
+            r#"{"properties": {"parties": {"type": "array"}}}"#,
+            r#"{"properties": {"docket number": {"type": "string"}}}"#,
+            r#"{"properties": {"court": {"type": "string", "enum": [1]}}}"#,
+            r#"{"properties": {}, "required": ["missing"]}"#,
+        ] {
+            assert!(MetadataSchema::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let schema = MetadataSchema::parse(CASE_SCHEMA).unwrap();
+
+        schema
+            .validate(&json!({
+                "docket_number": "1:24-cv-00042",
+                "filed_on": "2024-03-01",
+                "court": "district",
+                "witnesses": 3,
+                "sealed": null
+            }))
+            .unwrap();
+
+        let error = schema
+            .validate(&json!({
+                "filed_on": "March 1st",
+                "court": "supreme",
+                "witnesses": -1,
+                "sealed": "no",
+                "judge": "Lee"
+            }))
+            .unwrap_err()
+            .to_string();
+        for problem in [
+            "docket_number is required",
+            "filed_on must be a date",
+            "court must be one of",
+            "witnesses must be at least 0",
+            "sealed must be true or false",
+            "judge is not a field",
+        ] {
+            assert!(
+                error.contains(problem),
+                "{} missing from {}",
+                problem,
+                error
+            );
+        }
+
+        assert!(schema.validate(&json!(["not", "an", "object"])).is_err());
+    }
+
+    #[test]
+    fn test_fill_template() {
+        let variables = BTreeMap::from([
+            ("name".to_string(), "Smith v. Jones".to_string()),
+            ("docket_number".to_string(), "1:24-cv-00042".to_string()),
+        ]);
+        let prompt = "Case {folder.name} ({folder.docket_number}, {folder.court}) {folder.bad key}\n{transcript}";
+        assert_eq!(
+            fill_template(prompt, "Q: state your name {folder.name}", &variables),
+            "Case Smith v. Jones (1:24-cv-00042, ) {folder.bad key}\nQ: state your name {folder.name}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_and_template_variables() {
+        let pool = migrated_pool().await;
+        sqlx::query("INSERT INTO workspace_types (id, name, folder_label, metadata_schema, created_at, updated_at) VALUES ('litigation', 'Litigation', 'Case', ?, 0, 0)")
+            .bind(CASE_SCHEMA)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Firm', 'litigation', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(validate(&pool, "ws", None).await.is_err());
+        let stored = validate(
+            &pool,
+            "ws",
+            Some(r#"{ "docket_number": "42", "witnesses": 2 }"#),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            stored.as_deref(),
+            Some(r#"{"docket_number":"42","witnesses":2}"#)
+        );
+        assert!(validate(&pool, "missing", None).await.is_err());
+
+        sqlx::query("INSERT INTO folders (id, workspace_id, name, metadata, created_at, updated_at) VALUES ('f', 'ws', 'Smith v. Jones', ?, 0, 0)")
+            .bind(&stored)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES ('s', 'f', 'a.wav', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let variables = template_variables(&pool, "s").await.unwrap();
+        assert_eq!(variables["name"], "Smith v. Jones");
+        assert_eq!(variables["docket_number"], "42");
+        assert_eq!(variables["witnesses"], "2");
+        assert_eq!(variables["court"], "");
+        assert!(template_variables(&pool, "missing")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,454 @@
+//! Folder tree
+//!
+//! Folders nest through `parent_id` (migration 010), such as Case →
+//! Depositions → Witness, always within one workspace. A folder's session
+//! count covers its whole subtree, and moving a folder takes its subfolders
+//! and sessions with it. Trashing is tree-aware too; see `trash`. Metadata is
+//! checked against the workspace type's schema on every write.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use sqlx::sqlite::SqlitePool;
+use uuid::Uuid;
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::database::{fetch_session, folder_from_row};
+use super::folder_metadata;
+use crate::models::{CreateFolderRequest, Folder, Session, UpdateFolderRequest};
+
+/// Live folders matching `filter`, each with the number of live sessions in
+/// it and its live subfolders
+fn folder_query(filter: &str) -> String {
+    format!(
+        r#"
+        WITH RECURSIVE subtree(root_id, id) AS (
+            SELECT id, id FROM folders WHERE deleted_at IS NULL AND {filter}
+            UNION ALL
+            SELECT subtree.root_id, f.id
+            FROM folders f
+            JOIN subtree ON f.parent_id = subtree.id
+            WHERE f.deleted_at IS NULL
+        )
+        SELECT
+            f.id, f.workspace_id, f.parent_id, f.name, f.metadata, f.created_at, f.updated_at,
+            f.is_active,
+            (SELECT COUNT(*) FROM subtree t JOIN sessions s ON s.folder_id = t.id
+             WHERE t.root_id = f.id AND s.deleted_at IS NULL) AS session_count
+        FROM folders f
+        WHERE f.deleted_at IS NULL AND {filter}
+        "#,
+        filter = filter
+    )
+}
+
+/// A workspace's live folders, newest first. Nested folders are listed too;
+/// `parent_id` says where each one sits.
+pub async fn list(pool: &SqlitePool, workspace_id: &str) -> Result<Vec<Folder>> {
+    let rows = sqlx::query(&format!(
+        "{} AND f.is_active = 1 ORDER BY f.created_at DESC",
+        folder_query("workspace_id = ?")
+    ))
+    .bind(workspace_id)
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(folder_from_row).collect())
+}
+
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<Folder> {
+    let row = sqlx::query(&folder_query("id = ?"))
+        .bind(id)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow!("Folder not found: {}", id))?;
+    Ok(folder_from_row(row))
+}
+
+/// Live folders whose metadata `field` equals `value`, such as every case
+/// with a given docket number, across all workspaces unless one is given
+pub async fn find_by_metadata(
+    pool: &SqlitePool,
+    workspace_id: Option<&str>,
+    field: &str,
+    value: &Value,
+) -> Result<Vec<Folder>> {
+    if !folder_metadata::is_field_name(field) {
+        return Err(anyhow!("Not a metadata field name: {}", field));
+    }
+    let path = format!("$.{}", field);
+
+    // Legacy metadata may not be JSON; json_extract would fail on it
+    let sql = format!(
+        "{} AND f.is_active = 1 ORDER BY f.name COLLATE NOCASE",
+        folder_query(
+            "(? IS NULL OR workspace_id = ?) AND json_valid(metadata) AND json_extract(metadata, ?) = ?"
+        )
+    );
+    let mut query = sqlx::query(&sql);
+    // The filter appears twice: in the subtree and in the outer query
+    for _ in 0..2 {
+        query = query.bind(workspace_id).bind(workspace_id).bind(&path);
+        query = match value {
+            Value::String(text) => query.bind(text.as_str()),
+            // json_extract gives booleans back as 1 and 0
+            Value::Bool(flag) => query.bind(*flag as i64),
+            Value::Number(n) => match n.as_i64() {
+                Some(n) => query.bind(n),
+                None => query.bind(n.as_f64()),
+            },
+            _ => return Err(anyhow!("Search by text, a number, or true or false")),
+        };
+    }
+
+    let rows = query.fetch_all(pool).await?;
+    Ok(rows.into_iter().map(folder_from_row).collect())
+}
+
+/// Workspace of a live folder
+async fn live_workspace(pool: &SqlitePool, id: &str) -> Result<String> {
+    sqlx::query_scalar(
+        "SELECT workspace_id FROM folders WHERE id = ? AND deleted_at IS NULL AND is_active = 1",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow!("Folder not found: {}", id))
+}
+
+/// A parent must be a live folder in the same workspace
+async fn check_parent(pool: &SqlitePool, parent_id: &str, workspace_id: &str) -> Result<()> {
+    if live_workspace(pool, parent_id).await? != workspace_id {
+        return Err(anyhow!("A folder can only be nested in its own workspace"));
+    }
+    Ok(())
+}
+
+pub async fn create(pool: &SqlitePool, request: &CreateFolderRequest, now: i64) -> Result<Folder> {
+    if let Some(parent_id) = &request.parent_id {
+        check_parent(pool, parent_id, &request.workspace_id).await?;
+    }
+    let metadata =
+        folder_metadata::validate(pool, &request.workspace_id, request.metadata.as_deref()).await?;
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO folders (id, workspace_id, parent_id, name, metadata, created_at, updated_at, is_active) VALUES (?, ?, ?, ?, ?, ?, ?, 1)",
+    )
+    .bind(&id)
+    .bind(&request.workspace_id)
+    .bind(&request.parent_id)
+    .bind(&request.name)
+    .bind(&metadata)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    get(pool, &id).await
+}
+
+/// Rename a folder or replace its metadata
+pub async fn update(pool: &SqlitePool, request: &UpdateFolderRequest, now: i64) -> Result<Folder> {
+    let workspace_id = live_workspace(pool, &request.id).await?;
+    // Checked before anything is written, so a bad update changes nothing
+    let metadata = match &request.metadata {
+        Some(metadata) => {
+            Some(folder_metadata::validate(pool, &workspace_id, Some(metadata)).await?)
+        }
+        None => None,
+    };
+
+    if let Some(name) = &request.name {
+        sqlx::query("UPDATE folders SET name = ?, updated_at = ? WHERE id = ?")
+            .bind(name)
+            .bind(now)
+            .bind(&request.id)
+            .execute(pool)
+            .await?;
+    }
+
+    if let Some(metadata) = metadata {
+        sqlx::query("UPDATE folders SET metadata = ?, updated_at = ? WHERE id = ?")
+            .bind(metadata)
+            .bind(now)
+            .bind(&request.id)
+            .execute(pool)
+            .await?;
+    }
+
+    get(pool, &request.id).await
+}
+
+/// Put a folder under another one, or at the top level with `None`. A folder
+/// can't move into its own subtree or into another workspace.
+pub async fn move_folder(
+    pool: &SqlitePool,
+    id: &str,
+    parent_id: Option<&str>,
+    now: i64,
+) -> Result<Folder> {
+    let workspace_id = live_workspace(pool, id).await?;
+
+    if let Some(parent_id) = parent_id {
+        check_parent(pool, parent_id, &workspace_id).await?;
+
+        let inside: i64 = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE subtree(id) AS (
+                SELECT ?
+                UNION ALL
+                SELECT f.id FROM folders f JOIN subtree ON f.parent_id = subtree.id
+            )
+            SELECT COUNT(*) FROM subtree WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(parent_id)
+        .fetch_one(pool)
+        .await?;
+        if inside > 0 {
+            return Err(anyhow!(
+                "A folder can't be moved into itself or its subfolders"
+            ));
+        }
+    }
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE folders SET parent_id = ?, updated_at = ? WHERE id = ?")
+        .bind(parent_id)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    let event = AuditEvent::new(AuditAction::Update, "folder", Some(id))
+        .with_details(serde_json::json!({ "parentId": parent_id }));
+    audit::record_in(&mut *tx, &guard, event).await?;
+    tx.commit().await?;
+    drop(guard);
+    println!("[Folders] Moved folder {} under {:?}", id, parent_id);
+    get(pool, id).await
+}
+
+/// File a session under another folder, which may belong to another
+/// workspace
+pub async fn move_session(
+    pool: &SqlitePool,
+    session_id: &str,
+    folder_id: &str,
+    now: i64,
+) -> Result<Session> {
+    live_workspace(pool, folder_id).await?;
+
+    let from: String =
+        sqlx::query_scalar("SELECT folder_id FROM sessions WHERE id = ? AND deleted_at IS NULL")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE sessions SET folder_id = ?, updated_at = ? WHERE id = ?")
+        .bind(folder_id)
+        .bind(now)
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let event = AuditEvent::session(AuditAction::Update, session_id)
+        .with_details(serde_json::json!({ "fields": ["folderId"], "from": from, "to": folder_id }));
+    audit::record_in(&mut *tx, &guard, event).await?;
+    tx.commit().await?;
+    drop(guard);
+    println!(
+        "[Folders] Moved session {} to folder {}",
+        session_id, folder_id
+    );
+
+    fetch_session(pool, session_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+    use serde_json::json;
+
+    async fn setup() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Firm', 'legal', 0, 0)",
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('other', 'Other', 'general', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('case', 'ws', 'Case', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, parent_id, name, created_at, updated_at) VALUES ('depositions', 'ws', 'case', 'Depositions', 1, 1)",
+                "INSERT INTO folders (id, workspace_id, parent_id, name, created_at, updated_at) VALUES ('witness', 'ws', 'depositions', 'Witness', 2, 2)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('elsewhere', 'other', 'Elsewhere', 3, 3)",
+            ],
+        )
+        .await;
+        for (id, folder_id) in [("s1", "case"), ("s2", "depositions"), ("s3", "witness")] {
+            sqlx::query(
+                "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES (?, ?, 'a.wav', 0, 0)",
+            )
+            .bind(id)
+            .bind(folder_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        pool
+    }
+
+    async fn counts(pool: &SqlitePool) -> Vec<(String, i64)> {
+        list(pool, "ws")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.id, f.session_count))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_session_count_covers_subtree() {
+        let pool = setup().await;
+        assert_eq!(
+            counts(&pool).await,
+            vec![
+                ("witness".to_string(), 1),
+                ("depositions".to_string(), 2),
+                ("case".to_string(), 3),
+            ]
+        );
+
+        sqlx::query("UPDATE sessions SET deleted_at = 5 WHERE id = 's3'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, "case").await.unwrap().session_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_nested_folder() {
+        let pool = setup().await;
+        let request = |workspace_id: &str, parent_id: &str| CreateFolderRequest {
+            workspace_id: workspace_id.to_string(),
+            name: "Exhibits".to_string(),
+            metadata: None,
+            parent_id: Some(parent_id.to_string()),
+        };
+
+        let folder = create(&pool, &request("ws", "case"), 10).await.unwrap();
+        assert_eq!(folder.parent_id.as_deref(), Some("case"));
+        assert_eq!(folder.session_count, 0);
+
+        assert!(create(&pool, &request("ws", "elsewhere"), 10)
+            .await
+            .is_err());
+        assert!(create(&pool, &request("ws", "missing"), 10).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_move_folder() {
+        let pool = setup().await;
+
+        // Cycles and other workspaces are refused
+        assert!(move_folder(&pool, "case", Some("witness"), 10)
+            .await
+            .is_err());
+        assert!(move_folder(&pool, "case", Some("case"), 10).await.is_err());
+        assert!(move_folder(&pool, "witness", Some("elsewhere"), 10)
+            .await
+            .is_err());
+
+        let moved = move_folder(&pool, "witness", None, 10).await.unwrap();
+        assert_eq!(moved.parent_id, None);
+        assert_eq!(moved.updated_at, 10);
+        assert_eq!(get(&pool, "case").await.unwrap().session_count, 2);
+
+        move_folder(&pool, "depositions", Some("witness"), 20)
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, "witness").await.unwrap().session_count, 2);
+        assert_eq!(get(&pool, "case").await.unwrap().session_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_move_session() {
+        let pool = setup().await;
+
+        let session = move_session(&pool, "s3", "elsewhere", 10).await.unwrap();
+        assert_eq!(session.folder_id, "elsewhere");
+        assert_eq!(get(&pool, "case").await.unwrap().session_count, 2);
+        assert_eq!(get(&pool, "elsewhere").await.unwrap().session_count, 1);
+
+        assert!(move_session(&pool, "s3", "missing", 10).await.is_err());
+        assert!(move_session(&pool, "missing", "case", 10).await.is_err());
+
+        let details: String = sqlx::query_scalar(
+            "SELECT details FROM audit_log WHERE entity_id = 's3' AND action = 'update'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(details.contains(r#""from":"witness""#));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_is_validated_and_searchable() {
+        let pool = setup().await;
+        let update_metadata = |id: &str, metadata: &str| UpdateFolderRequest {
+            id: id.to_string(),
+            name: Some("Renamed".to_string()),
+            metadata: Some(metadata.to_string()),
+        };
+
+        // Legal folders follow the seeded schema, where court is text
+        assert!(
+            update(&pool, &update_metadata("case", r#"{"court": 4}"#), 10)
+                .await
+                .is_err()
+        );
+        assert_eq!(get(&pool, "case").await.unwrap().name, "Case");
+
+        let folder = update(
+            &pool,
+            &update_metadata("case", r#"{ "docket_number": "24-cv-1", "exhibits": 3 }"#),
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            folder.metadata.as_deref(),
+            Some(r#"{"docket_number":"24-cv-1","exhibits":3}"#)
+        );
+        // General workspaces have no schema, so anything goes
+        update(&pool, &update_metadata("elsewhere", "free text"), 10)
+            .await
+            .unwrap();
+
+        let found = find_by_metadata(&pool, None, "docket_number", &json!("24-cv-1"))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].id.as_str(), found[0].session_count), ("case", 3));
+        let found = find_by_metadata(&pool, Some("ws"), "exhibits", &json!(3))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(
+            find_by_metadata(&pool, Some("other"), "exhibits", &json!(3))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(find_by_metadata(&pool, None, "docket')", &json!("x"))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,708 @@
+//! Library integrity
+//!
+//! Over time the database, the audio folder and the search index can drift
+//! apart: a failed delete leaves a recording no session uses, a moved library
+//! leaves sessions pointing at audio that isn't there, and an edited
+//! transcript keeps chunks indexed from the old text. `check` only reports
+//! what it finds. `repair` fixes the issues the user has reviewed, and only
+//! those still found exactly as reviewed.
+
+use anyhow::Result;
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::chunking::{chunk_session, ChunkingConfig};
+use crate::models::{IntegrityIssue, IntegrityReport, RepairReport};
+
+const ORPHANED_AUDIO: &str = "orphaned_audio";
+const MISSING_AUDIO: &str = "missing_audio";
+const ORPHANED_CHUNKS: &str = "orphaned_chunks";
+const STALE_CHUNKS: &str = "stale_chunks";
+const STALE_INDEX: &str = "stale_index";
+
+/// Files newer than this may belong to a recording still being saved
+const ORPHAN_GRACE_SECS: i64 = 24 * 60 * 60;
+
+enum Fix {
+    DeleteFile(PathBuf),
+    Relink {
+        session_id: String,
+        path: String,
+    },
+    MarkAudioLost(String),
+    /// Drop a session's chunks and indexing status, then index it again if
+    /// it still has a transcript to index
+    ClearIndex {
+        session_id: String,
+        reindex: bool,
+    },
+}
+
+struct Finding {
+    issue: IntegrityIssue,
+    fix: Fix,
+}
+
+fn issue(
+    kind: &str,
+    session_id: Option<&str>,
+    path: Option<&str>,
+    description: String,
+    repair: String,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        kind: kind.to_string(),
+        session_id: session_id.map(String::from),
+        path: path.map(String::from),
+        description,
+        repair,
+    }
+}
+
+fn session_label(id: &str, title: &Option<String>) -> String {
+    match title {
+        Some(title) if !title.is_empty() => format!("\"{}\"", title),
+        _ => format!("Session {}", id),
+    }
+}
+
+/// Same file, even when the paths are spelled differently
+fn path_key(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+struct AudioFile {
+    path: PathBuf,
+    size: u64,
+    modified_at: i64,
+}
+
+fn audio_files(audio_dir: &Path) -> Result<Vec<AudioFile>> {
+    let mut files = Vec::new();
+    if !audio_dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in std::fs::read_dir(audio_dir)? {
+        let path = entry?.path();
+        let metadata = std::fs::metadata(&path)?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |age| age.as_secs() as i64);
+        files.push(AudioFile {
+            path,
+            size: metadata.len(),
+            modified_at,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Recordings in the audio folder that no session uses, and sessions whose
+/// recording is gone. A missing recording found under its own name in the
+/// audio folder, as after the library moves, is pointed to again.
+async fn find_audio_issues(
+    pool: &SqlitePool,
+    audio_dir: &Path,
+    now: i64,
+    findings: &mut Vec<Finding>,
+) -> Result<(i64, i64)> {
+    let sessions: Vec<(String, Option<String>, String)> = sqlx::query_as(
+        "SELECT id, title, audio_path FROM sessions WHERE audio_purged_at IS NULL AND live_started_at IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+    // Live sessions have no recording yet, but one named after them may be
+    // in progress or left for recovery
+    let live: HashSet<String> =
+        sqlx::query_scalar("SELECT id FROM sessions WHERE live_started_at IS NOT NULL")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    let files = audio_files(audio_dir)?;
+
+    let used: HashSet<PathBuf> = sessions
+        .iter()
+        .map(|(_, _, audio_path)| path_key(Path::new(audio_path)))
+        .collect();
+    let mut unused: Vec<&AudioFile> = files
+        .iter()
+        .filter(|file| !used.contains(&path_key(&file.path)))
+        .filter(|file| {
+            file.path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map_or(true, |stem| !live.contains(stem))
+        })
+        .collect();
+
+    for (id, title, audio_path) in &sessions {
+        let path = Path::new(audio_path);
+        if path.is_file() {
+            continue;
+        }
+        let file_name = path.file_name();
+        let found = unused.iter().position(|file| {
+            file.path.file_name() == file_name
+                || file.path.file_stem().and_then(|stem| stem.to_str()) == Some(id.as_str())
+        });
+
+        let description = format!(
+            "{}'s recording is missing from {}",
+            session_label(id, title),
+            audio_path
+        );
+        let finding = match found.map(|i| unused.remove(i)) {
+            Some(file) => {
+                let new_path = file.path.to_string_lossy().to_string();
+                Finding {
+                    issue: issue(
+                        MISSING_AUDIO,
+                        Some(id),
+                        Some(audio_path),
+                        description,
+                        format!("Use the recording at {}", new_path),
+                    ),
+                    fix: Fix::Relink {
+                        session_id: id.clone(),
+                        path: new_path,
+                    },
+                }
+            }
+            None => Finding {
+                issue: issue(
+                    MISSING_AUDIO,
+                    Some(id),
+                    Some(audio_path),
+                    description,
+                    "Record the recording as lost; the transcript and note are kept".to_string(),
+                ),
+                fix: Fix::MarkAudioLost(id.clone()),
+            },
+        };
+        findings.push(finding);
+    }
+
+    for file in unused {
+        if now - file.modified_at < ORPHAN_GRACE_SECS {
+            continue;
+        }
+        let path = file.path.to_string_lossy().to_string();
+        findings.push(Finding {
+            issue: issue(
+                ORPHANED_AUDIO,
+                None,
+                Some(&path),
+                format!(
+                    "{:.1} MB of audio not used by any session",
+                    file.size as f64 / 1_000_000.0
+                ),
+                "Delete the file".to_string(),
+            ),
+            fix: Fix::DeleteFile(file.path.clone()),
+        });
+    }
+
+    Ok((sessions.len() as i64, files.len() as i64))
+}
+
+/// Chunks and indexing status left by deleted sessions, chunks that no
+/// longer match their session's transcript, and sessions whose indexing
+/// status disagrees with their chunks
+async fn find_index_issues(pool: &SqlitePool, findings: &mut Vec<Finding>) -> Result<()> {
+    let orphaned: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT session_id, COUNT(*) FROM transcript_chunks
+        WHERE session_id NOT IN (SELECT id FROM sessions)
+        GROUP BY session_id ORDER BY session_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    for (session_id, count) in orphaned {
+        findings.push(Finding {
+            issue: issue(
+                ORPHANED_CHUNKS,
+                Some(&session_id),
+                None,
+                format!("{} search chunk(s) left by a deleted session", count),
+                "Delete the chunks".to_string(),
+            ),
+            fix: Fix::ClearIndex {
+                session_id,
+                reindex: false,
+            },
+        });
+    }
+
+    // Same conditions as `rag::index_all_pending_sessions`
+    let reindexable = "s.transcript IS NOT NULL AND s.deleted_at IS NULL AND s.chunks_purged_at IS NULL AND s.status = 'complete'";
+
+    let config = ChunkingConfig::default();
+    let indexed = sqlx::query(&format!(
+        r#"
+        SELECT s.id, s.title, s.transcript, s.transcript_segments, s.chunks_purged_at,
+            {} AS reindexable
+        FROM sessions s
+        WHERE EXISTS (SELECT 1 FROM transcript_chunks c WHERE c.session_id = s.id)
+        ORDER BY s.id
+        "#,
+        reindexable
+    ))
+    .fetch_all(pool)
+    .await?;
+    let mut stale = HashSet::new();
+    for row in indexed {
+        let id: String = row.get("id");
+        let stored: Vec<String> = sqlx::query_scalar(
+            "SELECT text FROM transcript_chunks WHERE session_id = ? ORDER BY chunk_index",
+        )
+        .bind(&id)
+        .fetch_all(pool)
+        .await?;
+
+        let expected: Vec<String> = if row.get::<Option<i64>, _>("chunks_purged_at").is_some() {
+            Vec::new()
+        } else {
+            let transcript: Option<String> = row.get("transcript");
+            let segments: Option<String> = row.get("transcript_segments");
+            chunk_session(&id, transcript.as_deref(), segments.as_deref(), &config)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|chunk| chunk.text)
+                .collect()
+        };
+        if stored == expected {
+            continue;
+        }
+
+        let reindex: bool = row.get("reindexable");
+        findings.push(Finding {
+            issue: issue(
+                STALE_CHUNKS,
+                Some(&id),
+                None,
+                format!(
+                    "{}'s search chunks don't match its transcript",
+                    session_label(&id, &row.get("title"))
+                ),
+                if reindex {
+                    "Index the transcript again".to_string()
+                } else {
+                    "Delete the chunks".to_string()
+                },
+            ),
+            fix: Fix::ClearIndex {
+                session_id: id.clone(),
+                reindex,
+            },
+        });
+        stale.insert(id);
+    }
+
+    let statuses = sqlx::query(&format!(
+        r#"
+        SELECT sis.session_id, s.title, s.id IS NOT NULL AS session_exists,
+            COALESCE({}, 0) AS reindexable
+        FROM session_indexing_status sis
+        LEFT JOIN sessions s ON s.id = sis.session_id
+        WHERE s.id IS NULL
+           OR (sis.is_indexed = 1 AND sis.chunk_count !=
+               (SELECT COUNT(*) FROM transcript_chunks c WHERE c.session_id = sis.session_id))
+        ORDER BY sis.session_id
+        "#,
+        reindexable
+    ))
+    .fetch_all(pool)
+    .await?;
+    for row in statuses {
+        let id: String = row.get("session_id");
+        if stale.contains(&id) {
+            continue;
+        }
+        let reindex: bool = row.get("reindexable");
+        let description = if row.get("session_exists") {
+            format!(
+                "{} is marked as indexed, but its search chunks don't agree",
+                session_label(&id, &row.get("title"))
+            )
+        } else {
+            "Indexing status left by a deleted session".to_string()
+        };
+        findings.push(Finding {
+            issue: issue(
+                STALE_INDEX,
+                Some(&id),
+                None,
+                description,
+                if reindex {
+                    "Index the transcript again".to_string()
+                } else {
+                    "Clear the indexing status".to_string()
+                },
+            ),
+            fix: Fix::ClearIndex {
+                session_id: id,
+                reindex,
+            },
+        });
+    }
+
+    Ok(())
+}
+
+async fn find(
+    pool: &SqlitePool,
+    audio_dir: &Path,
+    now: i64,
+) -> Result<(IntegrityReport, Vec<Fix>)> {
+    let mut findings = Vec::new();
+    let (sessions_checked, audio_files_checked) =
+        find_audio_issues(pool, audio_dir, now, &mut findings).await?;
+    find_index_issues(pool, &mut findings).await?;
+
+    let (issues, fixes) = findings
+        .into_iter()
+        .map(|finding| (finding.issue, finding.fix))
+        .unzip();
+    let report = IntegrityReport {
+        checked_at: now,
+        sessions_checked,
+        audio_files_checked,
+        issues,
+    };
+    Ok((report, fixes))
+}
+
+/// Look for inconsistencies without changing anything
+pub async fn check(pool: &SqlitePool, audio_dir: &Path, now: i64) -> Result<IntegrityReport> {
+    let (report, _) = find(pool, audio_dir, now).await?;
+    println!(
+        "[Integrity] Checked {} session(s) and {} audio file(s): {} issue(s)",
+        report.sessions_checked,
+        report.audio_files_checked,
+        report.issues.len()
+    );
+    Ok(report)
+}
+
+async fn apply(pool: &SqlitePool, fix: &Fix, now: i64, report: &mut RepairReport) -> Result<()> {
+    match fix {
+        Fix::DeleteFile(path) => std::fs::remove_file(path)?,
+        Fix::Relink { session_id, path } => {
+            sqlx::query("UPDATE sessions SET audio_path = ? WHERE id = ?")
+                .bind(path)
+                .bind(session_id)
+                .execute(pool)
+                .await?;
+            let event = AuditEvent::session(AuditAction::Update, session_id).with_details(
+                serde_json::json!({ "fields": ["audioPath"], "reason": "integrity_repair" }),
+            );
+            audit::record(pool, event).await?;
+        }
+        Fix::MarkAudioLost(session_id) => {
+            sqlx::query("UPDATE sessions SET audio_purged_at = ? WHERE id = ?")
+                .bind(now)
+                .bind(session_id)
+                .execute(pool)
+                .await?;
+            let event = AuditEvent::session(AuditAction::Purge, session_id)
+                .with_details(serde_json::json!({ "dataType": "audio", "reason": "missing" }));
+            audit::record(pool, event).await?;
+        }
+        Fix::ClearIndex {
+            session_id,
+            reindex,
+        } => {
+            let mut tx = pool.begin().await?;
+            sqlx::query("DELETE FROM transcript_chunks WHERE session_id = ?")
+                .bind(session_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM session_indexing_status WHERE session_id = ?")
+                .bind(session_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            if *reindex {
+                report.reindex.push(session_id.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fix the reviewed issues. Each is checked again first, and left alone
+/// unless it is still found with the same repair.
+pub async fn repair(
+    pool: &SqlitePool,
+    audio_dir: &Path,
+    reviewed: &[IntegrityIssue],
+    now: i64,
+) -> Result<RepairReport> {
+    let (current, fixes) = find(pool, audio_dir, now).await?;
+    let mut report = RepairReport::default();
+
+    for (issue, fix) in current.issues.into_iter().zip(fixes) {
+        if !reviewed.contains(&issue) {
+            continue;
+        }
+        match apply(pool, &fix, now, &mut report).await {
+            Ok(()) => report.repaired.push(issue),
+            Err(e) => report.errors.push(format!("{} ({})", issue.description, e)),
+        }
+    }
+    report.skipped = reviewed
+        .len()
+        .saturating_sub(report.repaired.len() + report.errors.len()) as i64;
+
+    println!(
+        "[Integrity] Repaired {} issue(s), skipped {}, {} failed",
+        report.repaired.len(),
+        report.skipped,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    async fn setup(audio_dir: &Path) -> SqlitePool {
+        let pool = migrated_pool().await;
+        std::fs::create_dir_all(audio_dir).unwrap();
+
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Practice', 'therapy', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f', 'ws', 'Client', 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    async fn insert_session(
+        pool: &SqlitePool,
+        id: &str,
+        audio_path: &Path,
+        transcript: Option<&str>,
+    ) {
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, title, audio_path, transcript, status, created_at, updated_at) VALUES (?, 'f', ?, ?, ?, 'complete', 0, 0)",
+        )
+        .bind(id)
+        .bind(id.to_uppercase())
+        .bind(audio_path.to_str().unwrap())
+        .bind(transcript)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn insert_chunks(pool: &SqlitePool, session_id: &str, texts: &[&str]) {
+        for (i, text) in texts.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO transcript_chunks (id, session_id, chunk_index, text, created_at, updated_at) VALUES (?, ?, ?, ?, 0, 0)",
+            )
+            .bind(format!("{}-{}", session_id, i))
+            .bind(session_id)
+            .bind(i as i64)
+            .bind(text)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO session_indexing_status (session_id, is_indexed, chunk_count, indexed_at) VALUES (?, 1, ?, 0)",
+        )
+        .bind(session_id)
+        .bind(texts.len() as i64)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn kinds(report: &IntegrityReport) -> Vec<(&str, Option<&str>)> {
+        report
+            .issues
+            .iter()
+            .map(|issue| (issue.kind.as_str(), issue.session_id.as_deref()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_audio_issues() {
+        let dir = std::env::temp_dir().join(format!("integrity-test-{}", uuid::Uuid::new_v4()));
+        let pool = setup(&dir).await;
+        let now = chrono::Utc::now().timestamp();
+
+        std::fs::write(dir.join("kept.wav"), b"audio").unwrap();
+        std::fs::write(dir.join("orphan.wav"), b"audio").unwrap();
+        // The library moved: the recording is here under its old name
+        std::fs::write(dir.join("moved.m4a"), b"audio").unwrap();
+        insert_session(&pool, "kept", &dir.join("kept.wav"), None).await;
+        insert_session(
+            &pool,
+            "moved",
+            Path::new("/old/library/audio/moved.m4a"),
+            None,
+        )
+        .await;
+        insert_session(&pool, "lost", &dir.join("lost.wav"), None).await;
+        // A live recording has no audio path yet, and keeps its file
+        std::fs::write(dir.join("live.wav"), b"audio").unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, audio_path, live_started_at, created_at, updated_at) VALUES ('live', 'f', '', 0, 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // A new file may be a recording still being saved
+        let report = check(&pool, &dir, now).await.unwrap();
+        assert_eq!(
+            kinds(&report),
+            [
+                (MISSING_AUDIO, Some("lost")),
+                (MISSING_AUDIO, Some("moved"))
+            ]
+        );
+        assert_eq!(
+            (report.sessions_checked, report.audio_files_checked),
+            (3, 4)
+        );
+
+        let later = now + 2 * DAY;
+        let report = check(&pool, &dir, later).await.unwrap();
+        assert_eq!(report.issues.len(), 3);
+        assert_eq!(report.issues[2].kind, ORPHANED_AUDIO);
+        assert!(report.issues[2]
+            .path
+            .as_deref()
+            .unwrap()
+            .ends_with("orphan.wav"));
+
+        // Only reviewed issues are repaired
+        let reviewed: Vec<_> = report.issues[1..].to_vec();
+        let repaired = repair(&pool, &dir, &reviewed, later).await.unwrap();
+        assert_eq!(repaired.repaired.len(), 2);
+        assert!(repaired.errors.is_empty());
+        assert!(!dir.join("orphan.wav").exists());
+        let moved: String =
+            sqlx::query_scalar("SELECT audio_path FROM sessions WHERE id = 'moved'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(moved.ends_with("moved.m4a") && Path::new(&moved).is_file());
+
+        // Repairing the same issues again finds nothing to do
+        let again = repair(&pool, &dir, &reviewed, later).await.unwrap();
+        assert_eq!((again.repaired.len(), again.skipped), (0, 2));
+
+        repair(&pool, &dir, &report.issues, later).await.unwrap();
+        assert!(check(&pool, &dir, later).await.unwrap().issues.is_empty());
+        let lost: Option<i64> =
+            sqlx::query_scalar("SELECT audio_purged_at FROM sessions WHERE id = 'lost'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(lost, Some(later));
+        assert!(dir.join("kept.wav").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_index_issues() {
+        let dir = std::env::temp_dir().join(format!("integrity-test-{}", uuid::Uuid::new_v4()));
+        let pool = setup(&dir).await;
+        let now = chrono::Utc::now().timestamp();
+        let transcript = "We talked about sleep. Things are improving.";
+        for id in ["current", "edited", "miscounted", "trashed"] {
+            std::fs::write(dir.join(format!("{}.wav", id)), b"audio").unwrap();
+            insert_session(
+                &pool,
+                id,
+                &dir.join(format!("{}.wav", id)),
+                Some(transcript),
+            )
+            .await;
+        }
+        let chunks = chunk_session(
+            "current",
+            Some(transcript),
+            None,
+            &ChunkingConfig::default(),
+        )
+        .unwrap();
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        insert_chunks(&pool, "current", &texts).await;
+        insert_chunks(&pool, "edited", &["An earlier draft."]).await;
+        insert_chunks(&pool, "miscounted", &texts).await;
+        insert_chunks(&pool, "trashed", &["An earlier draft."]).await;
+        sqlx::query(
+            "UPDATE session_indexing_status SET chunk_count = 3 WHERE session_id = 'miscounted'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE sessions SET deleted_at = 1 WHERE id = 'trashed'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Left behind from before foreign keys were enforced
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&pool)
+            .await
+            .unwrap();
+        insert_chunks(&pool, "gone", &["Old", "chunks"]).await;
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = check(&pool, &dir, now).await.unwrap();
+        assert_eq!(
+            kinds(&report),
+            [
+                (ORPHANED_CHUNKS, Some("gone")),
+                (STALE_CHUNKS, Some("edited")),
+                (STALE_CHUNKS, Some("trashed")),
+                (STALE_INDEX, Some("gone")),
+                (STALE_INDEX, Some("miscounted")),
+            ]
+        );
+        assert_eq!(report.issues[1].repair, "Index the transcript again");
+        assert_eq!(report.issues[2].repair, "Delete the chunks");
+
+        let repaired = repair(&pool, &dir, &report.issues, now).await.unwrap();
+        assert_eq!(repaired.repaired.len(), 5);
+        assert_eq!(repaired.reindex, ["edited", "miscounted"]);
+        assert!(check(&pool, &dir, now).await.unwrap().issues.is_empty());
+
+        let chunks: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT session_id FROM transcript_chunks")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(chunks, ["current"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,787 @@
+//! Persistent background job queue
+//!
+//! Transcription, note generation and RAG indexing can be queued as jobs in
+//! the `jobs` table, so queued work survives a restart. A dispatcher loop
+//! claims queued jobs in priority order and runs up to `MAX_CONCURRENT_JOBS`
+//! at once. Failed jobs are retried with exponential backoff, and a finished
+//! job with `chain` set enqueues the next step: transcribe → generate → index.
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Notify, Semaphore};
+use uuid::Uuid;
+
+use super::cancellation::{self, is_cancelled_error, CancellationGuard, Cancelled, JobKind};
+use super::whisper::{ProgressListener, TranscriptionProgressEvent};
+use super::{database, llm, rag, whisper};
+use crate::models::{EnqueueJobRequest, Job, UpdateSessionRequest};
+
+/// Jobs allowed to run at the same time
+const MAX_CONCURRENT_JOBS: usize = 2;
+/// Attempts before a job is marked failed
+const DEFAULT_MAX_ATTEMPTS: i64 = 3;
+/// Delay before the first retry; doubles with each attempt
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+/// How long the dispatcher sleeps when nothing is ready to run
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wakes the dispatcher when a job is enqueued or becomes runnable
+static WAKE: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// Kind of work a job performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobType {
+    Transcribe,
+    Generate,
+    Index,
+}
+
+impl JobType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobType::Transcribe => "transcribe",
+            JobType::Generate => "generate",
+            JobType::Index => "index",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "transcribe" => Ok(JobType::Transcribe),
+            "generate" => Ok(JobType::Generate),
+            "index" => Ok(JobType::Index),
+            other => Err(anyhow!("Unknown job kind: {}", other)),
+        }
+    }
+}
+
+/// The job to enqueue after `job` completes, if it is chained.
+/// Transcription goes straight to indexing when no template was chosen.
+fn next_step(job: &Job) -> Option<JobType> {
+    if !job.chain {
+        return None;
+    }
+    match JobType::parse(&job.kind).ok()? {
+        JobType::Transcribe if job.template_id.is_some() => Some(JobType::Generate),
+        JobType::Transcribe | JobType::Generate => Some(JobType::Index),
+        JobType::Index => None,
+    }
+}
+
+/// Cancellation registry kind for a job, if it can be stopped once started
+fn cancellation_kind(kind: JobType) -> Option<JobKind> {
+    match kind {
+        JobType::Transcribe => Some(JobKind::Transcription),
+        JobType::Generate => Some(JobKind::Generation),
+        JobType::Index => None,
+    }
+}
+
+/// Backoff before retrying a job that has failed `attempts` times
+fn retry_delay_secs(attempts: i64) -> i64 {
+    RETRY_BASE_DELAY_SECS * 2i64.pow(attempts.clamp(1, 10) as u32 - 1)
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn job_from_row(row: SqliteRow) -> Job {
+    Job {
+        id: row.get("id"),
+        session_id: row.get("session_id"),
+        kind: row.get("kind"),
+        status: row.get("status"),
+        priority: row.get("priority"),
+        attempts: row.get("attempts"),
+        max_attempts: row.get("max_attempts"),
+        template_id: row.get("template_id"),
+        chain: row.get::<i64, _>("chain") != 0,
+        progress: row.get("progress"),
+        error: row.get("error"),
+        run_after: row.get("run_after"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        started_at: row.get("started_at"),
+        finished_at: row.get("finished_at"),
+    }
+}
+
+/// Insert a queued job
+pub async fn insert_job(pool: &SqlitePool, request: &EnqueueJobRequest) -> Result<Job> {
+    let kind = JobType::parse(&request.kind)?;
+    if kind == JobType::Generate && request.template_id.is_none() {
+        return Err(anyhow!("A template is required to generate a note"));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let now = now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO jobs
+        (id, session_id, kind, status, priority, max_attempts, template_id, chain, created_at, updated_at)
+        VALUES (?, ?, ?, 'queued', ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&request.session_id)
+    .bind(kind.as_str())
+    .bind(request.priority.unwrap_or(0))
+    .bind(DEFAULT_MAX_ATTEMPTS)
+    .bind(&request.template_id)
+    .bind(request.chain.unwrap_or(false) as i64)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    get_job(pool, &id).await
+}
+
+pub async fn get_job(pool: &SqlitePool, id: &str) -> Result<Job> {
+    let row = sqlx::query("SELECT * FROM jobs WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow!("Job not found: {}", id))?;
+    Ok(job_from_row(row))
+}
+
+/// List jobs, running first, then queued in the order they will run, then finished
+pub async fn list_jobs(pool: &SqlitePool, status: Option<&str>) -> Result<Vec<Job>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM jobs
+        WHERE (?1 IS NULL OR status = ?1)
+        ORDER BY
+            CASE status WHEN 'running' THEN 0 WHEN 'queued' THEN 1 ELSE 2 END,
+            CASE WHEN status IN ('running', 'queued') THEN -priority ELSE 0 END,
+            CASE WHEN status IN ('running', 'queued') THEN created_at ELSE -updated_at END
+        "#,
+    )
+    .bind(status)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(job_from_row).collect())
+}
+
+/// Change the priority of a queued job
+pub async fn set_priority(pool: &SqlitePool, id: &str, priority: i64) -> Result<Job> {
+    let result = sqlx::query(
+        "UPDATE jobs SET priority = ?, updated_at = ? WHERE id = ? AND status = 'queued'",
+    )
+    .bind(priority)
+    .bind(now())
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        let job = get_job(pool, id).await?;
+        return Err(anyhow!(
+            "Only queued jobs can be reprioritized (job is {})",
+            job.status
+        ));
+    }
+
+    get_job(pool, id).await
+}
+
+/// Atomically mark the next runnable job as running and return it.
+/// Skips sessions that already have a running job so steps never overlap.
+pub async fn claim_next(pool: &SqlitePool, now: i64) -> Result<Option<Job>> {
+    let row = sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = 'running', attempts = attempts + 1, progress = 0,
+            started_at = ?1, updated_at = ?1
+        WHERE id = (
+            SELECT id FROM jobs
+            WHERE status = 'queued' AND run_after <= ?1
+              AND session_id NOT IN (SELECT session_id FROM jobs WHERE status = 'running')
+            ORDER BY priority DESC, created_at
+            LIMIT 1
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(now)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(job_from_row))
+}
+
+pub async fn mark_completed(pool: &SqlitePool, id: &str) -> Result<Job> {
+    let now = now();
+    sqlx::query(
+        "UPDATE jobs SET status = 'completed', progress = 100, error = NULL, finished_at = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(now)
+    .bind(now)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    get_job(pool, id).await
+}
+
+pub async fn mark_cancelled(pool: &SqlitePool, id: &str) -> Result<Job> {
+    let now = now();
+    sqlx::query(
+        "UPDATE jobs SET status = 'cancelled', finished_at = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(now)
+    .bind(now)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    get_job(pool, id).await
+}
+
+/// Record a failed attempt: requeue with backoff, or mark failed once out of attempts
+pub async fn mark_failed(pool: &SqlitePool, job: &Job, error: &str, now: i64) -> Result<Job> {
+    if job.attempts < job.max_attempts {
+        sqlx::query(
+            "UPDATE jobs SET status = 'queued', error = ?, run_after = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(error)
+        .bind(now + retry_delay_secs(job.attempts))
+        .bind(now)
+        .bind(&job.id)
+        .execute(pool)
+        .await?;
+    } else {
+        sqlx::query(
+            "UPDATE jobs SET status = 'failed', error = ?, finished_at = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .bind(&job.id)
+        .execute(pool)
+        .await?;
+    }
+    get_job(pool, &job.id).await
+}
+
+pub async fn update_progress(pool: &SqlitePool, id: &str, progress: f64) -> Result<()> {
+    sqlx::query("UPDATE jobs SET progress = ?, updated_at = ? WHERE id = ?")
+        .bind(progress)
+        .bind(now())
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Put jobs that were running when the app last exited back in the queue.
+/// The interrupted run doesn't count as an attempt.
+pub async fn requeue_interrupted(pool: &SqlitePool) -> Result<u64> {
+    let result = sqlx::query(
+        "UPDATE jobs SET status = 'queued', attempts = MAX(attempts - 1, 0), progress = 0, updated_at = ? WHERE status = 'running'",
+    )
+    .bind(now())
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Cancel a job. Queued jobs are cancelled immediately; running transcription
+/// and generation jobs are signalled and marked cancelled once they stop.
+/// The request is also saved on the job, for a job that hasn't registered
+/// its cancellation token yet to find when it does.
+pub async fn cancel_job(pool: &SqlitePool, id: &str) -> Result<Job> {
+    let job = get_job(pool, id).await?;
+    match job.status.as_str() {
+        "queued" => mark_cancelled(pool, id).await,
+        "running" => {
+            let kind = cancellation_kind(JobType::parse(&job.kind)?)
+                .ok_or_else(|| anyhow!("Indexing jobs can't be cancelled once started"))?;
+            sqlx::query("UPDATE jobs SET cancel_requested = 1, updated_at = ? WHERE id = ?")
+                .bind(now())
+                .bind(id)
+                .execute(pool)
+                .await?;
+            cancellation::cancel(kind, &job.session_id);
+            get_job(pool, id).await
+        }
+        status => Err(anyhow!("Job is already {}", status)),
+    }
+}
+
+/// Whether the job was asked to stop while it was running
+pub async fn cancel_requested(pool: &SqlitePool, id: &str) -> Result<bool> {
+    let requested: i64 = sqlx::query_scalar("SELECT cancel_requested FROM jobs WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(requested != 0)
+}
+
+/// Queue a job and wake the dispatcher
+pub async fn enqueue(app: &AppHandle, request: &EnqueueJobRequest) -> Result<Job> {
+    let pool = database::get_pool()?;
+    let job = insert_job(pool, request).await?;
+    println!(
+        "[Jobs] Queued {} job {} for session {}",
+        job.kind, job.id, job.session_id
+    );
+    emit_job(app, &job);
+    WAKE.notify_one();
+    Ok(job)
+}
+
+fn emit_job(app: &AppHandle, job: &Job) {
+    let _ = app.emit("job-updated", job);
+}
+
+/// Start the dispatcher. Call once, after the database is initialized.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_dispatcher(app).await {
+            eprintln!("[Jobs] Dispatcher stopped: {}", e);
+        }
+    });
+}
+
+async fn run_dispatcher(app: AppHandle) -> Result<()> {
+    let pool = database::get_pool()?;
+
+    let resumed = requeue_interrupted(pool).await?;
+    if resumed > 0 {
+        println!("[Jobs] Resuming {} interrupted job(s)", resumed);
+    }
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS));
+    loop {
+        let permit = semaphore.clone().acquire_owned().await?;
+
+        // Looked up each time: the pool is replaced when the database is re-keyed
+        let claimed = match database::get_pool() {
+            Ok(pool) => claim_next(pool, now())
+                .await
+                .map(|job| job.map(|job| (pool, job))),
+            Err(e) => Err(e),
+        };
+
+        match claimed {
+            Ok(Some((pool, job))) => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    run_job(&app, pool, job).await;
+                    drop(permit);
+                });
+            }
+            Ok(None) => {
+                drop(permit);
+                tokio::select! {
+                    _ = WAKE.notified() => {}
+                    _ = tokio::time::sleep(IDLE_POLL_INTERVAL) => {}
+                }
+            }
+            Err(e) => {
+                println!("[Jobs] Failed to claim next job: {}", e);
+                drop(permit);
+                tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+async fn run_job(app: &AppHandle, pool: &'static SqlitePool, job: Job) {
+    println!(
+        "[Jobs] Running {} job {} for session {} (attempt {}/{})",
+        job.kind, job.id, job.session_id, job.attempts, job.max_attempts
+    );
+    emit_job(app, &job);
+
+    let result = execute(app, pool, &job).await;
+
+    let updated = match result {
+        Ok(()) => {
+            let updated = mark_completed(pool, &job.id).await;
+            if let Some(next) = next_step(&job) {
+                let request = EnqueueJobRequest {
+                    session_id: job.session_id.clone(),
+                    kind: next.as_str().to_string(),
+                    template_id: job.template_id.clone(),
+                    chain: Some(true),
+                    priority: Some(job.priority),
+                };
+                if let Err(e) = enqueue(app, &request).await {
+                    println!("[Jobs] Failed to chain {} job: {}", next.as_str(), e);
+                }
+            }
+            updated
+        }
+        Err(e) if is_cancelled_error(&e) => {
+            println!("[Jobs] Job {} cancelled", job.id);
+            if let Err(e) = database::reset_status_after_cancel(app, &job.session_id).await {
+                println!("[Jobs] Failed to reset session status: {}", e);
+            }
+            mark_cancelled(pool, &job.id).await
+        }
+        Err(e) => {
+            println!("[Jobs] Job {} failed: {}", job.id, e);
+            let updated = mark_failed(pool, &job, &e.to_string(), now()).await;
+            let gave_up = matches!(updated, Ok(ref j) if j.status == "failed");
+            // Indexing failures don't affect the session itself
+            if gave_up && job.kind != JobType::Index.as_str() {
+                set_session_status(app, &job.session_id, "error", Some(e.to_string())).await;
+            }
+            updated
+        }
+    };
+
+    match updated {
+        Ok(job) => emit_job(app, &job),
+        Err(e) => println!("[Jobs] Failed to update job {}: {}", job.id, e),
+    }
+    // A finished job may unblock others for the same session
+    WAKE.notify_one();
+}
+
+async fn execute(app: &AppHandle, pool: &'static SqlitePool, job: &Job) -> Result<()> {
+    let kind = JobType::parse(&job.kind)?;
+
+    // Register before doing any work, then pick up a cancel that came in
+    // before there was a token to signal
+    let guard = cancellation_kind(kind).map(|kind| cancellation::register(kind, &job.session_id));
+    if guard.is_some() && cancel_requested(pool, &job.id).await? {
+        return Err(Cancelled.into());
+    }
+    let cancel = guard
+        .as_ref()
+        .map(CancellationGuard::token)
+        .unwrap_or_default();
+
+    let session = database::get_session(app, &job.session_id).await?;
+
+    match kind {
+        JobType::Transcribe => {
+            set_session_status(app, &session.id, "transcribing", None).await;
+            whisper::transcribe_and_save(
+                app,
+                &session.id,
+                &session.audio_path,
+                progress_listener(pool, job.id.clone()),
+                cancel,
+            )
+            .await?;
+            set_session_status(app, &session.id, "complete", None).await;
+        }
+        JobType::Generate => {
+            let template_id = job
+                .template_id
+                .as_deref()
+                .ok_or_else(|| anyhow!("Generate job has no template"))?;
+            let transcript = session
+                .transcript
+                .filter(|t| !t.trim().is_empty())
+                .ok_or_else(|| anyhow!("Session has no transcript"))?;
+
+            set_session_status(app, &session.id, "generating", None).await;
+            let note =
+                llm::generate_note_streaming(app, &session.id, &transcript, template_id, cancel)
+                    .await?;
+            database::update_session(
+                app,
+                UpdateSessionRequest {
+                    id: session.id.clone(),
+                    title: None,
+                    transcript: None,
+                    generated_note: Some(note),
+                    status: Some("complete".to_string()),
+                    error_message: None,
+                },
+            )
+            .await?;
+        }
+        JobType::Index => {
+            let chunks = rag::index_session(pool, &session.id).await?;
+            println!("[Jobs] Indexed session {} ({} chunks)", session.id, chunks);
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_session_status(
+    app: &AppHandle,
+    session_id: &str,
+    status: &str,
+    error_message: Option<String>,
+) {
+    let request = UpdateSessionRequest {
+        id: session_id.to_string(),
+        title: None,
+        transcript: None,
+        generated_note: None,
+        status: Some(status.to_string()),
+        error_message,
+    };
+    if let Err(e) = database::update_session(app, request).await {
+        println!("[Jobs] Failed to set session {} to {}: {}", session_id, status, e);
+    }
+}
+
+/// Persist transcription progress on the job, once per whole percent
+fn progress_listener(pool: &'static SqlitePool, job_id: String) -> ProgressListener {
+    let last_percent = Mutex::new(-1i64);
+    Arc::new(move |event: &TranscriptionProgressEvent| {
+        let percent = event.progress.floor() as i64;
+        {
+            let mut last = last_percent.lock();
+            if *last == percent {
+                return;
+            }
+            *last = percent;
+        }
+        let job_id = job_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = update_progress(pool, &job_id, percent as f64).await;
+        });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::migrated_pool;
+
+    async fn create_session(pool: &SqlitePool, id: &str) {
+        let now = now();
+        sqlx::query(
+            "INSERT OR IGNORE INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Test', 'general', ?, ?)",
+        )
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT OR IGNORE INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('folder', 'ws', 'Test', ?, ?)",
+        )
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES (?, 'folder', '/tmp/a.wav', ?, ?)",
+        )
+        .bind(id)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn request(session_id: &str, kind: &str, priority: i64) -> EnqueueJobRequest {
+        EnqueueJobRequest {
+            session_id: session_id.to_string(),
+            kind: kind.to_string(),
+            template_id: None,
+            chain: None,
+            priority: Some(priority),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claim_in_priority_order() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        create_session(&pool, "s2").await;
+
+        let low = insert_job(&pool, &request("s1", "transcribe", 0)).await.unwrap();
+        let high = insert_job(&pool, &request("s2", "transcribe", 5)).await.unwrap();
+
+        let first = claim_next(&pool, now()).await.unwrap().unwrap();
+        assert_eq!(first.id, high.id);
+        assert_eq!(first.status, "running");
+        assert_eq!(first.attempts, 1);
+
+        let second = claim_next(&pool, now()).await.unwrap().unwrap();
+        assert_eq!(second.id, low.id);
+        assert!(claim_next(&pool, now()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_one_running_job_per_session() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+
+        insert_job(&pool, &request("s1", "transcribe", 0)).await.unwrap();
+        insert_job(&pool, &request("s1", "index", 0)).await.unwrap();
+
+        assert!(claim_next(&pool, now()).await.unwrap().is_some());
+        assert!(claim_next(&pool, now()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_retries_with_backoff() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        insert_job(&pool, &request("s1", "index", 0)).await.unwrap();
+
+        let now = now();
+        let job = claim_next(&pool, now).await.unwrap().unwrap();
+        let retried = mark_failed(&pool, &job, "boom", now).await.unwrap();
+
+        assert_eq!(retried.status, "queued");
+        assert_eq!(retried.run_after, now + RETRY_BASE_DELAY_SECS);
+        assert_eq!(retried.error.as_deref(), Some("boom"));
+        // Not runnable until the backoff elapses
+        assert!(claim_next(&pool, now).await.unwrap().is_none());
+        assert!(claim_next(&pool, retried.run_after).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_job_fails_after_max_attempts() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        insert_job(&pool, &request("s1", "index", 0)).await.unwrap();
+
+        let mut at = now();
+        let mut job = claim_next(&pool, at).await.unwrap().unwrap();
+        loop {
+            job = mark_failed(&pool, &job, "boom", at).await.unwrap();
+            if job.status == "failed" {
+                break;
+            }
+            at = job.run_after;
+            job = claim_next(&pool, at).await.unwrap().unwrap();
+        }
+
+        assert_eq!(job.attempts, DEFAULT_MAX_ATTEMPTS);
+        assert!(job.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_requeue_interrupted() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        let job = insert_job(&pool, &request("s1", "transcribe", 0)).await.unwrap();
+        claim_next(&pool, now()).await.unwrap();
+
+        assert_eq!(requeue_interrupted(&pool).await.unwrap(), 1);
+
+        let job = get_job(&pool, &job.id).await.unwrap();
+        assert_eq!(job.status, "queued");
+        assert_eq!(job.attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_priority_and_cancel_queued() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        let job = insert_job(&pool, &request("s1", "transcribe", 0)).await.unwrap();
+
+        let job = set_priority(&pool, &job.id, 10).await.unwrap();
+        assert_eq!(job.priority, 10);
+
+        let job = cancel_job(&pool, &job.id).await.unwrap();
+        assert_eq!(job.status, "cancelled");
+        assert!(set_priority(&pool, &job.id, 1).await.is_err());
+        assert!(claim_next(&pool, now()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_job_is_saved_for_the_worker() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        insert_job(&pool, &request("s1", "transcribe", 0)).await.unwrap();
+        let job = claim_next(&pool, now()).await.unwrap().unwrap();
+        assert!(!cancel_requested(&pool, &job.id).await.unwrap());
+
+        // No token is registered yet, so only the saved request can stop it
+        let job = cancel_job(&pool, &job.id).await.unwrap();
+        assert_eq!(job.status, "running");
+        assert!(cancel_requested(&pool, &job.id).await.unwrap());
+
+        insert_job(&pool, &request("s1", "index", 0)).await.unwrap();
+        sqlx::query("UPDATE jobs SET status = 'completed' WHERE id = ?")
+            .bind(&job.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let index = claim_next(&pool, now()).await.unwrap().unwrap();
+        assert!(cancel_job(&pool, &index.id).await.is_err());
+        assert!(!cancel_requested(&pool, &index.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_list_jobs_order() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+        create_session(&pool, "s2").await;
+        create_session(&pool, "s3").await;
+
+        let queued_low = insert_job(&pool, &request("s1", "index", 0)).await.unwrap();
+        let queued_high = insert_job(&pool, &request("s2", "index", 3)).await.unwrap();
+        let running = insert_job(&pool, &request("s3", "index", 9)).await.unwrap();
+        claim_next(&pool, now()).await.unwrap();
+
+        let ids: Vec<String> = list_jobs(&pool, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|j| j.id)
+            .collect();
+        assert_eq!(ids, vec![running.id, queued_high.id, queued_low.id]);
+
+        let queued = list_jobs(&pool, Some("queued")).await.unwrap();
+        assert_eq!(queued.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_generate_requires_template() {
+        let pool = migrated_pool().await;
+        create_session(&pool, "s1").await;
+
+        assert!(insert_job(&pool, &request("s1", "generate", 0)).await.is_err());
+        assert!(insert_job(&pool, &request("s1", "bogus", 0)).await.is_err());
+    }
+
+    #[test]
+    fn test_next_step() {
+        let job = |kind: &str, template: Option<&str>, chain: bool| Job {
+            id: "j".to_string(),
+            session_id: "s".to_string(),
+            kind: kind.to_string(),
+            status: "completed".to_string(),
+            priority: 0,
+            attempts: 1,
+            max_attempts: 3,
+            template_id: template.map(str::to_string),
+            chain,
+            progress: 100.0,
+            error: None,
+            run_after: 0,
+            created_at: 0,
+            updated_at: 0,
+            started_at: None,
+            finished_at: None,
+        };
+
+        assert_eq!(next_step(&job("transcribe", Some("t"), true)), Some(JobType::Generate));
+        assert_eq!(next_step(&job("transcribe", None, true)), Some(JobType::Index));
+        assert_eq!(next_step(&job("generate", Some("t"), true)), Some(JobType::Index));
+        assert_eq!(next_step(&job("index", None, true)), None);
+        assert_eq!(next_step(&job("transcribe", Some("t"), false)), None);
+    }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        assert_eq!(retry_delay_secs(1), RETRY_BASE_DELAY_SECS);
+        assert_eq!(retry_delay_secs(2), RETRY_BASE_DELAY_SECS * 2);
+        assert_eq!(retry_delay_secs(3), RETRY_BASE_DELAY_SECS * 4);
+    }
+}
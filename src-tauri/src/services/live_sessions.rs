@@ -0,0 +1,422 @@
+//! Live session persistence
+//!
+//! A session recorded with live transcription is created when recording
+//! starts and marked live with `live_started_at` (see migration 013). Each
+//! segment the worker confirms is appended to its `transcript_segments`
+//! straight away, so a crash mid-recording loses at most the segment being
+//! transcribed. Saving the recording finishes the session. A session still
+//! live when nothing is recording it was interrupted, and can be recovered
+//! with the segments saved so far or discarded.
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use std::path::{Path, PathBuf};
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::{audio, encryption};
+use crate::models::{transcript_from_segments, InterruptedSession, TranscriptSegment};
+
+const PREVIEW_CHARS: usize = 200;
+
+fn parse_segments(segments_json: Option<&str>) -> Vec<TranscriptSegment> {
+    segments_json
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// A recording in the audio folder named after the session, such as a
+/// system recording cut short
+fn find_recording(audio_dir: &Path, session_id: &str) -> Option<PathBuf> {
+    let mut found: Vec<PathBuf> = std::fs::read_dir(audio_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file() && path.file_stem().and_then(|stem| stem.to_str()) == Some(session_id)
+        })
+        .collect();
+    found.sort();
+    found.into_iter().next()
+}
+
+/// Create the session for a recording that is starting. It has no audio yet;
+/// `finish` attaches the recording once it is saved.
+pub async fn begin(
+    pool: &SqlitePool,
+    session_id: &str,
+    folder_id: &str,
+    title: Option<&str>,
+    now: i64,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO sessions (id, folder_id, title, audio_path, transcript_segments, status, live_started_at, created_at, updated_at) VALUES (?, ?, ?, '', '[]', 'transcribing', ?, ?, ?)",
+    )
+    .bind(session_id)
+    .bind(folder_id)
+    .bind(title)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    println!("[LiveSessions] Started live session {}", session_id);
+    Ok(())
+}
+
+/// Append a confirmed segment to a live session. Returns false when the
+/// session isn't live, as when segments arrive after it was finished or for
+/// a recording started without one.
+pub async fn append_segment(
+    pool: &SqlitePool,
+    session_id: &str,
+    segment: &TranscriptSegment,
+    now: i64,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE sessions
+        SET transcript_segments = json_insert(COALESCE(transcript_segments, '[]'), '$[#]', json(?)),
+            updated_at = ?
+        WHERE id = ? AND live_started_at IS NOT NULL
+        "#,
+    )
+    .bind(serde_json::to_string(segment)?)
+    .bind(now)
+    .bind(session_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Derive the transcript from the saved segments and end the live state.
+/// The session is `complete` when anything was transcribed, otherwise
+/// `pending` so the recording can be transcribed in full.
+async fn finalize(
+    pool: &SqlitePool,
+    session_id: &str,
+    audio_path: Option<&str>,
+    audio_duration: Option<i64>,
+    now: i64,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let segments_json: Option<String> = sqlx::query_scalar(
+        "SELECT transcript_segments FROM sessions WHERE id = ? AND live_started_at IS NOT NULL",
+    )
+    .bind(session_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| anyhow!("No live recording for session {}", session_id))?;
+
+    let segments = parse_segments(segments_json.as_deref());
+    let transcript = transcript_from_segments(&segments);
+    let status = if transcript.is_empty() {
+        "pending"
+    } else {
+        "complete"
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE sessions
+        SET transcript = ?, status = ?, live_started_at = NULL,
+            audio_path = COALESCE(?, audio_path),
+            audio_duration = COALESCE(?, audio_duration),
+            audio_purged_at = CASE WHEN ? IS NULL THEN ? END,
+            updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind((!transcript.is_empty()).then_some(&transcript))
+    .bind(status)
+    .bind(audio_path)
+    .bind(audio_duration)
+    .bind(audio_path)
+    .bind(now)
+    .bind(now)
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Attach the saved recording to a live session and finish it
+pub async fn finish(
+    pool: &SqlitePool,
+    session_id: &str,
+    audio_path: &str,
+    audio_duration: Option<i64>,
+    now: i64,
+) -> Result<()> {
+    finalize(pool, session_id, Some(audio_path), audio_duration, now).await?;
+    println!("[LiveSessions] Finished live session {}", session_id);
+    Ok(())
+}
+
+/// Live sessions nothing is recording any more, oldest first. `active` are
+/// the sessions being transcribed right now.
+pub async fn interrupted(
+    pool: &SqlitePool,
+    audio_dir: &Path,
+    active: &[String],
+) -> Result<Vec<InterruptedSession>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, folder_id, title, live_started_at, transcript_segments
+        FROM sessions
+        WHERE live_started_at IS NOT NULL AND deleted_at IS NULL
+        ORDER BY live_started_at, id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter(|row| !active.contains(&row.get::<String, _>("id")))
+        .map(|row| {
+            let id: String = row.get("id");
+            let segments_json: Option<String> = row.get("transcript_segments");
+            let segments = parse_segments(segments_json.as_deref());
+            let transcript = transcript_from_segments(&segments);
+            InterruptedSession {
+                audio_path: find_recording(audio_dir, &id)
+                    .map(|path| path.to_string_lossy().to_string()),
+                folder_id: row.get("folder_id"),
+                title: row.get("title"),
+                started_at: row.get("live_started_at"),
+                segment_count: segments.len() as i64,
+                transcribed_seconds: segments.last().map_or(0.0, |s| s.end),
+                transcript_preview: (!transcript.is_empty())
+                    .then(|| transcript.chars().take(PREVIEW_CHARS).collect()),
+                id,
+            }
+        })
+        .collect())
+}
+
+/// Keep an interrupted session with the transcript saved so far. A partial
+/// recording in the audio folder is attached once its header is repaired and
+/// it's encrypted like any other recording; without one the session is kept
+/// with no audio.
+pub async fn recover(
+    pool: &SqlitePool,
+    audio_dir: &Path,
+    session_id: &str,
+    now: i64,
+) -> Result<()> {
+    let recording = find_recording(audio_dir, session_id);
+    if let Some(path) = &recording {
+        // Hold off a rekey until the recording is encrypted with the current key
+        let _rekey = encryption::REKEY_LOCK.read().await;
+        audio::repair_wav_header(path)?;
+        encryption::protect_audio_file(path)?;
+    }
+    let audio_path = recording
+        .as_ref()
+        .map(|path| path.to_string_lossy().to_string());
+    finalize(pool, session_id, audio_path.as_deref(), None, now).await?;
+
+    println!(
+        "[LiveSessions] Recovered session {} ({})",
+        session_id,
+        if audio_path.is_some() {
+            "with its partial recording"
+        } else {
+            "without audio"
+        }
+    );
+    Ok(())
+}
+
+/// Delete a live session for good, with any partial recording. Used when a
+/// recording is discarded, or an interrupted one isn't worth keeping.
+pub async fn discard(pool: &SqlitePool, audio_dir: &Path, session_id: &str) -> Result<()> {
+    let result = sqlx::query("DELETE FROM sessions WHERE id = ? AND live_started_at IS NOT NULL")
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(anyhow!("No live recording for session {}", session_id));
+    }
+
+    if let Some(path) = find_recording(audio_dir, session_id) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            println!("[LiveSessions] Failed to delete {:?}: {}", path, e);
+        }
+    }
+    let event = AuditEvent::session(AuditAction::Delete, session_id)
+        .with_details(serde_json::json!({ "reason": "live_recording_discarded" }));
+    audit::record(pool, event).await?;
+
+    println!("[LiveSessions] Discarded live session {}", session_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    async fn setup() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Practice', 'therapy', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f', 'ws', 'Client', 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("live-sessions-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn segment(start: f64, end: f64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start,
+            end,
+            text: text.to_string(),
+            speaker: None,
+            avg_logprob: None,
+            words: None,
+        }
+    }
+
+    async fn session_row(pool: &SqlitePool, id: &str) -> sqlx::sqlite::SqliteRow {
+        sqlx::query("SELECT * FROM sessions WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_segments_are_saved_as_they_arrive() {
+        let pool = setup().await;
+        let dir = temp_dir();
+        begin(&pool, "live", "f", Some("Intake"), 100)
+            .await
+            .unwrap();
+
+        assert!(
+            append_segment(&pool, "live", &segment(0.0, 2.5, " Hello"), 101)
+                .await
+                .unwrap()
+        );
+        assert!(
+            append_segment(&pool, "live", &segment(2.5, 4.0, "there."), 102)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !append_segment(&pool, "other", &segment(0.0, 1.0, "Lost"), 102)
+                .await
+                .unwrap()
+        );
+
+        let row = session_row(&pool, "live").await;
+        let segments: Vec<TranscriptSegment> =
+            serde_json::from_str(row.get("transcript_segments")).unwrap();
+        assert_eq!(
+            segments,
+            [segment(0.0, 2.5, " Hello"), segment(2.5, 4.0, "there.")]
+        );
+
+        finish(&pool, "live", "/audio/live.webm", Some(4), 110)
+            .await
+            .unwrap();
+        let row = session_row(&pool, "live").await;
+        assert_eq!(
+            row.get::<Option<String>, _>("transcript").as_deref(),
+            Some("Hello there.")
+        );
+        assert_eq!(row.get::<String, _>("status"), "complete");
+        assert_eq!(row.get::<String, _>("audio_path"), "/audio/live.webm");
+        assert_eq!(row.get::<Option<i64>, _>("audio_purged_at"), None);
+        assert_eq!(row.get::<Option<i64>, _>("live_started_at"), None);
+
+        // Finished sessions take no more segments and aren't interrupted
+        assert!(
+            !append_segment(&pool, "live", &segment(4.0, 5.0, "Late"), 111)
+                .await
+                .unwrap()
+        );
+        assert!(finish(&pool, "live", "/audio/live.webm", None, 112)
+            .await
+            .is_err());
+        assert!(interrupted(&pool, &dir, &[]).await.unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recover_interrupted_sessions() {
+        let pool = setup().await;
+        let dir = temp_dir();
+        begin(&pool, "with-audio", "f", None, 100).await.unwrap();
+        begin(&pool, "silent", "f", None, 200).await.unwrap();
+        begin(&pool, "recording", "f", None, 300).await.unwrap();
+        append_segment(&pool, "with-audio", &segment(0.0, 3.0, "Saved so far"), 101)
+            .await
+            .unwrap();
+        let recording = &dir.join("with-audio.wav");
+        std::fs::write(&recording, b"RIFF").unwrap();
+
+        let found = interrupted(&pool, &dir, &["recording".to_string()])
+            .await
+            .unwrap();
+        let ids: Vec<&str> = found.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["with-audio", "silent"]);
+        assert_eq!(found[0].segment_count, 1);
+        assert_eq!(found[0].transcribed_seconds, 3.0);
+        assert_eq!(found[0].transcript_preview.as_deref(), Some("Saved so far"));
+        assert_eq!(
+            found[0].audio_path.as_deref(),
+            Some(recording.to_string_lossy().as_ref())
+        );
+        assert_eq!(found[1].audio_path, None);
+
+        recover(&pool, &dir, "with-audio", 400).await.unwrap();
+        let row = session_row(&pool, "with-audio").await;
+        assert_eq!(row.get::<String, _>("status"), "complete");
+        assert_eq!(
+            row.get::<String, _>("audio_path"),
+            recording.to_string_lossy()
+        );
+
+        // Nothing transcribed and no recording: kept as pending, audio marked gone
+        recover(&pool, &dir, "silent", 400).await.unwrap();
+        let row = session_row(&pool, "silent").await;
+        assert_eq!(row.get::<String, _>("status"), "pending");
+        assert_eq!(row.get::<Option<String>, _>("transcript"), None);
+        assert_eq!(row.get::<Option<i64>, _>("audio_purged_at"), Some(400));
+        assert!(recover(&pool, &dir, "silent", 400).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_discard_removes_session_and_recording() {
+        let pool = setup().await;
+        let dir = temp_dir();
+        begin(&pool, "live", "f", None, 100).await.unwrap();
+        let recording = &dir.join("live.wav");
+        std::fs::write(&recording, b"RIFF").unwrap();
+
+        discard(&pool, &dir, "live").await.unwrap();
+        assert!(!recording.exists());
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        assert!(discard(&pool, &dir, "live").await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,14 @@
 use crate::models::{LlmStreamEvent, OllamaStatus};
+use crate::services::audit::{self, AuditAction, AuditEvent};
+use crate::services::cancellation::CancellationToken;
 use crate::services::database;
+use crate::services::folder_metadata;
 use crate::services::local_llm;
 use anyhow::Result;
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tauri::{AppHandle, Emitter};
 
 #[derive(Debug, Serialize)]
@@ -37,12 +41,31 @@ struct OllamaModel {
     name: String,
 }
 
+/// Record that transcript text is about to leave the machine for a cloud
+/// model. One entry per source session, or a bare transcript entry when the
+/// text did not come from a saved session.
+async fn audit_cloud_call(model: &str, purpose: &str, session_ids: &[&str]) -> Result<()> {
+    let pool = database::get_pool()?;
+    let details =
+        serde_json::json!({ "provider": "openrouter", "model": model, "purpose": purpose });
+    if session_ids.is_empty() {
+        let event = AuditEvent::new(AuditAction::CloudLlm, "transcript", None);
+        audit::record(pool, event.with_details(details)).await?;
+    }
+    for id in session_ids {
+        let event = AuditEvent::session(AuditAction::CloudLlm, id);
+        audit::record(pool, event.with_details(details.clone())).await?;
+    }
+    Ok(())
+}
+
 /// Generate a note from transcript using the configured LLM
 pub async fn generate_note(app: &AppHandle, transcript: &str, template_id: &str) -> Result<String> {
     let settings = database::get_settings(app).await?;
     let template = database::get_template(app, template_id).await?;
 
-    let prompt = template.prompt.replace("{transcript}", transcript);
+    // No session here, so `{folder.*}` placeholders come out empty
+    let prompt = folder_metadata::fill_template(&template.prompt, transcript, &BTreeMap::new());
 
     match settings.llm_provider.as_str() {
         "bundled" => {
@@ -71,6 +94,7 @@ pub async fn generate_note(app: &AppHandle, transcript: &str, template_id: &str)
             if let (Some(api_key), Some(model)) =
                 (&settings.openrouter_api_key, &settings.openrouter_model)
             {
+                audit_cloud_call(model, "note", &[]).await?;
                 generate_with_openrouter(api_key, model, &prompt).await
             } else {
                 Err(anyhow::anyhow!("OpenRouter not configured"))
@@ -80,22 +104,25 @@ pub async fn generate_note(app: &AppHandle, transcript: &str, template_id: &str)
     }
 }
 
-/// Generate a note with streaming (emits events as tokens are generated)
+/// Generate a note with streaming (emits events as tokens are generated).
+/// Stops with a `Cancelled` error once `cancel` is signalled.
 pub async fn generate_note_streaming(
     app: &AppHandle,
     session_id: &str,
     transcript: &str,
     template_id: &str,
+    cancel: CancellationToken,
 ) -> Result<String> {
     let settings = database::get_settings(app).await?;
     let template = database::get_template(app, template_id).await?;
 
-    let prompt = template.prompt.replace("{transcript}", transcript);
+    let variables = folder_metadata::template_variables(database::get_pool()?, session_id).await?;
+    let prompt = folder_metadata::fill_template(&template.prompt, transcript, &variables);
 
     let result = match settings.llm_provider.as_str() {
         "bundled" => {
             // Use bundled local LLM with streaming
-            local_llm::generate_streaming(app, session_id, &prompt, 2048).await
+            local_llm::generate_streaming(app, session_id, &prompt, 2048, cancel).await
         }
         "local" => {
             // Use external Ollama server with streaming
@@ -125,6 +152,7 @@ pub async fn generate_note_streaming(
                 &settings.ollama_endpoint,
                 &model,
                 &prompt,
+                &cancel,
             )
             .await
         }
@@ -133,7 +161,11 @@ pub async fn generate_note_streaming(
             if let (Some(api_key), Some(model)) =
                 (&settings.openrouter_api_key, &settings.openrouter_model)
             {
-                generate_with_openrouter_streaming(app, session_id, api_key, model, &prompt).await
+                audit_cloud_call(model, "note", &[session_id]).await?;
+                generate_with_openrouter_streaming(
+                    app, session_id, api_key, model, &prompt, &cancel,
+                )
+                .await
             } else {
                 Err(anyhow::anyhow!("OpenRouter not configured"))
             }
@@ -160,12 +192,14 @@ pub async fn generate_note_streaming(
     result
 }
 
-/// Generate text using current LLM provider with streaming (for RAG chat)
+/// Generate text using current LLM provider with streaming (for RAG chat).
+/// `sources` are the sessions whose text is in the prompt, for the audit log.
 pub async fn generate_with_current_provider(
     app: &AppHandle,
     session_id: &str,
     prompt: &str,
     max_tokens: u32,
+    sources: &[&str],
 ) -> Result<String> {
     let settings = database::get_settings(app).await?;
 
@@ -180,7 +214,14 @@ pub async fn generate_with_current_provider(
                 println!("[LLM] Auto-loading bundled model: {}", model_id);
                 local_llm::load_model(app, model_id).await?;
             }
-            local_llm::generate_streaming(app, session_id, prompt, max_tokens as usize).await
+            local_llm::generate_streaming(
+                app,
+                session_id,
+                prompt,
+                max_tokens as usize,
+                CancellationToken::new(),
+            )
+            .await
         }
         "local" => {
             let model = if settings.llm_model.is_empty() {
@@ -195,14 +236,30 @@ pub async fn generate_with_current_provider(
             } else {
                 settings.llm_model.clone()
             };
-            generate_with_ollama_streaming(app, session_id, &settings.ollama_endpoint, &model, prompt)
-                .await
+            generate_with_ollama_streaming(
+                app,
+                session_id,
+                &settings.ollama_endpoint,
+                &model,
+                prompt,
+                &CancellationToken::new(),
+            )
+            .await
         }
         "cloud" => {
             if let (Some(api_key), Some(model)) =
                 (&settings.openrouter_api_key, &settings.openrouter_model)
             {
-                generate_with_openrouter_streaming(app, session_id, api_key, model, prompt).await
+                audit_cloud_call(model, "chat", sources).await?;
+                generate_with_openrouter_streaming(
+                    app,
+                    session_id,
+                    api_key,
+                    model,
+                    prompt,
+                    &CancellationToken::new(),
+                )
+                .await
             } else {
                 Err(anyhow::anyhow!("OpenRouter not configured"))
             }
@@ -260,6 +317,7 @@ async fn generate_with_ollama_streaming(
     endpoint: &str,
     model: &str,
     prompt: &str,
+    cancel: &CancellationToken,
 ) -> Result<String> {
     let client = Client::new();
 
@@ -286,6 +344,8 @@ async fn generate_with_ollama_streaming(
     let mut stream = response.bytes_stream();
 
     while let Some(chunk) = stream.next().await {
+        // Returning drops the stream, which closes the connection
+        cancel.check()?;
         let chunk = chunk?;
         let text = String::from_utf8_lossy(&chunk);
 
@@ -373,6 +433,7 @@ async fn generate_with_openrouter_streaming(
     api_key: &str,
     model: &str,
     prompt: &str,
+    cancel: &CancellationToken,
 ) -> Result<String> {
     let client = Client::new();
 
@@ -405,6 +466,7 @@ async fn generate_with_openrouter_streaming(
     let mut buffer = String::new();
 
     while let Some(chunk) = stream.next().await {
+        cancel.check()?;
         let chunk = chunk?;
         buffer.push_str(&String::from_utf8_lossy(&chunk));
 
@@ -1,4 +1,5 @@
 use crate::models::LlmStreamEvent;
+use crate::services::cancellation::CancellationToken;
 use crate::services::llama_backend;
 use anyhow::{anyhow, Result};
 use llama_cpp_2::context::params::LlamaContextParams;
@@ -112,17 +113,21 @@ pub async fn generate(prompt: &str, max_tokens: usize) -> Result<String> {
 
     let prompt_owned = prompt.to_string();
 
-    tokio::task::spawn_blocking(move || generate_sync(&prompt_owned, max_tokens, None))
-        .await
-        .map_err(|e| anyhow!("Task join error: {}", e))?
+    tokio::task::spawn_blocking(move || {
+        generate_sync(&prompt_owned, max_tokens, None, CancellationToken::new())
+    })
+    .await
+    .map_err(|e| anyhow!("Task join error: {}", e))?
 }
 
-/// Generate text using local LLM with streaming (emits tokens as events)
+/// Generate text using local LLM with streaming (emits tokens as events).
+/// Stops early with [`Cancelled`](super::cancellation::Cancelled) when `cancel` fires.
 pub async fn generate_streaming(
     app: &AppHandle,
     session_id: &str,
     prompt: &str,
     max_tokens: usize,
+    cancel: CancellationToken,
 ) -> Result<String> {
     if !is_model_loaded() {
         return Err(anyhow!(
@@ -139,7 +144,7 @@ pub async fn generate_streaming(
 
     // Spawn the blocking generation task
     let generate_handle = tokio::task::spawn_blocking(move || {
-        generate_sync(&prompt_owned, max_tokens, Some(tx))
+        generate_sync(&prompt_owned, max_tokens, Some(tx), cancel)
     });
 
     // Spawn a task to forward tokens as events and wait for it
@@ -182,10 +187,12 @@ pub async fn generate_streaming(
 
 /// Synchronous generation (called from blocking task)
 /// If `token_sender` is provided, tokens are sent through the channel for streaming.
+/// The sampling loop checks `cancel` before every token.
 fn generate_sync(
     prompt: &str,
     max_tokens: usize,
     token_sender: Option<mpsc::Sender<String>>,
+    cancel: CancellationToken,
 ) -> Result<String> {
     let state_lock = get_llm_state().lock();
     let state = state_lock
@@ -242,6 +249,8 @@ fn generate_sync(
     let mut logits_idx = batch.n_tokens() - 1;
 
     for _ in 0..max_tokens {
+        cancel.check()?;
+
         let new_token = sampler.sample(&ctx, logits_idx);
         sampler.accept(new_token);
 
@@ -1,13 +1,37 @@
 pub mod audio;
+pub mod audit;
+pub mod backup;
+pub mod cancellation;
 pub mod chunking;
+pub mod confidence;
 pub mod database;
+pub mod diarization;
 pub mod embeddings;
+pub mod encryption;
 pub mod export;
+pub mod folder_metadata;
+pub mod folders;
+pub mod integrity;
+pub mod jobs;
+pub mod live_sessions;
 pub mod llama_backend;
 pub mod llm;
 pub mod local_llm;
 pub mod model_manager;
 pub mod rag;
+pub mod retention;
+pub mod search;
+pub mod session_list;
 pub mod streaming_transcription;
+// Run by the streaming-worker binary; the app only uses its constants
+#[allow(dead_code)]
+pub mod streaming_worker;
 pub mod system_audio;
+pub mod tags;
+pub mod transcription_backend;
+pub mod trash;
 pub mod whisper;
+#[cfg(not(target_os = "macos"))]
+pub mod whisper_cpp;
+pub mod whisperkit;
+pub mod workspace_types;
@@ -1,4 +1,4 @@
-use crate::services::chunking::{chunk_transcript, chunk_transcript_segments, ChunkingConfig};
+use crate::services::chunking::{chunk_session, ChunkingConfig};
 use crate::services::embeddings::{
     bytes_to_embedding, cosine_similarity, embedding_to_bytes,
     generate_embedding, EMBEDDING_DIM,
@@ -63,15 +63,13 @@ pub async fn index_session(pool: &SqlitePool, session_id: &str) -> Result<usize>
     let transcript: Option<String> = row.get("transcript");
     let transcript_segments: Option<String> = row.get("transcript_segments");
 
-    // Prefer transcript_segments if available (has speaker info)
-    let config = ChunkingConfig::default();
-    let chunks = if let Some(segments_json) = transcript_segments {
-        chunk_transcript_segments(session_id, &segments_json, &config)
-    } else if let Some(text) = transcript {
-        chunk_transcript(session_id, &text, &config)
-    } else {
-        return Err(anyhow!("Session has no transcript"));
-    };
+    let chunks = chunk_session(
+        session_id,
+        transcript.as_deref(),
+        transcript_segments.as_deref(),
+        &ChunkingConfig::default(),
+    )
+    .ok_or_else(|| anyhow!("Session has no transcript"))?;
 
     if chunks.is_empty() {
         return Ok(0);
@@ -156,6 +154,8 @@ pub async fn index_all_pending_sessions(pool: &SqlitePool) -> Result<usize> {
         FROM sessions s
         LEFT JOIN session_indexing_status sis ON s.id = sis.session_id
         WHERE s.transcript IS NOT NULL
+          AND s.deleted_at IS NULL
+          AND s.chunks_purged_at IS NULL
           AND s.status = 'complete'
           AND (sis.is_indexed IS NULL OR sis.is_indexed = 0)
         "#,
@@ -200,7 +200,10 @@ pub async fn search_chunks(
                s.title as session_title
         FROM transcript_chunks tc
         JOIN sessions s ON tc.session_id = s.id
+        JOIN folders f ON s.folder_id = f.id
         WHERE tc.embedding IS NOT NULL
+          AND s.deleted_at IS NULL
+          AND f.deleted_at IS NULL
         "#,
     )
     .fetch_all(pool)
@@ -462,9 +465,13 @@ pub async fn generate_rag_response(
     let context = build_rag_context(chunks);
     let prompt = format_rag_prompt(&context, query);
 
+    let mut sources: Vec<&str> = chunks.iter().map(|c| c.session_id.as_str()).collect();
+    sources.sort_unstable();
+    sources.dedup();
+
     // Use current LLM provider with streaming (emits llm-stream events)
     // 2048 tokens allows for detailed responses
-    llm::generate_with_current_provider(app, conversation_id, &prompt, 2048).await
+    llm::generate_with_current_provider(app, conversation_id, &prompt, 2048, &sources).await
 }
 
 #[cfg(test)]
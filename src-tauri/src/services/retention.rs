@@ -0,0 +1,711 @@
+//! Data retention policies
+//!
+//! A policy on a workspace or folder limits how long each kind of session
+//! data is kept: the audio recording, the transcript, the generated note and
+//! the RAG chunks. A folder's policy replaces its workspace's entirely and
+//! covers its subfolders, unless one has a policy of its own. Ages count from
+//! when the data was finished: transcripts and chunks from when the
+//! transcript was saved, notes and audio from when the note was saved (see
+//! migration 014), or from when the session was recorded if that is unknown.
+//! Audio is kept until the session has a note, since the note is written
+//! from it.
+//! A background sweeper clears
+//! data that has outlived its limit, marks it purged on the session (see
+//! migration 007) and records each purge in `retention_log`, which is never
+//! pruned.
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use std::time::Duration;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::database;
+use crate::models::{
+    RetentionCandidate, RetentionLogEntry, RetentionPolicy, SetRetentionPolicyRequest,
+};
+
+/// How often the sweeper runs
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// A kind of session data with its own retention limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetainedData {
+    Audio,
+    Transcript,
+    Note,
+    Chunks,
+}
+
+impl RetainedData {
+    pub const ALL: [RetainedData; 4] = [
+        RetainedData::Audio,
+        RetainedData::Transcript,
+        RetainedData::Note,
+        RetainedData::Chunks,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RetainedData::Audio => "audio",
+            RetainedData::Transcript => "transcript",
+            RetainedData::Note => "note",
+            RetainedData::Chunks => "chunks",
+        }
+    }
+
+    /// Policy column holding the limit in days
+    fn limit_column(self) -> &'static str {
+        match self {
+            RetainedData::Audio => "audio_days",
+            RetainedData::Transcript => "transcript_days",
+            RetainedData::Note => "note_days",
+            RetainedData::Chunks => "chunks_days",
+        }
+    }
+
+    /// Session column holding when the data was finished, which its age
+    /// counts from
+    fn anchor_column(self) -> &'static str {
+        match self {
+            RetainedData::Transcript | RetainedData::Chunks => "transcript_completed_at",
+            RetainedData::Audio | RetainedData::Note => "note_completed_at",
+        }
+    }
+
+    /// Condition on session `s` that the data's age can count yet. Audio
+    /// only ages once there is a note, so a session waiting for its note
+    /// never loses the recording it's written from.
+    fn anchored_condition(self) -> &'static str {
+        match self {
+            RetainedData::Audio => {
+                "(s.note_completed_at IS NOT NULL OR s.generated_note IS NOT NULL OR s.note_purged_at IS NOT NULL)"
+            }
+            RetainedData::Transcript | RetainedData::Note | RetainedData::Chunks => "1",
+        }
+    }
+
+    /// Session column set once the data is purged
+    fn purged_column(self) -> &'static str {
+        match self {
+            RetainedData::Audio => "audio_purged_at",
+            RetainedData::Transcript => "transcript_purged_at",
+            RetainedData::Note => "note_purged_at",
+            RetainedData::Chunks => "chunks_purged_at",
+        }
+    }
+
+    /// Condition on session `s` that there is still data to purge
+    fn present_condition(self) -> &'static str {
+        match self {
+            RetainedData::Audio => "s.audio_path != ''",
+            RetainedData::Transcript => {
+                "(s.transcript IS NOT NULL OR s.transcript_segments IS NOT NULL)"
+            }
+            RetainedData::Note => "s.generated_note IS NOT NULL",
+            RetainedData::Chunks => {
+                "EXISTS (SELECT 1 FROM transcript_chunks c WHERE c.session_id = s.id)"
+            }
+        }
+    }
+}
+
+fn policy_from_row(row: SqliteRow) -> RetentionPolicy {
+    RetentionPolicy {
+        id: row.get("id"),
+        workspace_id: row.get("workspace_id"),
+        folder_id: row.get("folder_id"),
+        audio_days: row.get("audio_days"),
+        transcript_days: row.get("transcript_days"),
+        note_days: row.get("note_days"),
+        chunks_days: row.get("chunks_days"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+const POLICY_COLUMNS: &str = "id, workspace_id, folder_id, audio_days, transcript_days, note_days, chunks_days, created_at, updated_at";
+
+/// All policies, workspace policies first
+pub async fn list_policies(pool: &SqlitePool) -> Result<Vec<RetentionPolicy>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM retention_policies ORDER BY folder_id IS NOT NULL, created_at",
+        POLICY_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(policy_from_row).collect())
+}
+
+/// Create the policy for a workspace or folder, or replace its limits
+pub async fn set_policy(
+    pool: &SqlitePool,
+    request: &SetRetentionPolicyRequest,
+    now: i64,
+) -> Result<RetentionPolicy> {
+    let (scope_column, scope_id) = match (&request.workspace_id, &request.folder_id) {
+        (Some(id), None) => ("workspace_id", id),
+        (None, Some(id)) => ("folder_id", id),
+        _ => {
+            return Err(anyhow!(
+                "A retention policy applies to either a workspace or a folder"
+            ))
+        }
+    };
+    for days in [
+        request.audio_days,
+        request.transcript_days,
+        request.note_days,
+        request.chunks_days,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if days < 1 {
+            return Err(anyhow!("Retention limits must be at least 1 day"));
+        }
+    }
+
+    let row = sqlx::query(&format!(
+        r#"
+        INSERT INTO retention_policies
+            (id, {scope_column}, audio_days, transcript_days, note_days, chunks_days, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT({scope_column}) DO UPDATE SET
+            audio_days = excluded.audio_days,
+            transcript_days = excluded.transcript_days,
+            note_days = excluded.note_days,
+            chunks_days = excluded.chunks_days,
+            updated_at = excluded.updated_at
+        RETURNING {POLICY_COLUMNS}
+        "#
+    ))
+    .bind(Uuid::new_v4().to_string())
+    .bind(scope_id)
+    .bind(request.audio_days)
+    .bind(request.transcript_days)
+    .bind(request.note_days)
+    .bind(request.chunks_days)
+    .bind(now)
+    .bind(now)
+    .fetch_one(pool)
+    .await?;
+
+    println!("[Retention] Set policy for {} {}", scope_column, scope_id);
+    Ok(policy_from_row(row))
+}
+
+/// Remove a policy. Data already purged stays purged.
+pub async fn delete_policy(pool: &SqlitePool, id: &str) -> Result<()> {
+    let result = sqlx::query("DELETE FROM retention_policies WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(anyhow!("Retention policy not found: {}", id));
+    }
+    println!("[Retention] Deleted policy {}", id);
+    Ok(())
+}
+
+fn candidate_from_row(row: SqliteRow, data: RetainedData) -> RetentionCandidate {
+    let anchored_at: i64 = row.get("anchored_at");
+    let retention_days: i64 = row.get("days");
+    RetentionCandidate {
+        session_id: row.get("id"),
+        session_title: row.get("title"),
+        folder_id: row.get("folder_id"),
+        workspace_id: row.get("workspace_id"),
+        policy_id: row.get("policy_id"),
+        data_type: data.as_str().to_string(),
+        retention_days,
+        session_created_at: row.get("created_at"),
+        anchored_at,
+        due_at: anchored_at + retention_days * SECONDS_PER_DAY,
+    }
+}
+
+/// Session data past its retention limit at `now`, oldest first. Nothing is
+/// changed, so this doubles as the dry run.
+pub async fn find_due(
+    pool: &SqlitePool,
+    now: i64,
+    workspace_id: Option<&str>,
+) -> Result<Vec<RetentionCandidate>> {
+    let mut due = Vec::new();
+    for data in RetainedData::ALL {
+        let rows = sqlx::query(&format!(
+            r#"
+            WITH RECURSIVE ancestry(folder_id, ancestor_id, depth) AS (
+                SELECT id, id, 0 FROM folders
+                UNION ALL
+                SELECT a.folder_id, f.parent_id, a.depth + 1
+                FROM ancestry a
+                JOIN folders f ON f.id = a.ancestor_id
+                WHERE f.parent_id IS NOT NULL
+            )
+            SELECT s.id, s.title, s.folder_id, s.created_at,
+                   COALESCE(s.{anchor}, s.created_at) AS anchored_at, f.workspace_id,
+                   p.id AS policy_id, p.{limit} AS days
+            FROM sessions s
+            JOIN folders f ON f.id = s.folder_id
+            JOIN retention_policies p ON p.id = COALESCE(
+                (SELECT fp.id FROM ancestry a
+                 JOIN retention_policies fp ON fp.folder_id = a.ancestor_id
+                 WHERE a.folder_id = s.folder_id
+                 ORDER BY a.depth LIMIT 1),
+                (SELECT id FROM retention_policies WHERE workspace_id = f.workspace_id)
+            )
+            WHERE p.{limit} IS NOT NULL
+              AND COALESCE(s.{anchor}, s.created_at) + p.{limit} * ? <= ?
+              AND s.{purged} IS NULL
+              AND {present}
+              AND {anchored}
+              AND (? IS NULL OR f.workspace_id = ?)
+            "#,
+            limit = data.limit_column(),
+            anchor = data.anchor_column(),
+            purged = data.purged_column(),
+            present = data.present_condition(),
+            anchored = data.anchored_condition(),
+        ))
+        .bind(SECONDS_PER_DAY)
+        .bind(now)
+        .bind(workspace_id)
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        due.extend(rows.into_iter().map(|row| candidate_from_row(row, data)));
+    }
+
+    due.sort_by(|a, b| a.due_at.cmp(&b.due_at).then(a.session_id.cmp(&b.session_id)));
+    Ok(due)
+}
+
+/// Purge everything due at `now`, logging and auditing each purge in the same
+/// transaction. Audio files are removed once it commits. Returns what was
+/// purged.
+pub async fn sweep(pool: &SqlitePool, now: i64) -> Result<Vec<RetentionCandidate>> {
+    let due = find_due(pool, now, None).await?;
+    if due.is_empty() {
+        return Ok(due);
+    }
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    let mut audio_paths = Vec::new();
+
+    for item in &due {
+        match item.data_type.as_str() {
+            "audio" => {
+                let path: String = sqlx::query_scalar("SELECT audio_path FROM sessions WHERE id = ?")
+                    .bind(&item.session_id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                audio_paths.push(path);
+                sqlx::query("UPDATE sessions SET audio_purged_at = ? WHERE id = ?")
+                    .bind(now)
+                    .bind(&item.session_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            "transcript" => {
+                sqlx::query(
+                    "UPDATE sessions SET transcript = NULL, transcript_segments = NULL, transcript_purged_at = ? WHERE id = ?",
+                )
+                .bind(now)
+                .bind(&item.session_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            "note" => {
+                sqlx::query(
+                    "UPDATE sessions SET generated_note = NULL, note_format = NULL, note_purged_at = ? WHERE id = ?",
+                )
+                .bind(now)
+                .bind(&item.session_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            _ => {
+                sqlx::query("DELETE FROM transcript_chunks WHERE session_id = ?")
+                    .bind(&item.session_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM session_indexing_status WHERE session_id = ?")
+                    .bind(&item.session_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sessions SET chunks_purged_at = ? WHERE id = ?")
+                    .bind(now)
+                    .bind(&item.session_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO retention_log
+                (id, session_id, folder_id, workspace_id, policy_id, data_type, retention_days, session_created_at, purged_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&item.session_id)
+        .bind(&item.folder_id)
+        .bind(&item.workspace_id)
+        .bind(&item.policy_id)
+        .bind(&item.data_type)
+        .bind(item.retention_days)
+        .bind(item.session_created_at)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let event = AuditEvent::session(AuditAction::Purge, &item.session_id).with_details(
+            serde_json::json!({
+                "dataType": item.data_type,
+                "policyId": item.policy_id,
+                "retentionDays": item.retention_days,
+            }),
+        );
+        audit::record_system_in(&mut *tx, &guard, event).await?;
+    }
+
+    tx.commit().await?;
+    drop(guard);
+
+    for path in &audio_paths {
+        if let Err(e) = std::fs::remove_file(path) {
+            eprintln!("Warning: Could not delete audio file {}: {}", path, e);
+        }
+    }
+
+    println!("[Retention] Purged {} item(s) past their retention limit", due.len());
+    Ok(due)
+}
+
+fn log_entry_from_row(row: SqliteRow) -> RetentionLogEntry {
+    RetentionLogEntry {
+        id: row.get("id"),
+        session_id: row.get("session_id"),
+        folder_id: row.get("folder_id"),
+        workspace_id: row.get("workspace_id"),
+        policy_id: row.get("policy_id"),
+        data_type: row.get("data_type"),
+        retention_days: row.get("retention_days"),
+        session_created_at: row.get("session_created_at"),
+        purged_at: row.get("purged_at"),
+    }
+}
+
+/// Purge records, most recent first
+pub async fn list_log(
+    pool: &SqlitePool,
+    workspace_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<RetentionLogEntry>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, session_id, folder_id, workspace_id, policy_id, data_type, retention_days, session_created_at, purged_at
+        FROM retention_log
+        WHERE ? IS NULL OR workspace_id = ?
+        ORDER BY purged_at DESC, session_id, data_type
+        LIMIT ?
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(workspace_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(log_entry_from_row).collect())
+}
+
+/// Start the background loop that enforces retention policies
+pub fn start(_app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            // Looked up each time: the pool is replaced when the database is re-keyed
+            let pool = match database::get_pool() {
+                Ok(pool) => pool,
+                Err(_) => continue,
+            };
+            if let Err(e) = sweep(pool, now()).await {
+                eprintln!("[Retention] Sweep failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Test', 'therapy', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f1', 'ws', 'Client A', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f2', 'ws', 'Client B', 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    async fn insert_session(pool: &SqlitePool, id: &str, folder_id: &str, audio_path: &str) {
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, title, audio_path, transcript, generated_note, status, created_at, updated_at) VALUES (?, ?, 'Session', ?, 'Talked about sleep', 'Note', 'complete', 0, 0)",
+        )
+        .bind(id)
+        .bind(folder_id)
+        .bind(audio_path)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO transcript_chunks (id, session_id, chunk_index, text, created_at, updated_at) VALUES (?, ?, 0, 'Talked about sleep', 0, 0)",
+        )
+        .bind(format!("{}-chunk", id))
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn policy(workspace_id: Option<&str>, folder_id: Option<&str>) -> SetRetentionPolicyRequest {
+        SetRetentionPolicyRequest {
+            workspace_id: workspace_id.map(String::from),
+            folder_id: folder_id.map(String::from),
+            audio_days: None,
+            transcript_days: None,
+            note_days: None,
+            chunks_days: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_policy_validates_and_replaces() {
+        let pool = create_test_pool().await;
+
+        assert!(set_policy(&pool, &policy(None, None), 0).await.is_err());
+        assert!(set_policy(&pool, &policy(Some("ws"), Some("f1")), 0).await.is_err());
+        let mut invalid = policy(Some("ws"), None);
+        invalid.audio_days = Some(0);
+        assert!(set_policy(&pool, &invalid, 0).await.is_err());
+
+        let mut request = policy(Some("ws"), None);
+        request.audio_days = Some(30);
+        let first = set_policy(&pool, &request, 1).await.unwrap();
+
+        request.audio_days = None;
+        request.transcript_days = Some(365 * 7);
+        let second = set_policy(&pool, &request, 2).await.unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.audio_days, None);
+        assert_eq!(second.transcript_days, Some(365 * 7));
+        assert_eq!(second.created_at, 1);
+        assert_eq!(second.updated_at, 2);
+
+        assert_eq!(list_policies(&pool).await.unwrap().len(), 1);
+        delete_policy(&pool, &second.id).await.unwrap();
+        assert!(list_policies(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_folder_policy_overrides_workspace() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "s1", "f1", "").await;
+        insert_session(&pool, "s2", "f2", "").await;
+
+        let mut workspace = policy(Some("ws"), None);
+        workspace.transcript_days = Some(10);
+        set_policy(&pool, &workspace, 0).await.unwrap();
+
+        // Folder f2 keeps transcripts longer and drops notes instead
+        let mut folder = policy(None, Some("f2"));
+        folder.transcript_days = Some(100);
+        folder.note_days = Some(10);
+        set_policy(&pool, &folder, 0).await.unwrap();
+
+        let due = find_due(&pool, 10 * DAY, None).await.unwrap();
+        let due: Vec<_> = due
+            .iter()
+            .map(|c| (c.session_id.as_str(), c.data_type.as_str()))
+            .collect();
+        assert_eq!(due, vec![("s1", "transcript"), ("s2", "note")]);
+
+        // Not due yet a day earlier
+        assert!(find_due(&pool, 9 * DAY, None).await.unwrap().is_empty());
+        assert!(find_due(&pool, 10 * DAY, Some("other")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_nearest_folder_policy_applies_to_subfolders() {
+        let pool = create_test_pool().await;
+        for (id, parent) in [("sub", "f2"), ("leaf", "sub")] {
+            sqlx::query(
+                "INSERT INTO folders (id, workspace_id, parent_id, name, created_at, updated_at) VALUES (?, 'ws', ?, ?, 0, 0)",
+            )
+            .bind(id)
+            .bind(parent)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        insert_session(&pool, "in-sub", "sub", "").await;
+        insert_session(&pool, "in-leaf", "leaf", "").await;
+
+        let mut outer = policy(None, Some("f2"));
+        outer.note_days = Some(10);
+        let outer = set_policy(&pool, &outer, 0).await.unwrap();
+        let mut inner = policy(None, Some("leaf"));
+        inner.note_days = Some(100);
+        set_policy(&pool, &inner, 0).await.unwrap();
+
+        let due = find_due(&pool, 10 * DAY, None).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].session_id, "in-sub");
+        assert_eq!(due[0].policy_id, outer.id);
+    }
+
+    #[tokio::test]
+    async fn test_age_counts_from_when_data_was_finished() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "s1", "f1", "/tmp/s1.wav").await;
+        // Recorded on day 0, note signed on day 20
+        sqlx::query(
+            "UPDATE sessions SET generated_note = 'Signed', updated_at = ? WHERE id = 's1'",
+        )
+        .bind(20 * DAY)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut request = policy(Some("ws"), None);
+        request.audio_days = Some(30);
+        request.note_days = Some(30);
+        request.transcript_days = Some(30);
+        set_policy(&pool, &request, 0).await.unwrap();
+
+        // The transcript was never re-saved, so it counts from the recording
+        let due = find_due(&pool, 30 * DAY, None).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].data_type, "transcript");
+        assert_eq!(due[0].anchored_at, 0);
+
+        let due = find_due(&pool, 50 * DAY, None).await.unwrap();
+        let signed: Vec<_> = due.iter().filter(|c| c.data_type != "transcript").collect();
+        assert_eq!(signed.len(), 2);
+        assert!(signed.iter().all(|c| c.anchored_at == 20 * DAY
+            && c.due_at == 50 * DAY
+            && c.session_created_at == 0));
+    }
+
+    #[tokio::test]
+    async fn test_audio_is_kept_until_there_is_a_note() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "s1", "f1", "/tmp/s1.wav").await;
+        sqlx::query("UPDATE sessions SET generated_note = NULL WHERE id = 's1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut request = policy(Some("ws"), None);
+        request.audio_days = Some(30);
+        set_policy(&pool, &request, 0).await.unwrap();
+        assert!(find_due(&pool, 365 * DAY, None).await.unwrap().is_empty());
+
+        // Its age counts from when the note is saved
+        sqlx::query(
+            "UPDATE sessions SET generated_note = 'Signed', updated_at = ? WHERE id = 's1'",
+        )
+        .bind(100 * DAY)
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(find_due(&pool, 129 * DAY, None).await.unwrap().is_empty());
+        let due = find_due(&pool, 130 * DAY, None).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].data_type, "audio");
+        assert_eq!(due[0].anchored_at, 100 * DAY);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_purges_and_logs_once() {
+        let pool = create_test_pool().await;
+
+        let audio = std::env::temp_dir().join(format!("retention-{}.wav", Uuid::new_v4()));
+        std::fs::write(&audio, b"audio").unwrap();
+        insert_session(&pool, "s1", "f1", audio.to_str().unwrap()).await;
+
+        let mut request = policy(Some("ws"), None);
+        request.audio_days = Some(30);
+        request.chunks_days = Some(30);
+        request.transcript_days = Some(365);
+        set_policy(&pool, &request, 0).await.unwrap();
+
+        // The dry run changes nothing
+        assert_eq!(find_due(&pool, 30 * DAY, None).await.unwrap().len(), 2);
+        assert!(audio.exists());
+
+        let purged = sweep(&pool, 30 * DAY).await.unwrap();
+        assert_eq!(purged.len(), 2);
+        assert!(!audio.exists());
+
+        let row = sqlx::query(
+            "SELECT transcript, audio_purged_at, chunks_purged_at, transcript_purged_at FROM sessions WHERE id = 's1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row.get::<Option<String>, _>("transcript").as_deref(), Some("Talked about sleep"));
+        assert_eq!(row.get::<Option<i64>, _>("audio_purged_at"), Some(30 * DAY));
+        assert_eq!(row.get::<Option<i64>, _>("chunks_purged_at"), Some(30 * DAY));
+        assert_eq!(row.get::<Option<i64>, _>("transcript_purged_at"), None);
+        let chunks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transcript_chunks")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(chunks, 0);
+
+        // Already purged data is not purged or logged again
+        assert!(sweep(&pool, 31 * DAY).await.unwrap().is_empty());
+
+        let purged = sweep(&pool, 365 * DAY).await.unwrap();
+        assert_eq!(purged.len(), 1);
+        let transcript: Option<String> =
+            sqlx::query_scalar("SELECT transcript FROM sessions WHERE id = 's1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(transcript, None);
+
+        // The log outlives the session
+        sqlx::query("DELETE FROM sessions").execute(&pool).await.unwrap();
+        let log = list_log(&pool, Some("ws"), 10).await.unwrap();
+        let types: Vec<_> = log.iter().map(|e| e.data_type.as_str()).collect();
+        assert_eq!(types, vec!["transcript", "audio", "chunks"]);
+        assert!(log.iter().all(|e| e.session_id == "s1"));
+    }
+}
@@ -0,0 +1,565 @@
+//! Full-text session search
+//!
+//! Queries the `sessions_fts` FTS5 index (see migration 005). User input is
+//! rewritten into a safe FTS5 expression supporting "quoted phrases",
+//! prefix*, AND / OR / NOT and parentheses; results are ranked with bm25 and
+//! come back with highlighted snippets for each field that matched. Results
+//! can be limited to sessions carrying given tags. Sessions come back without
+//! their content, and a snippet of a transcript or note is audited as a view.
+
+use anyhow::Result;
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::database::{session_from_row, without_content, SESSION_COLUMNS};
+use super::tags;
+use crate::models::{SearchSnippet, SessionSearchResult, SnippetPart};
+
+/// Marks the start and end of a highlighted match in raw snippets
+const HIGHLIGHT_START: char = '\u{2}';
+const HIGHLIGHT_END: char = '\u{3}';
+/// Approximate number of tokens of context per snippet
+const SNIPPET_TOKENS: i32 = 16;
+
+/// Indexed fields, in FTS column order, with their bm25 weights
+const FIELDS: [(&str, f64); 3] = [("title", 10.0), ("transcript", 1.0), ("note", 2.0)];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Phrase(String),
+    Term { text: String, prefix: bool },
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn is_operator(&self) -> bool {
+        matches!(self, Token::And | Token::Or | Token::Not)
+    }
+
+    /// Whether an expression can end with this token
+    fn ends_operand(&self) -> bool {
+        matches!(self, Token::Phrase(_) | Token::Term { .. } | Token::Close)
+    }
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            if !phrase.trim().is_empty() {
+                tokens.push(Token::Phrase(phrase.trim().to_string()));
+            }
+        } else if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' || c == '(' || c == ')' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            match word.as_str() {
+                "AND" => tokens.push(Token::And),
+                "OR" => tokens.push(Token::Or),
+                "NOT" => tokens.push(Token::Not),
+                _ => {
+                    let prefix = word.ends_with('*');
+                    let text = word.trim_end_matches('*');
+                    if !text.is_empty() {
+                        tokens.push(Token::Term {
+                            text: text.to_string(),
+                            prefix,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    tokens
+}
+
+fn parens_balanced(tokens: &[Token]) -> bool {
+    let mut depth = 0i32;
+    for token in tokens {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Rewrite user input into an FTS5 MATCH expression.
+///
+/// Terms and phrases are always quoted so punctuation can't cause syntax
+/// errors; misplaced operators and unbalanced parentheses are dropped.
+/// Returns `None` when nothing searchable is left.
+pub fn build_match_query(input: &str) -> Option<String> {
+    let mut tokens = tokenize(input);
+    if !parens_balanced(&tokens) {
+        tokens.retain(|t| !matches!(t, Token::Open | Token::Close));
+    }
+
+    let mut cleaned: Vec<Token> = Vec::new();
+    for token in tokens {
+        match token {
+            _ if token.is_operator() => match cleaned.last() {
+                Some(last) if last.ends_operand() => cleaned.push(token),
+                Some(last) if last.is_operator() => {
+                    cleaned.pop();
+                    cleaned.push(token);
+                }
+                // Operators can't start an expression or follow "("
+                _ => {}
+            },
+            Token::Close => {
+                if cleaned.last().is_some_and(Token::is_operator) {
+                    cleaned.pop();
+                }
+                if cleaned.last() == Some(&Token::Open) {
+                    // Drop empty groups
+                    cleaned.pop();
+                } else {
+                    cleaned.push(Token::Close);
+                }
+            }
+            _ => cleaned.push(token),
+        }
+    }
+    while cleaned.last().is_some_and(Token::is_operator) {
+        cleaned.pop();
+    }
+
+    if !cleaned
+        .iter()
+        .any(|t| matches!(t, Token::Phrase(_) | Token::Term { .. }))
+    {
+        return None;
+    }
+
+    let parts: Vec<String> = cleaned
+        .iter()
+        .map(|token| match token {
+            Token::Phrase(text) => quote(text),
+            Token::Term { text, prefix } => {
+                let mut term = quote(text);
+                if *prefix {
+                    term.push('*');
+                }
+                term
+            }
+            Token::And => "AND".to_string(),
+            Token::Or => "OR".to_string(),
+            Token::Not => "NOT".to_string(),
+            Token::Open => "(".to_string(),
+            Token::Close => ")".to_string(),
+        })
+        .collect();
+
+    Some(parts.join(" "))
+}
+
+/// Split a raw FTS5 snippet into plain and highlighted parts.
+/// Returns `None` when the snippet contains no match.
+fn parse_snippet(raw: &str) -> Option<Vec<SnippetPart>> {
+    if !raw.contains(HIGHLIGHT_START) {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut highlighted = false;
+
+    for c in raw.chars() {
+        if c == HIGHLIGHT_START || c == HIGHLIGHT_END {
+            if !text.is_empty() {
+                parts.push(SnippetPart {
+                    text: std::mem::take(&mut text),
+                    highlighted,
+                });
+            }
+            highlighted = c == HIGHLIGHT_START;
+        } else {
+            text.push(c);
+        }
+    }
+    if !text.is_empty() {
+        parts.push(SnippetPart { text, highlighted });
+    }
+
+    Some(parts)
+}
+
+/// Live sessions carrying every one of `tag_names`, newest first, for a
+/// search with tags but no text
+async fn tagged_sessions(
+    pool: &SqlitePool,
+    tag_names: &[String],
+    limit: i32,
+) -> Result<Vec<SessionSearchResult>> {
+    let sql = format!(
+        r#"
+        SELECT {}
+        FROM sessions s
+        JOIN folders f ON f.id = s.folder_id
+        WHERE s.deleted_at IS NULL
+          AND f.deleted_at IS NULL
+          AND {}
+        ORDER BY s.created_at DESC
+        LIMIT ?
+        "#,
+        SESSION_COLUMNS,
+        tags::filter_sql(tag_names)
+    );
+    let mut query = sqlx::query(&sql);
+    for name in tag_names {
+        query = query.bind(name);
+    }
+    let rows = query.bind(limit).fetch_all(pool).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SessionSearchResult {
+            session: without_content(session_from_row(row)),
+            score: 0.0,
+            matched_fields: Vec::new(),
+            snippets: Vec::new(),
+        })
+        .collect())
+}
+
+/// Search sessions by title, transcript and note, best matches first. With
+/// `tag_names`, only sessions carrying all of them are searched, and no text
+/// lists every such session.
+pub async fn search_sessions(
+    pool: &SqlitePool,
+    query: &str,
+    tag_names: &[String],
+    limit: i32,
+) -> Result<Vec<SessionSearchResult>> {
+    let tag_names = tags::normalize(tag_names);
+    let match_query = match build_match_query(query) {
+        Some(q) => q,
+        None if tag_names.is_empty() => return Ok(Vec::new()),
+        None => return tagged_sessions(pool, &tag_names, limit).await,
+    };
+    let tag_filter = if tag_names.is_empty() {
+        String::new()
+    } else {
+        format!("AND {}", tags::filter_sql(&tag_names))
+    };
+
+    let snippet_columns: Vec<String> = (0..FIELDS.len())
+        .map(|col| {
+            format!(
+                "snippet(sessions_fts, {col}, char(2), char(3), '…', {SNIPPET_TOKENS}) AS snippet_{col}"
+            )
+        })
+        .collect();
+    let weights: Vec<String> = FIELDS.iter().map(|(_, w)| w.to_string()).collect();
+
+    let sql = format!(
+        r#"
+        SELECT {}, bm25(sessions_fts, {}) AS bm25_score, {}
+        FROM sessions_fts
+        JOIN sessions s ON s.rowid = sessions_fts.rowid
+        JOIN folders f ON f.id = s.folder_id
+        WHERE sessions_fts MATCH ?
+          AND s.deleted_at IS NULL
+          AND f.deleted_at IS NULL
+          {}
+        ORDER BY bm25_score
+        LIMIT ?
+        "#,
+        SESSION_COLUMNS,
+        weights.join(", "),
+        snippet_columns.join(", "),
+        tag_filter
+    );
+
+    let mut query = sqlx::query(&sql).bind(&match_query);
+    for name in &tag_names {
+        query = query.bind(name);
+    }
+    let rows = query.bind(limit).fetch_all(pool).await?;
+
+    let results: Vec<SessionSearchResult> = rows
+        .into_iter()
+        .map(|row| {
+            let bm25_score: f64 = row.get("bm25_score");
+            let snippets: Vec<SearchSnippet> = FIELDS
+                .iter()
+                .enumerate()
+                .filter_map(|(col, (field, _))| {
+                    let raw: Option<String> = row.get(format!("snippet_{}", col).as_str());
+                    parse_snippet(raw.as_deref().unwrap_or("")).map(|parts| SearchSnippet {
+                        field: field.to_string(),
+                        parts,
+                    })
+                })
+                .collect();
+
+            SessionSearchResult {
+                // bm25 is lower for better matches
+                score: -bm25_score,
+                matched_fields: snippets.iter().map(|s| s.field.clone()).collect(),
+                snippets,
+                session: without_content(session_from_row(row)),
+            }
+        })
+        .collect();
+
+    // A snippet of the transcript or note shows content, like opening the session
+    for result in &results {
+        if result.snippets.iter().any(|s| s.field != "title") {
+            let event = AuditEvent::session(AuditAction::View, &result.session.id)
+                .with_details(serde_json::json!({ "source": "search" }));
+            audit::record(pool, event).await?;
+        }
+    }
+
+    Ok(results)
+}
+
+/// Rebuild the search index from the sessions table
+pub async fn rebuild_index(pool: &SqlitePool) -> Result<()> {
+    sqlx::query("INSERT INTO sessions_fts (sessions_fts) VALUES ('rebuild')")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Test', 'general', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('folder', 'ws', 'Test', 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    async fn insert_session(
+        pool: &SqlitePool,
+        id: &str,
+        title: &str,
+        transcript: &str,
+        note: Option<&str>,
+    ) {
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, title, audio_path, transcript, generated_note, created_at, updated_at) VALUES (?, 'folder', ?, '/tmp/a.wav', ?, ?, 0, 0)",
+        )
+        .bind(id)
+        .bind(title)
+        .bind(transcript)
+        .bind(note)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_build_match_query_quotes_terms() {
+        assert_eq!(
+            build_match_query("budget review").as_deref(),
+            Some(r#""budget" "review""#)
+        );
+        assert_eq!(
+            build_match_query("well-known").as_deref(),
+            Some(r#""well-known""#)
+        );
+    }
+
+    #[test]
+    fn test_build_match_query_phrases_prefixes_and_operators() {
+        assert_eq!(
+            build_match_query(r#""quarterly budget" OR forecast*"#).as_deref(),
+            Some(r#""quarterly budget" OR "forecast"*"#)
+        );
+        assert_eq!(
+            build_match_query("(anxiety OR stress) NOT sleep").as_deref(),
+            Some(r#"( "anxiety" OR "stress" ) NOT "sleep""#)
+        );
+    }
+
+    #[test]
+    fn test_build_match_query_repairs_bad_input() {
+        assert_eq!(build_match_query("NOT OR budget AND").as_deref(), Some(r#""budget""#));
+        assert_eq!(build_match_query("(budget").as_deref(), Some(r#""budget""#));
+        assert_eq!(build_match_query(r#"say "hi"#).as_deref(), Some(r#""say" "hi""#));
+        assert_eq!(build_match_query("a AND () b").as_deref(), Some(r#""a" AND "b""#));
+        assert_eq!(build_match_query("  * () AND "), None);
+    }
+
+    #[test]
+    fn test_parse_snippet() {
+        let parts = parse_snippet("…the \u{2}budget\u{3} was approved…").unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[1].text, "budget");
+        assert!(parts[1].highlighted);
+        assert!(!parts[2].highlighted);
+
+        assert!(parse_snippet("no match here").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_and_reports_fields() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "a", "Weekly sync", "We talked about the budget once.", None).await;
+        insert_session(
+            &pool,
+            "b",
+            "Budget review",
+            "The budget is over. The budget needs cuts.",
+            Some("Budget: reduce travel"),
+        )
+        .await;
+        insert_session(&pool, "c", "Standup", "Nothing relevant.", None).await;
+
+        let results = search_sessions(&pool, "budget", &[], 10).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].session.id, "b");
+        assert!(results[0].score > results[1].score);
+        assert_eq!(results[0].matched_fields, vec!["title", "transcript", "note"]);
+        assert_eq!(results[1].matched_fields, vec!["transcript"]);
+        assert!(results[1].snippets[0]
+            .parts
+            .iter()
+            .any(|p| p.highlighted && p.text == "budget"));
+    }
+
+    #[tokio::test]
+    async fn test_search_leaves_out_content_and_audits_snippets() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "a", "Budget review", "Nothing else.", None).await;
+        insert_session(&pool, "b", "Weekly sync", "The budget is over.", None).await;
+
+        let results = search_sessions(&pool, "budget", &[], 10).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.session.transcript.is_none()));
+        // Only the transcript snippet counts as a view, not the title
+        let viewed: Vec<String> =
+            sqlx::query_scalar("SELECT entity_id FROM audit_log WHERE action = 'view'")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(viewed, vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_phrase_prefix_and_boolean() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "a", "One", "The quarterly forecast looks strong.", None).await;
+        insert_session(&pool, "b", "Two", "Forecast for the quarter is weak.", None).await;
+
+        let phrase = search_sessions(&pool, r#""quarterly forecast""#, &[], 10).await.unwrap();
+        assert_eq!(phrase.len(), 1);
+        assert_eq!(phrase[0].session.id, "a");
+
+        let prefix = search_sessions(&pool, "quart*", &[], 10).await.unwrap();
+        assert_eq!(prefix.len(), 2);
+
+        let negated = search_sessions(&pool, "forecast NOT weak", &[], 10).await.unwrap();
+        assert_eq!(negated.len(), 1);
+        assert_eq!(negated[0].session.id, "a");
+    }
+
+    #[tokio::test]
+    async fn test_index_follows_updates_and_deletes() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "a", "Intake", "Initial notes", None).await;
+
+        sqlx::query("UPDATE sessions SET transcript = 'Discussed medication changes' WHERE id = 'a'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search_sessions(&pool, "initial", &[], 10).await.unwrap().is_empty());
+        assert_eq!(search_sessions(&pool, "medication", &[], 10).await.unwrap().len(), 1);
+
+        sqlx::query("DELETE FROM sessions WHERE id = 'a'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search_sessions(&pool, "medication", &[], 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_skips_trashed_sessions() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "a", "Intake", "Discussed sleep", None).await;
+
+        sqlx::query("UPDATE sessions SET deleted_at = 1 WHERE id = 'a'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search_sessions(&pool, "sleep", &[], 10).await.unwrap().is_empty());
+
+        sqlx::query("UPDATE sessions SET deleted_at = NULL WHERE id = 'a'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE folders SET deleted_at = 1 WHERE id = 'folder'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search_sessions(&pool, "sleep", &[], 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_by_tags() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "a", "Intake", "Discussed sleep", None).await;
+        insert_session(&pool, "b", "Follow-up", "Sleep is better", None).await;
+        tags::add_to_session(&pool, "a", "intake", 1).await.unwrap();
+        tags::add_to_session(&pool, "b", "intake", 1).await.unwrap();
+        tags::add_to_session(&pool, "b", "needs review", 1)
+            .await
+            .unwrap();
+
+        let both = ["Intake".to_string(), "needs review".to_string()];
+        let results = search_sessions(&pool, "sleep", &both, 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].session.id, "b");
+        assert_eq!(results[0].session.tags, ["intake", "needs review"]);
+
+        // Tags alone list every session carrying them
+        let results = search_sessions(&pool, "", &both[..1], 10).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.snippets.is_empty()));
+    }
+}
@@ -0,0 +1,401 @@
+//! Paged session listing
+//!
+//! Lists a folder's sessions as summaries, leaving out transcripts and notes,
+//! a page at a time. Pages are cut by keyset rather than offset: the cursor
+//! carries the sort key and id of the last session on a page, so sessions
+//! added or removed meanwhile don't shift later pages.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use super::database::{session_summary_from_row, SESSION_SUMMARY_COLUMNS};
+use super::tags;
+use crate::models::{SessionListQuery, SessionPage};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortKey {
+    Created,
+    Duration,
+    Title,
+    Status,
+}
+
+impl SortKey {
+    fn parse(sort: Option<&str>) -> Result<Self> {
+        match sort.unwrap_or("created") {
+            "created" => Ok(SortKey::Created),
+            "duration" => Ok(SortKey::Duration),
+            "title" => Ok(SortKey::Title),
+            "status" => Ok(SortKey::Status),
+            other => Err(anyhow!("Can't sort sessions by {}", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SortKey::Created => "created",
+            SortKey::Duration => "duration",
+            SortKey::Title => "title",
+            SortKey::Status => "status",
+        }
+    }
+
+    /// Never NULL, so every session has a place in the keyset order
+    fn expression(self) -> &'static str {
+        match self {
+            SortKey::Created => "s.created_at",
+            SortKey::Duration => "COALESCE(s.audio_duration, 0)",
+            SortKey::Title => "lower(COALESCE(s.title, ''))",
+            SortKey::Status => "s.status",
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, SortKey::Created | SortKey::Duration)
+    }
+
+    fn key_from_row(self, row: &SqliteRow) -> Value {
+        if self.is_numeric() {
+            Value::from(row.get::<i64, _>("sort_key"))
+        } else {
+            Value::from(row.get::<String, _>("sort_key"))
+        }
+    }
+}
+
+fn is_descending(key: SortKey, order: Option<&str>) -> Result<bool> {
+    match order {
+        None => Ok(matches!(key, SortKey::Created | SortKey::Duration)),
+        Some("asc") => Ok(false),
+        Some("desc") => Ok(true),
+        Some(other) => Err(anyhow!("Sort order must be asc or desc, not {}", other)),
+    }
+}
+
+/// Where the previous page stopped
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    descending: bool,
+    key: Value,
+    id: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        BASE64.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str, key: SortKey, descending: bool) -> Result<Self> {
+        let cursor: Cursor = BASE64
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| anyhow!("Invalid session cursor"))?;
+        let key_matches = if key.is_numeric() {
+            cursor.key.is_i64()
+        } else {
+            cursor.key.is_string()
+        };
+        if cursor.sort != key.name() || cursor.descending != descending || !key_matches {
+            return Err(anyhow!("Session cursor is for a different sort order"));
+        }
+        Ok(cursor)
+    }
+}
+
+/// One page of a folder's live sessions
+pub async fn list(pool: &SqlitePool, query: &SessionListQuery) -> Result<SessionPage> {
+    let key = SortKey::parse(query.sort.as_deref())?;
+    let descending = is_descending(key, query.order.as_deref())?;
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|cursor| Cursor::decode(cursor, key, descending))
+        .transpose()?;
+    let tag_names = tags::normalize(&query.tags);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let mut filters = String::new();
+    if !tag_names.is_empty() {
+        filters.push_str(&format!(" AND {}", tags::filter_sql(&tag_names)));
+    }
+    let (comparison, direction) = if descending {
+        ("<", "DESC")
+    } else {
+        (">", "ASC")
+    };
+    if cursor.is_some() {
+        filters.push_str(&format!(
+            " AND ({}, s.id) {} (?, ?)",
+            key.expression(),
+            comparison
+        ));
+    }
+
+    let sql = format!(
+        r#"
+        SELECT {columns}, {key} AS sort_key
+        FROM sessions s
+        WHERE s.folder_id = ? AND s.deleted_at IS NULL
+          AND (? IS NULL OR s.status = ?)
+          AND (? IS NULL OR s.created_at >= ?)
+          AND (? IS NULL OR s.created_at < ?)
+          AND (? IS NULL OR s.template_id = ?)
+          AND (? IS NULL OR (COALESCE(s.generated_note, '') != '') = ?)
+          {filters}
+        ORDER BY sort_key {direction}, s.id {direction}
+        LIMIT ?
+        "#,
+        columns = SESSION_SUMMARY_COLUMNS,
+        key = key.expression(),
+        filters = filters,
+        direction = direction
+    );
+
+    let mut sql_query = sqlx::query(&sql)
+        .bind(&query.folder_id)
+        .bind(&query.status)
+        .bind(&query.status)
+        .bind(query.since)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(query.until)
+        .bind(&query.template_id)
+        .bind(&query.template_id)
+        .bind(query.has_note)
+        .bind(query.has_note);
+    for name in &tag_names {
+        sql_query = sql_query.bind(name);
+    }
+    if let Some(cursor) = &cursor {
+        sql_query = match &cursor.key {
+            Value::String(text) => sql_query.bind(text.clone()),
+            key => sql_query.bind(key.as_i64()),
+        };
+        sql_query = sql_query.bind(&cursor.id);
+    }
+    // One extra row says whether there is another page
+    let mut rows = sql_query.bind(limit + 1).fetch_all(pool).await?;
+
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last().map(|row| {
+            Cursor {
+                sort: key.name().to_string(),
+                descending,
+                key: key.key_from_row(row),
+                id: row.get("id"),
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    Ok(SessionPage {
+        sessions: rows.into_iter().map(session_summary_from_row).collect(),
+        next_cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    async fn setup() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Practice', 'therapy', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f', 'ws', 'Client', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('other', 'ws', 'Other', 0, 0)",
+                "INSERT INTO templates (id, name, workspace_type, prompt, output_format, is_default, is_system, created_at, updated_at) VALUES ('soap', 'SOAP', 'therapy', 'p', 'markdown', 0, 0, 0, 0)",
+            ],
+        )
+        .await;
+
+        // id, title, duration, status, created_at, note
+        let sessions = [
+            (
+                "a",
+                Some("Intake"),
+                Some(3600),
+                "complete",
+                100,
+                Some("Note"),
+            ),
+            ("b", Some("follow-up"), Some(1800), "complete", 200, None),
+            ("c", None, None, "pending", 300, None),
+            ("d", Some("Discharge"), Some(2700), "error", 300, Some("")),
+            (
+                "e",
+                Some("Check-in"),
+                Some(1800),
+                "complete",
+                500,
+                Some("Note"),
+            ),
+        ];
+        for (id, title, duration, status, created_at, note) in sessions {
+            sqlx::query(
+                "INSERT INTO sessions (id, folder_id, title, audio_path, audio_duration, transcript, generated_note, status, created_at, updated_at) VALUES (?, 'f', ?, 'a.wav', ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(title)
+            .bind(duration)
+            .bind(format!("{} {}", id, "words ".repeat(100)))
+            .bind(note)
+            .bind(status)
+            .bind(created_at)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        seed(
+            &pool,
+            &[
+                "UPDATE sessions SET template_id = 'soap' WHERE id IN ('a', 'e')",
+                "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES ('elsewhere', 'other', 'a.wav', 0, 0)",
+                "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at, deleted_at) VALUES ('trashed', 'f', 'a.wav', 0, 0, 1)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    fn query(sort: Option<&str>, order: Option<&str>) -> SessionListQuery {
+        SessionListQuery {
+            folder_id: "f".to_string(),
+            sort: sort.map(String::from),
+            order: order.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    /// Session ids, following cursors a page of `limit` at a time
+    async fn all_pages(pool: &SqlitePool, mut query: SessionListQuery, limit: i64) -> Vec<String> {
+        query.limit = Some(limit);
+        let mut ids = Vec::new();
+        loop {
+            let page = list(pool, &query).await.unwrap();
+            assert!(page.sessions.len() as i64 <= limit);
+            ids.extend(page.sessions.into_iter().map(|s| s.id));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => return ids,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sort_and_paginate() {
+        let pool = setup().await;
+
+        // Ties fall back to the id, in the same direction
+        for (sort, order, expected) in [
+            (None, None, ["e", "d", "c", "b", "a"]),
+            (None, Some("asc"), ["a", "b", "c", "d", "e"]),
+            (Some("duration"), None, ["a", "d", "e", "b", "c"]),
+            (Some("title"), None, ["c", "e", "d", "b", "a"]),
+            (Some("title"), Some("desc"), ["a", "b", "d", "e", "c"]),
+            (Some("status"), None, ["a", "b", "e", "d", "c"]),
+        ] {
+            for limit in [1, 2, 5] {
+                assert_eq!(
+                    all_pages(&pool, query(sort, order), limit).await,
+                    expected,
+                    "sort {:?} {:?}, {} per page",
+                    sort,
+                    order,
+                    limit
+                );
+            }
+        }
+
+        let page = list(&pool, &query(None, None)).await.unwrap();
+        assert_eq!(page.next_cursor, None);
+        assert!(list(&pool, &query(Some("size"), None)).await.is_err());
+        assert!(list(&pool, &query(None, Some("up"))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_must_match_sort() {
+        let pool = setup().await;
+        let mut by_title = query(Some("title"), None);
+        by_title.limit = Some(2);
+        let cursor = list(&pool, &by_title).await.unwrap().next_cursor;
+
+        let mut by_date = query(None, None);
+        by_date.cursor = cursor.clone();
+        assert!(list(&pool, &by_date).await.is_err());
+        by_title.order = Some("desc".to_string());
+        by_title.cursor = cursor;
+        assert!(list(&pool, &by_title).await.is_err());
+        by_title.cursor = Some("not a cursor".to_string());
+        assert!(list(&pool, &by_title).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_filters() {
+        let pool = setup().await;
+        let ids = |query: SessionListQuery| {
+            let pool = pool.clone();
+            async move { all_pages(&pool, query, 2).await }
+        };
+
+        let mut filtered = query(None, None);
+        filtered.status = Some("complete".to_string());
+        assert_eq!(ids(filtered.clone()).await, ["e", "b", "a"]);
+        filtered.since = Some(200);
+        filtered.until = Some(500);
+        assert_eq!(ids(filtered).await, ["b"]);
+
+        let mut filtered = query(None, None);
+        filtered.template_id = Some("soap".to_string());
+        assert_eq!(ids(filtered).await, ["e", "a"]);
+
+        // An empty note counts as no note
+        let mut filtered = query(None, None);
+        filtered.has_note = Some(true);
+        assert_eq!(ids(filtered.clone()).await, ["e", "a"]);
+        filtered.has_note = Some(false);
+        assert_eq!(ids(filtered).await, ["d", "c", "b"]);
+
+        tags::add_to_session(&pool, "b", "intake", 1).await.unwrap();
+        tags::add_to_session(&pool, "c", "Intake", 1).await.unwrap();
+        let mut filtered = query(None, None);
+        filtered.tags = vec!["INTAKE".to_string()];
+        filtered.has_note = Some(false);
+        assert_eq!(ids(filtered).await, ["c", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_summary_leaves_out_content() {
+        let pool = setup().await;
+        tags::add_to_session(&pool, "a", "intake", 1).await.unwrap();
+        let page = list(&pool, &query(None, Some("asc"))).await.unwrap();
+        let summary = &page.sessions[0];
+
+        assert_eq!(summary.id, "a");
+        assert_eq!(summary.tags, ["intake"]);
+        assert!(summary.has_note);
+        assert!(!page.sessions[3].has_note);
+        let preview = summary.transcript_preview.as_deref().unwrap();
+        assert_eq!(preview.chars().count(), 200);
+        assert!(preview.starts_with("a words"));
+    }
+}
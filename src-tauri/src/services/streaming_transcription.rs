@@ -1,21 +1,90 @@
 //! Streaming transcription service
 //!
-//! Manages a long-running whisperkit-worker subprocess in streaming mode,
-//! handling bidirectional IPC for real-time transcription.
+//! Manages long-running whisperkit-worker subprocesses in streaming mode,
+//! handling bidirectional IPC for real-time transcription. Each worker
+//! transcribes one live session at a time, so concurrent sessions each get
+//! their own worker.
 
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
 use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tauri::{Emitter, Manager};
 
+use super::{database, live_sessions, streaming_worker};
+use crate::models::TranscriptSegment;
+
+// MARK: - Supervision
+
+/// Sample rate of the audio fed to the worker
+pub(crate) const SAMPLE_RATE: u64 = 16_000;
+
+/// How often the supervisor checks on the worker
+const SUPERVISOR_TICK: Duration = Duration::from_millis(500);
+
+/// How often an idle worker is asked for its status
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long the worker may stay silent before it counts as hung
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long loading a model may take, since it can mean downloading it first
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Consecutive restarts before giving up on the worker
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(1);
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a restarted worker must stay up before its restarts are forgiven
+const RESTART_STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// How long a worker asked to shut down may take before it is killed
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+const REAP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Writes queued for a worker before it counts as stalled: it has stopped
+/// reading its stdin and the pipe is full
+const WRITE_QUEUE_CAPACITY: usize = 64;
+
+/// Audio kept for replaying to a restarted worker: enough to cover noticing a
+/// hung worker, the longest restart delay and a new worker loading its model,
+/// plus a minute of speech not yet confirmed when the old one failed. At
+/// most about 46 MB per session, and only once a session runs that long.
+const REPLAY_HISTORY_SECS: u64 =
+    HEARTBEAT_TIMEOUT.as_secs() + RESTART_MAX_DELAY.as_secs() + STARTUP_TIMEOUT.as_secs() + 60;
+const REPLAY_HISTORY_SAMPLES: usize = (REPLAY_HISTORY_SECS * SAMPLE_RATE) as usize;
+
+/// Replay is sent in chunks of this size at twice real time, because the
+/// worker only transcribes the most recent few seconds it has been sent
+const REPLAY_CHUNK_SAMPLES: usize = 1600;
+const REPLAY_CHUNK_INTERVAL: Duration = Duration::from_millis(50);
+
+// MARK: - Protocol
+
+/// Newest worker protocol this backend speaks. Version 2 adds binary audio
+/// frames; a worker that doesn't report a version in `Ready` speaks version 1.
+pub(crate) const PROTOCOL_VERSION: u32 = 2;
+
+/// First protocol version that accepts binary audio frames
+const BINARY_AUDIO_VERSION: u32 = 2;
+
+/// First byte of a binary audio frame. A JSON command line never starts
+/// with it, so frames and commands can share stdin.
+pub(crate) const AUDIO_FRAME_MARKER: u8 = 0x00;
+
 // MARK: - Configuration Types
 
 /// Configuration for starting a live transcription session
@@ -26,6 +95,8 @@ pub struct LiveTranscriptionConfig {
     pub language: Option<String>,
     pub use_vad: Option<bool>,
     pub confirmation_threshold: Option<u32>,
+    /// Sample format for binary audio frames, when the worker supports them
+    pub audio_encoding: Option<AudioEncoding>,
 }
 
 impl Default for LiveTranscriptionConfig {
@@ -35,6 +106,36 @@ impl Default for LiveTranscriptionConfig {
             language: None,
             use_vad: Some(true),
             confirmation_threshold: Some(2),
+            audio_encoding: None,
+        }
+    }
+}
+
+/// Sample format of binary audio frames sent to the worker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioEncoding {
+    /// 32-bit float samples, exactly as captured
+    #[default]
+    F32,
+    /// 16-bit integer samples, half the size
+    I16,
+}
+
+impl AudioEncoding {
+    /// Code for the encoding in a frame header
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            AudioEncoding::F32 => 0,
+            AudioEncoding::I16 => 1,
+        }
+    }
+
+    /// Bytes per sample
+    pub(crate) fn sample_size(self) -> usize {
+        match self {
+            AudioEncoding::F32 => 4,
+            AudioEncoding::I16 => 2,
         }
     }
 }
@@ -76,12 +177,51 @@ pub struct WorkerReadyEvent {
     pub model_path: Option<String>,
 }
 
+/// Worker restarting event, sent when the worker exits or stops responding
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerRestartingEvent {
+    /// The live session that resumes once the worker is back
+    pub session_id: Option<String>,
+    pub reason: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub delay_ms: u64,
+}
+
+// MARK: - Status Types (for frontend)
+
+/// State of the streaming service and every active live session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingStatus {
+    /// Overall state, as reported by `get_state`
+    pub state: String,
+    /// Worker processes, including one kept idle for the next session
+    pub workers: usize,
+    pub sessions: Vec<LiveSessionStatus>,
+}
+
+/// A live session and the worker transcribing it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSessionStatus {
+    pub session_id: String,
+    pub worker_state: String,
+    pub config: LiveTranscriptionConfig,
+    /// Seconds of audio received so far
+    pub audio_seconds: f64,
+    pub confirmed_segments: usize,
+    pub stopping: bool,
+    pub restart_attempts: u32,
+}
+
 // MARK: - Command Types (to worker)
 
 /// Commands sent to the worker via stdin
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "cmd", rename_all = "camelCase")]
-enum WorkerCommand {
+pub(crate) enum WorkerCommand {
     Init {
         model: Option<String>,
         language: Option<String>,
@@ -105,18 +245,21 @@ enum WorkerCommand {
         #[serde(rename = "sessionId")]
         session_id: String,
     },
+    Status,
     Shutdown,
 }
 
 // MARK: - Worker Event Types (from worker)
 
 /// Events received from the worker via stdout
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
-enum WorkerEvent {
+pub(crate) enum WorkerEvent {
     Ready {
         model: String,
         model_path: Option<String>,
+        #[serde(default = "default_protocol_version")]
+        protocol_version: u32,
     },
     Tentative {
         session_id: String,
@@ -144,6 +287,11 @@ enum WorkerEvent {
     },
 }
 
+/// Protocol version of a worker that doesn't report one
+fn default_protocol_version() -> u32 {
+    1
+}
+
 // MARK: - Manager State
 
 /// State of the streaming transcription worker
@@ -154,27 +302,288 @@ pub enum WorkerState {
     Ready,
     Transcribing(String), // session_id
     Stopping,
+    Restarting,
     Error(String),
 }
 
+/// Recent audio of a live session, addressed by position in the session
+struct AudioHistory {
+    samples: VecDeque<f32>,
+    /// Position of the oldest kept sample
+    start: u64,
+    capacity: usize,
+}
+
+impl AudioHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            start: 0,
+            capacity,
+        }
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        self.samples.extend(samples);
+        if self.samples.len() > self.capacity {
+            let overflow = self.samples.len() - self.capacity;
+            self.samples.drain(..overflow);
+            self.start += overflow as u64;
+        }
+    }
+
+    /// Position just past the newest sample, i.e. all samples ever pushed
+    fn end(&self) -> u64 {
+        self.start + self.samples.len() as u64
+    }
+
+    /// Up to `max` samples from `from`, or from the oldest kept sample if
+    /// `from` has already been dropped. Returns where the samples start.
+    fn read(&self, from: u64, max: usize) -> (u64, Vec<f32>) {
+        let from = from.clamp(self.start, self.end());
+        let skip = (from - self.start) as usize;
+        let samples = self.samples.range(skip..).take(max).copied().collect();
+        (from, samples)
+    }
+}
+
+/// A live session, kept across worker restarts so it can be resumed
+struct LiveSession {
+    id: String,
+    /// The config the session was started with
+    config: LiveTranscriptionConfig,
+    use_vad: bool,
+    confirmation_threshold: u32,
+    audio_encoding: AudioEncoding,
+    /// Whether the current worker process has been sent `Start`
+    started: bool,
+    /// Whether `Stop` has been sent
+    stopping: bool,
+    history: AudioHistory,
+    /// Position the current worker's timestamps count from
+    offset: u64,
+    /// Position up to which audio has been sent to the current worker
+    sent_until: u64,
+    /// End of the last confirmed segment
+    confirmed_until: u64,
+    /// Every confirmed segment's text, in order
+    confirmed: Vec<String>,
+    /// How many of `confirmed` came from workers that have since been replaced
+    carried: usize,
+}
+
+impl LiveSession {
+    fn new(id: &str, config: &LiveTranscriptionConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            config: config.clone(),
+            use_vad: config.use_vad.unwrap_or(true),
+            confirmation_threshold: config.confirmation_threshold.unwrap_or(2),
+            audio_encoding: config.audio_encoding.unwrap_or_default(),
+            started: false,
+            stopping: false,
+            history: AudioHistory::new(REPLAY_HISTORY_SAMPLES),
+            offset: 0,
+            sent_until: 0,
+            confirmed_until: 0,
+            confirmed: Vec::new(),
+            carried: 0,
+        }
+    }
+
+    /// Whether audio can go straight to the worker: it has started the
+    /// session and nothing is waiting to be replayed
+    fn is_caught_up(&self) -> bool {
+        self.started && self.sent_until == self.history.end()
+    }
+
+    /// Where a restarted worker should pick up: after the last confirmed
+    /// segment, so unconfirmed speech is transcribed again
+    fn resume_position(&self) -> u64 {
+        self.confirmed_until
+            .clamp(self.history.start, self.history.end())
+    }
+
+    /// Convert a time from the current worker to seconds from the start of
+    /// the session
+    fn session_time(&self, worker_seconds: f64) -> f64 {
+        worker_seconds + self.offset as f64 / SAMPLE_RATE as f64
+    }
+
+    fn record_confirmed(&mut self, text: &str, end_time: f64) {
+        self.confirmed.push(text.to_string());
+        let end = (end_time.max(0.0) * SAMPLE_RATE as f64) as u64;
+        self.confirmed_until = self.confirmed_until.max(end);
+    }
+
+    /// The session's full transcript: text confirmed by replaced workers,
+    /// then `worker_text` from the current worker, or everything confirmed
+    /// if the current worker never finished
+    fn full_text(&self, worker_text: Option<&str>) -> String {
+        let tail = match worker_text {
+            Some(text) => vec![text],
+            None => self.confirmed[self.carried..]
+                .iter()
+                .map(String::as_str)
+                .collect(),
+        };
+        self.confirmed[..self.carried]
+            .iter()
+            .map(String::as_str)
+            .chain(tail)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn status(&self, worker_state: &WorkerState, restart_attempts: u32) -> LiveSessionStatus {
+        LiveSessionStatus {
+            session_id: self.id.clone(),
+            worker_state: format!("{:?}", worker_state),
+            config: self.config.clone(),
+            audio_seconds: self.history.end() as f64 / SAMPLE_RATE as f64,
+            confirmed_segments: self.confirmed.len(),
+            stopping: self.stopping,
+            restart_attempts,
+        }
+    }
+}
+
 /// Active worker state
 struct ActiveWorker {
-    child: Child,
-    stdin: ChildStdin,
+    app: AppHandle,
+    /// The current process, until it fails and is handed to a reaper
+    child: Option<Child>,
+    input: WorkerInput,
     state: WorkerState,
     current_model: Option<String>,
+    language: Option<String>,
+    /// Whether `Init` has been sent, so a restarted process gets it too
+    initialized: bool,
+    /// Protocol version the current process reported, decides how audio is sent
+    protocol_version: u32,
+    /// Identifies the current process, so events from a replaced one are ignored
+    generation: u64,
+    spawned_at: Instant,
+    /// When the current process last sent anything
+    last_seen: Instant,
+    last_ping: Instant,
+    /// When `Init` was sent, until the worker reports ready
+    init_sent_at: Option<Instant>,
+    session: Option<LiveSession>,
+    /// Restarts since the worker last stayed up
+    restart_attempts: u32,
+    /// When the next restart is due, while restarting
+    restart_at: Option<Instant>,
 }
 
-/// Global worker state
-static WORKER_STATE: OnceCell<Arc<Mutex<Option<ActiveWorker>>>> = OnceCell::new();
+impl ActiveWorker {
+    fn new(app: &AppHandle, child: Child, input: WorkerInput, generation: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            app: app.clone(),
+            child: Some(child),
+            input,
+            state: WorkerState::Starting,
+            current_model: None,
+            language: None,
+            initialized: false,
+            protocol_version: default_protocol_version(),
+            generation,
+            spawned_at: now,
+            last_seen: now,
+            last_ping: now,
+            init_sent_at: None,
+            session: None,
+            restart_attempts: 0,
+            restart_at: None,
+        }
+    }
+
+    /// Whether the worker is free to take a new session
+    fn is_idle(&self) -> bool {
+        self.session.is_none() && !matches!(self.state, WorkerState::Error(_))
+    }
+
+    fn has_session(&self, session_id: &str) -> bool {
+        self.session.as_ref().is_some_and(|s| s.id == session_id)
+    }
 
-fn get_worker_state() -> &'static Arc<Mutex<Option<ActiveWorker>>> {
-    WORKER_STATE.get_or_init(|| Arc::new(Mutex::new(None)))
+    /// Check the process is still running and the worker is healthy
+    fn is_running(&mut self) -> bool {
+        let Some(child) = self.child.as_mut() else {
+            return false;
+        };
+        match child.try_wait() {
+            Ok(Some(_status)) => {
+                // Process has exited; the supervisor restarts it
+                println!("[StreamingTranscription] Worker process has exited");
+                false
+            }
+            Ok(None) => {
+                // Process still running
+                matches!(
+                    self.state,
+                    WorkerState::Ready | WorkerState::Transcribing(_) | WorkerState::Starting
+                )
+            }
+            Err(e) => {
+                println!("[StreamingTranscription] Error checking worker status: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// A worker's stdin, written by a thread of its own so that a worker that
+/// stops reading never blocks a caller holding the worker state
+struct WorkerInput {
+    queue: SyncSender<Vec<u8>>,
+}
+
+impl WorkerInput {
+    fn new(mut stdin: ChildStdin) -> Self {
+        let (queue, writes) = mpsc::sync_channel::<Vec<u8>>(WRITE_QUEUE_CAPACITY);
+        thread::spawn(move || {
+            // Ends when the worker is dropped or its process goes away
+            for bytes in writes {
+                if let Err(e) = stdin.write_all(&bytes).and_then(|()| stdin.flush()) {
+                    println!("[StreamingTranscription] Error writing to worker: {}", e);
+                    break;
+                }
+            }
+        });
+        Self { queue }
+    }
+
+    /// Queue bytes for the worker without waiting for it to read them
+    fn write(&self, bytes: Vec<u8>) -> Result<()> {
+        self.queue.try_send(bytes).map_err(|e| match e {
+            TrySendError::Full(_) => anyhow!("Worker stopped reading its input"),
+            TrySendError::Disconnected(_) => anyhow!("Worker input is closed"),
+        })
+    }
+}
+
+/// Global worker state: a worker per live session, and at most one idle
+/// worker kept ready for the next session
+static WORKER_STATE: OnceCell<Arc<Mutex<Vec<ActiveWorker>>>> = OnceCell::new();
+
+/// Counter for worker process generations
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Set once the supervisor thread is running
+static SUPERVISOR: OnceCell<()> = OnceCell::new();
+
+fn get_worker_state() -> &'static Arc<Mutex<Vec<ActiveWorker>>> {
+    WORKER_STATE.get_or_init(|| Arc::new(Mutex::new(Vec::new())))
 }
 
 // MARK: - Worker Path Resolution
 
 /// Get path to the whisperkit-worker binary
+#[cfg(target_os = "macos")]
 fn get_worker_path(app: &AppHandle) -> Result<PathBuf> {
     // Try Tauri's sidecar resolution first (works in production)
     if let Ok(sidecar) = app.path().resolve(
@@ -229,25 +638,360 @@ fn get_worker_path(app: &AppHandle) -> Result<PathBuf> {
     ))
 }
 
+/// Get path to the Rust streaming-worker binary, used where WhisperKit isn't
+/// available
+#[cfg(not(target_os = "macos"))]
+fn get_worker_path(app: &AppHandle) -> Result<PathBuf> {
+    let exe_name = format!("streaming-worker{}", std::env::consts::EXE_SUFFIX);
+
+    // Try Tauri's sidecar resolution first (works in production)
+    if let Ok(sidecar) = app.path().resolve(
+        format!("binaries/{}", exe_name),
+        tauri::path::BaseDirectory::Resource,
+    ) {
+        if sidecar.exists() {
+            println!("[StreamingTranscription] Found bundled worker at: {:?}", sidecar);
+            return Ok(sidecar);
+        }
+    }
+
+    // Try various locations for the worker binary
+    let possible_paths = [
+        // Development: same directory as main binary, where cargo builds it
+        std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(|d| d.join(&exe_name))),
+        // Development: cargo target folders, from the repo root or src-tauri
+        Some(PathBuf::from("target/release").join(&exe_name)),
+        Some(PathBuf::from("target/debug").join(&exe_name)),
+        Some(PathBuf::from("src-tauri/target/release").join(&exe_name)),
+        Some(PathBuf::from("src-tauri/target/debug").join(&exe_name)),
+    ];
+
+    for path in possible_paths.iter().flatten() {
+        if path.exists() {
+            println!("[StreamingTranscription] Found worker at: {:?}", path);
+            return Ok(path.clone());
+        }
+    }
+
+    Err(anyhow!(
+        "streaming-worker binary not found. Build it with: cd src-tauri && cargo build --bin streaming-worker"
+    ))
+}
+
 // MARK: - Public API
 
-/// Start the streaming transcription worker
+/// Start the streaming transcription worker, so one is ready for the next
+/// session
 pub fn start_worker(app: &AppHandle) -> Result<()> {
-    let mut state = get_worker_state().lock();
+    let mut workers = get_worker_state().lock();
+    idle_worker(app, &mut workers, &None, &None)?;
+    Ok(())
+}
+
+/// Initialize the idle worker with a model, so the next session using it
+/// starts without loading it
+pub fn initialize_worker(app: &AppHandle, model: Option<String>, language: Option<String>) -> Result<()> {
+    println!("[StreamingTranscription] initialize_worker: model={:?}, language={:?}", model, language);
 
-    // Check if already running
-    if let Some(ref worker) = *state {
-        if worker.state != WorkerState::Error("".to_string()) {
-            println!("[StreamingTranscription] Worker already running");
+    let mut workers = get_worker_state().lock();
+    let index = idle_worker(app, &mut workers, &model, &language)?;
+    init_worker(&mut workers[index], model, language)
+}
+
+/// Start a live transcription session on a worker of its own. Other
+/// sessions carry on alongside it.
+pub fn start_session(
+    app: &AppHandle,
+    session_id: &str,
+    config: &LiveTranscriptionConfig,
+) -> Result<()> {
+    println!("[StreamingTranscription] start_session: session_id={}, config={:?}", session_id, config);
+
+    let mut workers = get_worker_state().lock();
+    if workers.iter().any(|w| w.has_session(session_id)) {
+        return Err(anyhow!("Session {} is already being transcribed", session_id));
+    }
+
+    // Ensure worker is initialized with the session's model
+    let index = idle_worker(app, &mut workers, &config.model, &config.language)?;
+    let worker = &mut workers[index];
+    if !worker.initialized
+        || worker.current_model != config.model
+        || worker.language != config.language
+    {
+        init_worker(worker, config.model.clone(), config.language.clone())?;
+    }
+
+    let mut session = LiveSession::new(session_id, config);
+
+    // A restarting worker is sent the session once it is back
+    if worker.state != WorkerState::Restarting {
+        let cmd = WorkerCommand::Start {
+            session_id: session_id.to_string(),
+            use_vad: session.use_vad,
+            confirmation_threshold: session.confirmation_threshold,
+        };
+
+        println!("[StreamingTranscription] Sending start command to worker...");
+        send_command(&worker.input, &cmd)?;
+        session.started = true;
+        worker.state = WorkerState::Transcribing(session_id.to_string());
+    }
+    worker.session = Some(session);
+
+    println!(
+        "[StreamingTranscription] Started session: {} ({} workers)",
+        session_id,
+        workers.len()
+    );
+    Ok(())
+}
+
+/// Feed audio samples to the worker transcribing the session. The audio is
+/// also kept for a while, and while the worker is restarting it is only
+/// kept, to be replayed once the worker is back.
+pub fn feed_audio(session_id: &str, samples: &[f32]) -> Result<()> {
+    let mut workers = get_worker_state().lock();
+
+    for worker in workers.iter_mut() {
+        let Some(session) = worker.session.as_mut().filter(|s| s.id == session_id) else {
+            continue;
+        };
+
+        let caught_up = session.is_caught_up();
+        session.history.push(samples);
+        if !caught_up {
+            // The supervisor sends it after what is waiting to be replayed
             return Ok(());
         }
+
+        match send_audio(
+            &worker.input,
+            worker.protocol_version,
+            session_id,
+            samples,
+            session.audio_encoding,
+        ) {
+            Ok(()) => {
+                session.sent_until = session.history.end();
+            }
+            Err(e) => fail_worker(worker, &format!("Failed to send audio: {}", e)),
+        }
+        return Ok(());
+    }
+
+    Err(anyhow!("No live session {}", session_id))
+}
+
+/// Stop a live transcription session
+pub fn stop_session(session_id: &str) -> Result<()> {
+    let mut workers = get_worker_state().lock();
+    let worker = workers
+        .iter_mut()
+        .find(|w| w.has_session(session_id))
+        .ok_or_else(|| anyhow!("No live session {}", session_id))?;
+
+    // Without a worker to finish the session, finish it with what was confirmed
+    if worker.state == WorkerState::Restarting {
+        if let Some(session) = worker.session.take() {
+            println!(
+                "[StreamingTranscription] Stopping session {} while the worker is restarting",
+                session_id
+            );
+            emit_complete(&worker.app, &session);
+        }
+        return Ok(());
+    }
+
+    let cmd = WorkerCommand::Stop {
+        session_id: session_id.to_string(),
+    };
+
+    send_command(&worker.input, &cmd)?;
+    worker.state = WorkerState::Stopping;
+    if let Some(session) = worker.session.as_mut() {
+        session.stopping = true;
+    }
+
+    println!("[StreamingTranscription] Stopping session: {}", session_id);
+    Ok(())
+}
+
+/// Shutdown every worker, ending any live sessions
+pub fn shutdown_worker() -> Result<()> {
+    // Taking the workers out also stops the supervisor restarting them
+    let workers: Vec<_> = get_worker_state().lock().drain(..).collect();
+
+    // Waited for with the lock released
+    let reapers: Vec<_> = workers
+        .into_iter()
+        .filter_map(|mut worker| {
+            let _ = send_command(&worker.input, &WorkerCommand::Shutdown);
+            worker.child.take().map(|child| reap(child, SHUTDOWN_GRACE))
+        })
+        .collect();
+    for reaper in reapers {
+        let _ = reaper.join();
+        println!("[StreamingTranscription] Worker shut down");
+    }
+
+    Ok(())
+}
+
+/// Check if any worker is running and healthy
+pub fn is_worker_running() -> bool {
+    let mut workers = get_worker_state().lock();
+    workers.iter_mut().any(ActiveWorker::is_running)
+}
+
+/// Get current worker state: that of the first worker with a live session,
+/// otherwise of the idle worker
+pub fn get_state() -> WorkerState {
+    let workers = get_worker_state().lock();
+    workers
+        .iter()
+        .find(|w| w.session.is_some())
+        .or(workers.first())
+        .map_or(WorkerState::NotStarted, |worker| worker.state.clone())
+}
+
+/// Get the state of the service and of every live session
+pub fn get_status() -> StreamingStatus {
+    let state = format!("{:?}", get_state());
+    let workers = get_worker_state().lock();
+    StreamingStatus {
+        state,
+        workers: workers.len(),
+        sessions: workers
+            .iter()
+            .filter_map(|worker| {
+                let session = worker.session.as_ref()?;
+                Some(session.status(&worker.state, worker.restart_attempts))
+            })
+            .collect(),
+    }
+}
+
+/// The live sessions being transcribed, including ones waiting for their
+/// worker to restart
+pub fn active_session_ids() -> Vec<String> {
+    let workers = get_worker_state().lock();
+    workers
+        .iter()
+        .filter_map(|worker| worker.session.as_ref())
+        .map(|session| session.id.clone())
+        .collect()
+}
+
+/// Ensure a worker is running for the next session, replacing an idle one
+/// that exited. Workers with a session are left to the supervisor.
+pub fn ensure_worker_running(app: &AppHandle) -> Result<()> {
+    {
+        let mut workers = get_worker_state().lock();
+        workers.retain_mut(|worker| {
+            if worker.session.is_some() || worker.state == WorkerState::Restarting {
+                return true;
+            }
+            // Check if process is still alive
+            let dead = match worker.child.as_mut().map(Child::try_wait) {
+                Some(Ok(Some(_))) => {
+                    println!("[StreamingTranscription] Worker exited unexpectedly, will restart");
+                    true
+                }
+                Some(Ok(None)) => matches!(worker.state, WorkerState::Error(_)),
+                Some(Err(e)) => {
+                    println!("[StreamingTranscription] Error checking worker: {}", e);
+                    true
+                }
+                None => true,
+            };
+            if dead {
+                if let Some(child) = worker.child.take() {
+                    reap(child, Duration::ZERO);
+                }
+            }
+            !dead
+        });
+    }
+
+    start_worker(app)
+}
+
+// MARK: - Internal Functions
+
+/// Index of an idle worker to take the next session, preferring one already
+/// initialized with `model` and `language`, and spawning one if none is idle.
+/// Workers that gave up are dropped first.
+fn idle_worker(
+    app: &AppHandle,
+    workers: &mut Vec<ActiveWorker>,
+    model: &Option<String>,
+    language: &Option<String>,
+) -> Result<usize> {
+    ensure_supervisor();
+    workers.retain(|w| !matches!(w.state, WorkerState::Error(_)));
+
+    let matching = workers.iter().position(|w| {
+        w.is_idle() && w.initialized && w.current_model == *model && w.language == *language
+    });
+    if let Some(index) = matching.or_else(|| workers.iter().position(ActiveWorker::is_idle)) {
+        println!("[StreamingTranscription] Worker already running");
+        return Ok(index);
+    }
+
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let (child, input) = spawn_worker(app, generation)?;
+    workers.push(ActiveWorker::new(app, child, input, generation));
+
+    println!("[StreamingTranscription] Worker started successfully");
+    Ok(workers.len() - 1)
+}
+
+/// Send a worker `Init` with the model to load
+fn init_worker(
+    worker: &mut ActiveWorker,
+    model: Option<String>,
+    language: Option<String>,
+) -> Result<()> {
+    worker.current_model = model.clone();
+    worker.language = language.clone();
+    worker.initialized = true;
+
+    // A restarting worker is sent the model once it is back
+    if worker.state == WorkerState::Restarting {
+        println!("[StreamingTranscription] Worker is restarting; it will be initialized when back");
+        return Ok(());
     }
 
+    let cmd = WorkerCommand::Init { model, language };
+
+    println!("[StreamingTranscription] Sending init command to worker...");
+    send_command(&worker.input, &cmd)?;
+    worker.init_sent_at = Some(Instant::now());
+    println!("[StreamingTranscription] Init command sent successfully");
+
+    Ok(())
+}
+
+/// Spawn a worker process with threads forwarding its output. Events are
+/// tagged with `generation` so those from a replaced process can be ignored.
+fn spawn_worker(app: &AppHandle, generation: u64) -> Result<(Child, WorkerInput)> {
     let worker_path = get_worker_path(app)?;
     println!("[StreamingTranscription] Starting worker: {:?}", worker_path);
 
-    let mut child = Command::new(&worker_path)
-        .arg("stream")
+    let mut command = Command::new(&worker_path);
+    command.arg("stream");
+    // Where the streaming-worker finds whisper.cpp models; WhisperKit ignores it
+    if let Ok(app_data_dir) = app.path().app_data_dir() {
+        command.env(
+            streaming_worker::MODELS_DIR_ENV,
+            app_data_dir.join("models").join("whisper"),
+        );
+    }
+
+    let mut child = command
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -265,7 +1009,7 @@ pub fn start_worker(app: &AppHandle) -> Result<()> {
         for line in reader.lines() {
             match line {
                 Ok(line) if !line.is_empty() => {
-                    if let Err(e) = handle_worker_event(&app_clone, &line) {
+                    if let Err(e) = handle_worker_event(&app_clone, generation, &line) {
                         println!("[StreamingTranscription] Error handling event: {}", e);
                     }
                 }
@@ -292,74 +1036,33 @@ pub fn start_worker(app: &AppHandle) -> Result<()> {
         });
     }
 
-    *state = Some(ActiveWorker {
-        child,
-        stdin,
-        state: WorkerState::Starting,
-        current_model: None,
-    });
-
-    println!("[StreamingTranscription] Worker started successfully");
-    Ok(())
+    Ok((child, WorkerInput::new(stdin)))
 }
 
-/// Initialize the worker with a model
-pub fn initialize_worker(app: &AppHandle, model: Option<String>, language: Option<String>) -> Result<()> {
-    println!("[StreamingTranscription] initialize_worker: model={:?}, language={:?}", model, language);
-
-    // Ensure worker is running
-    start_worker(app)?;
-
-    let mut state = get_worker_state().lock();
-    let worker = state.as_mut().ok_or_else(|| anyhow!("Worker not started"))?;
-
-    let cmd = WorkerCommand::Init {
-        model: model.clone(),
-        language,
-    };
-
-    println!("[StreamingTranscription] Sending init command to worker...");
-    send_command(&mut worker.stdin, &cmd)?;
-    worker.current_model = model;
-    println!("[StreamingTranscription] Init command sent successfully");
-
-    Ok(())
+/// Queue a command for the worker's stdin
+fn send_command(input: &WorkerInput, cmd: &WorkerCommand) -> Result<()> {
+    let json = serde_json::to_string(cmd)?;
+    // Log non-audio commands (audio commands are too frequent)
+    match cmd {
+        WorkerCommand::Audio { .. } | WorkerCommand::Status => {}
+        _ => println!("[StreamingTranscription] Sending command: {}", json),
+    }
+    input.write(format!("{}\n", json).into_bytes())
 }
 
-/// Start a live transcription session
-pub fn start_session(
-    app: &AppHandle,
+/// Queue audio samples for the worker: as a binary frame if it speaks a
+/// protocol version that accepts them, otherwise as a base64-encoded command
+fn send_audio(
+    input: &WorkerInput,
+    protocol_version: u32,
     session_id: &str,
-    config: &LiveTranscriptionConfig,
+    samples: &[f32],
+    encoding: AudioEncoding,
 ) -> Result<()> {
-    println!("[StreamingTranscription] start_session: session_id={}, config={:?}", session_id, config);
-
-    // Ensure worker is initialized
-    initialize_worker(app, config.model.clone(), config.language.clone())?;
-
-    let mut state = get_worker_state().lock();
-    let worker = state.as_mut().ok_or_else(|| anyhow!("Worker not started"))?;
-
-    let cmd = WorkerCommand::Start {
-        session_id: session_id.to_string(),
-        use_vad: config.use_vad.unwrap_or(true),
-        confirmation_threshold: config.confirmation_threshold.unwrap_or(2),
-    };
-
-    println!("[StreamingTranscription] Sending start command to worker...");
-    send_command(&mut worker.stdin, &cmd)?;
-    worker.state = WorkerState::Transcribing(session_id.to_string());
-
-    println!("[StreamingTranscription] Started session: {}", session_id);
-    Ok(())
-}
-
-/// Feed audio samples to the worker
-pub fn feed_audio(session_id: &str, samples: &[f32]) -> Result<()> {
-    let mut state = get_worker_state().lock();
-    let worker = state.as_mut().ok_or_else(|| anyhow!("Worker not started"))?;
+    if protocol_version >= BINARY_AUDIO_VERSION {
+        return input.write(encode_audio_frame(session_id, samples, encoding)?);
+    }
 
-    // Encode samples as base64
     let bytes: Vec<u8> = samples
         .iter()
         .flat_map(|f| f.to_le_bytes())
@@ -372,152 +1075,456 @@ pub fn feed_audio(session_id: &str, samples: &[f32]) -> Result<()> {
         sample_count: samples.len(),
     };
 
-    send_command(&mut worker.stdin, &cmd)?;
-    Ok(())
+    send_command(input, &cmd)
 }
 
-/// Stop a live transcription session
-pub fn stop_session(session_id: &str) -> Result<()> {
-    let mut state = get_worker_state().lock();
-    let worker = state.as_mut().ok_or_else(|| anyhow!("Worker not started"))?;
-
-    let cmd = WorkerCommand::Stop {
-        session_id: session_id.to_string(),
-    };
-
-    send_command(&mut worker.stdin, &cmd)?;
-    worker.state = WorkerState::Stopping;
+/// Encode samples as a binary audio frame: the marker byte, the encoding
+/// code, the session id prefixed by its u16 length, the u32 sample count and
+/// then the samples, all little-endian
+pub(crate) fn encode_audio_frame(
+    session_id: &str,
+    samples: &[f32],
+    encoding: AudioEncoding,
+) -> Result<Vec<u8>> {
+    let id_len = u16::try_from(session_id.len())
+        .map_err(|_| anyhow!("Session id too long for an audio frame"))?;
+    let sample_count = u32::try_from(samples.len())
+        .map_err(|_| anyhow!("Too many samples for an audio frame"))?;
+    let mut frame =
+        Vec::with_capacity(8 + session_id.len() + samples.len() * encoding.sample_size());
+    frame.push(AUDIO_FRAME_MARKER);
+    frame.push(encoding.code());
+    frame.extend_from_slice(&id_len.to_le_bytes());
+    frame.extend_from_slice(session_id.as_bytes());
+    frame.extend_from_slice(&sample_count.to_le_bytes());
+    match encoding {
+        AudioEncoding::F32 => {
+            for sample in samples {
+                frame.extend_from_slice(&sample.to_le_bytes());
+            }
+        }
+        AudioEncoding::I16 => {
+            for sample in samples {
+                let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+                frame.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    Ok(frame)
+}
 
-    println!("[StreamingTranscription] Stopping session: {}", session_id);
-    Ok(())
+/// Delay before the given restart attempt, doubling from one second
+fn restart_delay(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    RESTART_BASE_DELAY
+        .saturating_mul(factor)
+        .min(RESTART_MAX_DELAY)
 }
 
-/// Shutdown the worker
-pub fn shutdown_worker() -> Result<()> {
-    let mut state = get_worker_state().lock();
+/// Start the supervisor thread, once. It restarts a worker that exits or
+/// stops answering heartbeats, and resumes its live session on the new one.
+fn ensure_supervisor() {
+    SUPERVISOR.get_or_init(|| {
+        thread::spawn(|| loop {
+            supervise();
+            let replaying = send_pending_audio();
+            thread::sleep(if replaying {
+                REPLAY_CHUNK_INTERVAL
+            } else {
+                SUPERVISOR_TICK
+            });
+        });
+    });
+}
 
-    if let Some(mut worker) = state.take() {
-        let cmd = WorkerCommand::Shutdown;
-        let _ = send_command(&mut worker.stdin, &cmd);
-        let _ = worker.child.wait();
-        println!("[StreamingTranscription] Worker shut down");
+/// One supervisor check of every worker
+fn supervise() {
+    let mut workers = get_worker_state().lock();
+    for worker in workers.iter_mut() {
+        supervise_worker(worker);
     }
 
-    Ok(())
+    // A finished session leaves its worker idle; keep one for the next session
+    let mut idle = 0;
+    let (keep, spare): (Vec<_>, Vec<_>) = workers.drain(..).partition(|worker| {
+        if worker.session.is_some() || worker.state != WorkerState::Ready {
+            return true;
+        }
+        idle += 1;
+        idle == 1
+    });
+    *workers = keep;
+    for mut worker in spare {
+        println!("[StreamingTranscription] Shutting down spare worker");
+        let _ = send_command(&worker.input, &WorkerCommand::Shutdown);
+        if let Some(child) = worker.child.take() {
+            reap(child, SHUTDOWN_GRACE);
+        }
+    }
 }
 
-/// Check if worker is running and healthy
-pub fn is_worker_running() -> bool {
-    let mut state = get_worker_state().lock();
-    if let Some(ref mut worker) = *state {
-        // Actually check if the process is still running
-        match worker.child.try_wait() {
-            Ok(Some(_status)) => {
-                // Process has exited
-                println!("[StreamingTranscription] Worker process has exited");
-                worker.state = WorkerState::Error("Worker process exited".to_string());
-                false
-            }
-            Ok(None) => {
-                // Process still running
-                matches!(
-                    worker.state,
-                    WorkerState::Ready | WorkerState::Transcribing(_) | WorkerState::Starting
-                )
-            }
-            Err(e) => {
-                println!("[StreamingTranscription] Error checking worker status: {}", e);
-                false
+/// One supervisor check of a worker
+fn supervise_worker(worker: &mut ActiveWorker) {
+    let now = Instant::now();
+
+    match worker.state {
+        WorkerState::Restarting => {
+            if worker.restart_at.is_some_and(|at| now >= at) {
+                restart_worker(worker);
             }
+            return;
+        }
+        WorkerState::Error(_) | WorkerState::NotStarted => return,
+        _ => {}
+    }
+
+    let Some(child) = worker.child.as_mut() else {
+        return;
+    };
+    match child.try_wait() {
+        Ok(Some(status)) => {
+            fail_worker(worker, &format!("Worker exited ({})", status));
+            return;
         }
+        Err(e) => {
+            fail_worker(worker, &format!("Failed to check worker: {}", e));
+            return;
+        }
+        Ok(None) => {}
+    }
+
+    // While loading a model the worker answers nothing, so allow longer
+    let timeout = if worker.init_sent_at.is_some() {
+        STARTUP_TIMEOUT
     } else {
-        false
+        HEARTBEAT_TIMEOUT
+    };
+    if now.duration_since(worker.last_seen) > timeout {
+        fail_worker(worker, "Worker stopped responding");
+        return;
+    }
+
+    if worker.restart_attempts > 0 && now.duration_since(worker.spawned_at) > RESTART_STABLE_AFTER {
+        worker.restart_attempts = 0;
     }
+
+    if worker.init_sent_at.is_none()
+        && now.duration_since(worker.last_seen.max(worker.last_ping)) >= HEARTBEAT_INTERVAL
+    {
+        worker.last_ping = now;
+        if let Err(e) = send_command(&worker.input, &WorkerCommand::Status) {
+            fail_worker(worker, &format!("Failed to send heartbeat: {}", e));
+            return;
+        }
+    }
+
+    resume_session(worker);
 }
 
-/// Get current worker state
-pub fn get_state() -> WorkerState {
-    let state = get_worker_state().lock();
-    if let Some(ref worker) = *state {
-        worker.state.clone()
-    } else {
-        WorkerState::NotStarted
+/// Start the live session on a restarted worker once it is ready, from
+/// where the last confirmed segment ended
+fn resume_session(worker: &mut ActiveWorker) {
+    if worker.state != WorkerState::Ready {
+        return;
+    }
+    let Some(session) = worker.session.as_mut() else {
+        return;
+    };
+    if session.started {
+        return;
+    }
+
+    let cmd = WorkerCommand::Start {
+        session_id: session.id.clone(),
+        use_vad: session.use_vad,
+        confirmation_threshold: session.confirmation_threshold,
+    };
+    if let Err(e) = send_command(&worker.input, &cmd) {
+        fail_worker(worker, &format!("Failed to resume session: {}", e));
+        return;
     }
+
+    let from = session.resume_position();
+    println!(
+        "[StreamingTranscription] Resumed session {}, replaying {:.1}s of audio",
+        session.id,
+        (session.history.end() - from) as f64 / SAMPLE_RATE as f64
+    );
+    session.started = true;
+    session.offset = from;
+    session.sent_until = from;
+    worker.state = WorkerState::Transcribing(session.id.clone());
 }
 
-/// Ensure the worker is running, restarting if necessary
-pub fn ensure_worker_running(app: &AppHandle) -> Result<()> {
-    // Check current state
-    let needs_restart = {
-        let mut state = get_worker_state().lock();
-        if let Some(ref mut worker) = *state {
-            // Check if process is still alive
-            match worker.child.try_wait() {
-                Ok(Some(_)) => {
-                    // Process exited, need to restart
-                    println!("[StreamingTranscription] Worker exited unexpectedly, will restart");
-                    true
-                }
-                Ok(None) => {
-                    // Process running, check state
-                    matches!(worker.state, WorkerState::Error(_))
-                }
-                Err(e) => {
-                    println!("[StreamingTranscription] Error checking worker: {}", e);
-                    true
-                }
-            }
-        } else {
-            true // No worker, need to start
+/// Send each worker the next chunk of audio it has not had yet. Returns
+/// whether more is waiting.
+fn send_pending_audio() -> bool {
+    let mut workers = get_worker_state().lock();
+    workers
+        .iter_mut()
+        .fold(false, |pending, worker| replay_chunk(worker) || pending)
+}
+
+/// Send a worker the next chunk of its session's audio it has not had yet.
+/// Returns whether more is waiting.
+fn replay_chunk(worker: &mut ActiveWorker) -> bool {
+    let Some(session) = worker.session.as_mut() else {
+        return false;
+    };
+    if !session.started || session.sent_until >= session.history.end() {
+        return false;
+    }
+
+    let (from, samples) = session
+        .history
+        .read(session.sent_until, REPLAY_CHUNK_SAMPLES);
+    if from > session.sent_until {
+        println!(
+            "[StreamingTranscription] {:.1}s of audio was too old to replay",
+            (from - session.sent_until) as f64 / SAMPLE_RATE as f64
+        );
+    }
+    if let Err(e) = send_audio(
+        &worker.input,
+        worker.protocol_version,
+        &session.id,
+        &samples,
+        session.audio_encoding,
+    ) {
+        fail_worker(worker, &format!("Failed to replay audio: {}", e));
+        return false;
+    }
+    session.sent_until = from + samples.len() as u64;
+    session.sent_until < session.history.end()
+}
+
+/// Take down a worker that exited, hung or could not be written to, and
+/// schedule a restart, or give up after too many
+fn fail_worker(worker: &mut ActiveWorker, reason: &str) {
+    println!("[StreamingTranscription] Worker failed: {}", reason);
+    if let Some(mut child) = worker.child.take() {
+        let _ = child.kill();
+        reap(child, Duration::ZERO);
+    }
+    worker.init_sent_at = None;
+
+    if let Some(session) = worker.session.as_mut() {
+        session.started = false;
+        session.carried = session.confirmed.len();
+    }
+    // A session being stopped only needed its last words; finish with what
+    // was confirmed rather than waiting for a new worker
+    if let Some(session) = worker.session.take_if(|s| s.stopping) {
+        emit_complete(&worker.app, &session);
+    }
+
+    if worker.restart_attempts >= MAX_RESTART_ATTEMPTS {
+        println!(
+            "[StreamingTranscription] Giving up after {} restarts",
+            worker.restart_attempts
+        );
+        worker.state = WorkerState::Error(reason.to_string());
+        worker.restart_at = None;
+        let session_id = worker.session.take().map(|s| s.id);
+        let _ = worker.app.emit(
+            "transcription-error",
+            TranscriptionErrorEvent {
+                session_id,
+                message: format!(
+                    "Live transcription stopped: the worker failed {} times in a row ({})",
+                    worker.restart_attempts + 1,
+                    reason
+                ),
+                code: "WORKER_FAILED".to_string(),
+            },
+        );
+        return;
+    }
+
+    worker.restart_attempts += 1;
+    let delay = restart_delay(worker.restart_attempts);
+    worker.state = WorkerState::Restarting;
+    worker.restart_at = Some(Instant::now() + delay);
+    println!(
+        "[StreamingTranscription] Restarting worker in {:?} (attempt {}/{})",
+        delay, worker.restart_attempts, MAX_RESTART_ATTEMPTS
+    );
+
+    let _ = worker.app.emit(
+        "streaming-worker-restarting",
+        WorkerRestartingEvent {
+            session_id: worker.session.as_ref().map(|s| s.id.clone()),
+            reason: reason.to_string(),
+            attempt: worker.restart_attempts,
+            max_attempts: MAX_RESTART_ATTEMPTS,
+            delay_ms: delay.as_millis() as u64,
+        },
+    );
+}
+
+/// Replace a failed worker with a new process, initialized with the same
+/// model. The session is resumed once it reports ready.
+fn restart_worker(worker: &mut ActiveWorker) {
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let (child, input) = match spawn_worker(&worker.app, generation) {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            fail_worker(worker, &e.to_string());
+            return;
         }
     };
 
-    if needs_restart {
-        // Clear old state
-        {
-            let mut state = get_worker_state().lock();
-            if let Some(mut worker) = state.take() {
-                let _ = worker.child.kill();
-                let _ = worker.child.wait();
-            }
+    let now = Instant::now();
+    worker.child = Some(child);
+    worker.input = input;
+    worker.generation = generation;
+    worker.protocol_version = default_protocol_version();
+    worker.state = WorkerState::Starting;
+    worker.spawned_at = now;
+    worker.last_seen = now;
+    worker.last_ping = now;
+    worker.restart_at = None;
+    println!("[StreamingTranscription] Worker restarted");
+
+    if worker.initialized {
+        let cmd = WorkerCommand::Init {
+            model: worker.current_model.clone(),
+            language: worker.language.clone(),
+        };
+        if let Err(e) = send_command(&worker.input, &cmd) {
+            fail_worker(
+                worker,
+                &format!("Failed to initialize restarted worker: {}", e),
+            );
+            return;
         }
-        // Start new worker
-        start_worker(app)?;
+        worker.init_sent_at = Some(now);
     }
+}
 
-    Ok(())
+/// Wait for a worker process on a thread of its own, so nothing waits on it
+/// while holding the worker state. One still running after `grace` is killed.
+fn reap(mut child: Child, grace: Duration) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let deadline = Instant::now() + grace;
+        while Instant::now() < deadline {
+            match child.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) => thread::sleep(REAP_POLL_INTERVAL),
+                Err(_) => break,
+            }
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    })
 }
 
-// MARK: - Internal Functions
+/// Tell the frontend a session finished without its worker, with the text
+/// confirmed so far
+fn emit_complete(app: &AppHandle, session: &LiveSession) {
+    let _ = app.emit(
+        "transcription-complete",
+        TranscriptionCompleteEvent {
+            session_id: session.id.clone(),
+            full_text: session.full_text(None),
+        },
+    );
+}
 
-/// Send a command to the worker via stdin
-fn send_command(stdin: &mut ChildStdin, cmd: &WorkerCommand) -> Result<()> {
-    let json = serde_json::to_string(cmd)?;
-    // Log non-audio commands (audio commands are too frequent)
-    match cmd {
-        WorkerCommand::Audio { .. } => {}
-        _ => println!("[StreamingTranscription] Sending command: {}", json),
+/// Save a confirmed segment to its live session as soon as it arrives, so the
+/// transcript so far survives a crash. Runs on the stdout reader thread,
+/// which keeps segments in the order the worker sent them.
+fn persist_segment(session_id: &str, text: &str, start_time: f64, end_time: f64) {
+    if text.trim().is_empty() {
+        return;
+    }
+    let segment = TranscriptSegment {
+        start: start_time,
+        end: end_time,
+        text: text.to_string(),
+        speaker: None,
+        avg_logprob: None,
+        words: None,
+    };
+    let result = database::get_pool().and_then(|pool| {
+        tauri::async_runtime::block_on(live_sessions::append_segment(
+            pool,
+            session_id,
+            &segment,
+            chrono::Utc::now().timestamp(),
+        ))
+    });
+    match result {
+        Ok(true) => {}
+        Ok(false) => println!(
+            "[StreamingTranscription] No live session {} to save the segment to",
+            session_id
+        ),
+        Err(e) => println!(
+            "[StreamingTranscription] Failed to save segment for {}: {}",
+            session_id, e
+        ),
     }
-    writeln!(stdin, "{}", json)?;
-    stdin.flush()?;
-    Ok(())
 }
 
-/// Handle an event from the worker
-fn handle_worker_event(app: &AppHandle, line: &str) -> Result<()> {
+/// Run `f` on the live session with the given id, if it is active
+fn with_session<T>(session_id: &str, f: impl FnOnce(&mut LiveSession) -> T) -> Option<T> {
+    let mut workers = get_worker_state().lock();
+    workers
+        .iter_mut()
+        .find_map(|worker| worker.session.as_mut().filter(|s| s.id == session_id))
+        .map(f)
+}
+
+/// Handle an event from the worker process of the given generation
+fn handle_worker_event(app: &AppHandle, generation: u64, line: &str) -> Result<()> {
+    // Anything from the worker shows it is alive; anything from a process
+    // that has been replaced is stale
+    {
+        let mut workers = get_worker_state().lock();
+        match workers.iter_mut().find(|w| w.generation == generation) {
+            Some(worker) => {
+                worker.last_seen = Instant::now();
+            }
+            None => {
+                println!(
+                    "[StreamingTranscription] Ignoring event from replaced worker: {}",
+                    line
+                );
+                return Ok(());
+            }
+        }
+    }
+
     println!("[StreamingTranscription] Received from worker: {}", line);
     let event: WorkerEvent = serde_json::from_str(line)
         .map_err(|e| anyhow!("Failed to parse worker event: {} - line: {}", e, line))?;
 
     match event {
-        WorkerEvent::Ready { model, model_path } => {
-            println!("[StreamingTranscription] Worker ready with model: {}", model);
+        WorkerEvent::Ready {
+            model,
+            model_path,
+            protocol_version,
+        } => {
+            println!(
+                "[StreamingTranscription] Worker ready with model: {} (protocol v{})",
+                model, protocol_version
+            );
 
-            // Update state
+            // Update state; a session already started on this process carries
+            // on, and one waiting after a restart is resumed by the supervisor
             {
-                let mut state = get_worker_state().lock();
-                if let Some(ref mut worker) = *state {
-                    worker.state = WorkerState::Ready;
+                let mut workers = get_worker_state().lock();
+                if let Some(worker) = workers.iter_mut().find(|w| w.generation == generation) {
+                    worker.init_sent_at = None;
+                    worker.protocol_version = protocol_version.min(PROTOCOL_VERSION);
+                    worker.state = match &worker.session {
+                        Some(session) if session.started && !session.stopping => {
+                            WorkerState::Transcribing(session.id.clone())
+                        }
+                        Some(session) if session.started => WorkerState::Stopping,
+                        _ => WorkerState::Ready,
+                    };
                 }
             }
 
@@ -533,6 +1540,8 @@ fn handle_worker_event(app: &AppHandle, line: &str) -> Result<()> {
             timestamp,
         } => {
             println!("[LIVE] (tentative) \"{}\"", text);
+            let timestamp = with_session(&session_id, |session| session.session_time(timestamp))
+                .unwrap_or(timestamp);
             let _ = app.emit(
                 "live-transcription",
                 LiveTranscriptionEvent {
@@ -554,6 +1563,15 @@ fn handle_worker_event(app: &AppHandle, line: &str) -> Result<()> {
                 "[LIVE] ✓ CONFIRMED: \"{}\" ({:.2}s - {:.2}s)",
                 text, start_time, end_time
             );
+            // Times from a restarted worker count from where it resumed
+            let (start_time, end_time) = with_session(&session_id, |session| {
+                let start_time = session.session_time(start_time);
+                let end_time = session.session_time(end_time);
+                session.record_confirmed(&text, end_time);
+                (start_time, end_time)
+            })
+            .unwrap_or((start_time, end_time));
+            persist_segment(&session_id, &text, start_time, end_time);
             let _ = app.emit(
                 "live-transcription",
                 LiveTranscriptionEvent {
@@ -577,13 +1595,21 @@ fn handle_worker_event(app: &AppHandle, line: &str) -> Result<()> {
                 full_text.len()
             );
 
-            // Update state
-            {
-                let mut state = get_worker_state().lock();
-                if let Some(ref mut worker) = *state {
-                    worker.state = WorkerState::Ready;
+            // Update state, and put back what earlier workers confirmed if
+            // the session was resumed
+            let full_text = {
+                let mut workers = get_worker_state().lock();
+                match workers.iter_mut().find(|w| w.generation == generation) {
+                    Some(worker) => {
+                        worker.state = WorkerState::Ready;
+                        match worker.session.take_if(|s| s.id == session_id) {
+                            Some(session) => session.full_text(Some(&full_text)),
+                            None => full_text,
+                        }
+                    }
+                    None => full_text,
                 }
-            }
+            };
 
             let _ = app.emit(
                 "transcription-complete",
@@ -701,6 +1727,13 @@ mod tests {
         assert!(json.contains("\"cmd\":\"shutdown\""));
     }
 
+    #[test]
+    fn test_worker_command_status_serialization() {
+        let cmd = WorkerCommand::Status;
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, "{\"cmd\":\"status\"}");
+    }
+
     // ==========================================
     // Audio Encoding Tests
     // ==========================================
@@ -756,6 +1789,37 @@ mod tests {
         assert_eq!(decoded.len(), samples.len() * 4);
     }
 
+    #[test]
+    fn test_audio_frame_f32() {
+        let frame = encode_audio_frame("s1", &[0.5, -1.0], AudioEncoding::F32).unwrap();
+        assert_eq!(&frame[..6], &[AUDIO_FRAME_MARKER, 0, 2, 0, b's', b'1']);
+        assert_eq!(&frame[6..10], &2u32.to_le_bytes());
+        assert_eq!(&frame[10..14], &0.5f32.to_le_bytes());
+        assert_eq!(&frame[14..18], &(-1.0f32).to_le_bytes());
+        assert_eq!(frame.len(), 18);
+    }
+
+    #[test]
+    fn test_audio_frame_i16_halves_payload() {
+        let samples: Vec<f32> = vec![0.0, 1.0, -1.0, 2.0];
+        let frame = encode_audio_frame("s1", &samples, AudioEncoding::I16).unwrap();
+        assert_eq!(frame[1], 1);
+        assert_eq!(&frame[6..10], &4u32.to_le_bytes());
+
+        let values: Vec<i16> = frame[10..]
+            .chunks(2)
+            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+        // Out-of-range samples are clipped
+        assert_eq!(values, vec![0, i16::MAX, -i16::MAX, i16::MAX]);
+    }
+
+    #[test]
+    fn test_audio_frame_rejects_oversized_session_id() {
+        let session_id = "x".repeat(u16::MAX as usize + 1);
+        assert!(encode_audio_frame(&session_id, &[0.0], AudioEncoding::F32).is_err());
+    }
+
     // ==========================================
     // Worker Event Deserialization Tests
     // ==========================================
@@ -765,9 +1829,10 @@ mod tests {
         let json = r#"{"type":"ready","model":"base","model_path":null}"#;
         let event: WorkerEvent = serde_json::from_str(json).unwrap();
         match event {
-            WorkerEvent::Ready { model, model_path } => {
+            WorkerEvent::Ready { model, model_path, protocol_version } => {
                 assert_eq!(model, "base");
                 assert!(model_path.is_none());
+                assert_eq!(protocol_version, 1);
             }
             _ => panic!("Expected Ready event"),
         }
@@ -778,7 +1843,7 @@ mod tests {
         let json = r#"{"type":"ready","model":"large-v3","model_path":"/path/to/model"}"#;
         let event: WorkerEvent = serde_json::from_str(json).unwrap();
         match event {
-            WorkerEvent::Ready { model, model_path } => {
+            WorkerEvent::Ready { model, model_path, .. } => {
                 assert_eq!(model, "large-v3");
                 assert_eq!(model_path, Some("/path/to/model".to_string()));
             }
@@ -786,6 +1851,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_worker_event_ready_with_protocol_version() {
+        let json = r#"{"type":"ready","model":"base","model_path":null,"protocol_version":2}"#;
+        let event: WorkerEvent = serde_json::from_str(json).unwrap();
+        match event {
+            WorkerEvent::Ready { protocol_version, .. } => assert_eq!(protocol_version, 2),
+            _ => panic!("Expected Ready event"),
+        }
+    }
+
     #[test]
     fn test_worker_event_tentative_deserialization() {
         let json = r#"{"type":"tentative","session_id":"sess-1","text":"Hello wor","timestamp":1.5}"#;
@@ -889,12 +1964,14 @@ mod tests {
             language: Some("es".to_string()),
             use_vad: Some(false),
             confirmation_threshold: Some(5),
+            audio_encoding: Some(AudioEncoding::I16),
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("\"model\":\"small\""));
         assert!(json.contains("\"language\":\"es\""));
         assert!(json.contains("\"useVad\":false"));
         assert!(json.contains("\"confirmationThreshold\":5"));
+        assert!(json.contains("\"audioEncoding\":\"i16\""));
     }
 
     #[test]
@@ -905,6 +1982,7 @@ mod tests {
         assert_eq!(config.language, Some("fr".to_string()));
         assert_eq!(config.use_vad, Some(true));
         assert_eq!(config.confirmation_threshold, Some(3));
+        assert!(config.audio_encoding.is_none());
     }
 
     // ==========================================
@@ -994,10 +2072,7 @@ mod tests {
     #[test]
     fn test_get_state_when_not_started() {
         // Clear any existing state
-        {
-            let mut state = get_worker_state().lock();
-            *state = None;
-        }
+        get_worker_state().lock().clear();
 
         let state = get_state();
         assert_eq!(state, WorkerState::NotStarted);
@@ -1006,11 +2081,157 @@ mod tests {
     #[test]
     fn test_is_worker_running_when_not_started() {
         // Clear any existing state
-        {
-            let mut state = get_worker_state().lock();
-            *state = None;
-        }
+        get_worker_state().lock().clear();
 
         assert!(!is_worker_running());
     }
+
+    #[test]
+    fn test_get_status_when_not_started() {
+        get_worker_state().lock().clear();
+
+        let status = get_status();
+        assert_eq!(status.state, "NotStarted");
+        assert_eq!(status.workers, 0);
+        assert!(status.sessions.is_empty());
+        assert!(active_session_ids().is_empty());
+    }
+
+    // ==========================================
+    // Supervision Tests
+    // ==========================================
+
+    fn test_session() -> LiveSession {
+        LiveSession::new("sess-1", &LiveTranscriptionConfig::default())
+    }
+
+    #[test]
+    fn test_restart_delay_doubles_up_to_max() {
+        assert_eq!(restart_delay(1), Duration::from_secs(1));
+        assert_eq!(restart_delay(2), Duration::from_secs(2));
+        assert_eq!(restart_delay(5), Duration::from_secs(16));
+        assert_eq!(restart_delay(6), RESTART_MAX_DELAY);
+        assert_eq!(restart_delay(100), RESTART_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_audio_history_drops_oldest_past_capacity() {
+        let mut history = AudioHistory::new(4);
+        history.push(&[1.0, 2.0, 3.0]);
+        history.push(&[4.0, 5.0, 6.0]);
+
+        assert_eq!(history.start, 2);
+        assert_eq!(history.end(), 6);
+        assert_eq!(history.read(3, 10), (3, vec![4.0, 5.0, 6.0]));
+        assert_eq!(history.read(4, 1), (4, vec![5.0]));
+        // Dropped positions read from the oldest kept sample
+        assert_eq!(history.read(0, 2), (2, vec![3.0, 4.0]));
+        assert_eq!(history.read(6, 10), (6, vec![]));
+    }
+
+    #[test]
+    fn test_session_resumes_after_last_confirmed_segment() {
+        let mut session = test_session();
+        session.history.push(&vec![0.0; 5 * SAMPLE_RATE as usize]);
+        session.started = true;
+        session.sent_until = session.history.end();
+        assert!(session.is_caught_up());
+
+        session.record_confirmed("hello", 2.0);
+        assert_eq!(session.resume_position(), 2 * SAMPLE_RATE);
+
+        // Nothing confirmed within the kept audio: replay all of it
+        let mut session = test_session();
+        session.history = AudioHistory::new(SAMPLE_RATE as usize);
+        session.history.push(&vec![0.0; 3 * SAMPLE_RATE as usize]);
+        assert_eq!(session.resume_position(), 2 * SAMPLE_RATE);
+        assert!(!session.is_caught_up());
+    }
+
+    #[test]
+    fn test_session_replays_everything_since_confirmed_after_a_slow_restart() {
+        let mut session = test_session();
+        session.history.push(&vec![0.0; 10 * SAMPLE_RATE as usize]);
+        session.started = true;
+        session.sent_until = session.history.end();
+        session.record_confirmed("hello", 10.0);
+
+        // The worker hangs, the restart waits the longest delay and the new
+        // worker takes as long as it's allowed to load its model
+        let gap = HEARTBEAT_TIMEOUT + RESTART_MAX_DELAY + STARTUP_TIMEOUT;
+        assert!(gap > Duration::from_secs(60));
+        session
+            .history
+            .push(&vec![0.0; (gap.as_secs() * SAMPLE_RATE) as usize]);
+
+        assert_eq!(session.resume_position(), 10 * SAMPLE_RATE);
+        let (from, _) = session.history.read(session.resume_position(), 1);
+        assert_eq!(from, 10 * SAMPLE_RATE);
+    }
+
+    #[test]
+    fn test_session_time_counts_from_resume_point() {
+        let mut session = test_session();
+        assert_eq!(session.session_time(1.5), 1.5);
+
+        session.offset = 3 * SAMPLE_RATE;
+        assert_eq!(session.session_time(1.5), 4.5);
+    }
+
+    #[test]
+    fn test_session_status_reports_its_own_config() {
+        let config = LiveTranscriptionConfig {
+            language: Some("de".to_string()),
+            audio_encoding: Some(AudioEncoding::I16),
+            ..LiveTranscriptionConfig::default()
+        };
+        let mut session = LiveSession::new("system-audio", &config);
+        session.history.push(&vec![0.0; 3 * SAMPLE_RATE as usize]);
+        session.record_confirmed("Hallo.", 1.0);
+
+        let status = session.status(&WorkerState::Transcribing("system-audio".to_string()), 1);
+        assert_eq!(status.session_id, "system-audio");
+        assert_eq!(status.worker_state, "Transcribing(\"system-audio\")");
+        assert_eq!(status.audio_seconds, 3.0);
+        assert_eq!(status.confirmed_segments, 1);
+        assert_eq!(status.restart_attempts, 1);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["sessionId"], "system-audio");
+        assert_eq!(json["config"]["language"], "de");
+        assert_eq!(json["config"]["audioEncoding"], "i16");
+    }
+
+    #[test]
+    fn test_full_text_keeps_text_from_replaced_workers() {
+        let mut session = test_session();
+        session.record_confirmed("First part.", 1.0);
+        session.record_confirmed("Second part.", 2.0);
+        assert_eq!(
+            session.full_text(Some("First part. Second part.")),
+            "First part. Second part."
+        );
+
+        // The worker was replaced after two segments
+        session.carried = session.confirmed.len();
+        session.record_confirmed("Third part.", 3.0);
+        assert_eq!(
+            session.full_text(Some("Third part. Fourth part.")),
+            "First part. Second part. Third part. Fourth part."
+        );
+        // Finished without a worker: everything confirmed
+        assert_eq!(
+            session.full_text(None),
+            "First part. Second part. Third part."
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reap_kills_a_process_that_outlives_its_grace() {
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let started = Instant::now();
+        reap(child, Duration::from_millis(100)).join().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
 }
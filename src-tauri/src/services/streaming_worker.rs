@@ -0,0 +1,1260 @@
+//! Reference streaming worker
+//!
+//! A Rust implementation of the JSON-lines protocol spoken by the WhisperKit
+//! worker (`WorkerCommand` in, `WorkerEvent` out), so live transcription runs
+//! on Linux and can be tested end to end. The `streaming-worker` binary runs
+//! it with whisper.cpp on the CPU, or with a replay engine that emits
+//! scripted events regardless of what it hears.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+use super::streaming_transcription::{
+    AudioEncoding, WorkerCommand, WorkerEvent, AUDIO_FRAME_MARKER, PROTOCOL_VERSION, SAMPLE_RATE,
+};
+use crate::models::TranscriptSegment;
+
+/// Environment variable pointing at the downloaded whisper models
+pub const MODELS_DIR_ENV: &str = "PRIVATE_TRANSCRIPT_MODELS_DIR";
+
+/// Environment variable selecting the replay engine with the given script
+pub const REPLAY_SCRIPT_ENV: &str = "PRIVATE_TRANSCRIPT_REPLAY_SCRIPT";
+
+// MARK: - Engines
+
+/// Model an engine loaded, as reported in `Ready`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedModel {
+    pub name: String,
+    pub path: Option<String>,
+}
+
+/// What an engine produced for the current session
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineOutput {
+    Tentative {
+        text: String,
+        timestamp: f64,
+    },
+    Confirmed {
+        text: String,
+        start_time: f64,
+        end_time: f64,
+    },
+    Error {
+        message: String,
+        code: String,
+    },
+    /// End the worker process with this exit code
+    Exit {
+        code: i32,
+    },
+}
+
+/// Turns a session's streamed audio into transcript output
+///
+/// Times in outputs count from the start of the session.
+pub trait StreamingEngine: Send {
+    /// Load a model, by name or path
+    fn load(&mut self, model: Option<&str>, language: Option<&str>) -> Result<LoadedModel>;
+
+    /// Reset for a new session
+    fn start(&mut self, use_vad: bool, confirmation_threshold: u32);
+
+    /// Buffer audio for the session
+    fn feed(&mut self, samples: &[f32]);
+
+    /// Transcribe buffered audio if there is enough of it
+    fn poll(&mut self) -> Result<Vec<EngineOutput>>;
+
+    /// Transcribe whatever is left and confirm it
+    fn finish(&mut self) -> Result<Vec<EngineOutput>>;
+}
+
+// MARK: - Replay Engine
+
+/// A scripted output, fired once the session has been fed `at` seconds of
+/// audio. Whatever is left of the script fires when the session stops.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScriptStep {
+    Tentative {
+        at: f64,
+        text: String,
+    },
+    Confirmed {
+        at: f64,
+        text: String,
+        start_time: f64,
+        end_time: f64,
+    },
+    Error {
+        at: f64,
+        message: String,
+        code: String,
+    },
+    /// Exit the worker, to exercise the backend's supervisor
+    Exit {
+        at: f64,
+        #[serde(default = "default_exit_code")]
+        code: i32,
+    },
+}
+
+fn default_exit_code() -> i32 {
+    1
+}
+
+impl ScriptStep {
+    fn at(&self) -> f64 {
+        match self {
+            ScriptStep::Tentative { at, .. }
+            | ScriptStep::Confirmed { at, .. }
+            | ScriptStep::Error { at, .. }
+            | ScriptStep::Exit { at, .. } => *at,
+        }
+    }
+
+    fn output(&self) -> EngineOutput {
+        match self.clone() {
+            ScriptStep::Tentative { at, text } => EngineOutput::Tentative {
+                text,
+                timestamp: at,
+            },
+            ScriptStep::Confirmed {
+                text,
+                start_time,
+                end_time,
+                ..
+            } => EngineOutput::Confirmed {
+                text,
+                start_time,
+                end_time,
+            },
+            ScriptStep::Error { message, code, .. } => EngineOutput::Error { message, code },
+            ScriptStep::Exit { code, .. } => EngineOutput::Exit { code },
+        }
+    }
+}
+
+/// Deterministic engine that plays back a script, for tests
+pub struct ReplayEngine {
+    script: Vec<ScriptStep>,
+    next: usize,
+    fed: u64,
+}
+
+impl ReplayEngine {
+    pub fn new(script: Vec<ScriptStep>) -> Self {
+        Self {
+            script,
+            next: 0,
+            fed: 0,
+        }
+    }
+
+    /// Load a script from a JSON array of steps
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read replay script {:?}: {}", path, e))?;
+        let script = serde_json::from_str(&json)
+            .map_err(|e| anyhow!("Invalid replay script {:?}: {}", path, e))?;
+        Ok(Self::new(script))
+    }
+
+    /// Steps due by `until` seconds, or all remaining ones
+    fn take_due(&mut self, until: Option<f64>) -> Vec<EngineOutput> {
+        let mut outputs = Vec::new();
+        while let Some(step) = self.script.get(self.next) {
+            if until.is_some_and(|until| step.at() > until) {
+                break;
+            }
+            outputs.push(step.output());
+            self.next += 1;
+        }
+        outputs
+    }
+}
+
+impl StreamingEngine for ReplayEngine {
+    fn load(&mut self, model: Option<&str>, _language: Option<&str>) -> Result<LoadedModel> {
+        Ok(LoadedModel {
+            name: model.unwrap_or("replay").to_string(),
+            path: None,
+        })
+    }
+
+    fn start(&mut self, _use_vad: bool, _confirmation_threshold: u32) {
+        self.next = 0;
+        self.fed = 0;
+    }
+
+    fn feed(&mut self, samples: &[f32]) {
+        self.fed += samples.len() as u64;
+    }
+
+    fn poll(&mut self) -> Result<Vec<EngineOutput>> {
+        Ok(self.take_due(Some(self.fed as f64 / SAMPLE_RATE as f64)))
+    }
+
+    fn finish(&mut self) -> Result<Vec<EngineOutput>> {
+        Ok(self.take_due(None))
+    }
+}
+
+// MARK: - Windowed Engine
+
+/// New audio needed before transcribing again
+const POLL_SAMPLES: usize = SAMPLE_RATE as usize;
+
+/// Window length at which everything in it is confirmed, to keep it under
+/// the 30 seconds Whisper sees at once
+const MAX_WINDOW_SAMPLES: usize = 25 * SAMPLE_RATE as usize;
+
+/// Recent audio checked for speech before transcribing
+const VAD_SAMPLES: usize = 1600;
+
+/// Mean energy below which recent audio counts as silence
+const VAD_ENERGY_THRESHOLD: f32 = 1e-5;
+
+/// Transcribes a window of audio, for [`WindowedEngine`]
+pub trait WindowTranscriber: Send {
+    fn load(&mut self, model: Option<&str>, language: Option<&str>) -> Result<LoadedModel>;
+
+    /// Segments with times counting from the start of `samples`
+    fn transcribe(&mut self, samples: &[f32]) -> Result<Vec<TranscriptSegment>>;
+}
+
+/// Engine that re-transcribes a growing window of audio, confirming all but
+/// the last `confirmation_threshold` segments and dropping confirmed audio
+/// from the window
+pub struct WindowedEngine<T> {
+    transcriber: T,
+    use_vad: bool,
+    confirmation_threshold: usize,
+    window: Vec<f32>,
+    /// Position in the session of the first sample in the window
+    window_start: u64,
+    /// Window length when it was last transcribed
+    transcribed_len: usize,
+    last_tentative: String,
+}
+
+impl<T: WindowTranscriber> WindowedEngine<T> {
+    pub fn new(transcriber: T) -> Self {
+        Self {
+            transcriber,
+            use_vad: true,
+            confirmation_threshold: 2,
+            window: Vec::new(),
+            window_start: 0,
+            transcribed_len: 0,
+            last_tentative: String::new(),
+        }
+    }
+
+    fn seconds(&self, window_seconds: f64) -> f64 {
+        self.window_start as f64 / SAMPLE_RATE as f64 + window_seconds
+    }
+
+    /// Whether the most recent audio is too quiet to be speech
+    fn is_silent(&self) -> bool {
+        let recent = &self.window[self.window.len().saturating_sub(VAD_SAMPLES)..];
+        if recent.is_empty() {
+            return true;
+        }
+        let energy = recent.iter().map(|s| s * s).sum::<f32>() / recent.len() as f32;
+        energy < VAD_ENERGY_THRESHOLD
+    }
+
+    /// Transcribe the window and confirm all but the last segments, or all
+    /// of them when `confirm_all`
+    fn transcribe_window(&mut self, confirm_all: bool) -> Result<Vec<EngineOutput>> {
+        let segments = self.transcriber.transcribe(&self.window)?;
+        self.transcribed_len = self.window.len();
+
+        let confirm_count = if confirm_all || self.window.len() >= MAX_WINDOW_SAMPLES {
+            segments.len()
+        } else {
+            segments.len().saturating_sub(self.confirmation_threshold)
+        };
+
+        let mut outputs = Vec::new();
+        for segment in &segments[..confirm_count] {
+            outputs.push(EngineOutput::Confirmed {
+                text: segment.text.trim().to_string(),
+                start_time: self.seconds(segment.start),
+                end_time: self.seconds(segment.end),
+            });
+        }
+
+        let tentative = &segments[confirm_count..];
+        let tentative_text = tentative
+            .iter()
+            .map(|segment| segment.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !tentative_text.is_empty() && tentative_text != self.last_tentative {
+            outputs.push(EngineOutput::Tentative {
+                text: tentative_text.clone(),
+                timestamp: self.seconds(tentative[0].start),
+            });
+        }
+        self.last_tentative = tentative_text;
+
+        // Confirmed audio is never transcribed again
+        let cut = if confirm_count == segments.len() {
+            self.window.len()
+        } else if confirm_count > 0 {
+            let end = segments[confirm_count - 1].end.max(0.0);
+            ((end * SAMPLE_RATE as f64) as usize).min(self.window.len())
+        } else {
+            0
+        };
+        self.window.drain(..cut);
+        self.window_start += cut as u64;
+        self.transcribed_len -= cut;
+
+        Ok(outputs)
+    }
+}
+
+impl<T: WindowTranscriber> StreamingEngine for WindowedEngine<T> {
+    fn load(&mut self, model: Option<&str>, language: Option<&str>) -> Result<LoadedModel> {
+        self.transcriber.load(model, language)
+    }
+
+    fn start(&mut self, use_vad: bool, confirmation_threshold: u32) {
+        self.use_vad = use_vad;
+        self.confirmation_threshold = confirmation_threshold as usize;
+        self.window.clear();
+        self.window_start = 0;
+        self.transcribed_len = 0;
+        self.last_tentative.clear();
+    }
+
+    fn feed(&mut self, samples: &[f32]) {
+        self.window.extend_from_slice(samples);
+    }
+
+    fn poll(&mut self) -> Result<Vec<EngineOutput>> {
+        if self.window.len() - self.transcribed_len < POLL_SAMPLES {
+            return Ok(Vec::new());
+        }
+
+        if self.use_vad && self.is_silent() {
+            self.transcribed_len = self.window.len();
+            // Silence with nothing pending is dropped rather than kept around
+            if self.last_tentative.is_empty() {
+                self.window_start += self.window.len() as u64;
+                self.window.clear();
+                self.transcribed_len = 0;
+            }
+            return Ok(Vec::new());
+        }
+
+        self.transcribe_window(false)
+    }
+
+    fn finish(&mut self) -> Result<Vec<EngineOutput>> {
+        if self.window.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.transcribe_window(true)
+    }
+}
+
+/// whisper.cpp on the CPU, with GGML models downloaded by the model manager
+#[cfg(not(target_os = "macos"))]
+pub struct WhisperCppTranscriber {
+    backend: super::whisper_cpp::WhisperCppBackend,
+    models_dir: Option<PathBuf>,
+    model_id: String,
+    model_path: Option<PathBuf>,
+    language: Option<String>,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl WhisperCppTranscriber {
+    pub fn new(models_dir: Option<PathBuf>) -> Self {
+        Self {
+            backend: super::whisper_cpp::WhisperCppBackend::new(),
+            models_dir,
+            model_id: String::new(),
+            model_path: None,
+            language: None,
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl WindowTranscriber for WhisperCppTranscriber {
+    fn load(&mut self, model: Option<&str>, language: Option<&str>) -> Result<LoadedModel> {
+        use super::transcription_backend::TranscriptionBackend;
+
+        let (model_id, path) = resolve_model(model, self.models_dir.as_deref())?;
+        self.backend.load_model(&model_id, Some(&path))?;
+        self.model_id = model_id;
+        self.model_path = Some(path.clone());
+        self.language = language.map(str::to_string);
+        Ok(LoadedModel {
+            name: self.model_id.clone(),
+            path: Some(path.to_string_lossy().into_owned()),
+        })
+    }
+
+    fn transcribe(&mut self, samples: &[f32]) -> Result<Vec<TranscriptSegment>> {
+        self.backend.transcribe_samples(
+            &self.model_id,
+            self.model_path.as_deref(),
+            samples,
+            self.language.as_deref(),
+            super::transcription_backend::ProgressReporter::noop(),
+            super::cancellation::CancellationToken::new(),
+        )
+    }
+}
+
+/// Resolve a model given as a file path, a model ID such as `whisper-base`,
+/// or a bare size such as `base`, to its ID and GGML file
+pub fn resolve_model(
+    model: Option<&str>,
+    models_dir: Option<&std::path::Path>,
+) -> Result<(String, PathBuf)> {
+    let model = model.unwrap_or("base");
+    let path = PathBuf::from(model);
+    if path.is_file() {
+        return Ok((model.to_string(), path));
+    }
+
+    let size = model
+        .trim_start_matches("openai_whisper-")
+        .trim_start_matches("whisper-");
+    let model_id = format!("whisper-{}", size);
+    let info = super::model_manager::get_whisper_models()
+        .into_iter()
+        .find(|m| m.id == model_id)
+        .ok_or_else(|| anyhow!("Unknown whisper model: {}", model))?;
+    let models_dir = models_dir.ok_or_else(|| {
+        anyhow!(
+            "No models directory; set {} or pass --models-dir",
+            MODELS_DIR_ENV
+        )
+    })?;
+    let path = models_dir.join(&info.filename);
+    if !path.exists() {
+        return Err(anyhow!(
+            "Model {} is not downloaded. Download it in Settings > Models.",
+            model_id
+        ));
+    }
+    Ok((model_id, path))
+}
+
+// MARK: - Protocol Loop
+
+/// Something read from stdin
+#[derive(Debug)]
+enum Input {
+    Command(WorkerCommand),
+    Frame {
+        session_id: String,
+        samples: Vec<f32>,
+    },
+    Invalid(String),
+}
+
+/// Read the next command line or binary audio frame. Returns `None` at end
+/// of input.
+fn read_input(reader: &mut impl BufRead) -> std::io::Result<Option<Input>> {
+    loop {
+        let first = match reader.fill_buf()?.first() {
+            Some(&byte) => byte,
+            None => return Ok(None),
+        };
+
+        if first == AUDIO_FRAME_MARKER {
+            return read_frame(reader).map(Some);
+        }
+
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        return Ok(Some(match serde_json::from_str(line) {
+            Ok(cmd) => Input::Command(cmd),
+            Err(e) => Input::Invalid(format!("Failed to parse command: {}", e)),
+        }));
+    }
+}
+
+/// Read a binary audio frame, laid out as by `encode_audio_frame`
+fn read_frame(reader: &mut impl BufRead) -> std::io::Result<Input> {
+    let mut header = [0u8; 4];
+    reader.read_exact(&mut header)?;
+    let encoding = [AudioEncoding::F32, AudioEncoding::I16]
+        .into_iter()
+        .find(|encoding| encoding.code() == header[1]);
+    let encoding = encoding.ok_or_else(|| {
+        // The payload size is unknown, so the rest of stdin can't be read
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Unknown audio frame encoding: {}", header[1]),
+        )
+    })?;
+
+    let mut session_id = vec![0u8; u16::from_le_bytes([header[2], header[3]]) as usize];
+    reader.read_exact(&mut session_id)?;
+    let mut count = [0u8; 4];
+    reader.read_exact(&mut count)?;
+    let mut payload = vec![0u8; u32::from_le_bytes(count) as usize * encoding.sample_size()];
+    reader.read_exact(&mut payload)?;
+
+    let samples = match encoding {
+        AudioEncoding::F32 => payload
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        AudioEncoding::I16 => payload
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+            .collect(),
+    };
+
+    Ok(Input::Frame {
+        session_id: String::from_utf8_lossy(&session_id).into_owned(),
+        samples,
+    })
+}
+
+/// Decode base64 little-endian f32 samples from an `audio` command
+fn decode_samples(encoded: &str, expected_count: usize) -> Option<Vec<f32>> {
+    let bytes = BASE64.decode(encoded).ok()?;
+    if bytes.len() != expected_count * 4 {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    )
+}
+
+/// The session being transcribed
+struct WorkerSession {
+    id: String,
+    confirmed: Vec<String>,
+}
+
+/// Protocol state of a worker process
+struct Worker<W: Write> {
+    engine: Box<dyn StreamingEngine>,
+    out: W,
+    initialized: bool,
+    session: Option<WorkerSession>,
+}
+
+impl<W: Write> Worker<W> {
+    fn new(engine: Box<dyn StreamingEngine>, out: W) -> Self {
+        Self {
+            engine,
+            out,
+            initialized: false,
+            session: None,
+        }
+    }
+
+    fn emit(&mut self, event: WorkerEvent) -> Result<()> {
+        writeln!(self.out, "{}", serde_json::to_string(&event)?)?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn emit_error(
+        &mut self,
+        session_id: Option<String>,
+        message: String,
+        code: &str,
+    ) -> Result<()> {
+        eprintln!("[StreamingWorker] Error: {} ({})", message, code);
+        self.emit(WorkerEvent::Error {
+            session_id,
+            message,
+            code: code.to_string(),
+        })
+    }
+
+    /// Emit engine output for the current session. Returns an exit code if
+    /// the engine asked to exit.
+    fn emit_outputs(&mut self, outputs: Vec<EngineOutput>) -> Result<Option<i32>> {
+        let Some(session_id) = self.session.as_ref().map(|s| s.id.clone()) else {
+            return Ok(None);
+        };
+
+        for output in outputs {
+            match output {
+                EngineOutput::Tentative { text, timestamp } => {
+                    self.emit(WorkerEvent::Tentative {
+                        session_id: session_id.clone(),
+                        text,
+                        timestamp,
+                    })?;
+                }
+                EngineOutput::Confirmed {
+                    text,
+                    start_time,
+                    end_time,
+                } => {
+                    if text.trim().is_empty() {
+                        continue;
+                    }
+                    if let Some(session) = self.session.as_mut() {
+                        session.confirmed.push(text.clone());
+                    }
+                    self.emit(WorkerEvent::Confirmed {
+                        session_id: session_id.clone(),
+                        text,
+                        start_time,
+                        end_time,
+                    })?;
+                }
+                EngineOutput::Error { message, code } => {
+                    self.emit_error(Some(session_id.clone()), message, &code)?;
+                }
+                EngineOutput::Exit { code } => {
+                    eprintln!("[StreamingWorker] Exiting with code {} as scripted", code);
+                    return Ok(Some(code));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finish the current session and report its transcript
+    fn stop_session(&mut self) -> Result<Option<i32>> {
+        let outputs = match self.engine.finish() {
+            Ok(outputs) => outputs,
+            Err(e) => vec![EngineOutput::Error {
+                message: format!("Transcription failed: {}", e),
+                code: "TRANSCRIPTION_FAILED".to_string(),
+            }],
+        };
+        if let Some(code) = self.emit_outputs(outputs)? {
+            return Ok(Some(code));
+        }
+
+        let Some(session) = self.session.take() else {
+            return Ok(None);
+        };
+        self.emit(WorkerEvent::Complete {
+            session_id: session.id,
+            full_text: session.confirmed.join(" "),
+        })?;
+        self.emit(WorkerEvent::Status {
+            state: "idle".to_string(),
+            session_id: None,
+        })?;
+        Ok(None)
+    }
+
+    fn feed(&mut self, session_id: &str, samples: &[f32]) {
+        if self.session.as_ref().is_some_and(|s| s.id == session_id) {
+            self.engine.feed(samples);
+        }
+    }
+
+    /// Handle one input. Returns an exit code once the worker should exit.
+    fn handle(&mut self, input: Input) -> Result<Option<i32>> {
+        let cmd = match input {
+            Input::Command(cmd) => cmd,
+            Input::Frame {
+                session_id,
+                samples,
+            } => {
+                self.feed(&session_id, &samples);
+                return Ok(None);
+            }
+            Input::Invalid(message) => {
+                self.emit_error(None, message, "INVALID_COMMAND")?;
+                return Ok(None);
+            }
+        };
+
+        match cmd {
+            WorkerCommand::Init { model, language } => {
+                eprintln!("[StreamingWorker] Loading model {:?}", model);
+                match self.engine.load(model.as_deref(), language.as_deref()) {
+                    Ok(loaded) => {
+                        self.initialized = true;
+                        self.emit(WorkerEvent::Ready {
+                            model: loaded.name,
+                            model_path: loaded.path,
+                            protocol_version: PROTOCOL_VERSION,
+                        })?;
+                    }
+                    Err(e) => self.emit_error(None, e.to_string(), "MODEL_LOAD_FAILED")?,
+                }
+            }
+
+            WorkerCommand::Start {
+                session_id,
+                use_vad,
+                confirmation_threshold,
+            } => {
+                if !self.initialized {
+                    self.emit_error(
+                        Some(session_id),
+                        "Worker is not initialized".to_string(),
+                        "NOT_INITIALIZED",
+                    )?;
+                } else if self.session.is_some() {
+                    self.emit_error(
+                        Some(session_id),
+                        "A session is already active".to_string(),
+                        "INTERNAL_ERROR",
+                    )?;
+                } else {
+                    eprintln!("[StreamingWorker] Starting session {}", session_id);
+                    self.engine.start(use_vad, confirmation_threshold);
+                    self.session = Some(WorkerSession {
+                        id: session_id.clone(),
+                        confirmed: Vec::new(),
+                    });
+                    self.emit(WorkerEvent::Status {
+                        state: "transcribing".to_string(),
+                        session_id: Some(session_id),
+                    })?;
+                }
+            }
+
+            WorkerCommand::Audio {
+                session_id,
+                samples,
+                sample_count,
+            } => match decode_samples(&samples, sample_count) {
+                Some(samples) => self.feed(&session_id, &samples),
+                None => self.emit_error(
+                    Some(session_id),
+                    "Failed to decode audio samples".to_string(),
+                    "AUDIO_DECODE_FAILED",
+                )?,
+            },
+
+            WorkerCommand::Stop { session_id } => {
+                if self.session.as_ref().is_some_and(|s| s.id == session_id) {
+                    eprintln!("[StreamingWorker] Stopping session {}", session_id);
+                    return self.stop_session();
+                }
+            }
+
+            WorkerCommand::Status => {
+                let (state, session_id) = match &self.session {
+                    Some(session) => ("transcribing", Some(session.id.clone())),
+                    None if self.initialized => ("idle", None),
+                    None => ("uninitialized", None),
+                };
+                self.emit(WorkerEvent::Status {
+                    state: state.to_string(),
+                    session_id,
+                })?;
+            }
+
+            WorkerCommand::Shutdown => {
+                eprintln!("[StreamingWorker] Shutting down");
+                if self.session.is_some() {
+                    if let Some(code) = self.stop_session()? {
+                        return Ok(Some(code));
+                    }
+                }
+                return Ok(Some(0));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Transcribe what the session has been fed so far
+    fn poll(&mut self) -> Result<Option<i32>> {
+        if self.session.is_none() {
+            return Ok(None);
+        }
+        let outputs = match self.engine.poll() {
+            Ok(outputs) => outputs,
+            Err(e) => vec![EngineOutput::Error {
+                message: format!("Transcription failed: {}", e),
+                code: "TRANSCRIPTION_FAILED".to_string(),
+            }],
+        };
+        self.emit_outputs(outputs)
+    }
+}
+
+/// Run the protocol until `shutdown`, a scripted exit or the end of input.
+/// Input is read on its own thread so audio keeps flowing while the engine
+/// transcribes. Returns the process exit code.
+pub fn run<R, W>(input: R, output: W, engine: Box<dyn StreamingEngine>) -> Result<i32>
+where
+    R: Read + Send + 'static,
+    W: Write,
+{
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut reader = BufReader::new(input);
+        loop {
+            match read_input(&mut reader) {
+                Ok(Some(input)) => {
+                    if tx.send(input).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = tx.send(Input::Invalid(format!("Failed to read input: {}", e)));
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut worker = Worker::new(engine, output);
+    while let Ok(input) = rx.recv() {
+        if let Some(code) = worker.handle(input)? {
+            return Ok(code);
+        }
+        // Catch up on everything already sent before transcribing again
+        while let Ok(input) = rx.try_recv() {
+            if let Some(code) = worker.handle(input)? {
+                return Ok(code);
+            }
+        }
+        if let Some(code) = worker.poll()? {
+            return Ok(code);
+        }
+    }
+
+    eprintln!("[StreamingWorker] Input closed");
+    Ok(0)
+}
+
+/// Command-line options of the `streaming-worker` binary
+#[derive(Debug, Default, PartialEq)]
+pub struct WorkerOptions {
+    /// Replay this script instead of transcribing
+    pub replay_script: Option<PathBuf>,
+    /// Where downloaded GGML models live
+    pub models_dir: Option<PathBuf>,
+}
+
+impl WorkerOptions {
+    /// Parse `stream [--replay <script>] [--models-dir <dir>]`, falling back
+    /// to the environment for options not given
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut options = WorkerOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                // Accepted for compatibility with how the WhisperKit worker is run
+                "stream" => {}
+                "--replay" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--replay needs a path"))?;
+                    options.replay_script = Some(PathBuf::from(path));
+                }
+                "--models-dir" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--models-dir needs a path"))?;
+                    options.models_dir = Some(PathBuf::from(path));
+                }
+                other => return Err(anyhow!("Unknown argument: {}", other)),
+            }
+        }
+
+        if options.replay_script.is_none() {
+            options.replay_script = std::env::var_os(REPLAY_SCRIPT_ENV).map(PathBuf::from);
+        }
+        if options.models_dir.is_none() {
+            options.models_dir = std::env::var_os(MODELS_DIR_ENV).map(PathBuf::from);
+        }
+        Ok(options)
+    }
+
+    /// The engine these options select
+    pub fn engine(&self) -> Result<Box<dyn StreamingEngine>> {
+        if let Some(script) = &self.replay_script {
+            return Ok(Box::new(ReplayEngine::from_file(script)?));
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            Ok(Box::new(WindowedEngine::new(WhisperCppTranscriber::new(
+                self.models_dir.clone(),
+            ))))
+        }
+        #[cfg(target_os = "macos")]
+        {
+            Err(anyhow!(
+                "The CPU engine is not available on macOS; use whisperkit-worker or --replay"
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::streaming_transcription::encode_audio_frame;
+    use std::io::Cursor;
+
+    fn command_line(cmd: &WorkerCommand) -> Vec<u8> {
+        let mut line = serde_json::to_vec(cmd).unwrap();
+        line.push(b'\n');
+        line
+    }
+
+    fn run_script(script: Vec<ScriptStep>, input: Vec<u8>) -> (i32, Vec<serde_json::Value>) {
+        let mut output = Vec::new();
+        let code = run(
+            Cursor::new(input),
+            &mut output,
+            Box::new(ReplayEngine::new(script)),
+        )
+        .unwrap();
+        let events = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        (code, events)
+    }
+
+    fn event_types(events: &[serde_json::Value]) -> Vec<&str> {
+        events.iter().map(|e| e["type"].as_str().unwrap()).collect()
+    }
+
+    fn start(session_id: &str) -> WorkerCommand {
+        WorkerCommand::Start {
+            session_id: session_id.to_string(),
+            use_vad: true,
+            confirmation_threshold: 2,
+        }
+    }
+
+    fn init() -> WorkerCommand {
+        WorkerCommand::Init {
+            model: None,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_read_input_mixes_lines_and_frames() {
+        let mut input = command_line(&WorkerCommand::Status);
+        input.extend(encode_audio_frame("s1", &[0.5, -0.25], AudioEncoding::F32).unwrap());
+        input.extend(b"\n");
+        input.extend(encode_audio_frame("s1", &[1.0], AudioEncoding::I16).unwrap());
+        input.extend(command_line(&WorkerCommand::Shutdown));
+        let mut reader = Cursor::new(input);
+
+        assert!(matches!(
+            read_input(&mut reader).unwrap(),
+            Some(Input::Command(WorkerCommand::Status))
+        ));
+        match read_input(&mut reader).unwrap() {
+            Some(Input::Frame {
+                session_id,
+                samples,
+            }) => {
+                assert_eq!(session_id, "s1");
+                assert_eq!(samples, vec![0.5, -0.25]);
+            }
+            other => panic!("Expected frame, got {:?}", other),
+        }
+        // The blank line between them is skipped
+        match read_input(&mut reader).unwrap() {
+            Some(Input::Frame { samples, .. }) => assert_eq!(samples, vec![1.0]),
+            other => panic!("Expected frame, got {:?}", other),
+        }
+        assert!(matches!(
+            read_input(&mut reader).unwrap(),
+            Some(Input::Command(WorkerCommand::Shutdown))
+        ));
+        assert!(read_input(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_samples_checks_count() {
+        let encoded = BASE64.encode(0.5f32.to_le_bytes());
+        assert_eq!(decode_samples(&encoded, 1), Some(vec![0.5]));
+        assert_eq!(decode_samples(&encoded, 2), None);
+    }
+
+    #[test]
+    fn test_replay_session_protocol() {
+        let script = vec![
+            ScriptStep::Tentative {
+                at: 0.5,
+                text: "Hello wor".to_string(),
+            },
+            ScriptStep::Confirmed {
+                at: 1.0,
+                text: "Hello world.".to_string(),
+                start_time: 0.0,
+                end_time: 1.0,
+            },
+            ScriptStep::Confirmed {
+                at: 5.0,
+                text: "Goodbye.".to_string(),
+                start_time: 1.0,
+                end_time: 2.0,
+            },
+        ];
+        let mut input = command_line(&init());
+        input.extend(command_line(&start("s1")));
+        input.extend(encode_audio_frame("s1", &vec![0.0; 16_000], AudioEncoding::I16).unwrap());
+        input.extend(command_line(&WorkerCommand::Stop {
+            session_id: "s1".to_string(),
+        }));
+        input.extend(command_line(&WorkerCommand::Shutdown));
+
+        let (code, events) = run_script(script, input);
+
+        assert_eq!(code, 0);
+        assert_eq!(
+            event_types(&events),
+            vec![
+                "ready",
+                "status",
+                "tentative",
+                "confirmed",
+                "confirmed",
+                "complete",
+                "status"
+            ]
+        );
+        assert_eq!(events[0]["protocol_version"], PROTOCOL_VERSION);
+        assert_eq!(events[3]["text"], "Hello world.");
+        assert_eq!(events[5]["full_text"], "Hello world. Goodbye.");
+        assert_eq!(events[6]["state"], "idle");
+    }
+
+    #[test]
+    fn test_replay_ignores_audio_for_other_sessions() {
+        let script = vec![ScriptStep::Tentative {
+            at: 1.0,
+            text: "Heard".to_string(),
+        }];
+        let mut input = command_line(&init());
+        input.extend(command_line(&start("s1")));
+        input.extend(encode_audio_frame("s2", &vec![0.0; 16_000], AudioEncoding::F32).unwrap());
+        input.extend(command_line(&WorkerCommand::Status));
+
+        let (code, events) = run_script(script, input);
+
+        assert_eq!(code, 0);
+        assert_eq!(event_types(&events), vec!["ready", "status", "status"]);
+        assert_eq!(events[2]["session_id"], "s1");
+    }
+
+    #[test]
+    fn test_replay_exit_step_ends_worker() {
+        let script = vec![ScriptStep::Exit { at: 0.0, code: 3 }];
+        let mut input = command_line(&init());
+        input.extend(command_line(&start("s1")));
+        input.extend(command_line(&WorkerCommand::Status));
+
+        let (code, _) = run_script(script, input);
+
+        assert_eq!(code, 3);
+    }
+
+    #[test]
+    fn test_start_before_init_is_an_error() {
+        let (_, events) = run_script(Vec::new(), command_line(&start("s1")));
+        assert_eq!(event_types(&events), vec!["error"]);
+        assert_eq!(events[0]["code"], "NOT_INITIALIZED");
+    }
+
+    #[test]
+    fn test_invalid_command_is_reported() {
+        let (_, events) = run_script(Vec::new(), b"{\"cmd\":\"dance\"}\n".to_vec());
+        assert_eq!(events[0]["code"], "INVALID_COMMAND");
+    }
+
+    #[test]
+    fn test_script_parses_from_json() {
+        let json = r#"[
+            {"type":"tentative","at":0.5,"text":"Hi"},
+            {"type":"confirmed","at":1,"text":"Hi.","start_time":0,"end_time":1},
+            {"type":"exit","at":2}
+        ]"#;
+        let script: Vec<ScriptStep> = serde_json::from_str(json).unwrap();
+        assert_eq!(script.len(), 3);
+        assert!(matches!(script[2], ScriptStep::Exit { code: 1, .. }));
+    }
+
+    /// Returns the next scripted transcription of whatever window it gets
+    struct FakeTranscriber {
+        results: Vec<Vec<(f64, f64, &'static str)>>,
+        windows: Vec<usize>,
+    }
+
+    impl WindowTranscriber for FakeTranscriber {
+        fn load(&mut self, _model: Option<&str>, _language: Option<&str>) -> Result<LoadedModel> {
+            Ok(LoadedModel {
+                name: "fake".to_string(),
+                path: None,
+            })
+        }
+
+        fn transcribe(&mut self, samples: &[f32]) -> Result<Vec<TranscriptSegment>> {
+            self.windows.push(samples.len());
+            let result = self.results.remove(0);
+            Ok(result
+                .into_iter()
+                .map(|(start, end, text)| TranscriptSegment {
+                    start,
+                    end,
+                    text: text.to_string(),
+                    speaker: None,
+                    avg_logprob: None,
+                    words: None,
+                })
+                .collect())
+        }
+    }
+
+    fn speech(seconds: f64) -> Vec<f32> {
+        vec![0.1; (seconds * SAMPLE_RATE as f64) as usize]
+    }
+
+    #[test]
+    fn test_windowed_engine_confirms_and_drops_older_segments() {
+        let mut engine = WindowedEngine::new(FakeTranscriber {
+            results: vec![
+                vec![(0.0, 1.0, " One.")],
+                vec![(0.0, 1.0, " One."), (1.0, 1.5, " Two."), (1.5, 2.0, " Thr")],
+                vec![(0.0, 0.5, " Three.")],
+            ],
+            windows: Vec::new(),
+        });
+        engine.start(true, 2);
+
+        // Not enough audio yet
+        engine.feed(&speech(0.5));
+        assert!(engine.poll().unwrap().is_empty());
+
+        engine.feed(&speech(0.5));
+        assert_eq!(
+            engine.poll().unwrap(),
+            vec![EngineOutput::Tentative {
+                text: "One.".to_string(),
+                timestamp: 0.0
+            }]
+        );
+
+        engine.feed(&speech(1.0));
+        assert_eq!(
+            engine.poll().unwrap(),
+            vec![
+                EngineOutput::Confirmed {
+                    text: "One.".to_string(),
+                    start_time: 0.0,
+                    end_time: 1.0
+                },
+                EngineOutput::Tentative {
+                    text: "Two. Thr".to_string(),
+                    timestamp: 1.0
+                },
+            ]
+        );
+
+        // The confirmed second was dropped; the rest is confirmed at the end
+        // with times counting from the start of the session
+        assert_eq!(
+            engine.finish().unwrap(),
+            vec![EngineOutput::Confirmed {
+                text: "Three.".to_string(),
+                start_time: 1.0,
+                end_time: 1.5
+            }]
+        );
+        assert_eq!(engine.transcriber.windows, vec![16_000, 32_000, 16_000]);
+    }
+
+    #[test]
+    fn test_windowed_engine_skips_silence() {
+        let mut engine = WindowedEngine::new(FakeTranscriber {
+            results: vec![vec![(0.0, 1.0, "Hi.")]],
+            windows: Vec::new(),
+        });
+        engine.start(true, 0);
+
+        engine.feed(&vec![0.0; 2 * SAMPLE_RATE as usize]);
+        assert!(engine.poll().unwrap().is_empty());
+
+        // The silence was dropped, so speech times follow it
+        engine.feed(&speech(1.0));
+        assert_eq!(
+            engine.poll().unwrap(),
+            vec![EngineOutput::Confirmed {
+                text: "Hi.".to_string(),
+                start_time: 2.0,
+                end_time: 3.0
+            }]
+        );
+        assert_eq!(engine.transcriber.windows, vec![16_000]);
+    }
+
+    #[test]
+    fn test_worker_options_parse() {
+        let options = WorkerOptions::parse(
+            [
+                "stream",
+                "--replay",
+                "script.json",
+                "--models-dir",
+                "/models",
+            ]
+            .map(String::from),
+        )
+        .unwrap();
+        assert_eq!(options.replay_script, Some(PathBuf::from("script.json")));
+        assert_eq!(options.models_dir, Some(PathBuf::from("/models")));
+
+        assert!(WorkerOptions::parse(["--bogus".to_string()]).is_err());
+        assert!(WorkerOptions::parse(["--replay".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_model_by_name() {
+        let dir = std::env::temp_dir().join(format!("streaming-worker-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ggml-base.bin"), b"model").unwrap();
+
+        let (id, path) = resolve_model(None, Some(&dir)).unwrap();
+        assert_eq!(id, "whisper-base");
+        assert_eq!(path, dir.join("ggml-base.bin"));
+        assert_eq!(
+            resolve_model(Some("whisper-base"), Some(&dir)).unwrap().0,
+            "whisper-base"
+        );
+        // Known but not downloaded, and unknown
+        assert!(resolve_model(Some("tiny"), Some(&dir)).is_err());
+        assert!(resolve_model(Some("enormous"), Some(&dir)).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
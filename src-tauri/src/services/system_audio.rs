@@ -10,6 +10,8 @@ use std::thread;
 use tauri::AppHandle;
 use tauri::{Emitter, Manager};
 
+use super::encryption;
+
 /// Audio device information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -223,6 +225,11 @@ pub fn start_recording(
     if state.is_some() {
         return Err(anyhow!("Recording already in progress"));
     }
+    // Re-encryption refuses to start while recording, so only keep a
+    // recording from starting once it has
+    let _lock = encryption::REKEY_LOCK.try_read().map_err(|_| {
+        anyhow!("The library is being re-encrypted or backed up; try again when it finishes")
+    })?;
 
     let worker_path = get_worker_path(app)?;
 
@@ -372,10 +379,10 @@ pub fn start_recording(
 }
 
 /// Stop recording and return the audio file path
-pub fn stop_recording() -> Result<String> {
-    let mut state = get_recorder_state().lock();
+pub async fn stop_recording() -> Result<String> {
+    let recording = get_recorder_state().lock().take();
 
-    match state.take() {
+    match recording {
         Some(mut recording) => {
             println!("[SystemAudio] Stopping recording...");
 
@@ -398,6 +405,12 @@ pub fn stop_recording() -> Result<String> {
             // Wait for the process to exit
             let _ = recording.child.wait();
 
+            // The worker writes plain WAV; encrypt it now if a passphrase is set
+            {
+                let _lock = encryption::REKEY_LOCK.read().await;
+                encryption::protect_audio_file(&recording.output_path)?;
+            }
+
             let path = recording.output_path.to_string_lossy().to_string();
             println!("[SystemAudio] Recording stopped, file: {}", path);
 
@@ -407,6 +420,11 @@ pub fn stop_recording() -> Result<String> {
     }
 }
 
+/// Whether a recording is in progress
+pub fn is_recording() -> bool {
+    get_recorder_state().lock().is_some()
+}
+
 /// Get current recording status
 pub fn get_status() -> RecordingStatus {
     let state = get_recorder_state().lock();
@@ -541,10 +559,10 @@ mod tests {
         assert_eq!(status.state, RecordingState::Idle);
     }
 
-    #[test]
-    fn test_stop_recording_when_not_recording() {
+    #[tokio::test]
+    async fn test_stop_recording_when_not_recording() {
         // Stopping when not recording should return an error
-        let result = stop_recording();
+        let result = stop_recording().await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No recording in progress"));
     }
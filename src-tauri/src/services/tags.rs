@@ -0,0 +1,341 @@
+//! Session tags
+//!
+//! Tags such as "intake" or "needs review" are shared by every workspace, so
+//! filtering by one finds sessions in any folder. Sessions come back with
+//! their tag names (see `database::SESSION_COLUMNS`), and `filter_sql` narrows
+//! a session query to those carrying every given tag.
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::{SqliteConnection, SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::database::fetch_session;
+use crate::models::{Session, Tag};
+
+const TAG_QUERY: &str = r#"
+    SELECT t.id, t.name, t.created_at, t.updated_at,
+        (SELECT COUNT(*)
+         FROM session_tags st
+         JOIN sessions s ON s.id = st.session_id
+         WHERE st.tag_id = t.id AND s.deleted_at IS NULL) AS session_count
+    FROM tags t
+"#;
+
+fn tag_from_row(row: SqliteRow) -> Tag {
+    Tag {
+        id: row.get("id"),
+        name: row.get("name"),
+        session_count: row.get("session_count"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn tag_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Tag name can't be empty"));
+    }
+    Ok(name)
+}
+
+/// Trimmed tag names without blanks or duplicates, ignoring case
+pub fn normalize(names: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for name in names.iter().map(|name| name.trim()) {
+        if !name.is_empty() && !normalized.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            normalized.push(name.to_string());
+        }
+    }
+    normalized
+}
+
+/// Condition on `s.id` matching sessions that carry every one of `names`,
+/// which must be normalized and not empty. Bind each name in order.
+pub fn filter_sql(names: &[String]) -> String {
+    format!(
+        "s.id IN (SELECT st.session_id FROM session_tags st JOIN tags t ON t.id = st.tag_id WHERE t.name IN ({}) GROUP BY st.session_id HAVING COUNT(*) = {})",
+        vec!["?"; names.len()].join(", "),
+        names.len()
+    )
+}
+
+/// All tags by name, with how many live sessions carry each
+pub async fn list(pool: &SqlitePool) -> Result<Vec<Tag>> {
+    let rows = sqlx::query(&format!("{} ORDER BY t.name", TAG_QUERY))
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(tag_from_row).collect())
+}
+
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<Tag> {
+    let row = sqlx::query(&format!("{} WHERE t.id = ?", TAG_QUERY))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| anyhow!("Tag not found: {}", id))?;
+    Ok(tag_from_row(row))
+}
+
+async fn find_by_name(pool: &SqlitePool, name: &str) -> Result<Option<String>> {
+    Ok(sqlx::query_scalar("SELECT id FROM tags WHERE name = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?)
+}
+
+/// Add a tag, or return the existing one with the same name
+pub async fn create(pool: &SqlitePool, name: &str, now: i64) -> Result<Tag> {
+    let name = tag_name(name)?;
+    if let Some(id) = find_by_name(pool, name).await? {
+        return get(pool, &id).await;
+    }
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO tags (id, name, created_at, updated_at) VALUES (?, ?, ?, ?)")
+        .bind(&id)
+        .bind(name)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+    println!("[Tags] Created tag {}", name);
+    get(pool, &id).await
+}
+
+/// Rename a tag everywhere it is used
+pub async fn rename(pool: &SqlitePool, id: &str, name: &str, now: i64) -> Result<Tag> {
+    let name = tag_name(name)?;
+    get(pool, id).await?;
+    // A change of case alone is a rename, not a clash
+    if find_by_name(pool, name)
+        .await?
+        .is_some_and(|other| other != id)
+    {
+        return Err(anyhow!("A tag named {} already exists", name));
+    }
+
+    sqlx::query("UPDATE tags SET name = ?, updated_at = ? WHERE id = ?")
+        .bind(name)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    get(pool, id).await
+}
+
+/// Remove a tag from every session and delete it
+pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    let tag = get(pool, id).await?;
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    let session_ids: Vec<String> =
+        sqlx::query_scalar("SELECT session_id FROM session_tags WHERE tag_id = ?")
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await?;
+    sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    for session_id in &session_ids {
+        record_change(&mut tx, &guard, session_id, "removed", &tag.name).await?;
+    }
+    tx.commit().await?;
+    drop(guard);
+    println!(
+        "[Tags] Deleted tag {} from {} session(s)",
+        tag.name, tag.session_count
+    );
+    Ok(())
+}
+
+async fn record_change(
+    conn: &mut SqliteConnection,
+    guard: &audit::AppendGuard,
+    session_id: &str,
+    change: &str,
+    tag: &str,
+) -> Result<()> {
+    let event = AuditEvent::session(AuditAction::Update, session_id)
+        .with_details(serde_json::json!({ "fields": ["tags"], "change": change, "tag": tag }));
+    audit::record_in(conn, guard, event).await?;
+    Ok(())
+}
+
+/// Tag a session, creating the tag if no tag has that name yet
+pub async fn add_to_session(
+    pool: &SqlitePool,
+    session_id: &str,
+    name: &str,
+    now: i64,
+) -> Result<Session> {
+    fetch_session(pool, session_id).await?;
+    let tag = create(pool, name, now).await?;
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    let added = sqlx::query(
+        "INSERT OR IGNORE INTO session_tags (session_id, tag_id, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(session_id)
+    .bind(&tag.id)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+    if added.rows_affected() > 0 {
+        record_change(&mut tx, &guard, session_id, "added", &tag.name).await?;
+    }
+    tx.commit().await?;
+    drop(guard);
+
+    fetch_session(pool, session_id).await
+}
+
+/// Take a tag off a session by name. The tag itself is kept.
+pub async fn remove_from_session(
+    pool: &SqlitePool,
+    session_id: &str,
+    name: &str,
+) -> Result<Session> {
+    let tag_id = find_by_name(pool, name.trim())
+        .await?
+        .ok_or_else(|| anyhow!("Tag not found: {}", name))?;
+    let tag = get(pool, &tag_id).await?;
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    let removed = sqlx::query("DELETE FROM session_tags WHERE session_id = ? AND tag_id = ?")
+        .bind(session_id)
+        .bind(&tag_id)
+        .execute(&mut *tx)
+        .await?;
+    if removed.rows_affected() > 0 {
+        record_change(&mut tx, &guard, session_id, "removed", &tag.name).await?;
+    }
+    tx.commit().await?;
+    drop(guard);
+
+    fetch_session(pool, session_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    async fn setup() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Practice', 'therapy', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('a', 'ws', 'A', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('b', 'ws', 'B', 0, 0)",
+                "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES ('s1', 'a', 'a.wav', 0, 0)",
+                "INSERT INTO sessions (id, folder_id, audio_path, created_at, updated_at) VALUES ('s2', 'b', 'b.wav', 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    async fn tagged(pool: &SqlitePool, names: &[&str]) -> Vec<String> {
+        let names = normalize(&names.iter().map(|n| n.to_string()).collect::<Vec<_>>());
+        let sql = format!(
+            "SELECT s.id FROM sessions s WHERE {} ORDER BY s.id",
+            filter_sql(&names)
+        );
+        let mut query = sqlx::query_scalar(&sql);
+        for name in &names {
+            query = query.bind(name);
+        }
+        query.fetch_all(pool).await.unwrap()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let names = ["Intake", " intake ", "", "Needs review"].map(String::from);
+        assert_eq!(normalize(&names), ["Intake", "Needs review"]);
+    }
+
+    #[tokio::test]
+    async fn test_tag_sessions_and_filter() {
+        let pool = setup().await;
+
+        let session = add_to_session(&pool, "s1", "needs review", 1)
+            .await
+            .unwrap();
+        assert_eq!(session.tags, ["needs review"]);
+        let session = add_to_session(&pool, "s1", "Intake", 2).await.unwrap();
+        assert_eq!(session.tags, ["Intake", "needs review"]);
+        // Names match ignoring case, so this reuses the tag
+        add_to_session(&pool, "s2", "INTAKE", 3).await.unwrap();
+        add_to_session(&pool, "s2", "intake", 3).await.unwrap();
+
+        let tags = list(&pool).await.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(
+            (tags[0].name.as_str(), tags[0].session_count),
+            ("Intake", 2)
+        );
+
+        assert_eq!(tagged(&pool, &["intake"]).await, ["s1", "s2"]);
+        assert_eq!(tagged(&pool, &["Intake", "needs review"]).await, ["s1"]);
+        assert_eq!(tagged(&pool, &["Intake", "intake"]).await, ["s1", "s2"]);
+
+        let session = remove_from_session(&pool, "s1", "intake").await.unwrap();
+        assert_eq!(session.tags, ["needs review"]);
+
+        // Adding once and removing once are audited; the repeat is not
+        let changes: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_log WHERE action = 'update' AND details LIKE '%tags%'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(changes, 4);
+    }
+
+    #[tokio::test]
+    async fn test_rename_and_delete() {
+        let pool = setup().await;
+        add_to_session(&pool, "s1", "followup", 1).await.unwrap();
+        let other = create(&pool, "deposition", 1).await.unwrap();
+        let tag = list(&pool).await.unwrap().pop().unwrap();
+        assert_eq!(tag.name, "followup");
+
+        assert!(rename(&pool, &tag.id, "Deposition", 2).await.is_err());
+        assert_eq!(
+            rename(&pool, &tag.id, "Follow-up", 2).await.unwrap().name,
+            "Follow-up"
+        );
+        assert_eq!(
+            fetch_session(&pool, "s1").await.unwrap().tags,
+            ["Follow-up"]
+        );
+
+        delete(&pool, &tag.id).await.unwrap();
+        assert!(fetch_session(&pool, "s1").await.unwrap().tags.is_empty());
+        // Each session losing the tag is audited
+        let removed: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_log WHERE entity_id = 's1' AND details LIKE '%removed%'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(list(&pool).await.unwrap()[0].id, other.id);
+
+        // Purging a session drops its tags with it
+        add_to_session(&pool, "s2", "deposition", 3).await.unwrap();
+        sqlx::query("DELETE FROM sessions WHERE id = 's2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(get(&pool, &other.id).await.unwrap().session_count, 0);
+    }
+}
@@ -0,0 +1,193 @@
+//! Pluggable batch transcription backends
+//!
+//! `whisper::transcribe` dispatches through the [`TranscriptionBackend`] trait so
+//! the same pipeline can run on WhisperKit (macOS, CoreML/Metal) or on
+//! whisper.cpp (pure CPU, any platform). The active backend is chosen by
+//! `AppSettings::transcription_backend`.
+
+use super::cancellation::CancellationToken;
+use crate::models::TranscriptSegment;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Available transcription backends
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionBackendKind {
+    /// WhisperKit Swift worker subprocess (macOS only)
+    WhisperKit,
+    /// whisper.cpp running in-process on the CPU with GGML models
+    WhisperCpp,
+}
+
+impl TranscriptionBackendKind {
+    /// Settings value for this backend
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptionBackendKind::WhisperKit => "whisperkit",
+            TranscriptionBackendKind::WhisperCpp => "whispercpp",
+        }
+    }
+
+    /// Parse a settings value into a backend kind
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "whisperkit" => Ok(TranscriptionBackendKind::WhisperKit),
+            "whispercpp" => Ok(TranscriptionBackendKind::WhisperCpp),
+            other => Err(anyhow!("Unknown transcription backend: {}", other)),
+        }
+    }
+
+    /// Backend used when the user hasn't picked one
+    pub fn platform_default() -> Self {
+        if cfg!(target_os = "macos") {
+            TranscriptionBackendKind::WhisperKit
+        } else {
+            TranscriptionBackendKind::WhisperCpp
+        }
+    }
+
+    /// Model ID used when transcription starts before any model was loaded
+    pub fn default_model_id(&self) -> &'static str {
+        match self {
+            TranscriptionBackendKind::WhisperKit => "whisperkit-base",
+            TranscriptionBackendKind::WhisperCpp => "whisper-base",
+        }
+    }
+}
+
+/// A single batch transcription job handed to a backend
+#[derive(Debug, Clone)]
+pub struct TranscriptionRequest {
+    pub audio_path: PathBuf,
+    pub model_id: String,
+    /// Resolved model file, for backends that need a downloaded model
+    pub model_path: Option<PathBuf>,
+    pub language: Option<String>,
+}
+
+/// Receives the fraction (0.0-1.0) of the audio a backend has processed so far,
+/// and the audio's duration once a backend that decodes it knows
+#[derive(Clone)]
+pub struct ProgressReporter {
+    on_progress: Arc<dyn Fn(f32) + Send + Sync>,
+    on_duration: Arc<dyn Fn(f64) + Send + Sync>,
+}
+
+impl ProgressReporter {
+    pub fn new(callback: impl Fn(f32) + Send + Sync + 'static) -> Self {
+        Self {
+            on_progress: Arc::new(callback),
+            on_duration: Arc::new(|_| {}),
+        }
+    }
+
+    /// Also receive the decoded duration in seconds
+    pub fn with_duration(mut self, callback: impl Fn(f64) + Send + Sync + 'static) -> Self {
+        self.on_duration = Arc::new(callback);
+        self
+    }
+
+    /// Reporter that discards updates
+    pub fn noop() -> Self {
+        Self::new(|_| {})
+    }
+
+    pub fn report(&self, fraction: f32) {
+        (self.on_progress)(fraction.clamp(0.0, 1.0));
+    }
+
+    pub fn report_duration(&self, seconds: f64) {
+        (self.on_duration)(seconds);
+    }
+}
+
+/// A batch transcription engine
+///
+/// Methods are blocking; callers run them on `tokio::task::spawn_blocking`.
+pub trait TranscriptionBackend: Send + Sync {
+    /// Which backend this is
+    fn kind(&self) -> TranscriptionBackendKind;
+
+    /// Whether the backend needs a `ggml-*.bin` file from the model manager
+    fn requires_downloaded_model(&self) -> bool;
+
+    /// Prepare a model for transcription (load weights, validate files)
+    fn load_model(&self, model_id: &str, model_path: Option<&Path>) -> Result<()>;
+
+    /// Release any model held in memory
+    fn unload_model(&self);
+
+    /// Transcribe an audio file into timestamped segments, reporting progress as it goes.
+    /// Returns [`Cancelled`](super::cancellation::Cancelled) if `cancel` fires mid-run.
+    fn transcribe(
+        &self,
+        request: &TranscriptionRequest,
+        progress: ProgressReporter,
+        cancel: CancellationToken,
+    ) -> Result<Vec<TranscriptSegment>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_kind_round_trip() {
+        for kind in [
+            TranscriptionBackendKind::WhisperKit,
+            TranscriptionBackendKind::WhisperCpp,
+        ] {
+            assert_eq!(TranscriptionBackendKind::parse(kind.as_str()).unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn test_backend_kind_parse_unknown() {
+        assert!(TranscriptionBackendKind::parse("coreml").is_err());
+    }
+
+    #[test]
+    fn test_backend_kind_serialization() {
+        let json = serde_json::to_string(&TranscriptionBackendKind::WhisperCpp).unwrap();
+        assert_eq!(json, "\"whispercpp\"");
+    }
+
+    #[test]
+    fn test_progress_reporter_clamps() {
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let reporter = ProgressReporter::new(move |f| sink.lock().push(f));
+
+        reporter.report(-0.5);
+        reporter.clone().report(0.25);
+        reporter.report(3.0);
+
+        assert_eq!(*seen.lock(), vec![0.0, 0.25, 1.0]);
+    }
+
+    #[test]
+    fn test_progress_reporter_duration() {
+        let seen = Arc::new(parking_lot::Mutex::new(None));
+        let sink = seen.clone();
+        let reporter = ProgressReporter::noop().with_duration(move |s| *sink.lock() = Some(s));
+
+        reporter.clone().report_duration(61.5);
+        assert_eq!(*seen.lock(), Some(61.5));
+
+        // Without a duration callback the update is dropped
+        ProgressReporter::noop().report_duration(1.0);
+    }
+
+    #[test]
+    fn test_platform_default() {
+        let kind = TranscriptionBackendKind::platform_default();
+        if cfg!(target_os = "macos") {
+            assert_eq!(kind, TranscriptionBackendKind::WhisperKit);
+        } else {
+            assert_eq!(kind, TranscriptionBackendKind::WhisperCpp);
+        }
+    }
+}
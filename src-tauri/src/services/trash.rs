@@ -0,0 +1,535 @@
+//! Trash bin for sessions and folders
+//!
+//! Deleting a session or folder only sets `deleted_at` (see migration 006),
+//! which hides it everywhere else in the app. Trashed items can be restored
+//! until they are older than the retention period; a background loop then
+//! deletes the rows and their audio files for good. Each change is audited in
+//! the transaction that makes it.
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::audit::{self, AuditAction, AuditEvent};
+use super::database;
+use crate::models::TrashItem;
+
+/// Days a trashed item is kept when no retention is configured
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+/// How often expired items are purged
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Move a session to the trash
+pub async fn trash_session(pool: &SqlitePool, id: &str, now: i64) -> Result<()> {
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        "UPDATE sessions SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(now)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(anyhow!("Session not found: {}", id));
+    }
+    let event = AuditEvent::session(AuditAction::Delete, id);
+    audit::record_in(&mut *tx, &guard, event).await?;
+    tx.commit().await?;
+    println!("[Trash] Moved session {} to the trash", id);
+    Ok(())
+}
+
+/// Move a folder to the trash. Its subfolders are trashed with it, sharing
+/// its `deleted_at` so they come back together; sessions are hidden with
+/// their folder and come back when it is restored.
+pub async fn trash_folder(pool: &SqlitePool, id: &str, now: i64) -> Result<()> {
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        r#"
+        WITH RECURSIVE subtree(id) AS (
+            SELECT id FROM folders WHERE id = ? AND deleted_at IS NULL AND is_active = 1
+            UNION ALL
+            SELECT f.id FROM folders f
+            JOIN subtree ON f.parent_id = subtree.id
+            WHERE f.deleted_at IS NULL
+        )
+        UPDATE folders SET deleted_at = ?, updated_at = ? WHERE id IN (SELECT id FROM subtree)
+        "#,
+    )
+    .bind(id)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(anyhow!("Folder not found: {}", id));
+    }
+    let event = AuditEvent::new(AuditAction::Delete, "folder", Some(id));
+    audit::record_in(&mut *tx, &guard, event).await?;
+    tx.commit().await?;
+    println!("[Trash] Moved folder {} to the trash", id);
+    Ok(())
+}
+
+fn trash_item_from_row(row: SqliteRow, retention_days: i64) -> TrashItem {
+    let deleted_at: i64 = row.get("deleted_at");
+    TrashItem {
+        kind: row.get("kind"),
+        id: row.get("id"),
+        name: row.get("name"),
+        workspace_id: row.get("workspace_id"),
+        folder_id: row.get("folder_id"),
+        folder_name: row.get("folder_name"),
+        session_count: row.get("session_count"),
+        deleted_at,
+        purge_at: deleted_at + retention_days * SECONDS_PER_DAY,
+    }
+}
+
+/// List trashed items, most recently deleted first.
+///
+/// Sessions and subfolders inside a trashed folder are not listed on their
+/// own; the sessions of the whole subtree are counted in the folder's
+/// `session_count`, and it is restored or purged as one.
+pub async fn list_trash(
+    pool: &SqlitePool,
+    workspace_id: Option<&str>,
+    retention_days: i64,
+) -> Result<Vec<TrashItem>> {
+    let rows = sqlx::query(
+        r#"
+        WITH RECURSIVE subtree(root_id, id) AS (
+            SELECT id, id FROM folders WHERE deleted_at IS NOT NULL
+            UNION ALL
+            SELECT subtree.root_id, f.id FROM folders f JOIN subtree ON f.parent_id = subtree.id
+        )
+        SELECT * FROM (
+            SELECT 'folder' AS kind, f.id, f.name, f.workspace_id,
+                   NULL AS folder_id, NULL AS folder_name,
+                   (SELECT COUNT(*) FROM subtree t JOIN sessions s ON s.folder_id = t.id
+                    WHERE t.root_id = f.id) AS session_count,
+                   f.deleted_at
+            FROM folders f
+            LEFT JOIN folders parent ON parent.id = f.parent_id
+            WHERE f.deleted_at IS NOT NULL AND f.is_active = 1 AND parent.deleted_at IS NULL
+            UNION ALL
+            SELECT 'session' AS kind, s.id, COALESCE(s.title, '') AS name, f.workspace_id,
+                   f.id AS folder_id, f.name AS folder_name,
+                   0 AS session_count,
+                   s.deleted_at
+            FROM sessions s
+            JOIN folders f ON f.id = s.folder_id
+            WHERE s.deleted_at IS NOT NULL AND f.deleted_at IS NULL
+        )
+        WHERE ? IS NULL OR workspace_id = ?
+        ORDER BY deleted_at DESC
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| trash_item_from_row(row, retention_days))
+        .collect())
+}
+
+/// Take a session out of the trash
+pub async fn restore_session(pool: &SqlitePool, id: &str, now: i64) -> Result<()> {
+    let folder_trashed: Option<Option<i64>> = sqlx::query_scalar(
+        "SELECT f.deleted_at FROM sessions s JOIN folders f ON f.id = s.folder_id WHERE s.id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    match folder_trashed {
+        None => return Err(anyhow!("Session not found: {}", id)),
+        Some(Some(_)) => {
+            return Err(anyhow!(
+                "The session's folder is in the trash. Restore the folder instead."
+            ))
+        }
+        Some(None) => {}
+    }
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query(
+        "UPDATE sessions SET deleted_at = NULL, updated_at = ? WHERE id = ? AND deleted_at IS NOT NULL",
+    )
+    .bind(now)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(anyhow!("Session is not in the trash: {}", id));
+    }
+    let event = AuditEvent::session(AuditAction::Restore, id);
+    audit::record_in(&mut *tx, &guard, event).await?;
+    tx.commit().await?;
+    println!("[Trash] Restored session {}", id);
+    Ok(())
+}
+
+/// Take a folder out of the trash along with the subfolders and sessions
+/// trashed with it. Subfolders trashed earlier on their own stay trashed.
+pub async fn restore_folder(pool: &SqlitePool, id: &str, now: i64) -> Result<()> {
+    let trashed: Option<(Option<i64>, Option<i64>)> = sqlx::query_as(
+        r#"
+        SELECT f.deleted_at, parent.deleted_at
+        FROM folders f
+        LEFT JOIN folders parent ON parent.id = f.parent_id
+        WHERE f.id = ?
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    let deleted_at = match trashed {
+        None | Some((None, _)) => return Err(anyhow!("Folder is not in the trash: {}", id)),
+        Some((_, Some(_))) => {
+            return Err(anyhow!(
+                "The folder's parent is in the trash. Restore the parent instead."
+            ))
+        }
+        Some((Some(deleted_at), None)) => deleted_at,
+    };
+
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        WITH RECURSIVE subtree(id) AS (
+            SELECT ?
+            UNION ALL
+            SELECT f.id FROM folders f
+            JOIN subtree ON f.parent_id = subtree.id
+            WHERE f.deleted_at = ?
+        )
+        UPDATE folders SET deleted_at = NULL, updated_at = ? WHERE id IN (SELECT id FROM subtree)
+        "#,
+    )
+    .bind(id)
+    .bind(deleted_at)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+    let event = AuditEvent::new(AuditAction::Restore, "folder", Some(id));
+    audit::record_in(&mut *tx, &guard, event).await?;
+    tx.commit().await?;
+    println!("[Trash] Restored folder {}", id);
+    Ok(())
+}
+
+/// Permanently delete everything trashed at or before `cutoff`, including the
+/// audio files. Returns the number of sessions and folders removed.
+///
+/// A subfolder is never trashed later than its parent, so whole subtrees go in
+/// the same statement and `parent_id` never points at a purged folder.
+pub async fn purge(pool: &SqlitePool, cutoff: i64) -> Result<usize> {
+    let guard = audit::lock().await;
+    let mut tx = pool.begin().await?;
+
+    let purged_sessions: Vec<(String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT s.id, CASE WHEN s.audio_purged_at IS NULL THEN s.audio_path END
+        FROM sessions s
+        JOIN folders f ON f.id = s.folder_id
+        WHERE s.deleted_at <= ? OR f.deleted_at <= ?
+        "#,
+    )
+    .bind(cutoff)
+    .bind(cutoff)
+    .fetch_all(&mut *tx)
+    .await?;
+    let purged_folders: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM folders WHERE deleted_at <= ?")
+            .bind(cutoff)
+            .fetch_all(&mut *tx)
+            .await?;
+
+    let sessions = sqlx::query(
+        "DELETE FROM sessions WHERE deleted_at <= ? OR folder_id IN (SELECT id FROM folders WHERE deleted_at <= ?)",
+    )
+    .bind(cutoff)
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let folders = sqlx::query("DELETE FROM folders WHERE deleted_at <= ?")
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    for (id, _) in &purged_sessions {
+        let event = AuditEvent::session(AuditAction::Purge, id);
+        audit::record_system_in(&mut *tx, &guard, event).await?;
+    }
+    for id in &purged_folders {
+        let event = AuditEvent::new(AuditAction::Purge, "folder", Some(id));
+        audit::record_system_in(&mut *tx, &guard, event).await?;
+    }
+
+    tx.commit().await?;
+    drop(guard);
+
+    // Files go only once the rows are gone, so a failed purge loses nothing
+    for path in purged_sessions.iter().filter_map(|(_, path)| path.as_ref()) {
+        if let Err(e) = std::fs::remove_file(path) {
+            // Log but don't fail if file doesn't exist or can't be deleted
+            eprintln!("Warning: Could not delete audio file {}: {}", path, e);
+        }
+    }
+
+    if sessions + folders > 0 {
+        println!(
+            "[Trash] Purged {} session(s) and {} folder(s)",
+            sessions, folders
+        );
+    }
+    Ok((sessions + folders) as usize)
+}
+
+/// Permanently delete everything in the trash
+pub async fn empty_trash(pool: &SqlitePool) -> Result<usize> {
+    purge(pool, i64::MAX).await
+}
+
+/// Permanently delete items that have been in the trash longer than the
+/// retention period
+pub async fn purge_expired(pool: &SqlitePool, now: i64, retention_days: i64) -> Result<usize> {
+    purge(pool, now - retention_days * SECONDS_PER_DAY).await
+}
+
+/// Configured retention period in days
+pub async fn retention_days(pool: &SqlitePool) -> Result<i64> {
+    let value: Option<String> =
+        sqlx::query_scalar("SELECT value FROM settings WHERE key = 'trash_retention_days'")
+            .fetch_optional(pool)
+            .await?;
+    Ok(value
+        .and_then(|v| v.parse().ok())
+        .filter(|days| *days >= 1)
+        .unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+/// Start the background loop that purges expired items
+pub fn start(_app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            // Looked up each time: the pool is replaced when the database is re-keyed
+            let pool = match database::get_pool() {
+                Ok(pool) => pool,
+                Err(_) => continue,
+            };
+            let result = match retention_days(pool).await {
+                Ok(days) => purge_expired(pool, now(), days).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                eprintln!("[Trash] Purge failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Test', 'general', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f1', 'ws', 'f1', 0, 0)",
+                "INSERT INTO folders (id, workspace_id, name, created_at, updated_at) VALUES ('f2', 'ws', 'f2', 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    async fn insert_session(pool: &SqlitePool, id: &str, folder_id: &str, audio_path: &str) {
+        sqlx::query(
+            "INSERT INTO sessions (id, folder_id, title, audio_path, created_at, updated_at) VALUES (?, ?, ?, ?, 0, 0)",
+        )
+        .bind(id)
+        .bind(folder_id)
+        .bind(id)
+        .bind(audio_path)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_trash_and_restore_session() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "s1", "f1", "/nonexistent/s1.wav").await;
+
+        trash_session(&pool, "s1", 100).await.unwrap();
+        assert!(trash_session(&pool, "s1", 101).await.is_err());
+
+        let items = list_trash(&pool, None, 30).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, "session");
+        assert_eq!(items[0].folder_name.as_deref(), Some("f1"));
+        assert_eq!(items[0].purge_at, 100 + 30 * SECONDS_PER_DAY);
+
+        restore_session(&pool, "s1", 200).await.unwrap();
+        assert!(list_trash(&pool, None, 30).await.unwrap().is_empty());
+        assert!(restore_session(&pool, "s1", 201).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trashed_folder_hides_its_sessions() {
+        let pool = create_test_pool().await;
+        insert_session(&pool, "s1", "f1", "/nonexistent/s1.wav").await;
+        insert_session(&pool, "s2", "f1", "/nonexistent/s2.wav").await;
+        trash_session(&pool, "s2", 50).await.unwrap();
+        trash_folder(&pool, "f1", 100).await.unwrap();
+
+        let items = list_trash(&pool, Some("ws"), 30).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, "folder");
+        assert_eq!(items[0].session_count, 2);
+        assert!(list_trash(&pool, Some("other"), 30).await.unwrap().is_empty());
+
+        // Sessions can't come back on their own while the folder is trashed
+        assert!(restore_session(&pool, "s2", 150).await.is_err());
+
+        // The folder comes back, the separately trashed session stays trashed
+        restore_folder(&pool, "f1", 200).await.unwrap();
+        let items = list_trash(&pool, None, 30).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "s2");
+    }
+
+    #[tokio::test]
+    async fn test_trashing_a_folder_takes_its_subtree() {
+        let pool = create_test_pool().await;
+        for (id, parent) in [("sub", "f1"), ("leaf", "sub"), ("early", "f1")] {
+            sqlx::query(
+                "INSERT INTO folders (id, workspace_id, parent_id, name, created_at, updated_at) VALUES (?, 'ws', ?, ?, 0, 0)",
+            )
+            .bind(id)
+            .bind(parent)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        insert_session(&pool, "s1", "f1", "/nonexistent/s1.wav").await;
+        insert_session(&pool, "s2", "leaf", "/nonexistent/s2.wav").await;
+        insert_session(&pool, "s3", "early", "/nonexistent/s3.wav").await;
+
+        trash_folder(&pool, "early", 50).await.unwrap();
+        trash_folder(&pool, "f1", 100).await.unwrap();
+
+        // Only the top folder is listed, counting the sessions of its subtree
+        let items = list_trash(&pool, None, 30).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "f1");
+        assert_eq!(items[0].session_count, 3);
+        assert!(restore_folder(&pool, "sub", 150).await.is_err());
+
+        // The subfolder trashed on its own stays in the trash
+        restore_folder(&pool, "f1", 200).await.unwrap();
+        let trashed: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM folders WHERE deleted_at IS NOT NULL")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(trashed, vec!["early".to_string()]);
+
+        // Nested folders are purged together
+        trash_folder(&pool, "f1", 300).await.unwrap();
+        assert_eq!(empty_trash(&pool).await.unwrap(), 7); // three sessions, four folders
+        assert_eq!(count(&pool, "folders").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_respects_retention() {
+        let pool = create_test_pool().await;
+        let dir = std::env::temp_dir().join(format!("trash-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let old_audio = dir.join("old.wav");
+        let folder_audio = dir.join("folder.wav");
+        let recent_audio = dir.join("recent.wav");
+        for path in [&old_audio, &folder_audio, &recent_audio] {
+            std::fs::write(path, b"audio").unwrap();
+        }
+
+        insert_session(&pool, "old", "f1", old_audio.to_str().unwrap()).await;
+        insert_session(&pool, "in-folder", "f2", folder_audio.to_str().unwrap()).await;
+        insert_session(&pool, "recent", "f1", recent_audio.to_str().unwrap()).await;
+
+        let day = SECONDS_PER_DAY;
+        trash_session(&pool, "old", 0).await.unwrap();
+        trash_folder(&pool, "f2", day).await.unwrap();
+        trash_session(&pool, "recent", 10 * day).await.unwrap();
+
+        let purged = purge_expired(&pool, 7 * day, 5).await.unwrap();
+        assert_eq!(purged, 3); // two sessions and a folder
+
+        assert_eq!(count(&pool, "sessions").await, 1);
+        assert_eq!(count(&pool, "folders").await, 1);
+        assert!(!old_audio.exists());
+        assert!(!folder_audio.exists());
+        assert!(recent_audio.exists());
+
+        assert_eq!(empty_trash(&pool).await.unwrap(), 1);
+        assert!(!recent_audio.exists());
+        assert_eq!(count(&pool, "folders").await, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retention_days_setting() {
+        let pool = create_test_pool().await;
+        assert_eq!(retention_days(&pool).await.unwrap(), DEFAULT_RETENTION_DAYS);
+
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_at) VALUES ('trash_retention_days', '7', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(retention_days(&pool).await.unwrap(), 7);
+    }
+}
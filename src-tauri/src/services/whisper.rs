@@ -2,11 +2,19 @@ use anyhow::{anyhow, Result};
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use serde::Serialize;
-use std::path::PathBuf;
-use std::process::Command;
 use std::sync::Arc;
+use std::time::Instant;
 use tauri::AppHandle;
-use tauri::{Emitter, Manager};
+use tauri::Emitter;
+
+use super::cancellation::{is_cancelled_error, CancellationToken};
+use super::model_manager::ModelManager;
+use super::transcription_backend::{
+    ProgressReporter, TranscriptionBackend, TranscriptionBackendKind, TranscriptionRequest,
+};
+use super::whisperkit::WhisperKitBackend;
+use super::{audio, database, diarization};
+use crate::models::{Session, TranscriptSegment};
 
 /// Transcription progress event sent to frontend
 #[derive(Debug, Clone, Serialize)]
@@ -17,206 +25,335 @@ pub struct TranscriptionProgressEvent {
     pub status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Seconds of audio processed so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processed_seconds: Option<f64>,
+    /// Total audio duration in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_seconds: Option<f64>,
+    /// Estimated seconds until transcription finishes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<f64>,
 }
 
-/// Currently loaded model ID (for compatibility with UI)
-static LOADED_MODEL_ID: OnceCell<Arc<Mutex<Option<String>>>> = OnceCell::new();
+/// Callback that receives every progress update alongside the frontend event
+pub type ProgressListener = Arc<dyn Fn(&TranscriptionProgressEvent) + Send + Sync>;
+
+/// Overall progress range covered by the backend's own progress reports
+const TRANSCRIBE_PROGRESS_START: f32 = 10.0;
+const TRANSCRIBE_PROGRESS_END: f32 = 90.0;
+/// Don't estimate an ETA until this fraction of the audio has been processed
+const MIN_FRACTION_FOR_ETA: f32 = 0.02;
 
-fn get_loaded_model_id() -> &'static Arc<Mutex<Option<String>>> {
-    LOADED_MODEL_ID.get_or_init(|| Arc::new(Mutex::new(None)))
+/// Currently loaded model and the backend it was loaded into
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedWhisperModel {
+    pub model_id: String,
+    pub backend: TranscriptionBackendKind,
 }
 
-/// Get path to the whisperkit-worker binary
-fn get_worker_path(app: &AppHandle) -> Result<PathBuf> {
-    // Try Tauri's sidecar resolution first (works in production)
-    if let Ok(sidecar) = app
-        .path()
-        .resolve("binaries/whisperkit-worker", tauri::path::BaseDirectory::Resource)
-    {
-        if sidecar.exists() {
-            println!("[WhisperKit] Found bundled worker at: {:?}", sidecar);
-            return Ok(sidecar);
-        }
-    }
+static LOADED_MODEL: OnceCell<Arc<Mutex<Option<LoadedWhisperModel>>>> = OnceCell::new();
 
-    // Get the architecture suffix for the current platform
-    let arch_suffix = if cfg!(target_arch = "aarch64") {
-        "aarch64-apple-darwin"
-    } else if cfg!(target_arch = "x86_64") {
-        "x86_64-apple-darwin"
-    } else {
-        "unknown"
-    };
+fn get_loaded_model_state() -> &'static Arc<Mutex<Option<LoadedWhisperModel>>> {
+    LOADED_MODEL.get_or_init(|| Arc::new(Mutex::new(None)))
+}
 
-    // Try various locations for the worker binary
-    let possible_paths = [
-        // Development: binaries folder with architecture suffix (Tauri sidecar format)
-        Some(PathBuf::from(format!(
-            "binaries/whisperkit-worker-{}",
-            arch_suffix
-        ))),
-        // Development: built whisperkit-worker in the whisperkit-worker folder
-        Some(PathBuf::from(
-            "whisperkit-worker/.build/release/whisperkit-worker",
-        )),
-        // Development: relative from src-tauri
-        Some(PathBuf::from(
-            "../whisperkit-worker/.build/release/whisperkit-worker",
-        )),
-        // Development: same directory as main binary
-        std::env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|d| d.join("whisperkit-worker"))),
-        // Fallback: current directory
-        Some(PathBuf::from("./whisperkit-worker")),
-    ];
-
-    for path in possible_paths.iter().flatten() {
-        println!("[WhisperKit] Checking path: {:?}", path);
-        if path.exists() {
-            println!("[WhisperKit] Found worker at: {:?}", path);
-            return Ok(path.clone());
+/// Read the configured transcription backend from settings
+pub async fn configured_backend(app: &AppHandle) -> Result<TranscriptionBackendKind> {
+    let settings = database::get_settings(app).await?;
+    TranscriptionBackendKind::parse(&settings.transcription_backend)
+}
+
+/// Construct a backend instance
+fn create_backend(
+    app: &AppHandle,
+    kind: TranscriptionBackendKind,
+) -> Result<Arc<dyn TranscriptionBackend>> {
+    match kind {
+        TranscriptionBackendKind::WhisperKit => Ok(Arc::new(WhisperKitBackend::new(app)?)),
+        #[cfg(not(target_os = "macos"))]
+        TranscriptionBackendKind::WhisperCpp => {
+            Ok(Arc::new(super::whisper_cpp::WhisperCppBackend::new()))
         }
+        #[cfg(target_os = "macos")]
+        TranscriptionBackendKind::WhisperCpp => Err(anyhow!(
+            "The whisper.cpp backend is not available on macOS. Use WhisperKit instead."
+        )),
     }
+}
 
-    Err(anyhow!(
-        "WhisperKit worker binary not found. Build it with: cd whisperkit-worker && swift build -c release"
-    ))
+/// Resolve the on-disk model file for backends that need one
+async fn resolve_model_path(
+    app: &AppHandle,
+    backend: &dyn TranscriptionBackend,
+    model_id: &str,
+) -> Result<Option<std::path::PathBuf>> {
+    if !backend.requires_downloaded_model() {
+        return Ok(None);
+    }
+
+    let model_info = ModelManager::get_model_info(model_id)
+        .ok_or_else(|| anyhow!("Unknown whisper model: {}", model_id))?;
+    let manager = ModelManager::new(app).await?;
+    let path = manager.get_model_path(&model_info).ok_or_else(|| {
+        anyhow!(
+            "Model {} is not downloaded. Download it in Settings > Models.",
+            model_id
+        )
+    })?;
+
+    Ok(Some(path))
 }
 
-/// Load whisper model (just stores the model ID for UI compatibility)
-/// WhisperKit automatically downloads and caches models
-pub async fn load_model(_app: &AppHandle, model_id: &str) -> Result<()> {
-    println!("[WhisperKit] Setting model preference: {}", model_id);
+/// Load a whisper model into the configured transcription backend
+pub async fn load_model(app: &AppHandle, model_id: &str) -> Result<()> {
+    let kind = configured_backend(app).await?;
+    let backend = create_backend(app, kind)?;
+    let model_path = resolve_model_path(app, backend.as_ref(), model_id).await?;
 
-    let mut lock = get_loaded_model_id().lock();
-    *lock = Some(model_id.to_string());
+    println!("[Whisper] Loading model {} with {} backend", model_id, kind.as_str());
+
+    let id = model_id.to_string();
+    tokio::task::spawn_blocking(move || backend.load_model(&id, model_path.as_deref()))
+        .await
+        .map_err(|e| anyhow!("Task join error: {}", e))??;
+
+    let mut lock = get_loaded_model_state().lock();
+    *lock = Some(LoadedWhisperModel {
+        model_id: model_id.to_string(),
+        backend: kind,
+    });
 
     Ok(())
 }
 
 /// Unload whisper model from memory
 pub fn unload_model() {
-    let mut lock = get_loaded_model_id().lock();
+    let mut lock = get_loaded_model_state().lock();
+    #[cfg(not(target_os = "macos"))]
+    if let Some(ref loaded) = *lock {
+        if loaded.backend == TranscriptionBackendKind::WhisperCpp {
+            super::whisper_cpp::WhisperCppBackend::new().unload_model();
+        }
+    }
     *lock = None;
 }
 
 /// Check if a model is loaded
 pub fn is_model_loaded() -> bool {
-    // WhisperKit auto-downloads models, so we're always "ready"
-    // Return true if a model preference has been set
-    let lock = get_loaded_model_id().lock();
+    let lock = get_loaded_model_state().lock();
     lock.is_some()
 }
 
-/// Get the currently loaded model ID
-pub fn get_loaded_model() -> Option<String> {
-    let lock = get_loaded_model_id().lock();
+/// Get the currently loaded model and its backend
+pub fn get_loaded_model() -> Option<LoadedWhisperModel> {
+    let lock = get_loaded_model_state().lock();
     lock.clone()
 }
 
-/// Emit transcription progress event
-fn emit_progress(app: &AppHandle, session_id: &str, progress: f32, status: &str, message: Option<&str>) {
-    println!(
-        "[WhisperKit] Progress event: session={}, progress={:.1}%, status={}, message={:?}",
-        session_id, progress, status, message
-    );
+/// Overall progress, processed seconds and ETA for a backend progress fraction
+fn progress_details(
+    fraction: f32,
+    elapsed_secs: f64,
+    total_seconds: Option<f64>,
+) -> (f32, Option<f64>, Option<f64>) {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let progress =
+        TRANSCRIBE_PROGRESS_START + fraction * (TRANSCRIBE_PROGRESS_END - TRANSCRIBE_PROGRESS_START);
+    let processed = total_seconds.map(|total| total * fraction as f64);
+    let eta = (fraction >= MIN_FRACTION_FOR_ETA)
+        .then(|| elapsed_secs * (1.0 - fraction as f64) / fraction as f64);
+
+    (progress, processed, eta)
+}
 
-    let event = TranscriptionProgressEvent {
-        session_id: session_id.to_string(),
-        progress,
-        status: status.to_string(),
-        message: message.map(|s| s.to_string()),
-    };
+/// Emits progress for a single transcription run to the frontend and a listener
+#[derive(Clone)]
+struct ProgressEmitter {
+    app: AppHandle,
+    session_id: String,
+    /// Audio duration, from the decoded samples once the backend reports it
+    total_seconds: Arc<Mutex<Option<f64>>>,
+    listener: ProgressListener,
+}
+
+impl ProgressEmitter {
+    fn total_seconds(&self) -> Option<f64> {
+        *self.total_seconds.lock()
+    }
+
+    /// Emit a progress event without audio timing details
+    fn emit(&self, progress: f32, status: &str, message: Option<&str>) {
+        self.emit_event(TranscriptionProgressEvent {
+            session_id: self.session_id.clone(),
+            progress,
+            status: status.to_string(),
+            message: message.map(|s| s.to_string()),
+            processed_seconds: None,
+            total_seconds: self.total_seconds(),
+            eta_seconds: None,
+        });
+    }
+
+    fn emit_event(&self, event: TranscriptionProgressEvent) {
+        println!(
+            "[Whisper] Progress event: session={}, progress={:.1}%, status={}, eta={:?}",
+            event.session_id, event.progress, event.status, event.eta_seconds
+        );
+
+        (self.listener)(&event);
+
+        if let Err(e) = self.app.emit("transcription-progress", &event) {
+            println!("[Whisper] ERROR: Failed to emit progress event: {}", e);
+        }
+    }
 
-    match app.emit("transcription-progress", &event) {
-        Ok(_) => println!("[WhisperKit] Successfully emitted progress event"),
-        Err(e) => println!("[WhisperKit] ERROR: Failed to emit progress event: {}", e),
+    /// Build a reporter that converts backend fractions into progress events
+    fn reporter(&self) -> ProgressReporter {
+        let emitter = self.clone();
+        let total_seconds = self.total_seconds.clone();
+        let started = Instant::now();
+        ProgressReporter::new(move |fraction| {
+            let total_seconds = emitter.total_seconds();
+            let (progress, processed_seconds, eta_seconds) =
+                progress_details(fraction, started.elapsed().as_secs_f64(), total_seconds);
+            emitter.emit_event(TranscriptionProgressEvent {
+                session_id: emitter.session_id.clone(),
+                progress,
+                status: "transcribing".to_string(),
+                message: None,
+                processed_seconds,
+                total_seconds,
+                eta_seconds,
+            });
+        })
+        .with_duration(move |seconds| *total_seconds.lock() = Some(seconds))
     }
 }
 
-/// Transcribe audio file using whisperkit-worker subprocess
-/// WhisperKit provides fast CoreML/Metal-accelerated transcription
-pub async fn transcribe(app: &AppHandle, session_id: &str, audio_path: &str) -> Result<String> {
-    println!("[WhisperKit] transcribe() called for: {}", audio_path);
+/// Transcribe audio file with the configured backend into timestamped segments
+/// WhisperKit runs as a CoreML/Metal-accelerated subprocess; whisper.cpp runs in-process on the CPU
+pub async fn transcribe(
+    app: &AppHandle,
+    session_id: &str,
+    audio_path: &str,
+    listener: ProgressListener,
+    cancel: CancellationToken,
+) -> Result<Vec<TranscriptSegment>> {
+    println!("[Whisper] transcribe() called for: {}", audio_path);
+
+    let emitter = ProgressEmitter {
+        app: app.clone(),
+        session_id: session_id.to_string(),
+        // Duration lets progress be shown as audio seconds processed. The
+        // container's is a first guess until the backend decodes the audio.
+        total_seconds: Arc::new(Mutex::new(audio::probe_duration_secs(audio_path).ok())),
+        listener,
+    };
 
     // Emit starting event
-    emit_progress(app, session_id, 0.0, "starting", Some("Preparing transcription..."));
+    emitter.emit(0.0, "starting", Some("Preparing transcription..."));
 
     // Verify audio file exists
     let audio_file = std::path::Path::new(audio_path);
     if !audio_file.exists() {
-        emit_progress(app, session_id, 0.0, "error", Some("Audio file not found"));
+        emitter.emit(0.0, "error", Some("Audio file not found"));
         return Err(anyhow!("Audio file not found: {}", audio_path));
     }
 
-    // Get worker binary path
-    let worker_path = get_worker_path(app)?;
+    let kind = configured_backend(app).await?;
 
-    println!("[WhisperKit] Running whisperkit-worker subprocess...");
-
-    // Emit transcribing event - progress will be estimated
-    emit_progress(app, session_id, 10.0, "transcribing", Some("Loading model..."));
+    // Reload if nothing is loaded or the backend was switched since the last load
+    let loaded = match get_loaded_model() {
+        Some(loaded) if loaded.backend == kind => loaded,
+        _ => {
+            load_model(app, kind.default_model_id()).await?;
+            get_loaded_model().ok_or_else(|| anyhow!("No whisper model loaded"))?
+        }
+    };
 
-    // Spawn worker process
-    // WhisperKit will auto-download and cache the model on first run
-    let audio_path_str = audio_path.to_string();
-    let worker_path_clone = worker_path.clone();
-    let session_id_clone = session_id.to_string();
-    let app_clone = app.clone();
+    let backend = create_backend(app, kind).map_err(|e| {
+        emitter.emit(0.0, "error", Some(&e.to_string()));
+        e
+    })?;
+    let model_path = resolve_model_path(app, backend.as_ref(), &loaded.model_id).await?;
+    let request = TranscriptionRequest {
+        audio_path: audio_file.to_path_buf(),
+        model_id: loaded.model_id.clone(),
+        model_path,
+        language: None,
+    };
 
-    // Run transcription in background and emit progress updates
-    let handle = tokio::task::spawn_blocking(move || {
-        // Emit progress update when model is loaded
-        emit_progress(&app_clone, &session_id_clone, 30.0, "transcribing", Some("Transcribing audio..."));
+    emitter.emit(
+        TRANSCRIBE_PROGRESS_START,
+        "transcribing",
+        Some("Transcribing audio..."),
+    );
 
-        Command::new(&worker_path_clone)
-            .arg(&audio_path_str)
-            .output()
-    });
+    // Run transcription in background; the backend reports progress as it goes
+    let reporter = emitter.reporter();
+    let handle =
+        tokio::task::spawn_blocking(move || backend.transcribe(&request, reporter, cancel));
 
-    let output = handle
+    let segments = handle
         .await
         .map_err(|e| {
-            emit_progress(app, session_id, 0.0, "error", Some(&format!("Task error: {}", e)));
+            emitter.emit(0.0, "error", Some(&format!("Task error: {}", e)));
             anyhow!("Task join error: {}", e)
         })?
         .map_err(|e| {
-            emit_progress(app, session_id, 0.0, "error", Some(&format!("Worker error: {}", e)));
-            anyhow!("Failed to run whisperkit-worker: {}", e)
+            if is_cancelled_error(&e) {
+                emitter.emit(0.0, "cancelled", Some("Transcription cancelled"));
+            } else {
+                emitter.emit(0.0, "error", Some(&e.to_string()));
+            }
+            e
         })?;
 
-    // Check for errors
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        println!("[WhisperKit] Worker failed with stderr: {}", stderr);
-        emit_progress(app, session_id, 0.0, "error", Some(&format!("Transcription failed: {}", stderr)));
-        return Err(anyhow!("Transcription failed: {}", stderr));
-    }
-
     // Emit processing result event
-    emit_progress(app, session_id, 90.0, "processing", Some("Processing result..."));
-
-    // Get transcript from stdout
-    let transcript = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-    if transcript.is_empty() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        println!("[WhisperKit] Worker stderr: {}", stderr);
-        emit_progress(app, session_id, 0.0, "error", Some("Transcription produced no output"));
-        return Err(anyhow!("Transcription produced no output"));
-    }
+    emitter.emit(
+        TRANSCRIBE_PROGRESS_END,
+        "processing",
+        Some("Processing result..."),
+    );
 
     println!(
-        "[WhisperKit] Transcription complete: {} chars",
-        transcript.len()
+        "[Whisper] Transcription complete ({}): {} segments",
+        kind.as_str(),
+        segments.len()
     );
-    println!("[WhisperKit] Result: {}", transcript);
 
     // Emit completion event
-    emit_progress(app, session_id, 100.0, "complete", Some("Transcription complete"));
+    emitter.emit(100.0, "complete", Some("Transcription complete"));
 
-    Ok(transcript)
+    Ok(segments)
+}
+
+/// Transcribe a session's audio, label speakers and persist the segments.
+/// Returns the updated session.
+pub async fn transcribe_and_save(
+    app: &AppHandle,
+    session_id: &str,
+    audio_path: &str,
+    listener: ProgressListener,
+    cancel: CancellationToken,
+) -> Result<Session> {
+    let segments = transcribe(app, session_id, audio_path, listener, cancel).await?;
+
+    // Label speakers; a diarization failure shouldn't lose the transcript
+    let segments = match diarization::diarize_file_async(audio_path.to_string(), segments.clone())
+        .await
+    {
+        Ok(labelled) => labelled,
+        Err(e) => {
+            println!("[Whisper] Diarization failed, skipping: {}", e);
+            segments
+        }
+    };
+
+    // Persist segments so playback, subtitles and RAG get timestamps
+    database::save_transcript_segments(app, session_id, &segments).await
 }
 
 #[cfg(test)]
@@ -226,12 +363,24 @@ mod tests {
     #[test]
     fn test_get_loaded_model_returns_option() {
         let result = get_loaded_model();
-        // Should return Some(String) or None, never panic
-        if let Some(model_id) = result {
-            assert!(!model_id.is_empty());
+        // Should return Some(LoadedWhisperModel) or None, never panic
+        if let Some(loaded) = result {
+            assert!(!loaded.model_id.is_empty());
         }
     }
 
+    #[test]
+    fn test_loaded_whisper_model_serialization() {
+        let loaded = LoadedWhisperModel {
+            model_id: "whisper-base".to_string(),
+            backend: TranscriptionBackendKind::WhisperCpp,
+        };
+
+        let json = serde_json::to_string(&loaded).unwrap();
+        assert!(json.contains("\"modelId\":\"whisper-base\""));
+        assert!(json.contains("\"backend\":\"whispercpp\""));
+    }
+
     #[test]
     fn test_unload_model_no_panic() {
         // Verify unloading when nothing is loaded doesn't panic
@@ -253,6 +402,9 @@ mod tests {
             progress: 50.0,
             status: "transcribing".to_string(),
             message: Some("Processing audio...".to_string()),
+            processed_seconds: Some(42.0),
+            total_seconds: Some(120.0),
+            eta_seconds: Some(78.5),
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -260,6 +412,9 @@ mod tests {
         assert!(json.contains("\"progress\":50.0"));
         assert!(json.contains("\"status\":\"transcribing\""));
         assert!(json.contains("\"message\":\"Processing audio...\""));
+        assert!(json.contains("\"processedSeconds\":42.0"));
+        assert!(json.contains("\"totalSeconds\":120.0"));
+        assert!(json.contains("\"etaSeconds\":78.5"));
     }
 
     #[test]
@@ -269,6 +424,9 @@ mod tests {
             progress: 100.0,
             status: "complete".to_string(),
             message: None,
+            processed_seconds: None,
+            total_seconds: None,
+            eta_seconds: None,
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -277,5 +435,33 @@ mod tests {
         assert!(json.contains("\"status\":\"complete\""));
         // message should be skipped when None due to skip_serializing_if
         assert!(!json.contains("\"message\""));
+        assert!(!json.contains("\"etaSeconds\""));
+    }
+
+    #[test]
+    fn test_progress_details_midway() {
+        // Half the audio done after 30s: 30s left, progress halfway through the range
+        let (progress, processed, eta) = progress_details(0.5, 30.0, Some(600.0));
+
+        assert!((progress - 50.0).abs() < 0.001);
+        assert_eq!(processed, Some(300.0));
+        assert_eq!(eta, Some(30.0));
+    }
+
+    #[test]
+    fn test_progress_details_without_duration() {
+        let (progress, processed, eta) = progress_details(1.0, 10.0, None);
+
+        assert!((progress - TRANSCRIBE_PROGRESS_END).abs() < 0.001);
+        assert!(processed.is_none());
+        assert_eq!(eta, Some(0.0));
+    }
+
+    #[test]
+    fn test_progress_details_no_eta_at_start() {
+        let (progress, _, eta) = progress_details(0.0, 5.0, Some(60.0));
+
+        assert!((progress - TRANSCRIBE_PROGRESS_START).abs() < 0.001);
+        assert!(eta.is_none());
     }
 }
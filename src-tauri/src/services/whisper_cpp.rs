@@ -0,0 +1,320 @@
+//! whisper.cpp transcription backend
+//!
+//! Pure CPU transcription with GGML models downloaded by the model manager.
+//! Used on Linux and Windows, where the WhisperKit worker isn't available.
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::path::Path;
+use std::sync::Arc;
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+use super::audio;
+use super::cancellation::CancellationToken;
+use super::transcription_backend::{
+    ProgressReporter, TranscriptionBackend, TranscriptionBackendKind, TranscriptionRequest,
+};
+use crate::models::{TranscriptSegment, TranscriptWord};
+
+/// Loaded whisper.cpp context, keyed by model ID
+static CONTEXT: Lazy<Mutex<Option<(String, Arc<WhisperContext>)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Timing and probability of a single decoded token
+struct TokenTiming {
+    text: String,
+    start: f64,
+    end: f64,
+    probability: f32,
+    logprob: f32,
+}
+
+/// Merge BPE tokens into words; a leading space marks the start of a new word.
+/// A word's probability is that of its least confident token.
+fn group_tokens_into_words(tokens: &[TokenTiming]) -> Vec<TranscriptWord> {
+    let mut words: Vec<TranscriptWord> = Vec::new();
+
+    for token in tokens {
+        let starts_word = token.text.starts_with(' ') || words.is_empty();
+        if starts_word {
+            let text = token.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            words.push(TranscriptWord {
+                text: text.to_string(),
+                start: token.start,
+                end: token.end,
+                probability: token.probability,
+            });
+        } else if let Some(word) = words.last_mut() {
+            word.text.push_str(&token.text);
+            word.end = word.end.max(token.end);
+            word.probability = word.probability.min(token.probability);
+        }
+    }
+
+    words
+}
+
+/// In-process whisper.cpp transcription
+pub struct WhisperCppBackend;
+
+impl WhisperCppBackend {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Number of CPU threads to hand to whisper.cpp
+    fn thread_count() -> i32 {
+        std::thread::available_parallelism()
+            .map(|n| n.get().min(8) as i32)
+            .unwrap_or(4)
+    }
+
+    /// Read the text tokens of a segment, skipping special and timestamp tokens
+    fn segment_tokens(state: &WhisperState, segment: i32) -> Vec<TokenTiming> {
+        let n_tokens = match state.full_n_tokens(segment) {
+            Ok(n) => n,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut tokens = Vec::new();
+        for j in 0..n_tokens {
+            let text = match state.full_get_token_text(segment, j) {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            // Skip special tokens such as [_BEG_] and timestamps
+            if text.starts_with("[_") || text.starts_with("<|") {
+                continue;
+            }
+            if let Ok(data) = state.full_get_token_data(segment, j) {
+                tokens.push(TokenTiming {
+                    text,
+                    // Token timestamps are in 10ms units
+                    start: data.t0 as f64 / 100.0,
+                    end: data.t1 as f64 / 100.0,
+                    probability: data.p,
+                    logprob: data.plog,
+                });
+            }
+        }
+
+        tokens
+    }
+
+    /// Get the loaded context, loading it from disk if a different model is requested
+    fn context_for(model_id: &str, model_path: Option<&Path>) -> Result<Arc<WhisperContext>> {
+        {
+            let lock = CONTEXT.lock();
+            if let Some((ref loaded_id, ref ctx)) = *lock {
+                if loaded_id == model_id {
+                    return Ok(ctx.clone());
+                }
+            }
+        }
+
+        let path = model_path.ok_or_else(|| {
+            anyhow!(
+                "Model {} is not downloaded. Download it in Settings > Models.",
+                model_id
+            )
+        })?;
+        if !path.exists() {
+            return Err(anyhow!("Model file not found: {:?}", path));
+        }
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid model path: {:?}", path))?;
+
+        println!("[WhisperCpp] Loading model {} from {:?}", model_id, path);
+        let ctx = WhisperContext::new_with_params(path_str, WhisperContextParameters::default())
+            .map_err(|e| anyhow!("Failed to load whisper model: {}", e))?;
+        let ctx = Arc::new(ctx);
+
+        let mut lock = CONTEXT.lock();
+        *lock = Some((model_id.to_string(), ctx.clone()));
+        println!("[WhisperCpp] Model {} loaded", model_id);
+
+        Ok(ctx)
+    }
+
+    /// Transcribe 16kHz mono samples with an already loaded or loadable model.
+    /// Returns no segments for audio without speech.
+    pub fn transcribe_samples(
+        &self,
+        model_id: &str,
+        model_path: Option<&Path>,
+        samples: &[f32],
+        language: Option<&str>,
+        progress: ProgressReporter,
+        cancel: CancellationToken,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let ctx = Self::context_for(model_id, model_path)?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_n_threads(Self::thread_count());
+        params.set_language(Some(language.unwrap_or("auto")));
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_token_timestamps(true);
+        params.set_progress_callback_safe(move |percent: i32| {
+            progress.report(percent as f32 / 100.0);
+        });
+        let abort = cancel.clone();
+        params.set_abort_callback_safe(move || abort.is_cancelled());
+
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| anyhow!("Failed to create whisper state: {}", e))?;
+
+        println!(
+            "[WhisperCpp] Transcribing {} samples with {} threads",
+            samples.len(),
+            Self::thread_count()
+        );
+        let result = state.full(params, samples);
+        // whisper.cpp reports an abort as a generic failure
+        cancel.check()?;
+        result.map_err(|e| anyhow!("Transcription failed: {}", e))?;
+
+        let num_segments = state
+            .full_n_segments()
+            .map_err(|e| anyhow!("Failed to read segments: {}", e))?;
+
+        let mut segments = Vec::new();
+        for i in 0..num_segments {
+            let text = state
+                .full_get_segment_text(i)
+                .map_err(|e| anyhow!("Failed to read segment {}: {}", i, e))?;
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            // Segment timestamps are in 10ms units
+            let t0 = state.full_get_segment_t0(i).unwrap_or(0);
+            let t1 = state.full_get_segment_t1(i).unwrap_or(t0);
+
+            let tokens = Self::segment_tokens(&state, i);
+            let avg_logprob = (!tokens.is_empty())
+                .then(|| tokens.iter().map(|t| t.logprob).sum::<f32>() / tokens.len() as f32);
+            let words = group_tokens_into_words(&tokens);
+
+            segments.push(TranscriptSegment {
+                start: t0 as f64 / 100.0,
+                end: t1 as f64 / 100.0,
+                text: text.trim().to_string(),
+                speaker: None,
+                avg_logprob,
+                words: (!words.is_empty()).then_some(words),
+            });
+        }
+
+        Ok(segments)
+    }
+}
+
+impl Default for WhisperCppBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TranscriptionBackend for WhisperCppBackend {
+    fn kind(&self) -> TranscriptionBackendKind {
+        TranscriptionBackendKind::WhisperCpp
+    }
+
+    fn requires_downloaded_model(&self) -> bool {
+        true
+    }
+
+    fn load_model(&self, model_id: &str, model_path: Option<&Path>) -> Result<()> {
+        Self::context_for(model_id, model_path).map(|_| ())
+    }
+
+    fn unload_model(&self) {
+        let mut lock = CONTEXT.lock();
+        *lock = None;
+    }
+
+    fn transcribe(
+        &self,
+        request: &TranscriptionRequest,
+        progress: ProgressReporter,
+        cancel: CancellationToken,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let audio_path = request
+            .audio_path
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid audio path: {:?}", request.audio_path))?;
+        let samples = audio::decode_audio_to_whisper_format(audio_path)?;
+        progress.report_duration(audio::whisper_samples_duration_secs(&samples));
+        cancel.check()?;
+
+        let segments = self.transcribe_samples(
+            &request.model_id,
+            request.model_path.as_deref(),
+            &samples,
+            request.language.as_deref(),
+            progress,
+            cancel,
+        )?;
+
+        if segments.is_empty() {
+            return Err(anyhow!("Transcription produced no output"));
+        }
+
+        Ok(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, start: f64, end: f64, probability: f32) -> TokenTiming {
+        TokenTiming {
+            text: text.to_string(),
+            start,
+            end,
+            probability,
+            logprob: probability.ln(),
+        }
+    }
+
+    #[test]
+    fn test_group_tokens_into_words() {
+        let tokens = vec![
+            token(" Hel", 0.0, 0.2, 0.9),
+            token("lo", 0.2, 0.4, 0.6),
+            token(" world", 0.5, 0.9, 0.95),
+            token(".", 0.9, 1.0, 0.99),
+        ];
+
+        let words = group_tokens_into_words(&tokens);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!(words[0].end, 0.4);
+        assert_eq!(words[0].probability, 0.6);
+        assert_eq!(words[1].text, "world.");
+        assert_eq!(words[1].start, 0.5);
+    }
+
+    #[test]
+    fn test_group_tokens_skips_blank_tokens() {
+        let tokens = vec![token(" ", 0.0, 0.1, 0.5), token(" Hi", 0.1, 0.3, 0.8)];
+
+        let words = group_tokens_into_words(&tokens);
+
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].text, "Hi");
+    }
+}
@@ -0,0 +1,272 @@
+//! WhisperKit transcription backend
+//!
+//! Runs the `whisperkit-worker` Swift binary as a subprocess. WhisperKit
+//! downloads and caches its own CoreML models, so no model file is needed.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri::Manager;
+
+use super::cancellation::CancellationToken;
+use super::encryption;
+use super::transcription_backend::{
+    ProgressReporter, TranscriptionBackend, TranscriptionBackendKind, TranscriptionRequest,
+};
+use crate::models::TranscriptSegment;
+use crate::utils::platform::target_triple;
+
+/// How often the worker is checked for cancellation
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Get path to the whisperkit-worker binary
+pub fn get_worker_path(app: &AppHandle) -> Result<PathBuf> {
+    // Try Tauri's sidecar resolution first (works in production)
+    if let Ok(sidecar) = app
+        .path()
+        .resolve("binaries/whisperkit-worker", tauri::path::BaseDirectory::Resource)
+    {
+        if sidecar.exists() {
+            println!("[WhisperKit] Found bundled worker at: {:?}", sidecar);
+            return Ok(sidecar);
+        }
+    }
+
+    // Try various locations for the worker binary
+    let possible_paths = [
+        // Development: binaries folder with target triple suffix (Tauri sidecar format)
+        Some(PathBuf::from(format!(
+            "binaries/whisperkit-worker-{}",
+            target_triple()
+        ))),
+        // Development: built whisperkit-worker in the whisperkit-worker folder
+        Some(PathBuf::from(
+            "whisperkit-worker/.build/release/whisperkit-worker",
+        )),
+        // Development: relative from src-tauri
+        Some(PathBuf::from(
+            "../whisperkit-worker/.build/release/whisperkit-worker",
+        )),
+        // Development: same directory as main binary
+        std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(|d| d.join("whisperkit-worker"))),
+        // Fallback: current directory
+        Some(PathBuf::from("./whisperkit-worker")),
+    ];
+
+    for path in possible_paths.iter().flatten() {
+        println!("[WhisperKit] Checking path: {:?}", path);
+        if path.exists() {
+            println!("[WhisperKit] Found worker at: {:?}", path);
+            return Ok(path.clone());
+        }
+    }
+
+    Err(anyhow!(
+        "WhisperKit worker binary not found. Build it with: cd whisperkit-worker && swift build -c release"
+    ))
+}
+
+/// Batch transcription through the whisperkit-worker subprocess
+pub struct WhisperKitBackend {
+    worker_path: PathBuf,
+}
+
+impl WhisperKitBackend {
+    /// Create a backend, failing early if WhisperKit can't run on this platform
+    pub fn new(app: &AppHandle) -> Result<Self> {
+        if !cfg!(target_os = "macos") {
+            return Err(anyhow!(
+                "WhisperKit is only available on macOS. Switch the transcription backend to whisper.cpp in Settings."
+            ));
+        }
+
+        Ok(Self {
+            worker_path: get_worker_path(app)?,
+        })
+    }
+}
+
+impl TranscriptionBackend for WhisperKitBackend {
+    fn kind(&self) -> TranscriptionBackendKind {
+        TranscriptionBackendKind::WhisperKit
+    }
+
+    fn requires_downloaded_model(&self) -> bool {
+        // WhisperKit auto-downloads and caches its models on first run
+        false
+    }
+
+    fn load_model(&self, model_id: &str, _model_path: Option<&Path>) -> Result<()> {
+        println!("[WhisperKit] Setting model preference: {}", model_id);
+        Ok(())
+    }
+
+    fn unload_model(&self) {}
+
+    fn transcribe(
+        &self,
+        request: &TranscriptionRequest,
+        progress: ProgressReporter,
+        cancel: CancellationToken,
+    ) -> Result<Vec<TranscriptSegment>> {
+        println!("[WhisperKit] Running whisperkit-worker subprocess...");
+
+        // The worker opens the file itself, so give it a decrypted copy if needed
+        let audio = encryption::decrypted_audio(&request.audio_path)?;
+
+        let mut command = Command::new(&self.worker_path);
+        command
+            .arg(audio.path())
+            .arg("--json")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(ref language) = request.language {
+            command.arg("--language").arg(language);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run whisperkit-worker: {}", e))?;
+
+        // Drain stdout on its own thread so a large JSON result can't block the worker
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("Failed to capture worker stdout"))?;
+        let stdout_reader = std::thread::spawn(move || {
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).map(|_| output)
+        });
+
+        // Stream stderr line by line, forwarding progress updates
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| anyhow!("Failed to capture worker stderr"))?;
+
+        // Kill the worker if the job is cancelled; stderr then closes and the loop below ends
+        let child = Arc::new(Mutex::new(child));
+        let watcher = {
+            let child = child.clone();
+            let cancel = cancel.clone();
+            std::thread::spawn(move || loop {
+                {
+                    let mut child = child.lock();
+                    if !matches!(child.try_wait(), Ok(None)) {
+                        break;
+                    }
+                    if cancel.is_cancelled() {
+                        println!("[WhisperKit] Cancelled, killing worker");
+                        let _ = child.kill();
+                        break;
+                    }
+                }
+                std::thread::sleep(CANCEL_POLL_INTERVAL);
+            })
+        };
+
+        let mut stderr_log = String::new();
+        for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+            match parse_progress_line(&line) {
+                Some(fraction) => progress.report(fraction),
+                None => {
+                    println!("[WhisperKit] {}", line);
+                    stderr_log.push_str(&line);
+                    stderr_log.push('\n');
+                }
+            }
+        }
+
+        let _ = watcher.join();
+        let status = child
+            .lock()
+            .wait()
+            .map_err(|e| anyhow!("Failed to wait for whisperkit-worker: {}", e))?;
+        let stdout = stdout_reader
+            .join()
+            .map_err(|_| anyhow!("Worker stdout reader panicked"))?
+            .map_err(|e| anyhow!("Failed to read worker output: {}", e))?;
+
+        cancel.check()?;
+
+        // Check for errors
+        if !status.success() {
+            println!("[WhisperKit] Worker failed with stderr: {}", stderr_log);
+            return Err(anyhow!("Transcription failed: {}", stderr_log.trim()));
+        }
+
+        // Get segments from stdout
+        let segments = parse_worker_output(&stdout)?;
+
+        if segments.is_empty() {
+            println!("[WhisperKit] Worker stderr: {}", stderr_log);
+            return Err(anyhow!("Transcription produced no output"));
+        }
+
+        Ok(segments)
+    }
+}
+
+/// Parse a `PROGRESS <fraction>` line printed by the worker on stderr
+fn parse_progress_line(line: &str) -> Option<f32> {
+    line.trim()
+        .strip_prefix("PROGRESS ")
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .filter(|f| f.is_finite())
+}
+
+/// Parse the JSON segment array printed by `whisperkit-worker --json`
+fn parse_worker_output(stdout: &str) -> Result<Vec<TranscriptSegment>> {
+    let segments: Vec<TranscriptSegment> = serde_json::from_str(stdout.trim())
+        .map_err(|e| anyhow!("Failed to parse worker output: {}", e))?;
+
+    Ok(segments
+        .into_iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_worker_output() {
+        let stdout = r#"[{"start":0.0,"end":2.4,"text":" Hello there.","avgLogprob":-0.21},{"start":2.4,"end":3.0,"text":" "}]"#;
+        let segments = parse_worker_output(stdout).unwrap();
+
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].end, 2.4);
+        assert_eq!(segments[0].avg_logprob, Some(-0.21));
+    }
+
+    #[test]
+    fn test_parse_worker_output_with_words() {
+        let stdout = r#"[{"start":0.0,"end":1.0,"text":"Hi all","avgLogprob":-0.1,"words":[{"text":"Hi","start":0.0,"end":0.4,"probability":0.97},{"text":"all","start":0.4,"end":1.0,"probability":0.42}]}]"#;
+        let segments = parse_worker_output(stdout).unwrap();
+
+        let words = segments[0].words.as_ref().unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].probability, 0.42);
+    }
+
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(parse_progress_line("PROGRESS 0.42"), Some(0.42));
+        assert_eq!(parse_progress_line("PROGRESS 1"), Some(1.0));
+        assert_eq!(parse_progress_line("Transcribing audio..."), None);
+        assert_eq!(parse_progress_line("PROGRESS nan"), None);
+    }
+
+    #[test]
+    fn test_parse_worker_output_rejects_plain_text() {
+        assert!(parse_worker_output("Hello there.").is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! Workspace types
+//!
+//! Every workspace and template belongs to a type. The four built-in types
+//! (therapy, legal, research, general) are seeded by migration 009; teams can
+//! add their own, such as coaching or HR interviews. A type names what its
+//! folders are called, can point at the template notes default to, and can
+//! carry a JSON schema for folder metadata (see `folder_metadata`).
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use super::folder_metadata::MetadataSchema;
+use crate::models::{CreateWorkspaceTypeRequest, UpdateWorkspaceTypeRequest, WorkspaceType};
+
+const TYPE_COLUMNS: &str = "id, name, folder_label, description, icon, default_template_id, metadata_schema, is_system, created_at, updated_at";
+
+fn type_from_row(row: SqliteRow) -> WorkspaceType {
+    WorkspaceType {
+        id: row.get("id"),
+        name: row.get("name"),
+        folder_label: row.get("folder_label"),
+        description: row.get("description"),
+        icon: row.get("icon"),
+        default_template_id: row.get("default_template_id"),
+        metadata_schema: row.get("metadata_schema"),
+        is_system: row.get::<i32, _>("is_system") == 1,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/// Slug for a type's id: lowercase ASCII letters and digits joined by
+/// underscores, so "Medical Dictation" becomes "medical_dictation"
+pub fn slugify(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Empty strings clear an optional field
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn required(value: &str, field: &str) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(anyhow!("Workspace type {} can't be empty", field));
+    }
+    Ok(value.to_string())
+}
+
+/// Folders of the type are checked against the schema, so it must be one
+/// `folder_metadata` understands
+fn check_schema(schema: Option<&str>) -> Result<()> {
+    if let Some(schema) = schema {
+        MetadataSchema::parse(schema)?;
+    }
+    Ok(())
+}
+
+async fn check_template(pool: &SqlitePool, template_id: Option<&str>) -> Result<()> {
+    if let Some(template_id) = template_id {
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM templates WHERE id = ?")
+            .bind(template_id)
+            .fetch_one(pool)
+            .await?;
+        if exists == 0 {
+            return Err(anyhow!("Template not found: {}", template_id));
+        }
+    }
+    Ok(())
+}
+
+/// Built-in types first, then custom ones by name
+pub async fn list(pool: &SqlitePool) -> Result<Vec<WorkspaceType>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM workspace_types ORDER BY is_system DESC, name",
+        TYPE_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(type_from_row).collect())
+}
+
+pub async fn get(pool: &SqlitePool, id: &str) -> Result<WorkspaceType> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM workspace_types WHERE id = ?",
+        TYPE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow!("Unknown workspace type: {}", id))?;
+    Ok(type_from_row(row))
+}
+
+/// Add a custom type. Its id is the slug of its name.
+pub async fn create(
+    pool: &SqlitePool,
+    request: &CreateWorkspaceTypeRequest,
+    now: i64,
+) -> Result<WorkspaceType> {
+    let name = required(&request.name, "name")?;
+    let folder_label = required(&request.folder_label, "folder label")?;
+    let id = slugify(&name);
+    if id.is_empty() {
+        return Err(anyhow!(
+            "Workspace type name needs at least one letter or digit"
+        ));
+    }
+    let metadata_schema = non_empty(request.metadata_schema.as_deref());
+    check_schema(metadata_schema)?;
+    let default_template_id = non_empty(request.default_template_id.as_deref());
+    check_template(pool, default_template_id).await?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO workspace_types
+            (id, name, folder_label, description, icon, default_template_id, metadata_schema, is_system, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&name)
+    .bind(&folder_label)
+    .bind(non_empty(request.description.as_deref()))
+    .bind(non_empty(request.icon.as_deref()))
+    .bind(default_template_id)
+    .bind(metadata_schema)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+    if inserted.rows_affected() == 0 {
+        return Err(anyhow!("A workspace type named {} already exists", name));
+    }
+
+    println!("[WorkspaceTypes] Created workspace type {}", id);
+    get(pool, &id).await
+}
+
+/// Change a type's labels, default template or schema. Its id never changes,
+/// since workspaces and templates refer to it.
+pub async fn update(
+    pool: &SqlitePool,
+    request: &UpdateWorkspaceTypeRequest,
+    now: i64,
+) -> Result<WorkspaceType> {
+    let current = get(pool, &request.id).await?;
+
+    let name = match &request.name {
+        Some(name) => required(name, "name")?,
+        None => current.name,
+    };
+    let folder_label = match &request.folder_label {
+        Some(label) => required(label, "folder label")?,
+        None => current.folder_label,
+    };
+    let pick = |update: &Option<String>, current: Option<String>| match update {
+        Some(value) => non_empty(Some(value.as_str())).map(str::to_string),
+        None => current,
+    };
+    let description = pick(&request.description, current.description);
+    let icon = pick(&request.icon, current.icon);
+    let default_template_id = pick(&request.default_template_id, current.default_template_id);
+    let metadata_schema = pick(&request.metadata_schema, current.metadata_schema);
+    check_schema(metadata_schema.as_deref())?;
+    check_template(pool, default_template_id.as_deref()).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE workspace_types
+        SET name = ?, folder_label = ?, description = ?, icon = ?,
+            default_template_id = ?, metadata_schema = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&name)
+    .bind(&folder_label)
+    .bind(&description)
+    .bind(&icon)
+    .bind(&default_template_id)
+    .bind(&metadata_schema)
+    .bind(now)
+    .bind(&request.id)
+    .execute(pool)
+    .await?;
+
+    get(pool, &request.id).await
+}
+
+/// Remove a custom type along with its templates. Built-in types, and types
+/// any workspace still uses, including deleted ones, are kept.
+pub async fn delete(pool: &SqlitePool, id: &str) -> Result<()> {
+    let workspace_type = get(pool, id).await?;
+    if workspace_type.is_system {
+        return Err(anyhow!(
+            "{} is a built-in workspace type and can't be deleted",
+            workspace_type.name
+        ));
+    }
+    let workspaces: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM workspaces WHERE workspace_type = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await?;
+    if workspaces > 0 {
+        return Err(anyhow!(
+            "{} is used by {} workspace(s) and can't be deleted",
+            workspace_type.name,
+            workspaces
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM templates WHERE workspace_type = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM workspace_types WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    println!("[WorkspaceTypes] Deleted workspace type {}", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{migrated_pool, seed};
+
+    async fn setup() -> SqlitePool {
+        let pool = migrated_pool().await;
+        seed(
+            &pool,
+            &[
+                "INSERT INTO templates (id, name, workspace_type, prompt, is_default, created_at, updated_at) VALUES ('meeting', 'Meeting Notes', 'general', 'p', 1, 0, 0)",
+            ],
+        )
+        .await;
+        pool
+    }
+
+    fn coaching() -> CreateWorkspaceTypeRequest {
+        CreateWorkspaceTypeRequest {
+            name: "Executive Coaching".to_string(),
+            folder_label: "Coachee".to_string(),
+            description: None,
+            icon: Some("🎯".to_string()),
+            default_template_id: Some("meeting".to_string()),
+            metadata_schema: Some(r#"{"type":"object"}"#.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Medical Dictation"), "medical_dictation");
+        assert_eq!(slugify("  HR -- Interviews 2 "), "hr_interviews_2");
+        assert_eq!(slugify("✨"), "");
+    }
+
+    #[tokio::test]
+    async fn test_create_and_list_types() {
+        let pool = setup().await;
+
+        let created = create(&pool, &coaching(), 100).await.unwrap();
+        assert_eq!(created.id, "executive_coaching");
+        assert_eq!(created.default_template_id.as_deref(), Some("meeting"));
+        assert!(!created.is_system);
+
+        let types = list(&pool).await.unwrap();
+        assert_eq!(types.len(), 5);
+        assert!(types[..4].iter().all(|t| t.is_system));
+        assert_eq!(types[4].id, "executive_coaching");
+
+        // Workspaces and templates can now use it
+        sqlx::query("INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at) VALUES ('ws', 'Coaching', 'executive_coaching', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Same slug twice is rejected
+        assert!(create(&pool, &coaching(), 200).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_validates_input() {
+        let pool = setup().await;
+
+        let mut bad_schema = coaching();
+        bad_schema.metadata_schema = Some("[1, 2]".to_string());
+        assert!(create(&pool, &bad_schema, 0).await.is_err());
+
+        let mut bad_template = coaching();
+        bad_template.default_template_id = Some("missing".to_string());
+        assert!(create(&pool, &bad_template, 0).await.is_err());
+
+        let mut no_label = coaching();
+        no_label.folder_label = "  ".to_string();
+        assert!(create(&pool, &no_label, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_clears_with_empty_string() {
+        let pool = setup().await;
+        create(&pool, &coaching(), 100).await.unwrap();
+
+        let updated = update(
+            &pool,
+            &UpdateWorkspaceTypeRequest {
+                id: "executive_coaching".to_string(),
+                name: None,
+                folder_label: Some("Client".to_string()),
+                description: None,
+                icon: Some(String::new()),
+                default_template_id: None,
+                metadata_schema: None,
+            },
+            200,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(updated.name, "Executive Coaching");
+        assert_eq!(updated.folder_label, "Client");
+        assert_eq!(updated.icon, None);
+        assert_eq!(updated.default_template_id.as_deref(), Some("meeting"));
+        assert_eq!(updated.updated_at, 200);
+    }
+
+    #[tokio::test]
+    async fn test_delete_only_unused_custom_types() {
+        let pool = setup().await;
+        create(&pool, &coaching(), 100).await.unwrap();
+
+        assert!(delete(&pool, "therapy").await.is_err());
+
+        sqlx::query("INSERT INTO workspaces (id, name, workspace_type, created_at, updated_at, is_active) VALUES ('ws', 'Old', 'executive_coaching', 0, 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(delete(&pool, "executive_coaching").await.is_err());
+
+        sqlx::query("DELETE FROM workspaces")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO templates (id, name, workspace_type, prompt, is_system, created_at, updated_at) VALUES ('t', 'Goals', 'executive_coaching', 'p', 0, 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        delete(&pool, "executive_coaching").await.unwrap();
+
+        assert!(get(&pool, "executive_coaching").await.is_err());
+        let templates: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM templates WHERE id = 't'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(templates, 0);
+    }
+}
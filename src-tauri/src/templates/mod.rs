@@ -30,6 +30,20 @@ pub async fn insert_default_templates(pool: &SqlitePool) -> Result<()> {
         insert_template(pool, "general", name, description, prompt, is_default, now).await?;
     }
 
+    // Point each built-in workspace type at its default template
+    sqlx::query(
+        r#"
+        UPDATE workspace_types SET default_template_id = (
+            SELECT id FROM templates
+            WHERE templates.workspace_type = workspace_types.id AND is_default = 1
+            LIMIT 1
+        )
+        WHERE default_template_id IS NULL
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
     Ok(())
 }
 
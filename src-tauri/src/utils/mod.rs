@@ -1,5 +1,6 @@
 //! Utility modules for the application.
 
 pub mod error;
+pub mod platform;
 
 pub use error::IntoTauriResult;
@@ -0,0 +1,36 @@
+//! Platform helpers shared by the sidecar binary lookups.
+
+/// Rust target triple of the running build, as used in Tauri sidecar file names
+/// (e.g. `binaries/whisperkit-worker-aarch64-apple-darwin`).
+pub fn target_triple() -> &'static str {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        "aarch64-apple-darwin"
+    } else if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        "x86_64-apple-darwin"
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        "x86_64-unknown-linux-gnu"
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        "aarch64-unknown-linux-gnu"
+    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        "x86_64-pc-windows-msvc"
+    } else if cfg!(all(target_os = "windows", target_arch = "aarch64")) {
+        "aarch64-pc-windows-msvc"
+    } else {
+        "unknown"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_triple_matches_os() {
+        let triple = target_triple();
+        if cfg!(target_os = "macos") {
+            assert!(triple.ends_with("apple-darwin"));
+        } else if cfg!(target_os = "linux") {
+            assert!(triple.ends_with("linux-gnu"));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! End-to-end tests for the streaming-worker binary
+//!
+//! These run the real binary with the replay engine and talk to it over
+//! stdin/stdout the way the app does, mixing JSON commands with base64 and
+//! binary audio.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const SCRIPT: &str = r#"[
+    {"type":"tentative","at":0.5,"text":"Hello wor"},
+    {"type":"confirmed","at":1.0,"text":"Hello world.","start_time":0.0,"end_time":1.0},
+    {"type":"confirmed","at":2.0,"text":"Still here.","start_time":1.0,"end_time":2.0}
+]"#;
+
+fn write_script(contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("replay-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Binary audio frame, as the app sends to protocol v2 workers
+fn f32_frame(session_id: &str, samples: &[f32]) -> Vec<u8> {
+    let mut frame = vec![0x00, 0x00];
+    frame.extend_from_slice(&(session_id.len() as u16).to_le_bytes());
+    frame.extend_from_slice(session_id.as_bytes());
+    frame.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    for sample in samples {
+        frame.extend_from_slice(&sample.to_le_bytes());
+    }
+    frame
+}
+
+fn line(value: Value) -> Vec<u8> {
+    format!("{}\n", value).into_bytes()
+}
+
+/// Run the worker on `input` and collect its events and exit code
+fn run_worker(script: &str, input: Vec<u8>) -> (Vec<Value>, i32) {
+    let script_path = write_script(script);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_streaming-worker"))
+        .args(["stream", "--replay"])
+        .arg(&script_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to spawn streaming-worker");
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(&input).unwrap();
+    drop(stdin);
+
+    let events = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+        .collect();
+    let status = child.wait().unwrap();
+    std::fs::remove_file(script_path).unwrap();
+    (events, status.code().unwrap_or(-1))
+}
+
+#[test]
+fn test_worker_runs_a_session() {
+    let mut input = line(json!({"cmd":"init","model":null,"language":"en"}));
+    input.extend(line(json!({
+        "cmd":"start","sessionId":"s1","useVAD":true,"confirmationThreshold":2
+    })));
+    // One second as JSON, one as a binary frame
+    let samples = vec![0.1f32; 16_000];
+    let bytes: Vec<u8> = samples.iter().flat_map(|f| f.to_le_bytes()).collect();
+    input.extend(line(json!({
+        "cmd":"audio","sessionId":"s1","samples":BASE64.encode(&bytes),"sampleCount":16_000
+    })));
+    input.extend(f32_frame("s1", &samples));
+    input.extend(line(json!({"cmd":"stop","sessionId":"s1"})));
+    input.extend(line(json!({"cmd":"shutdown"})));
+
+    let (events, code) = run_worker(SCRIPT, input);
+
+    assert_eq!(code, 0);
+    let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+    assert_eq!(
+        types,
+        vec![
+            "ready",
+            "status",
+            "tentative",
+            "confirmed",
+            "confirmed",
+            "complete",
+            "status"
+        ]
+    );
+    assert_eq!(events[0]["model"], "replay");
+    assert_eq!(events[0]["protocol_version"], 2);
+    assert_eq!(events[3]["session_id"], "s1");
+    assert_eq!(events[5]["full_text"], "Hello world. Still here.");
+}
+
+#[test]
+fn test_worker_exits_as_scripted() {
+    let mut input = line(json!({"cmd":"init","model":null,"language":null}));
+    input.extend(line(json!({
+        "cmd":"start","sessionId":"s1","useVAD":false,"confirmationThreshold":2
+    })));
+    input.extend(f32_frame("s1", &[0.0; 1600]));
+
+    let (events, code) = run_worker(r#"[{"type":"exit","at":0.1,"code":7}]"#, input);
+
+    assert_eq!(code, 7);
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn test_worker_rejects_bad_script() {
+    let (events, code) = run_worker("not json", Vec::new());
+    assert_eq!(code, 1);
+    assert!(events.is_empty());
+}
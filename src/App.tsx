@@ -2,11 +2,14 @@ import { useEffect, useState, useCallback } from 'react';
 import { Sidebar } from './components/layout/Sidebar';
 import { MainContent } from './components/layout/MainContent';
 import { OnboardingView } from './components/onboarding/OnboardingView';
+import { UnlockView } from './components/onboarding/UnlockView';
 import { GlobalSearch } from './components/search/GlobalSearch';
 import { ChatPanel } from './components/chat';
+import { InterruptedRecordingsDialog } from './components/recording';
 import { KeyboardShortcutsModal, useKeyboardShortcuts } from './components/ui/KeyboardShortcutsModal';
 import { useAppStore } from './stores/appStore';
 import { logger } from './lib/logger';
+import { getEncryptionStatus } from './lib/tauri';
 import { initializeTheme } from './hooks/useTheme';
 
 // Initialize theme early to prevent flash
@@ -15,19 +18,32 @@ initializeTheme();
 function App() {
   const { initialized, initialize, onboardingComplete } = useAppStore();
   const [loading, setLoading] = useState(true);
+  const [locked, setLocked] = useState(false);
   const [searchOpen, setSearchOpen] = useState(false);
   const shortcuts = useKeyboardShortcuts();
 
-  useEffect(() => {
-    const init = async () => {
-      logger.info('App initializing...', { context: 'App' });
-      await initialize();
-      logger.info('App initialized successfully', { context: 'App' });
+  const init = useCallback(async () => {
+    // An encrypted database stays closed until the passphrase is entered
+    const encryption = await getEncryptionStatus().catch(() => null);
+    if (encryption?.enabled && !encryption.unlocked) {
+      logger.info('Database is locked', { context: 'App' });
+      setLocked(true);
       setLoading(false);
-    };
-    init();
+      return;
+    }
+
+    setLocked(false);
+    setLoading(true);
+    logger.info('App initializing...', { context: 'App' });
+    await initialize();
+    logger.info('App initialized successfully', { context: 'App' });
+    setLoading(false);
   }, [initialize]);
 
+  useEffect(() => {
+    init();
+  }, [init]);
+
   // Global keyboard shortcut for search (Cmd+K / Ctrl+K)
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -53,6 +69,10 @@ function App() {
     );
   }
 
+  if (locked) {
+    return <UnlockView onUnlocked={init} />;
+  }
+
   // Show onboarding if not complete (no workspace or no models)
   if (initialized && !onboardingComplete) {
     return <OnboardingView />;
@@ -65,6 +85,7 @@ function App() {
       <GlobalSearch isOpen={searchOpen} onClose={handleCloseSearch} />
       <ChatPanel />
       <KeyboardShortcutsModal isOpen={shortcuts.isOpen} onClose={shortcuts.close} />
+      <InterruptedRecordingsDialog />
     </div>
   );
 }
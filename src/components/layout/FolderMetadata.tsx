@@ -0,0 +1,161 @@
+import { useState } from 'react';
+import { Button, Input } from '../ui';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import { parseMetadata } from '../../lib/utils';
+import type { Folder, MetadataField } from '../../types';
+
+// Form values are kept as text; booleans as 'true', 'false' or '' for unset
+export type MetadataValues = Record<string, string>;
+
+export function metadataValues(fields: MetadataField[], metadata?: string): MetadataValues {
+  const current = parseMetadata(metadata);
+  return Object.fromEntries(
+    fields.map((field) => {
+      const value = current[field.name];
+      return [field.name, value === undefined || value === null ? '' : String(value)];
+    })
+  );
+}
+
+/** Metadata JSON from the form, keeping fields the schema doesn't list */
+export function metadataJson(fields: MetadataField[], values: MetadataValues, metadata?: string): string {
+  const result = parseMetadata(metadata);
+  for (const field of fields) {
+    const text = (values[field.name] ?? '').trim();
+    if (!text) {
+      delete result[field.name];
+    } else if (field.type === 'boolean') {
+      result[field.name] = text === 'true';
+    } else if (field.type === 'number' || field.type === 'integer') {
+      // Left as text when not a number, so the backend reports the field
+      result[field.name] = Number.isNaN(Number(text)) ? text : Number(text);
+    } else {
+      result[field.name] = text;
+    }
+  }
+  return JSON.stringify(result);
+}
+
+interface MetadataFieldInputProps {
+  field: MetadataField;
+  value: string;
+  onChange: (value: string) => void;
+}
+
+export function MetadataFieldInput({ field, value, onChange }: MetadataFieldInputProps) {
+  const id = `metadata-${field.name}`;
+  const label = field.required ? `${field.title} *` : field.title;
+
+  if (field.type === 'boolean' || field.options) {
+    const options = field.type === 'boolean' ? ['true', 'false'] : field.options!.map(String);
+    return (
+      <div className="space-y-1">
+        <label htmlFor={id} className="block text-[12px] font-medium text-[var(--foreground)]">
+          {label}
+        </label>
+        <select
+          id={id}
+          value={value}
+          onChange={(e) => onChange(e.target.value)}
+          className="w-full h-7 px-2 rounded-md border border-[var(--border)] bg-[var(--card)] text-[13px]"
+        >
+          <option value="">—</option>
+          {options.map((option) => (
+            <option key={option} value={option}>
+              {field.type === 'boolean' ? (option === 'true' ? 'Yes' : 'No') : option}
+            </option>
+          ))}
+        </select>
+      </div>
+    );
+  }
+
+  return (
+    <Input
+      id={id}
+      label={label}
+      type={field.date ? 'date' : field.type === 'string' ? 'text' : 'number'}
+      step={field.type === 'integer' ? 1 : 'any'}
+      value={value}
+      onChange={(e) => onChange(e.target.value)}
+    />
+  );
+}
+
+interface FolderMetadataPanelProps {
+  folder: Folder;
+  fields: MetadataField[];
+}
+
+/** The current folder's metadata fields, editable in place */
+export function FolderMetadataPanel({ folder, fields }: FolderMetadataPanelProps) {
+  const [editing, setEditing] = useState(false);
+  const [values, setValues] = useState<MetadataValues>({});
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  if (fields.length === 0) return null;
+
+  const current = metadataValues(fields, folder.metadata);
+  const filled = fields.filter((field) => current[field.name]);
+
+  const startEditing = () => {
+    setValues(current);
+    setError(null);
+    setEditing(true);
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      await useWorkspaceStore.getState().updateFolder(folder.id, {
+        metadata: metadataJson(fields, values, folder.metadata),
+      });
+      setEditing(false);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (!editing) {
+    return (
+      <div className="mx-4 mt-3 p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] flex items-start justify-between gap-3">
+        <p className="text-[12px] text-[var(--muted-foreground)]">
+          {filled.length === 0
+            ? 'No details yet'
+            : filled.map((field) => `${field.title}: ${current[field.name]}`).join(' · ')}
+        </p>
+        <Button size="sm" variant="ghost" onClick={startEditing}>
+          Edit
+        </Button>
+      </div>
+    );
+  }
+
+  return (
+    <div className="mx-4 mt-3 p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+      <div className="grid grid-cols-2 gap-2">
+        {fields.map((field) => (
+          <MetadataFieldInput
+            key={field.name}
+            field={field}
+            value={values[field.name] ?? ''}
+            onChange={(value) => setValues((v) => ({ ...v, [field.name]: value }))}
+          />
+        ))}
+      </div>
+      {error && <p className="text-[11px] text-[var(--destructive)]">{error}</p>}
+      <div className="flex justify-end gap-2">
+        <Button size="sm" variant="ghost" onClick={() => setEditing(false)}>
+          Cancel
+        </Button>
+        <Button size="sm" loading={saving} onClick={handleSave}>
+          Save
+        </Button>
+      </div>
+    </div>
+  );
+}
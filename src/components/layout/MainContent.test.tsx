@@ -214,29 +214,17 @@ describe('MainContent', () => {
       expect(screen.getByText('Interview')).toBeInTheDocument();
     });
 
-    it('should show transcript preview', () => {
+    it('should not show transcript content in the list', () => {
       mockState = {
         ...defaultMockState,
         currentFolder: createMockFolder({ id: 'f1', name: 'Test Folder', sessionCount: 1 }),
         sessions: [
-          createMockSession({ id: 's1', title: 'Session', status: 'complete', transcript: 'This is a preview of the transcript content', createdAt: Date.now() / 1000 }),
+          createMockSession({ id: 's1', title: 'Session', status: 'complete', transcript: 'This is the transcript content', createdAt: Date.now() / 1000 }),
         ],
       };
       render(<MainContent />);
-      expect(screen.getByText('This is a preview of the transcript content')).toBeInTheDocument();
-    });
-
-    it('should truncate long transcript preview', () => {
-      const longTranscript = 'A'.repeat(100);
-      mockState = {
-        ...defaultMockState,
-        currentFolder: createMockFolder({ id: 'f1', name: 'Test Folder', sessionCount: 1 }),
-        sessions: [
-          createMockSession({ id: 's1', title: 'Session', status: 'complete', transcript: longTranscript, createdAt: Date.now() / 1000 }),
-        ],
-      };
-      render(<MainContent />);
-      expect(screen.getByText('A'.repeat(80) + '...')).toBeInTheDocument();
+      expect(screen.getByText('Transcribed')).toBeInTheDocument();
+      expect(screen.queryByText('This is the transcript content')).not.toBeInTheDocument();
     });
 
     it('should select session on click', () => {
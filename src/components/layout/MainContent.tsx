@@ -1,10 +1,16 @@
 import { useAppStore } from '../../stores/appStore';
-import { WORKSPACE_CONFIG } from '../../types';
+import { getWorkspaceConfig } from '../../types';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import { useSessionStore } from '../../stores/useSessionStore';
 import { Button } from '../ui/Button';
 import { RecordingView } from '../recording/RecordingView';
 import { SessionDetail } from '../session/SessionDetail';
 import { SettingsView } from '../settings/SettingsView';
 import { formatDistanceToNow } from 'date-fns';
+import { SESSION_DRAG_TYPE, metadataFields } from '../../lib/utils';
+import { FolderMetadataPanel } from './FolderMetadata';
+import { TagFilter } from './TagFilter';
+import { Badge } from '../ui';
 
 export function MainContent() {
   const {
@@ -17,9 +23,14 @@ export function MainContent() {
     selectSession,
   } = useAppStore();
 
+  const workspaceTypes = useWorkspaceStore((state) => state.workspaceTypes);
+  const tagFilter = useSessionStore((state) => state.tagFilter);
   const config = currentWorkspace
-    ? WORKSPACE_CONFIG[currentWorkspace.workspaceType as keyof typeof WORKSPACE_CONFIG]
+    ? getWorkspaceConfig(currentWorkspace.workspaceType, workspaceTypes)
     : null;
+  const fields = metadataFields(
+    workspaceTypes.find((t) => t.id === currentWorkspace?.workspaceType)?.metadataSchema
+  );
 
   if (view === 'recording') {
     return <RecordingView />;
@@ -60,6 +71,9 @@ export function MainContent() {
         )}
       </header>
 
+      {currentFolder && <FolderMetadataPanel key={currentFolder.id} folder={currentFolder} fields={fields} />}
+      {currentFolder && <TagFilter folderId={currentFolder.id} />}
+
       {/* Content */}
       <div className="flex-1 overflow-y-auto">
         {!currentFolder ? (
@@ -76,6 +90,12 @@ export function MainContent() {
               </p>
             </div>
           </div>
+        ) : sessions.length === 0 && tagFilter.length > 0 ? (
+          <div className="h-full flex items-center justify-center p-6">
+            <p className="text-[13px] text-[var(--muted-foreground)]">
+              No sessions here carry {tagFilter.length === 1 ? 'this tag' : 'all of these tags'}.
+            </p>
+          </div>
         ) : sessions.length === 0 ? (
           <div className="h-full flex items-center justify-center p-6">
             <div className="text-center max-w-xs">
@@ -107,6 +127,12 @@ export function MainContent() {
                 <button
                   key={session.id}
                   onClick={() => selectSession(session)}
+                  draggable
+                  onDragStart={(e) => {
+                    // Dropped on a folder in the sidebar to move it there
+                    e.dataTransfer.setData(SESSION_DRAG_TYPE, session.id);
+                    e.dataTransfer.effectAllowed = 'move';
+                  }}
                   className="w-full text-left px-3 py-2 rounded-md border-l-2 border-l-transparent hover:bg-[var(--secondary)] hover:border-l-[var(--primary)] hover:shadow-[var(--shadow-xs)] transition-all duration-150 group"
                 >
                   <div className="flex items-center gap-3">
@@ -138,10 +164,16 @@ export function MainContent() {
                           {session.title || formatDate(session.createdAt)}
                         </h3>
                         <StatusBadge status={session.status} />
+                        {session.tags?.map((tag) => (
+                          <Badge key={tag} variant="outline">
+                            {tag}
+                          </Badge>
+                        ))}
                       </div>
+                      {/* The list carries no content; opening a session loads it */}
                       <p className="text-[12px] text-[var(--muted-foreground)] truncate">
-                        {session.transcript
-                          ? session.transcript.slice(0, 80) + (session.transcript.length > 80 ? '...' : '')
+                        {session.status === 'complete'
+                          ? 'Transcribed'
                           : session.status === 'transcribing'
                           ? 'Transcribing...'
                           : session.status === 'error'
@@ -1,18 +1,27 @@
-import { useState } from 'react';
+import { useState, useEffect } from 'react';
 import { clsx } from 'clsx';
 import { useAppStore } from '../../stores/appStore';
-import { WORKSPACE_CONFIG } from '../../types';
+import { getWorkspaceConfig } from '../../types';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
 import { Button, Input, Dialog, DialogActions } from '../ui';
 import { useToast } from '../ui/Toast';
 import { logger } from '../../lib/logger';
+import { folderTree, metadataFields, SESSION_DRAG_TYPE } from '../../lib/utils';
+import { MetadataFieldInput, metadataJson, type MetadataValues } from './FolderMetadata';
+import type { Folder as FolderType } from '../../types';
 import {
   Plus,
   Folder,
+  FolderPlus,
   Settings,
   Trash2,
   ChevronDown,
 } from 'lucide-react';
 
+const FOLDER_DRAG_TYPE = 'application/x-transcript-folder';
+// Drop target id for moving a folder to the top level
+const TOP_LEVEL = '';
+
 export function Sidebar() {
   const {
     workspaces,
@@ -29,19 +38,87 @@ export function Sidebar() {
 
   const [showNewFolder, setShowNewFolder] = useState(false);
   const [newFolderName, setNewFolderName] = useState('');
+  const [newFolderParent, setNewFolderParent] = useState<FolderType | null>(null);
+  const [newFolderValues, setNewFolderValues] = useState<MetadataValues>({});
+  const [dropTarget, setDropTarget] = useState<string | null>(null);
   const [pendingDelete, setPendingDelete] = useState<{ id: string; name: string } | null>(null);
 
+  const workspaceTypes = useWorkspaceStore((state) => state.workspaceTypes);
   const config = currentWorkspace
-    ? WORKSPACE_CONFIG[currentWorkspace.workspaceType as keyof typeof WORKSPACE_CONFIG]
+    ? getWorkspaceConfig(currentWorkspace.workspaceType, workspaceTypes)
     : null;
+  // Fields the type's schema requires must be filled in to create a folder
+  const requiredFields = metadataFields(
+    workspaceTypes.find((t) => t.id === currentWorkspace?.workspaceType)?.metadataSchema
+  ).filter((field) => field.required);
+
+  useEffect(() => {
+    useWorkspaceStore
+      .getState()
+      .loadWorkspaceTypes()
+      .catch((error) => logger.error(`Failed to load workspace types: ${error}`, { context: 'Sidebar' }));
+  }, []);
 
   const handleCreateFolder = async () => {
     if (!newFolderName.trim()) return;
-    await createFolder(newFolderName.trim());
+    try {
+      if (requiredFields.length > 0) {
+        const metadata = metadataJson(requiredFields, newFolderValues);
+        await createFolder(newFolderName.trim(), newFolderParent?.id, metadata);
+      } else if (newFolderParent) {
+        await createFolder(newFolderName.trim(), newFolderParent.id);
+      } else {
+        await createFolder(newFolderName.trim());
+      }
+    } catch (error) {
+      logger.error(`Failed to create folder: ${error}`, { context: 'Sidebar', data: error });
+      addToast(String(error), 'error');
+      return;
+    }
     setNewFolderName('');
+    setNewFolderParent(null);
+    setNewFolderValues({});
     setShowNewFolder(false);
   };
 
+  const openNewFolder = (parent: FolderType | null) => {
+    setNewFolderParent(parent);
+    setShowNewFolder(true);
+  };
+
+  const handleNewSubfolder = (e: React.MouseEvent, parent: FolderType) => {
+    e.stopPropagation(); // Prevent selecting the folder
+    openNewFolder(parent);
+  };
+
+  const handleDragOver = (e: React.DragEvent, targetId: string) => {
+    const types = e.dataTransfer.types;
+    // Sessions need a folder to land in
+    if (types.includes(FOLDER_DRAG_TYPE) || (targetId !== TOP_LEVEL && types.includes(SESSION_DRAG_TYPE))) {
+      e.preventDefault();
+      setDropTarget(targetId);
+    }
+  };
+
+  const handleDrop = async (e: React.DragEvent, targetId: string) => {
+    e.preventDefault();
+    setDropTarget(null);
+    const folderId = e.dataTransfer.getData(FOLDER_DRAG_TYPE);
+    const sessionId = e.dataTransfer.getData(SESSION_DRAG_TYPE);
+
+    try {
+      if (folderId && folderId !== targetId) {
+        await useWorkspaceStore.getState().moveFolder(folderId, targetId || null);
+      } else if (sessionId && targetId) {
+        await useAppStore.getState().moveSession(sessionId, targetId);
+        addToast('Session moved', 'success');
+      }
+    } catch (error) {
+      logger.error(`Failed to move: ${error}`, { context: 'Sidebar', data: error });
+      addToast(String(error), 'error');
+    }
+  };
+
   const handleDeleteFolder = (e: React.MouseEvent, folderId: string, folderName: string) => {
     e.stopPropagation(); // Prevent selecting the folder
     logger.info(`Delete folder clicked: ${folderName} (${folderId})`, { context: 'Sidebar' });
@@ -94,7 +171,7 @@ export function Sidebar() {
           >
             {workspaces.map((workspace) => (
               <option key={workspace.id} value={workspace.id}>
-                {WORKSPACE_CONFIG[workspace.workspaceType as keyof typeof WORKSPACE_CONFIG]?.icon}{' '}
+                {getWorkspaceConfig(workspace.workspaceType, workspaceTypes)?.icon}{' '}
                 {workspace.name}
               </option>
             ))}
@@ -107,7 +184,15 @@ export function Sidebar() {
 
       {/* Folders List */}
       <div className="flex-1 overflow-y-auto px-2 pb-3">
-        <div className="flex items-center justify-between mb-1 px-2">
+        <div
+          className={clsx(
+            'flex items-center justify-between mb-1 px-2 rounded-md',
+            dropTarget === TOP_LEVEL && 'ring-2 ring-[var(--ring)]'
+          )}
+          onDragOver={(e) => handleDragOver(e, TOP_LEVEL)}
+          onDragLeave={() => setDropTarget(null)}
+          onDrop={(e) => handleDrop(e, TOP_LEVEL)}
+        >
           <h3 className="text-[11px] font-medium text-[var(--muted-foreground)]">
             {config?.folderLabel || 'Folders'}
           </h3>
@@ -116,6 +201,11 @@ export function Sidebar() {
         {/* New Folder Form */}
         {showNewFolder ? (
           <div className="mb-2 p-2 rounded-md bg-[var(--secondary)] space-y-2">
+            {newFolderParent && (
+              <p className="text-[11px] text-[var(--muted-foreground)] truncate">
+                Inside {newFolderParent.name}
+              </p>
+            )}
             <Input
               placeholder={`${config?.folderLabel?.slice(0, -1) || 'Folder'} name`}
               value={newFolderName}
@@ -124,18 +214,34 @@ export function Sidebar() {
               autoFocus
               className="bg-[var(--card)] text-[13px] h-7"
             />
+            {requiredFields.map((field) => (
+              <MetadataFieldInput
+                key={field.name}
+                field={field}
+                value={newFolderValues[field.name] ?? ''}
+                onChange={(value) => setNewFolderValues((v) => ({ ...v, [field.name]: value }))}
+              />
+            ))}
             <div className="flex gap-1.5">
               <Button size="sm" onClick={handleCreateFolder} className="flex-1 h-6 text-[12px]">
                 Create
               </Button>
-              <Button size="sm" variant="ghost" onClick={() => setShowNewFolder(false)} className="h-6 text-[12px]">
+              <Button
+                size="sm"
+                variant="ghost"
+                onClick={() => {
+                  setShowNewFolder(false);
+                  setNewFolderParent(null);
+                }}
+                className="h-6 text-[12px]"
+              >
                 Cancel
               </Button>
             </div>
           </div>
         ) : (
           <button
-            onClick={() => setShowNewFolder(true)}
+            onClick={() => openNewFolder(null)}
             className="w-full mb-1 px-2 py-1.5 text-[13px] text-[var(--muted-foreground)] hover:text-[var(--foreground)]
                        hover:bg-[var(--secondary)] rounded-md transition-colors text-left flex items-center gap-2"
             aria-label={`New ${config?.folderLabel?.slice(0, -1) || 'Folder'}`}
@@ -146,16 +252,26 @@ export function Sidebar() {
         )}
 
         <div className="space-y-px">
-          {folders.map((folder) => (
+          {folderTree(folders).map(({ folder, depth }) => (
             <div
               key={folder.id}
               className={clsx(
                 'group relative w-full text-left px-2 py-1.5 rounded-md text-[13px] transition-all duration-150 cursor-pointer',
                 currentFolder?.id === folder.id
                   ? 'bg-[var(--primary)] text-white shadow-[var(--shadow-sm)]'
-                  : 'hover:bg-[var(--secondary)] hover:shadow-[var(--shadow-xs)] text-[var(--foreground)]'
+                  : 'hover:bg-[var(--secondary)] hover:shadow-[var(--shadow-xs)] text-[var(--foreground)]',
+                dropTarget === folder.id && 'ring-2 ring-[var(--ring)]'
               )}
+              style={{ paddingLeft: 8 + depth * 12 }}
               onClick={() => selectFolder(folder)}
+              draggable
+              onDragStart={(e) => {
+                e.dataTransfer.setData(FOLDER_DRAG_TYPE, folder.id);
+                e.dataTransfer.effectAllowed = 'move';
+              }}
+              onDragOver={(e) => handleDragOver(e, folder.id)}
+              onDragLeave={() => setDropTarget(null)}
+              onDrop={(e) => handleDrop(e, folder.id)}
             >
               <div className="flex items-center justify-between">
                 <div className="flex items-center gap-2 min-w-0 flex-1">
@@ -173,6 +289,18 @@ export function Sidebar() {
                       {folder.sessionCount}
                     </span>
                   )}
+                  <button
+                    onClick={(e) => handleNewSubfolder(e, folder)}
+                    className={clsx(
+                      'opacity-0 group-hover:opacity-100 p-0.5 rounded transition-opacity',
+                      currentFolder?.id === folder.id
+                        ? 'hover:bg-white/20 text-white/70 hover:text-white'
+                        : 'hover:bg-[var(--secondary)] text-[var(--muted-foreground)] hover:text-[var(--foreground)]'
+                    )}
+                    aria-label={`New subfolder in ${folder.name}`}
+                  >
+                    <FolderPlus size={12} strokeWidth={2} aria-hidden="true" />
+                  </button>
                   <button
                     onClick={(e) => handleDeleteFolder(e, folder.id, folder.name)}
                     className={clsx(
@@ -210,7 +338,7 @@ export function Sidebar() {
         open={!!pendingDelete}
         onClose={cancelDelete}
         title="Delete Folder"
-        description={`Are you sure you want to delete "${pendingDelete?.name}"? This will also delete all sessions inside this folder.`}
+        description={`Are you sure you want to delete "${pendingDelete?.name}"? The folder, its subfolders and their sessions will be moved to the trash.`}
         showClose={false}
       >
         <DialogActions>
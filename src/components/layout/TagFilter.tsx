@@ -0,0 +1,64 @@
+import { useEffect } from 'react';
+import { X } from 'lucide-react';
+import { Badge } from '../ui';
+import { useSessionStore } from '../../stores/useSessionStore';
+import { useTagStore } from '../../stores/useTagStore';
+import { logger } from '../../lib/logger';
+
+interface TagFilterProps {
+  folderId: string;
+}
+
+/** Narrows the session list to sessions carrying every chosen tag */
+export function TagFilter({ folderId }: TagFilterProps) {
+  const tags = useTagStore((state) => state.tags);
+  const tagFilter = useSessionStore((state) => state.tagFilter);
+
+  useEffect(() => {
+    useTagStore
+      .getState()
+      .loadTags()
+      .catch((error) => logger.error(`Failed to load tags: ${error}`, { context: 'TagFilter' }));
+  }, []);
+
+  if (tags.length === 0 && tagFilter.length === 0) return null;
+
+  const setFilter = (names: string[]) =>
+    useSessionStore
+      .getState()
+      .setTagFilter(folderId, names)
+      .catch((error) => logger.error(`Failed to filter sessions: ${error}`, { context: 'TagFilter' }));
+
+  const available = tags.filter((t) => !tagFilter.some((n) => n.toLowerCase() === t.name.toLowerCase()));
+
+  return (
+    <div className="px-4 pt-3 flex flex-wrap items-center gap-1.5">
+      {tagFilter.map((name) => (
+        <Badge key={name} variant="info" size="md" className="gap-1">
+          {name}
+          <button
+            onClick={() => setFilter(tagFilter.filter((n) => n !== name))}
+            aria-label={`Stop filtering by ${name}`}
+          >
+            <X className="w-3 h-3" />
+          </button>
+        </Badge>
+      ))}
+      {available.length > 0 && (
+        <select
+          value=""
+          onChange={(e) => e.target.value && setFilter([...tagFilter, e.target.value])}
+          aria-label="Filter by tag"
+          className="h-6 px-1.5 rounded-md border border-[var(--border)] bg-[var(--card)] text-[12px] text-[var(--muted-foreground)]"
+        >
+          <option value="">Filter by tag…</option>
+          {available.map((tag) => (
+            <option key={tag.id} value={tag.name}>
+              {tag.name} ({tag.sessionCount})
+            </option>
+          ))}
+        </select>
+      )}
+    </div>
+  );
+}
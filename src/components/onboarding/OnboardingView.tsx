@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { useAppStore } from '../../stores/appStore';
-import { WORKSPACE_CONFIG, WorkspaceType, ModelInfo, DownloadProgress } from '../../types';
+import { WORKSPACE_CONFIG, BuiltInWorkspaceType, ModelInfo, DownloadProgress } from '../../types';
 import { Button } from '../ui/Button';
 import { Input } from '../ui/Input';
 
@@ -19,7 +19,7 @@ function formatBytes(bytes: number): string {
 export function OnboardingView() {
   const { createWorkspace, setOnboardingComplete } = useAppStore();
   const [step, setStep] = useState<Step>('welcome');
-  const [selectedType, setSelectedType] = useState<WorkspaceType | null>(null);
+  const [selectedType, setSelectedType] = useState<BuiltInWorkspaceType | null>(null);
   const [workspaceName, setWorkspaceName] = useState('');
   const [finishing, setFinishing] = useState(false);
 
@@ -145,7 +145,7 @@ export function OnboardingView() {
     }
   };
 
-  const handleSelectType = (type: WorkspaceType) => {
+  const handleSelectType = (type: BuiltInWorkspaceType) => {
     setSelectedType(type);
     setWorkspaceName(WORKSPACE_CONFIG[type].label);
   };
@@ -220,7 +220,7 @@ export function OnboardingView() {
             </p>
 
             <div className="grid grid-cols-2 gap-4 mb-8">
-              {(Object.entries(WORKSPACE_CONFIG) as [WorkspaceType, typeof WORKSPACE_CONFIG[WorkspaceType]][]).map(
+              {(Object.entries(WORKSPACE_CONFIG) as [BuiltInWorkspaceType, typeof WORKSPACE_CONFIG[BuiltInWorkspaceType]][]).map(
                 ([type, config]) => (
                   <button
                     key={type}
@@ -0,0 +1,57 @@
+import { useState } from 'react';
+import { Button, Input } from '../ui';
+import { unlockDatabase } from '../../lib/tauri';
+
+interface UnlockViewProps {
+  onUnlocked: () => void;
+}
+
+export function UnlockView({ onUnlocked }: UnlockViewProps) {
+  const [passphrase, setPassphrase] = useState('');
+  const [unlocking, setUnlocking] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!passphrase) return;
+
+    setUnlocking(true);
+    setError(null);
+    try {
+      await unlockDatabase(passphrase);
+      setPassphrase('');
+      onUnlocked();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setUnlocking(false);
+    }
+  };
+
+  return (
+    <div className="flex h-screen items-center justify-center bg-[var(--background)]" data-tauri-drag-region>
+      <form
+        onSubmit={handleSubmit}
+        className="w-full max-w-sm mx-4 p-6 rounded-xl border border-[var(--border)] bg-[var(--card)] shadow-lg space-y-4"
+      >
+        <div>
+          <h1 className="text-[15px] font-semibold">Unlock Private Transcript</h1>
+          <p className="text-[12px] text-[var(--muted-foreground)] mt-1">
+            Your sessions and recordings are encrypted. Enter your passphrase to open them.
+          </p>
+        </div>
+        <Input
+          type="password"
+          label="Passphrase"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          error={error ?? undefined}
+          autoFocus
+        />
+        <Button type="submit" className="w-full" loading={unlocking} disabled={!passphrase}>
+          Unlock
+        </Button>
+      </form>
+    </div>
+  );
+}
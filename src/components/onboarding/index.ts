@@ -1 +1,2 @@
 export { OnboardingView } from './OnboardingView';
+export { UnlockView } from './UnlockView';
@@ -0,0 +1,125 @@
+import { useEffect, useState } from 'react';
+import { Button, Dialog } from '../ui';
+import { discardLiveSession, getInterruptedSessions, recoverLiveSession } from '../../lib/tauri';
+import { formatDateTime, formatDuration } from '../../lib/utils';
+import { useSessionStore } from '../../stores/useSessionStore';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import type { InterruptedSession } from '../../types';
+
+/**
+ * Offers recordings that stopped before they were saved, such as after a
+ * crash, for recovery with the transcript saved while recording.
+ */
+export function InterruptedRecordingsDialog() {
+  const [sessions, setSessions] = useState<InterruptedSession[]>([]);
+  const [open, setOpen] = useState(false);
+  const [busyId, setBusyId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getInterruptedSessions()
+      .then((found) => {
+        setSessions(found);
+        setOpen(found.length > 0);
+      })
+      .catch((e) => console.error('Failed to check for interrupted recordings:', e));
+  }, []);
+
+  const remove = (id: string) => {
+    const remaining = sessions.filter((s) => s.id !== id);
+    setSessions(remaining);
+    if (remaining.length === 0) setOpen(false);
+  };
+
+  const handleRecover = async (session: InterruptedSession) => {
+    setBusyId(session.id);
+    setError(null);
+    try {
+      await recoverLiveSession(session.id);
+      remove(session.id);
+      // Show it straight away if its folder is open
+      const currentFolder = useWorkspaceStore.getState().currentFolder;
+      if (currentFolder?.id === session.folderId) {
+        await useSessionStore.getState().loadSessions(currentFolder.id);
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  const handleDiscard = async (session: InterruptedSession) => {
+    setBusyId(session.id);
+    setError(null);
+    try {
+      await discardLiveSession(session.id);
+      remove(session.id);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  return (
+    <Dialog
+      open={open}
+      onClose={() => setOpen(false)}
+      title="Interrupted Recordings"
+      description="These recordings stopped before they were saved. Recover keeps the transcript saved while recording; Discard deletes it for good."
+      size="md"
+    >
+      <div className="space-y-2 max-h-80 overflow-y-auto">
+        {sessions.map((session) => (
+          <div
+            key={session.id}
+            className="p-3 rounded-lg border border-[var(--border)] space-y-2"
+          >
+            <div className="flex items-start justify-between gap-3">
+              <div className="min-w-0">
+                <p className="text-[13px] font-medium truncate">
+                  {session.title || 'Untitled recording'}
+                </p>
+                <p className="text-[11px] text-[var(--muted-foreground)]">
+                  Started {formatDateTime(session.startedAt)}
+                  {' · '}
+                  {session.segmentCount > 0
+                    ? `${formatDuration(Math.round(session.transcribedSeconds))} transcribed`
+                    : 'Nothing transcribed'}
+                  {' · '}
+                  {session.audioPath ? 'Partial recording found' : 'No recording saved'}
+                </p>
+              </div>
+              <div className="flex gap-1 shrink-0">
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  disabled={busyId !== null}
+                  onClick={() => handleDiscard(session)}
+                >
+                  Discard
+                </Button>
+                <Button
+                  size="sm"
+                  loading={busyId === session.id}
+                  disabled={busyId !== null}
+                  onClick={() => handleRecover(session)}
+                >
+                  Recover
+                </Button>
+              </div>
+            </div>
+            {session.transcriptPreview && (
+              <p className="text-[12px] text-[var(--muted-foreground)] line-clamp-2">
+                {session.transcriptPreview}
+              </p>
+            )}
+          </div>
+        ))}
+      </div>
+
+      {error && <p className="mt-2 text-[11px] text-[var(--destructive)]">{error}</p>}
+    </Dialog>
+  );
+}
@@ -284,6 +284,67 @@ describe('LiveTranscriptionDisplay', () => {
     });
   });
 
+  describe('Worker Restarts', () => {
+    it('should show reconnecting until transcription resumes', async () => {
+      render(<LiveTranscriptionDisplay sessionId="test-session" isActive={true} />);
+
+      await waitFor(() => {
+        expect(mockListen).toHaveBeenCalledWith('streaming-worker-restarting', expect.any(Function));
+      });
+
+      act(() => {
+        emitEvent('live-transcription', {
+          sessionId: 'test-session',
+          text: 'Half a sen',
+          isFinal: false,
+          timestamp: 1,
+        });
+        emitEvent('streaming-worker-restarting', {
+          sessionId: 'test-session',
+          reason: 'Worker exited',
+          attempt: 1,
+          maxAttempts: 5,
+          delayMs: 1000,
+        });
+      });
+
+      expect(screen.getByText('Reconnecting...')).toBeInTheDocument();
+      expect(screen.queryByText('Half a sen')).not.toBeInTheDocument();
+
+      act(() => {
+        emitEvent('live-transcription', {
+          sessionId: 'test-session',
+          text: 'Half a sentence',
+          isFinal: true,
+          timestamp: 1,
+        });
+      });
+
+      expect(screen.getByText('Live Transcription')).toBeInTheDocument();
+      expect(screen.getByText(/Half a sentence/)).toBeInTheDocument();
+    });
+
+    it('should ignore restarts for other sessions', async () => {
+      render(<LiveTranscriptionDisplay sessionId="test-session" isActive={true} />);
+
+      await waitFor(() => {
+        expect(mockListen).toHaveBeenCalledWith('streaming-worker-restarting', expect.any(Function));
+      });
+
+      act(() => {
+        emitEvent('streaming-worker-restarting', {
+          sessionId: 'other-session',
+          reason: 'Worker exited',
+          attempt: 1,
+          maxAttempts: 5,
+          delayMs: 1000,
+        });
+      });
+
+      expect(screen.getByText('Live Transcription')).toBeInTheDocument();
+    });
+  });
+
   describe('Statistics', () => {
     it('should show segment count', async () => {
       render(<LiveTranscriptionDisplay sessionId="test-session" isActive={true} />);
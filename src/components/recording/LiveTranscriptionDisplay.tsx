@@ -1,6 +1,10 @@
 import { useState, useEffect, useRef } from 'react';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
-import type { LiveTranscriptionEvent, TranscriptionErrorEvent } from '../../types';
+import type {
+  LiveTranscriptionEvent,
+  TranscriptionErrorEvent,
+  WorkerRestartingEvent,
+} from '../../types';
 
 interface Props {
   sessionId: string;
@@ -22,6 +26,7 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
   const [confirmedSegments, setConfirmedSegments] = useState<ConfirmedSegment[]>([]);
   const [tentativeText, setTentativeText] = useState('');
   const [error, setError] = useState<string | null>(null);
+  const [reconnecting, setReconnecting] = useState(false);
   const containerRef = useRef<HTMLDivElement>(null);
 
   // Auto-scroll to bottom when new text arrives
@@ -39,6 +44,7 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
 
     let unlistenTranscription: UnlistenFn | null = null;
     let unlistenError: UnlistenFn | null = null;
+    let unlistenRestarting: UnlistenFn | null = null;
 
     const setupListeners = async () => {
       // Listen for live transcription events
@@ -50,6 +56,7 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
           // Only process events for this session
           if (eventSessionId !== sessionId) return;
 
+          setReconnecting(false);
           if (isFinal) {
             // Add to confirmed segments
             setConfirmedSegments((prev) => [...prev, { text, timestamp }]);
@@ -72,9 +79,22 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
           if (eventSessionId && eventSessionId !== sessionId) return;
 
           setError(message);
+          setReconnecting(false);
           onError?.(message);
         }
       );
+
+      // Listen for worker restarts; the transcript picks up where it left off
+      unlistenRestarting = await listen<WorkerRestartingEvent>(
+        'streaming-worker-restarting',
+        (event) => {
+          if (event.payload.sessionId !== sessionId) return;
+
+          setReconnecting(true);
+          // Unconfirmed text is transcribed again by the new worker
+          setTentativeText('');
+        }
+      );
     };
 
     setupListeners();
@@ -82,6 +102,7 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
     return () => {
       unlistenTranscription?.();
       unlistenError?.();
+      unlistenRestarting?.();
     };
   }, [sessionId, isActive, onError]);
 
@@ -90,6 +111,7 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
     setConfirmedSegments([]);
     setTentativeText('');
     setError(null);
+    setReconnecting(false);
   }, [sessionId]);
 
   const confirmedText = confirmedSegments.map((s) => s.text).join(' ');
@@ -101,11 +123,11 @@ export function LiveTranscriptionDisplay({ sessionId, isActive, onError }: Props
       <div className="flex items-center gap-2 mb-2">
         <div
           className={`w-2 h-2 rounded-full ${
-            isActive ? 'bg-green-500 animate-pulse' : 'bg-gray-400'
+            !isActive ? 'bg-gray-400' : reconnecting ? 'bg-amber-500 animate-pulse' : 'bg-green-500 animate-pulse'
           }`}
         />
         <span className="text-xs font-medium text-[var(--muted-foreground)]">
-          {isActive ? 'Live Transcription' : 'Transcription Paused'}
+          {!isActive ? 'Transcription Paused' : reconnecting ? 'Reconnecting...' : 'Live Transcription'}
         </span>
       </div>
 
@@ -2,6 +2,7 @@ import { useState, useRef, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { useAppStore } from '../../stores/appStore';
+import { beginLiveSession, cancelTranscription, discardLiveSession, isCancelledError } from '../../lib/tauri';
 import { Button } from '../ui/Button';
 import { Progress } from '../ui/Progress';
 import { useToast } from '../ui/Toast';
@@ -13,10 +14,13 @@ interface TranscriptionProgress {
   progress: number;
   status: string;
   message?: string;
+  processedSeconds?: number;
+  totalSeconds?: number;
+  etaSeconds?: number;
 }
 
 export function RecordingView() {
-  const { currentFolder, createSession, setView, updateSession } = useAppStore();
+  const { currentFolder, createSession, finishLiveSession, setView, updateSession } = useAppStore();
   const { addToast } = useToast();
   const [isRecording, setIsRecording] = useState(false);
   const [isPaused, setIsPaused] = useState(false);
@@ -51,6 +55,8 @@ export function RecordingView() {
   const audioWorkletRef = useRef<AudioWorkletNode | null>(null);
   const liveTranscriptionContextRef = useRef<AudioContext | null>(null);
   const liveTranscriptionStreamRef = useRef<MediaStream | null>(null);
+  // Session created when live transcription started; saving attaches the recording to it
+  const liveSessionRef = useRef<string | null>(null);
 
   // Listen for transcription progress events
   useEffect(() => {
@@ -60,7 +66,7 @@ export function RecordingView() {
       unlisten = await listen<TranscriptionProgress>('transcription-progress', (event) => {
         setTranscriptionProgress(event.payload);
 
-        if (event.payload.status === 'complete' || event.payload.status === 'error') {
+        if (['complete', 'error', 'cancelled'].includes(event.payload.status)) {
           setIsTranscribing(false);
         }
       });
@@ -180,6 +186,16 @@ export function RecordingView() {
         confirmationThreshold: 2,
       };
 
+      // Create the session now so confirmed segments are saved as they arrive
+      if (currentFolder) {
+        try {
+          await beginLiveSession(sessionId, currentFolder.id, `Recording ${new Date().toLocaleString()}`);
+          liveSessionRef.current = sessionId;
+        } catch (e) {
+          console.error('[LiveTranscription] Failed to create live session:', e);
+        }
+      }
+
       console.log('[LiveTranscription] Invoking start_live_transcription...');
       await invoke('start_live_transcription', {
         sessionId,
@@ -192,7 +208,7 @@ export function RecordingView() {
       console.error('[LiveTranscription] Failed to start:', error);
       addToast('Live transcription unavailable. Recording will continue.', 'warning');
     }
-  }, [addToast]);
+  }, [addToast, currentFolder]);
 
   // Stop live transcription
   const stopLiveTranscription = useCallback(async () => {
@@ -396,10 +412,11 @@ export function RecordingView() {
     const sessionId = crypto.randomUUID();
     setLiveSessionId(sessionId);
     setLiveTranscriptText('');
+    liveSessionRef.current = null;
 
     // Use native recording if system audio is enabled
     if (captureSystemAudio) {
-      await startNativeRecording();
+      await startNativeRecording(sessionId);
       return;
     }
 
@@ -457,13 +474,11 @@ export function RecordingView() {
     }
   };
 
-  const startNativeRecording = async () => {
+  const startNativeRecording = async (sessionId: string) => {
     try {
-      const nativeSessionId = crypto.randomUUID();
-      setNativeSessionId(nativeSessionId);
-
-      // Use the same session ID for live transcription that we set in startRecording
-      const transcriptionSessionId = liveSessionId || nativeSessionId;
+      // The recording file is named after the live session, so an interrupted
+      // recording can be found again
+      setNativeSessionId(sessionId);
 
       const config: RecordingConfig = {
         micDeviceId: selectedMicId,
@@ -474,7 +489,7 @@ export function RecordingView() {
       };
 
       await invoke('start_system_recording', {
-        sessionId: nativeSessionId,
+        sessionId,
         config,
       });
 
@@ -485,7 +500,7 @@ export function RecordingView() {
         try {
           const stream = await navigator.mediaDevices.getUserMedia({ audio: true });
           streamRef.current = stream;
-          await startLiveTranscription(stream, transcriptionSessionId);
+          await startLiveTranscription(stream, sessionId);
         } catch (e) {
           console.warn('Could not start live transcription during system audio recording:', e);
           // Continue without live transcription - native recording will still work
@@ -544,6 +559,16 @@ export function RecordingView() {
     }
   };
 
+  // Attach the recording to the live session if there is one, otherwise create the session now
+  const saveSession = async (audioPath: string) => {
+    const liveId = liveSessionRef.current;
+    liveSessionRef.current = null;
+    if (liveId) {
+      return finishLiveSession(liveId, audioPath, duration);
+    }
+    return createSession(audioPath, `Recording ${new Date().toLocaleString()}`);
+  };
+
   const saveNativeRecording = async (audioPath: string) => {
     if (!currentFolder) return;
 
@@ -552,7 +577,7 @@ export function RecordingView() {
     setTranscriptionProgress(null);
 
     try {
-      const session = await createSession(audioPath, `Recording ${new Date().toLocaleString()}`);
+      const session = await saveSession(audioPath);
       await updateSession(session.id, { status: 'transcribing' });
 
       try {
@@ -566,11 +591,16 @@ export function RecordingView() {
           status: 'complete',
         });
       } catch (error) {
-        console.error('Transcription failed:', error);
-        await updateSession(session.id, {
-          status: 'error',
-          errorMessage: String(error),
-        });
+        if (isCancelledError(error)) {
+          await updateSession(session.id, { status: 'pending' });
+          addToast('Transcription cancelled. The recording was saved.', 'info');
+        } else {
+          console.error('Transcription failed:', error);
+          await updateSession(session.id, {
+            status: 'error',
+            errorMessage: String(error),
+          });
+        }
       }
 
       setView('list');
@@ -638,7 +668,7 @@ export function RecordingView() {
     try {
       const arrayBuffer = await audioBlob.arrayBuffer();
       const audioData = Array.from(new Uint8Array(arrayBuffer));
-      const tempId = crypto.randomUUID();
+      const fileId = liveSessionRef.current ?? crypto.randomUUID();
 
       const mimeType = audioBlob.type;
       let format = 'webm';
@@ -647,12 +677,12 @@ export function RecordingView() {
       else if (mimeType.includes('wav')) format = 'wav';
 
       const audioPath = await invoke<string>('save_audio_file', {
-        sessionId: tempId,
+        sessionId: fileId,
         audioData,
         format,
       });
 
-      const session = await createSession(audioPath, `Recording ${new Date().toLocaleString()}`);
+      const session = await saveSession(audioPath);
       await updateSession(session.id, { status: 'transcribing' });
 
       try {
@@ -666,11 +696,16 @@ export function RecordingView() {
           status: 'complete',
         });
       } catch (error) {
-        console.error('Transcription failed:', error);
-        await updateSession(session.id, {
-          status: 'error',
-          errorMessage: String(error),
-        });
+        if (isCancelledError(error)) {
+          await updateSession(session.id, { status: 'pending' });
+          addToast('Transcription cancelled. The recording was saved.', 'info');
+        } else {
+          console.error('Transcription failed:', error);
+          await updateSession(session.id, {
+            status: 'error',
+            errorMessage: String(error),
+          });
+        }
       }
 
       setView('list');
@@ -697,6 +732,12 @@ export function RecordingView() {
     if (isRecording) {
       stopRecording();
     }
+    if (liveSessionRef.current) {
+      discardLiveSession(liveSessionRef.current).catch((e) =>
+        console.error('Failed to discard live session:', e)
+      );
+      liveSessionRef.current = null;
+    }
     setAudioBlob(null);
     setDuration(0);
     setView('list');
@@ -881,10 +922,27 @@ export function RecordingView() {
                 />
                 <p className="text-xs text-[var(--muted-foreground)] mt-3 text-center">
                   {transcriptionProgress?.status === 'starting' && 'Preparing...'}
-                  {transcriptionProgress?.status === 'transcribing' && 'Processing audio...'}
+                  {transcriptionProgress?.status === 'transcribing' &&
+                    (transcriptionProgress.processedSeconds !== undefined && transcriptionProgress.totalSeconds
+                      ? `${formatDuration(Math.floor(transcriptionProgress.processedSeconds))} of ${formatDuration(Math.floor(transcriptionProgress.totalSeconds))}`
+                      : 'Processing audio...')}
+                  {transcriptionProgress?.status === 'transcribing' &&
+                    transcriptionProgress.etaSeconds !== undefined &&
+                    ` · about ${formatDuration(Math.ceil(transcriptionProgress.etaSeconds))} left`}
                   {transcriptionProgress?.status === 'processing' && 'Almost done...'}
                   {!transcriptionProgress && 'Starting transcription...'}
                 </p>
+                {transcriptionProgress?.sessionId && (
+                  <div className="mt-3 flex justify-center">
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => cancelTranscription(transcriptionProgress.sessionId)}
+                    >
+                      Cancel
+                    </Button>
+                  </div>
+                )}
               </div>
             ) : (
               <div className="flex gap-3 animate-scale-in">
@@ -1 +1,2 @@
 export { RecordingView } from './RecordingView';
+export { InterruptedRecordingsDialog } from './InterruptedRecordingsDialog';
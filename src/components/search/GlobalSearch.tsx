@@ -1,7 +1,13 @@
 import { useState, useEffect, useRef, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../../stores/appStore';
-import type { Session } from '../../types';
+import type { SearchField, Session, SessionSearchResult } from '../../types';
+
+const FIELD_LABELS: Record<SearchField, string> = {
+  title: 'Title',
+  transcript: 'Transcript',
+  note: 'Note',
+};
 
 interface GlobalSearchProps {
   isOpen: boolean;
@@ -10,7 +16,7 @@ interface GlobalSearchProps {
 
 export function GlobalSearch({ isOpen, onClose }: GlobalSearchProps) {
   const [query, setQuery] = useState('');
-  const [results, setResults] = useState<Session[]>([]);
+  const [results, setResults] = useState<SessionSearchResult[]>([]);
   const [loading, setLoading] = useState(false);
   const [selectedIndex, setSelectedIndex] = useState(0);
   const inputRef = useRef<HTMLInputElement>(null);
@@ -36,7 +42,7 @@ export function GlobalSearch({ isOpen, onClose }: GlobalSearchProps) {
     const timer = setTimeout(async () => {
       setLoading(true);
       try {
-        const sessions = await invoke<Session[]>('search_sessions', {
+        const sessions = await invoke<SessionSearchResult[]>('search_sessions', {
           query: query.trim(),
           limit: 20,
         });
@@ -79,7 +85,7 @@ export function GlobalSearch({ isOpen, onClose }: GlobalSearchProps) {
     [results, selectedIndex, onClose]
   );
 
-  const handleSelect = (session: Session) => {
+  const handleSelect = (session: SessionSearchResult) => {
     selectSession(session);
     onClose();
   };
@@ -92,31 +98,42 @@ export function GlobalSearch({ isOpen, onClose }: GlobalSearchProps) {
     });
   };
 
+  const renderSnippet = (result: SessionSearchResult, selected: boolean) => {
+    // Prefer body text over the title, which is already shown above
+    const snippet =
+      result.snippets?.find((s) => s.field !== 'title') ?? result.snippets?.[0];
+    if (!snippet) return getPreview(result);
+
+    return snippet.parts.map((part, i) =>
+      part.highlighted ? (
+        <mark
+          key={i}
+          className={`rounded-sm px-0.5 ${
+            selected ? 'bg-white/25 text-white' : 'bg-yellow-200/70 text-inherit'
+          }`}
+        >
+          {part.text}
+        </mark>
+      ) : (
+        <span key={i}>{part.text}</span>
+      )
+    );
+  };
+
+  // Results carry no content beyond their snippets, so without one only the
+  // title can be previewed
   const getPreview = (session: Session) => {
-    // Find where the query matches in the content
     const searchTerm = query.toLowerCase();
-    const sources = [
-      session.title,
-      session.transcript,
-      session.generatedNote,
-    ].filter(Boolean);
-
-    for (const source of sources) {
-      if (!source) continue;
-      const lowerSource = source.toLowerCase();
-      const index = lowerSource.indexOf(searchTerm);
-      if (index !== -1) {
-        const start = Math.max(0, index - 30);
-        const end = Math.min(source.length, index + searchTerm.length + 30);
-        let preview = source.slice(start, end);
-        if (start > 0) preview = '...' + preview;
-        if (end < source.length) preview = preview + '...';
-        return preview;
-      }
-    }
+    const title = session.title ?? '';
+    const index = title.toLowerCase().indexOf(searchTerm);
+    if (index === -1) return '';
 
-    // Fallback to transcript start
-    return session.transcript?.slice(0, 60) + '...';
+    const start = Math.max(0, index - 30);
+    const end = Math.min(title.length, index + searchTerm.length + 30);
+    let preview = title.slice(start, end);
+    if (start > 0) preview = '...' + preview;
+    if (end < title.length) preview = preview + '...';
+    return preview;
   };
 
   if (!isOpen) return null;
@@ -194,6 +211,17 @@ export function GlobalSearch({ isOpen, onClose }: GlobalSearchProps) {
                     <span className="text-[13px] font-medium truncate">
                       {session.title || 'Untitled Session'}
                     </span>
+                    {(session.matchedFields?.length ?? 0) > 0 && (
+                      <span
+                        className={`text-[10px] shrink-0 ml-2 ${
+                          index === selectedIndex
+                            ? 'text-white/70'
+                            : 'text-[var(--muted-foreground)]'
+                        }`}
+                      >
+                        in {session.matchedFields.map((f) => FIELD_LABELS[f] ?? f).join(', ')}
+                      </span>
+                    )}
                     <span
                       className={`text-[11px] shrink-0 ml-2 ${
                         index === selectedIndex
@@ -211,7 +239,7 @@ export function GlobalSearch({ isOpen, onClose }: GlobalSearchProps) {
                         : 'text-[var(--muted-foreground)]'
                     }`}
                   >
-                    {getPreview(session)}
+                    {renderSnippet(session, index === selectedIndex)}
                   </p>
                 </button>
               ))}
@@ -4,8 +4,18 @@ import { convertFileSrc } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { Panel, Group as PanelGroup, Separator as PanelResizeHandle } from 'react-resizable-panels';
 import { useAppStore } from '../../stores/appStore';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import {
+  cancelGeneration,
+  cancelTranscription,
+  getEncryptionStatus,
+  isCancelledError,
+  readAudioFile,
+} from '../../lib/tauri';
 import { Button, Dialog, DialogActions } from '../ui';
 import { useToast } from '../ui/Toast';
+import { SessionTags } from './SessionTags';
+import { folderTree } from '../../lib/utils';
 import type { TranscriptSegment, LlmStreamEvent } from '../../types';
 import {
   ArrowLeft,
@@ -58,8 +68,14 @@ interface SearchMatch {
 export function SessionDetail() {
   const { currentSession, currentWorkspace, currentFolder, templates, setView, updateSession, deleteSession } = useAppStore();
   const { addToast } = useToast();
+  const workspaceTypes = useWorkspaceStore((state) => state.workspaceTypes);
+  const folders = useWorkspaceStore((state) => state.folders);
+  const typeDefault = workspaceTypes.find((t) => t.id === currentWorkspace?.workspaceType)?.defaultTemplateId;
   const [selectedTemplate, setSelectedTemplate] = useState(
-    templates.find((t) => t.isDefault)?.id || templates[0]?.id || ''
+    templates.find((t) => t.id === typeDefault)?.id ||
+      templates.find((t) => t.isDefault)?.id ||
+      templates[0]?.id ||
+      ''
   );
   const [generating, setGenerating] = useState(false);
   const [streamingNote, setStreamingNote] = useState('');
@@ -104,6 +120,19 @@ export function SessionDetail() {
 
   // Parse transcript into speaker segments when speaker view is enabled
   useEffect(() => {
+    if (showSpeakerView && currentSession?.transcriptSegments) {
+      // Prefer real timestamps from batch transcription
+      try {
+        const stored = JSON.parse(currentSession.transcriptSegments) as TranscriptSegment[];
+        if (Array.isArray(stored) && stored.length > 0) {
+          setSpeakerSegments(stored);
+          return;
+        }
+      } catch {
+        // Fall through to parsing the plain transcript
+      }
+    }
+
     if (showSpeakerView && currentSession?.transcript) {
       // Check if transcript already has speaker labels (e.g., "Name: text")
       const hasInlineLabels = /^[A-Z][a-zA-Z\s]*?:\s/m.test(currentSession.transcript);
@@ -117,7 +146,7 @@ export function SessionDetail() {
       }
       setSpeakerSegments(segments);
     }
-  }, [showSpeakerView, currentSession?.transcript, duration]);
+  }, [showSpeakerView, currentSession?.transcript, currentSession?.transcriptSegments, duration]);
 
   // Track which segment is currently playing
   const activeSegmentIndex = useMemo(() => {
@@ -180,6 +209,14 @@ export function SessionDetail() {
     if (editingSpeaker && newSpeakerName.trim()) {
       const updatedSegments = renameSpeaker(speakerSegments, editingSpeaker, newSpeakerName.trim());
       setSpeakerSegments(updatedSegments);
+      // Persist the rename when the speakers came from diarization
+      if (currentSession?.transcriptSegments) {
+        invoke('rename_speaker', {
+          sessionId: currentSession.id,
+          oldName: editingSpeaker,
+          newName: newSpeakerName.trim(),
+        }).catch((error) => console.error('Failed to rename speaker:', error));
+      }
       setEditingSpeaker(null);
       setNewSpeakerName('');
     }
@@ -353,17 +390,41 @@ export function SessionDetail() {
 
   // Convert file path to audio source
   useEffect(() => {
-    if (currentSession?.audioPath) {
-      try {
-        const src = convertFileSrc(currentSession.audioPath);
-        setAudioSrc(src);
-        setAudioError(null);
-      } catch (err) {
-        setAudioError(`Failed to convert audio path: ${err}`);
-      }
-    } else {
+    const audioPath = currentSession?.audioPath;
+    if (!audioPath) {
       setAudioSrc(null);
+      return;
     }
+
+    try {
+      const src = convertFileSrc(audioPath);
+      setAudioSrc(src);
+      setAudioError(null);
+    } catch (err) {
+      setAudioError(`Failed to convert audio path: ${err}`);
+    }
+
+    // Encrypted recordings can't be served by the asset protocol, so read
+    // them through the backend and play them from memory instead
+    let cancelled = false;
+    let objectUrl: string | null = null;
+    getEncryptionStatus()
+      .then(async (encryption) => {
+        if (!encryption?.enabled) return;
+        const data = await readAudioFile(audioPath);
+        if (cancelled) return;
+        objectUrl = URL.createObjectURL(new Blob([data]));
+        setAudioSrc(objectUrl);
+        setAudioError(null);
+      })
+      .catch((err) => {
+        if (!cancelled) setAudioError(`Failed to load audio: ${err}`);
+      });
+
+    return () => {
+      cancelled = true;
+      if (objectUrl) URL.revokeObjectURL(objectUrl);
+    };
   }, [currentSession?.audioPath, currentSession?.id]);
 
   const togglePlayPause = () => {
@@ -445,12 +506,32 @@ export function SessionDetail() {
 
       addToast('Transcript regenerated successfully', 'success');
     } catch (error) {
+      if (isCancelledError(error)) {
+        await updateSession(currentSession.id, {
+          status: currentSession.transcript ? 'complete' : 'pending',
+        });
+        addToast('Transcription cancelled', 'info');
+        return;
+      }
       console.error('Regenerate transcript failed:', error);
       await updateSession(currentSession.id, { status: 'error' });
       addToast('Failed to regenerate transcript', 'error');
     }
   };
 
+  const handleCancel = async () => {
+    if (!currentSession) return;
+    try {
+      if (generating) {
+        await cancelGeneration(currentSession.id);
+      } else {
+        await cancelTranscription(currentSession.id);
+      }
+    } catch (error) {
+      console.error('Cancel failed:', error);
+    }
+  };
+
   if (!currentSession) {
     return null;
   }
@@ -485,6 +566,7 @@ export function SessionDetail() {
         if (sessionId !== currentSession.id) return;
 
         if (error) {
+          if (isCancelledError(error)) return;
           console.error('LLM stream error:', error);
           addToast(`LLM error: ${error}`, 'error');
           return;
@@ -516,6 +598,12 @@ export function SessionDetail() {
       setStreamingNote('');
       addToast('Note generated successfully', 'success');
     } catch (error) {
+      if (isCancelledError(error)) {
+        await updateSession(currentSession.id, { status: 'complete' });
+        setStreamingNote('');
+        addToast('Note generation cancelled', 'info');
+        return;
+      }
       console.error('Failed to generate note:', error);
       addToast(`Failed to generate note: ${error}`, 'error');
       await updateSession(currentSession.id, {
@@ -546,7 +634,7 @@ export function SessionDetail() {
   const handleExport = async (format: 'markdown' | 'pdf' | 'docx' | 'srt' | 'vtt' | 'obsidian') => {
     const filename = `session-${currentSession.id.slice(0, 8)}`;
 
-    // Handle SRT/VTT exports client-side
+    // SRT/VTT are built client-side, then written and audited by the backend
     if (format === 'srt' || format === 'vtt') {
       const segments = displaySegments.length > 0 ? displaySegments : speakerSegments;
       if (segments.length === 0) {
@@ -555,16 +643,17 @@ export function SessionDetail() {
       }
 
       const content = format === 'srt' ? segmentsToSRT(segments) : segmentsToVTT(segments);
-      const blob = new Blob([content], { type: 'text/plain' });
-      const url = URL.createObjectURL(blob);
-      const a = document.createElement('a');
-      a.href = url;
-      a.download = `${filename}.${format}`;
-      document.body.appendChild(a);
-      a.click();
-      document.body.removeChild(a);
-      URL.revokeObjectURL(url);
-      addToast(`Exported ${format.toUpperCase()} file`, 'success');
+      try {
+        const path = await invoke<string>('export_subtitles', {
+          content,
+          filename,
+          format,
+          sessionId: currentSession.id,
+        });
+        addToast(`Exported to: ${path}`, 'success');
+      } catch (error) {
+        addToast('Export failed. Please try again.', 'error');
+      }
       return;
     }
 
@@ -587,6 +676,7 @@ export function SessionDetail() {
           filename: currentSession.title || filename,
           vaultPath,
           tags,
+          sessionId: currentSession.id,
         });
         addToast(`Exported to Obsidian: ${path}`, 'success');
         await invoke('open_file', { path });
@@ -600,7 +690,11 @@ export function SessionDetail() {
     const content = `# ${currentSession.title || 'Session'}\n\n## Transcript\n\n${currentSession.transcript || ''}\n\n## Notes\n\n${currentSession.generatedNote || ''}`;
 
     try {
-      const path = await invoke<string>(`export_${format}`, { content, filename });
+      const path = await invoke<string>(`export_${format}`, {
+        content,
+        filename,
+        sessionId: currentSession.id,
+      });
       addToast(`Exported to: ${path}`, 'success');
       // Open the exported file with the system's default application
       await invoke('open_file', { path });
@@ -625,6 +719,20 @@ export function SessionDetail() {
     setShowDeleteConfirm(true);
   };
 
+  const handleMoveSession = async (folderId: string) => {
+    if (!currentSession || folderId === currentSession.folderId) return;
+    const folder = folders.find((f) => f.id === folderId);
+
+    try {
+      await useAppStore.getState().moveSession(currentSession.id, folderId);
+      addToast(`Moved to ${folder?.name ?? 'folder'}`, 'success');
+      setView('list');
+    } catch (error) {
+      console.error('Failed to move session:', error);
+      addToast('Failed to move session', 'error');
+    }
+  };
+
   const confirmDeleteSession = async () => {
     setShowDeleteConfirm(false);
     if (!currentSession) return;
@@ -677,6 +785,22 @@ export function SessionDetail() {
           </nav>
         </div>
         <div className="flex items-center gap-2">
+          {folders.length > 1 && (
+            <select
+              value={currentSession.folderId}
+              onChange={(e) => handleMoveSession(e.target.value)}
+              className="h-8 px-3 rounded-lg bg-[var(--muted)] border-0 text-xs font-medium cursor-pointer
+                         focus:outline-none focus:ring-2 focus:ring-[var(--primary)]/20"
+              aria-label="Move session to folder"
+            >
+              {folderTree(folders).map(({ folder, depth }) => (
+                <option key={folder.id} value={folder.id}>
+                  {'\u00a0\u00a0'.repeat(depth)}
+                  {folder.name}
+                </option>
+              ))}
+            </select>
+          )}
           <select
             value={selectedTemplate}
             onChange={(e) => setSelectedTemplate(e.target.value)}
@@ -697,6 +821,12 @@ export function SessionDetail() {
           >
             Generate Note
           </Button>
+          {(generating || currentSession.status === 'transcribing') && (
+            <Button variant="secondary" size="sm" onClick={handleCancel}>
+              <X size={12} aria-hidden="true" />
+              Cancel
+            </Button>
+          )}
           <div className="relative group">
             <Button variant="secondary" size="sm">
               <Download size={12} aria-hidden="true" />
@@ -757,6 +887,8 @@ export function SessionDetail() {
         </div>
       </header>
 
+      <SessionTags session={currentSession} />
+
       {/* Audio Player */}
       {audioSrc ? (
         <div className="px-6 py-3 border-b border-[var(--border)] bg-[var(--muted)]/30">
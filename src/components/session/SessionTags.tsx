@@ -0,0 +1,68 @@
+import { useState } from 'react';
+import { X } from 'lucide-react';
+import { Badge } from '../ui';
+import { useToast } from '../ui/Toast';
+import { useSessionStore } from '../../stores/useSessionStore';
+import { useTagStore } from '../../stores/useTagStore';
+import type { Session } from '../../types';
+
+interface SessionTagsProps {
+  session: Session;
+}
+
+/** A session's tags, with a field to add one by name */
+export function SessionTags({ session }: SessionTagsProps) {
+  const [name, setName] = useState('');
+  const tags = useTagStore((state) => state.tags);
+  const { addToast } = useToast();
+  const current = session.tags ?? [];
+
+  const handleAdd = async () => {
+    if (!name.trim()) return;
+    try {
+      await useSessionStore.getState().addTag(session.id, name.trim());
+      setName('');
+      await useTagStore.getState().loadTags();
+    } catch (error) {
+      addToast(`Failed to add tag: ${error}`, 'error');
+    }
+  };
+
+  const handleRemove = async (tag: string) => {
+    try {
+      await useSessionStore.getState().removeTag(session.id, tag);
+      await useTagStore.getState().loadTags();
+    } catch (error) {
+      addToast(`Failed to remove tag: ${error}`, 'error');
+    }
+  };
+
+  return (
+    <div className="px-6 py-2 border-b border-[var(--border)] flex flex-wrap items-center gap-1.5">
+      {current.map((tag) => (
+        <Badge key={tag} variant="outline" size="md" className="gap-1">
+          {tag}
+          <button onClick={() => handleRemove(tag)} aria-label={`Remove tag ${tag}`}>
+            <X size={12} aria-hidden="true" />
+          </button>
+        </Badge>
+      ))}
+      <input
+        value={name}
+        onChange={(e) => setName(e.target.value)}
+        onKeyDown={(e) => e.key === 'Enter' && handleAdd()}
+        placeholder="Add tag…"
+        list="session-tag-names"
+        aria-label="Add tag"
+        className="h-6 w-28 px-1.5 rounded-md bg-transparent text-xs placeholder:text-[var(--muted-foreground)] focus:outline-none focus:ring-2 focus:ring-[var(--primary)]/20"
+      />
+      <datalist id="session-tag-names">
+        {tags
+          .filter((tag) => !current.some((n) => n.toLowerCase() === tag.name.toLowerCase()))
+          .map((tag) => (
+            <option key={tag.id} value={tag.name} />
+          ))}
+      </datalist>
+    </div>
+  );
+}
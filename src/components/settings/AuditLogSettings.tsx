@@ -0,0 +1,146 @@
+import { useState, useEffect } from 'react';
+import { Button } from '../ui';
+import { queryAuditLog, verifyAuditLog } from '../../lib/tauri';
+import type { AuditAction, AuditEntry, AuditVerification } from '../../types';
+
+const PAGE_SIZE = 50;
+
+const ACTION_LABELS: Record<AuditAction, string> = {
+  view: 'Viewed',
+  update: 'Edited',
+  export: 'Exported',
+  delete: 'Deleted',
+  restore: 'Restored',
+  purge: 'Permanently deleted',
+  cloud_llm: 'Sent to cloud AI',
+};
+
+function formatTime(timestamp: number) {
+  return new Date(timestamp * 1000).toLocaleString(undefined, {
+    month: 'short',
+    day: 'numeric',
+    hour: 'numeric',
+    minute: '2-digit',
+  });
+}
+
+export function AuditLogSettings() {
+  const [entries, setEntries] = useState<AuditEntry[]>([]);
+  const [action, setAction] = useState<AuditAction | ''>('');
+  const [hasMore, setHasMore] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [verifying, setVerifying] = useState(false);
+  const [verification, setVerification] = useState<AuditVerification | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async (beforeSeq?: number) => {
+    setLoading(true);
+    setError(null);
+    try {
+      const page = await queryAuditLog({
+        action: action || undefined,
+        beforeSeq,
+        limit: PAGE_SIZE,
+      });
+      setEntries((current) => (beforeSeq ? [...current, ...page] : page));
+      setHasMore(page.length === PAGE_SIZE);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    load();
+  }, [action]);
+
+  const handleVerify = async () => {
+    setVerifying(true);
+    setError(null);
+    try {
+      setVerification(await verifyAuditLog());
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setVerifying(false);
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <h3 className="section-header">Audit Log</h3>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+        <div className="flex items-center justify-between gap-3">
+          <p className="text-[11px] text-[var(--muted-foreground)]">
+            Every view, edit, export and deletion is recorded. Each entry is chained to the one
+            before it, so changes to past entries can be detected.
+          </p>
+          <Button size="sm" variant="secondary" loading={verifying} onClick={handleVerify}>
+            Verify
+          </Button>
+        </div>
+        {verification && (
+          <p
+            className={`text-[11px] ${
+              verification.valid ? 'text-[var(--muted-foreground)]' : 'text-[var(--destructive)]'
+            }`}
+          >
+            {verification.valid
+              ? `All ${verification.entriesChecked} entries are intact`
+              : `Entry ${verification.firstInvalidSeq} has been altered: ${verification.error}`}
+          </p>
+        )}
+      </div>
+
+      <div className="flex gap-1 flex-wrap px-1">
+        {(['', ...Object.keys(ACTION_LABELS)] as (AuditAction | '')[]).map((option) => (
+          <button
+            key={option || 'all'}
+            onClick={() => setAction(option)}
+            className={`px-2 py-1 rounded-md text-[11px] transition-colors ${
+              action === option
+                ? 'bg-[var(--primary)]/10 text-[var(--foreground)]'
+                : 'text-[var(--muted-foreground)] hover:bg-[var(--secondary)]'
+            }`}
+          >
+            {option ? ACTION_LABELS[option] : 'All'}
+          </button>
+        ))}
+      </div>
+
+      {entries.length === 0 ? (
+        <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] text-center">
+          <p className="text-[13px] text-[var(--muted-foreground)]">
+            {loading ? 'Loading…' : 'No entries'}
+          </p>
+        </div>
+      ) : (
+        <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-1">
+          {entries.map((entry) => (
+            <p key={entry.id} className="text-[12px]">
+              {formatTime(entry.occurredAt)} · {ACTION_LABELS[entry.action]} {entry.entityType}
+              {entry.entityId && ` ${entry.entityId.slice(0, 8)}`}
+              <span className="text-[var(--muted-foreground)]">{' · '}{entry.actor}</span>
+            </p>
+          ))}
+          {hasMore && (
+            <div className="flex justify-center pt-1">
+              <Button
+                size="sm"
+                variant="ghost"
+                loading={loading}
+                onClick={() => load(entries[entries.length - 1].seq)}
+              >
+                Load More
+              </Button>
+            </div>
+          )}
+        </div>
+      )}
+
+      {error && <p className="text-[11px] text-[var(--destructive)] px-1">{error}</p>}
+    </section>
+  );
+}
@@ -0,0 +1,217 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Button, Input, Switch } from '../ui';
+import { createBackup, restoreBackup } from '../../lib/tauri';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import type { BackupInfo, RestoreMode } from '../../types';
+
+export function BackupSettings() {
+  const [includeSettings, setIncludeSettings] = useState(true);
+  const [creating, setCreating] = useState(false);
+  const [backup, setBackup] = useState<BackupInfo | null>(null);
+
+  const [path, setPath] = useState('');
+  const [mode, setMode] = useState<RestoreMode>('merge');
+  const [restoreSettings, setRestoreSettings] = useState(false);
+  const [passphrase, setPassphrase] = useState('');
+  const [confirmReplace, setConfirmReplace] = useState(false);
+  const [restoring, setRestoring] = useState(false);
+
+  const [error, setError] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const handleCreate = async () => {
+    setCreating(true);
+    setError(null);
+    setMessage(null);
+    try {
+      setBackup(await createBackup(includeSettings));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  // The restored library may not contain the workspace that was open
+  const refreshLists = async () => {
+    const { currentWorkspace, loadWorkspaces, selectWorkspace } = useWorkspaceStore.getState();
+    const workspaces = await loadWorkspaces();
+    const next = workspaces.find((w) => w.id === currentWorkspace?.id) ?? workspaces[0];
+    if (next) await selectWorkspace(next);
+  };
+
+  const handleRestore = async () => {
+    if (mode === 'replace' && !confirmReplace) {
+      setConfirmReplace(true);
+      return;
+    }
+
+    setRestoring(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const summary = await restoreBackup(path.trim(), mode, restoreSettings, passphrase);
+      setConfirmReplace(false);
+      setPassphrase('');
+      setMessage(
+        summary.mode === 'merge'
+          ? `Added ${summary.sessionsRestored} ${summary.sessionsRestored === 1 ? 'session' : 'sessions'} from the backup`
+          : `Library replaced with ${summary.sessionsRestored} ${summary.sessionsRestored === 1 ? 'session' : 'sessions'} from the backup`
+      );
+      await refreshLists();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setRestoring(false);
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <h3 className="section-header">Backup</h3>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+        <div className="flex items-center justify-between">
+          <div>
+            <label htmlFor="backup-settings" className="text-[13px] font-medium">
+              Include settings
+            </label>
+            <p className="text-[11px] text-[var(--muted-foreground)]">
+              Sessions, folders, templates, chats and recordings are always included
+            </p>
+          </div>
+          <Switch
+            id="backup-settings"
+            checked={includeSettings}
+            onChange={setIncludeSettings}
+            aria-label="Include settings in backup"
+          />
+        </div>
+        <div className="flex items-center justify-between gap-3">
+          <p className="text-[11px] text-[var(--muted-foreground)]">
+            Saved to your Downloads folder. Encrypted libraries give encrypted backups that need
+            your passphrase to restore.
+          </p>
+          <Button size="sm" loading={creating} disabled={restoring} onClick={handleCreate}>
+            Back Up Now
+          </Button>
+        </div>
+        {backup && (
+          <div className="flex items-center justify-between gap-3">
+            <p className="text-[11px] text-[var(--muted-foreground)] truncate" title={backup.path}>
+              Backed up {backup.manifest.sessionCount}{' '}
+              {backup.manifest.sessionCount === 1 ? 'session' : 'sessions'} to{' '}
+              {backup.path.split(/[\\/]/).pop()}
+            </p>
+            <button
+              onClick={() => {
+                invoke('show_in_folder', { path: backup.path }).catch((err) => {
+                  console.error('Failed to open folder:', err);
+                  navigator.clipboard.writeText(backup.path);
+                });
+              }}
+              className="text-[11px] text-[var(--muted-foreground)] hover:text-[var(--foreground)] hover:underline shrink-0"
+            >
+              Show in Finder
+            </button>
+          </div>
+        )}
+      </div>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+        <Input
+          label="Backup file"
+          placeholder="/Users/you/Downloads/private-transcript-backup.zip"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+        />
+
+        <div className="flex gap-2">
+          {(['merge', 'replace'] as const).map((option) => (
+            <button
+              key={option}
+              onClick={() => {
+                setMode(option);
+                setConfirmReplace(false);
+              }}
+              className={`flex-1 px-3 py-2 rounded-lg border text-left transition-colors ${
+                mode === option
+                  ? 'border-[var(--primary)] bg-[var(--primary)]/10'
+                  : 'border-[var(--border)] hover:bg-[var(--secondary)]'
+              }`}
+            >
+              <p className="text-[13px] font-medium">
+                {option === 'merge' ? 'Merge' : 'Replace'}
+              </p>
+              <p className="text-[11px] text-[var(--muted-foreground)]">
+                {option === 'merge'
+                  ? 'Add sessions from the backup to this library'
+                  : 'Swap this library for the backup'}
+              </p>
+            </button>
+          ))}
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div>
+            <label htmlFor="restore-settings" className="text-[13px] font-medium">
+              Restore settings
+            </label>
+            <p className="text-[11px] text-[var(--muted-foreground)]">
+              Use the settings saved in the backup, if it has any
+            </p>
+          </div>
+          <Switch
+            id="restore-settings"
+            checked={restoreSettings}
+            onChange={setRestoreSettings}
+            aria-label="Restore settings from backup"
+          />
+        </div>
+
+        <Input
+          type="password"
+          label="Backup passphrase"
+          hint="Only needed for encrypted backups"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+        />
+
+        {confirmReplace && (
+          <div className="p-3 rounded-lg border border-[var(--destructive)] bg-[var(--destructive)]/10">
+            <p className="text-[12px]">
+              Replacing deletes every session, folder and recording in this library. Back up
+              first if you might need them.
+            </p>
+          </div>
+        )}
+
+        <div className="flex gap-2 justify-end">
+          {confirmReplace && (
+            <Button
+              size="sm"
+              variant="ghost"
+              disabled={restoring}
+              onClick={() => setConfirmReplace(false)}
+            >
+              Cancel
+            </Button>
+          )}
+          <Button
+            size="sm"
+            variant={mode === 'replace' ? 'destructive' : 'secondary'}
+            loading={restoring}
+            disabled={!path.trim() || creating}
+            onClick={handleRestore}
+          >
+            {confirmReplace ? 'Replace Library' : 'Restore'}
+          </Button>
+        </div>
+      </div>
+
+      {error && <p className="text-[11px] text-[var(--destructive)] px-1">{error}</p>}
+      {message && <p className="text-[11px] text-[var(--muted-foreground)] px-1">{message}</p>}
+    </section>
+  );
+}
@@ -0,0 +1,183 @@
+import { useState, useEffect } from 'react';
+import { Button, Input, StatusDot } from '../ui';
+import {
+  getEncryptionStatus,
+  setEncryptionPassphrase,
+  changeEncryptionPassphrase,
+  removeEncryptionPassphrase,
+} from '../../lib/tauri';
+import type { EncryptionStatus } from '../../types';
+
+type Mode = 'idle' | 'enable' | 'change' | 'disable';
+
+const MIN_PASSPHRASE_LENGTH = 8;
+
+export function EncryptionSettings() {
+  const [status, setStatus] = useState<EncryptionStatus | null>(null);
+  const [mode, setMode] = useState<Mode>('idle');
+  const [current, setCurrent] = useState('');
+  const [next, setNext] = useState('');
+  const [confirm, setConfirm] = useState('');
+  const [working, setWorking] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const loadStatus = async () => {
+    try {
+      setStatus(await getEncryptionStatus());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    loadStatus();
+  }, []);
+
+  const reset = (nextMode: Mode) => {
+    setMode(nextMode);
+    setCurrent('');
+    setNext('');
+    setConfirm('');
+    setError(null);
+    setMessage(null);
+  };
+
+  const needsNew = mode === 'enable' || mode === 'change';
+  const needsCurrent = mode === 'change' || mode === 'disable';
+  const mismatch = needsNew && confirm.length > 0 && next !== confirm;
+  const tooShort = needsNew && next.length > 0 && next.length < MIN_PASSPHRASE_LENGTH;
+  const canSubmit =
+    (!needsCurrent || current.length > 0) &&
+    (!needsNew || (next.length >= MIN_PASSPHRASE_LENGTH && next === confirm));
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!canSubmit) return;
+
+    setWorking(true);
+    setError(null);
+    try {
+      if (mode === 'enable') {
+        await setEncryptionPassphrase(next);
+      } else if (mode === 'change') {
+        await changeEncryptionPassphrase(current, next);
+      } else if (mode === 'disable') {
+        await removeEncryptionPassphrase(current);
+      }
+      const done =
+        mode === 'enable'
+          ? 'Encryption enabled'
+          : mode === 'change'
+            ? 'Passphrase changed'
+            : 'Encryption disabled';
+      reset('idle');
+      setMessage(done);
+      await loadStatus();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setWorking(false);
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <h3 className="section-header">Encryption</h3>
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+        <div className="flex items-center justify-between">
+          <div className="flex items-center gap-2">
+            <StatusDot status={status?.enabled ? 'success' : 'neutral'} />
+            <span className="text-[13px] font-medium">
+              {status?.enabled ? 'Encrypted with a passphrase' : 'Not encrypted'}
+            </span>
+          </div>
+          {mode === 'idle' && status && (
+            <div className="flex gap-2">
+              {status.enabled ? (
+                <>
+                  <Button size="sm" variant="secondary" onClick={() => reset('change')}>
+                    Change
+                  </Button>
+                  <Button size="sm" variant="ghost" onClick={() => reset('disable')}>
+                    Turn Off
+                  </Button>
+                </>
+              ) : (
+                <Button size="sm" onClick={() => reset('enable')}>
+                  Set Passphrase
+                </Button>
+              )}
+            </div>
+          )}
+        </div>
+
+        <p className="text-[11px] text-[var(--muted-foreground)]">
+          Encrypts the database and audio recordings on disk. You will need the passphrase each
+          time the app starts. If you forget it, your data cannot be recovered.
+        </p>
+
+        {mode !== 'idle' && (
+          <form onSubmit={handleSubmit} className="space-y-2">
+            {needsCurrent && (
+              <Input
+                type="password"
+                label="Current passphrase"
+                value={current}
+                onChange={(e) => setCurrent(e.target.value)}
+                autoFocus
+              />
+            )}
+            {needsNew && (
+              <>
+                <Input
+                  type="password"
+                  label="New passphrase"
+                  value={next}
+                  onChange={(e) => setNext(e.target.value)}
+                  error={tooShort ? `At least ${MIN_PASSPHRASE_LENGTH} characters` : undefined}
+                  autoFocus={!needsCurrent}
+                />
+                <Input
+                  type="password"
+                  label="Confirm passphrase"
+                  value={confirm}
+                  onChange={(e) => setConfirm(e.target.value)}
+                  error={mismatch ? 'Passphrases do not match' : undefined}
+                />
+              </>
+            )}
+            {working && (
+              <p className="text-[11px] text-[var(--muted-foreground)]">
+                Re-encrypting your data. This can take a while with many recordings.
+              </p>
+            )}
+            <div className="flex gap-2 justify-end">
+              <Button
+                type="button"
+                size="sm"
+                variant="ghost"
+                onClick={() => reset('idle')}
+                disabled={working}
+              >
+                Cancel
+              </Button>
+              <Button
+                type="submit"
+                size="sm"
+                variant={mode === 'disable' ? 'destructive' : 'primary'}
+                loading={working}
+                disabled={!canSubmit}
+              >
+                {mode === 'enable' ? 'Encrypt' : mode === 'change' ? 'Change' : 'Decrypt'}
+              </Button>
+            </div>
+          </form>
+        )}
+
+        {error && <p className="text-[11px] text-[var(--destructive)]">{error}</p>}
+        {message && <p className="text-[11px] text-[var(--muted-foreground)]">{message}</p>}
+      </div>
+    </section>
+  );
+}
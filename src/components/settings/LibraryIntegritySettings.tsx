@@ -0,0 +1,148 @@
+import { useState } from 'react';
+import { Button } from '../ui';
+import { checkLibraryIntegrity, repairLibrary } from '../../lib/tauri';
+import type { IntegrityIssue, IntegrityIssueKind, IntegrityReport } from '../../types';
+
+const KIND_LABELS: Record<IntegrityIssueKind, string> = {
+  orphaned_audio: 'Unused recording',
+  missing_audio: 'Missing recording',
+  orphaned_chunks: 'Leftover search data',
+  stale_chunks: 'Outdated search data',
+  stale_index: 'Outdated search data',
+};
+
+export function LibraryIntegritySettings() {
+  const [report, setReport] = useState<IntegrityReport | null>(null);
+  const [selected, setSelected] = useState<Set<number>>(new Set());
+  const [checking, setChecking] = useState(false);
+  const [repairing, setRepairing] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const runCheck = async () => {
+    setChecking(true);
+    setError(null);
+    try {
+      const result = await checkLibraryIntegrity();
+      setReport(result);
+      setSelected(new Set(result.issues.map((_, i) => i)));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setChecking(false);
+    }
+  };
+
+  const handleCheck = async () => {
+    setMessage(null);
+    await runCheck();
+  };
+
+  const toggle = (index: number) =>
+    setSelected((current) => {
+      const next = new Set(current);
+      if (next.has(index)) next.delete(index);
+      else next.add(index);
+      return next;
+    });
+
+  const handleRepair = async () => {
+    if (!report) return;
+    const issues: IntegrityIssue[] = report.issues.filter((_, i) => selected.has(i));
+    setRepairing(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await repairLibrary(issues);
+      // Check again so the list shows what is left
+      await runCheck();
+      const count = result.repaired.length;
+      const parts = [`Repaired ${count} ${count === 1 ? 'issue' : 'issues'}`];
+      if (result.reindex.length > 0) parts.push(`re-indexing ${result.reindex.length}`);
+      if (result.skipped > 0) parts.push(`${result.skipped} changed since the check and left alone`);
+      setMessage(parts.join(', '));
+      if (result.errors.length > 0) setError(result.errors.join('\n'));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setRepairing(false);
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <h3 className="section-header">Library Check</h3>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+        <div className="flex items-center justify-between gap-3">
+          <p className="text-[11px] text-[var(--muted-foreground)]">
+            Look for recordings no session uses, sessions whose recording is missing, and
+            outdated search data. Nothing changes until you repair.
+          </p>
+          <Button
+            size="sm"
+            variant="secondary"
+            loading={checking}
+            disabled={repairing}
+            onClick={handleCheck}
+          >
+            Check Library
+          </Button>
+        </div>
+
+        {report && report.issues.length === 0 && (
+          <p className="text-[12px] text-[var(--muted-foreground)]">
+            No problems found in {report.sessionsChecked}{' '}
+            {report.sessionsChecked === 1 ? 'session' : 'sessions'} and {report.audioFilesChecked}{' '}
+            {report.audioFilesChecked === 1 ? 'recording' : 'recordings'}.
+          </p>
+        )}
+
+        {report && report.issues.length > 0 && (
+          <>
+            <div className="space-y-1 max-h-64 overflow-y-auto">
+              {report.issues.map((issue, index) => (
+                <label
+                  key={`${issue.kind}-${issue.sessionId ?? issue.path}`}
+                  className="flex items-start gap-2 py-1 cursor-pointer"
+                >
+                  <input
+                    type="checkbox"
+                    checked={selected.has(index)}
+                    onChange={() => toggle(index)}
+                    className="mt-0.5"
+                  />
+                  <span className="min-w-0">
+                    <span className="block text-[12px]">
+                      <span className="font-medium">{KIND_LABELS[issue.kind] ?? issue.kind}:</span>{' '}
+                      {issue.description}
+                    </span>
+                    <span
+                      className="block text-[11px] text-[var(--muted-foreground)] truncate"
+                      title={issue.path ?? undefined}
+                    >
+                      {issue.repair}
+                    </span>
+                  </span>
+                </label>
+              ))}
+            </div>
+            <div className="flex justify-end">
+              <Button
+                size="sm"
+                loading={repairing}
+                disabled={checking || selected.size === 0}
+                onClick={handleRepair}
+              >
+                Repair {selected.size} {selected.size === 1 ? 'Issue' : 'Issues'}
+              </Button>
+            </div>
+          </>
+        )}
+      </div>
+
+      {message && <p className="text-[11px] text-[var(--muted-foreground)] px-1">{message}</p>}
+      {error && <p className="text-[11px] text-[var(--destructive)] px-1 whitespace-pre-line">{error}</p>}
+    </section>
+  );
+}
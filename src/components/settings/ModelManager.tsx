@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { ModelInfo, DownloadProgress } from '../../types';
+import { ModelInfo, DownloadProgress, LoadedWhisperModel } from '../../types';
 import { Button } from '../ui/Button';
 import { useChatStore } from '../../stores/useChatStore';
 
@@ -55,7 +55,7 @@ export function ModelManager() {
         invoke<ModelInfo[]>('get_available_whisper_models'),
         invoke<ModelInfo[]>('get_available_llm_models'),
         invoke<string[]>('get_downloaded_models'),
-        invoke<string | null>('get_loaded_whisper_model'),
+        invoke<LoadedWhisperModel | null>('get_loaded_whisper_model'),
         invoke<string | null>('get_loaded_llm_model'),
         invoke<number>('get_models_total_size'),
         invoke<boolean>('check_embedding_model'),
@@ -64,7 +64,7 @@ export function ModelManager() {
       setWhisperModels(whisper);
       setLlmModels(llm);
       setDownloadedModels(downloaded);
-      setLoadedWhisperModel(whisperLoaded);
+      setLoadedWhisperModel(whisperLoaded?.modelId ?? null);
       setLoadedLlmModel(llmLoaded);
       setTotalSize(size);
       setEmbeddingModelAvailable(embeddingAvailable);
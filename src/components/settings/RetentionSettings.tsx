@@ -0,0 +1,273 @@
+import { useState, useEffect } from 'react';
+import { Button, Input } from '../ui';
+import {
+  getRetentionPolicies,
+  setRetentionPolicy,
+  deleteRetentionPolicy,
+  previewRetention,
+  getRetentionLog,
+} from '../../lib/tauri';
+import { useAppStore } from '../../stores/appStore';
+import type {
+  RetainedData,
+  RetentionCandidate,
+  RetentionLimits,
+  RetentionLogEntry,
+  RetentionPolicy,
+} from '../../types';
+
+const LIMIT_FIELDS: { key: keyof RetentionLimits; label: string }[] = [
+  { key: 'audioDays', label: 'Audio' },
+  { key: 'transcriptDays', label: 'Transcript' },
+  { key: 'noteDays', label: 'Generated note' },
+  { key: 'chunksDays', label: 'Search index (AI chat)' },
+];
+
+const DATA_LABELS: Record<RetainedData, string> = {
+  audio: 'Audio',
+  transcript: 'Transcript',
+  note: 'Note',
+  chunks: 'Search index',
+};
+
+type Drafts = Record<keyof RetentionLimits, string>;
+
+function toDrafts(policy?: RetentionPolicy): Drafts {
+  const value = (days: number | null | undefined) => (days == null ? '' : String(days));
+  return {
+    audioDays: value(policy?.audioDays),
+    transcriptDays: value(policy?.transcriptDays),
+    noteDays: value(policy?.noteDays),
+    chunksDays: value(policy?.chunksDays),
+  };
+}
+
+function formatDate(timestamp: number) {
+  return new Date(timestamp * 1000).toLocaleDateString(undefined, {
+    month: 'short',
+    day: 'numeric',
+    year: 'numeric',
+  });
+}
+
+interface PolicyEditorProps {
+  title: string;
+  description: string;
+  policy?: RetentionPolicy;
+  onSave: (limits: RetentionLimits) => Promise<void>;
+  onRemove: (policy: RetentionPolicy) => Promise<void>;
+}
+
+function PolicyEditor({ title, description, policy, onSave, onRemove }: PolicyEditorProps) {
+  const [drafts, setDrafts] = useState<Drafts>(() => toDrafts(policy));
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setDrafts(toDrafts(policy));
+  }, [policy]);
+
+  const run = async (action: () => Promise<void>) => {
+    setSaving(true);
+    setError(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleSave = () => {
+    const limits = {} as RetentionLimits;
+    for (const { key, label } of LIMIT_FIELDS) {
+      const text = drafts[key].trim();
+      const days = Number(text);
+      if (text && (!Number.isInteger(days) || days < 1)) {
+        setError(`${label}: enter a whole number of days, at least 1`);
+        return;
+      }
+      limits[key] = text ? days : null;
+    }
+    run(() => onSave(limits));
+  };
+
+  return (
+    <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+      <div>
+        <p className="text-[13px] font-medium">{title}</p>
+        <p className="text-[11px] text-[var(--muted-foreground)]">{description}</p>
+      </div>
+      <div className="grid grid-cols-2 gap-2">
+        {LIMIT_FIELDS.map(({ key, label }) => (
+          <Input
+            key={key}
+            type="number"
+            min={1}
+            label={`${label} (days)`}
+            placeholder="Keep"
+            value={drafts[key]}
+            onChange={(e) => setDrafts({ ...drafts, [key]: e.target.value })}
+          />
+        ))}
+      </div>
+      <div className="flex gap-2 justify-end">
+        {policy && (
+          <Button size="sm" variant="ghost" disabled={saving} onClick={() => run(() => onRemove(policy))}>
+            Remove Policy
+          </Button>
+        )}
+        <Button size="sm" loading={saving} onClick={handleSave}>
+          Save
+        </Button>
+      </div>
+      {error && <p className="text-[11px] text-[var(--destructive)]">{error}</p>}
+    </div>
+  );
+}
+
+export function RetentionSettings() {
+  const { currentWorkspace, currentFolder } = useAppStore();
+  const [policies, setPolicies] = useState<RetentionPolicy[]>([]);
+  const [preview, setPreview] = useState<RetentionCandidate[] | null>(null);
+  const [log, setLog] = useState<RetentionLogEntry[]>([]);
+  const [previewing, setPreviewing] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async () => {
+    setError(null);
+    try {
+      const [allPolicies, entries] = await Promise.all([
+        getRetentionPolicies(),
+        getRetentionLog(currentWorkspace?.id, 50),
+      ]);
+      setPolicies(allPolicies ?? []);
+      setLog(entries ?? []);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    load();
+    setPreview(null);
+  }, [currentWorkspace?.id]);
+
+  if (!currentWorkspace) {
+    return (
+      <div className="p-4 max-w-xl">
+        <p className="text-[13px] text-[var(--muted-foreground)]">
+          Open a workspace to set its retention policy.
+        </p>
+      </div>
+    );
+  }
+
+  const workspacePolicy = policies.find((p) => p.workspaceId === currentWorkspace.id);
+  const folderPolicy = currentFolder
+    ? policies.find((p) => p.folderId === currentFolder.id)
+    : undefined;
+
+  const save = async (scope: { workspaceId: string } | { folderId: string }, limits: RetentionLimits) => {
+    await setRetentionPolicy(scope, limits);
+    setPreview(null);
+    await load();
+  };
+
+  const remove = async (policy: RetentionPolicy) => {
+    await deleteRetentionPolicy(policy.id);
+    setPreview(null);
+    await load();
+  };
+
+  const handlePreview = async () => {
+    setPreviewing(true);
+    setError(null);
+    try {
+      setPreview(await previewRetention(currentWorkspace.id));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setPreviewing(false);
+    }
+  };
+
+  return (
+    <div className="p-4 max-w-xl space-y-4">
+      <section className="space-y-2">
+        <h3 className="section-header">Policies</h3>
+        <p className="text-[11px] text-[var(--muted-foreground)] px-1">
+          Data older than its limit, counted from the recording date, is deleted automatically and
+          the deletion is logged. Leave a field empty to keep that data indefinitely.
+        </p>
+        <PolicyEditor
+          title={currentWorkspace.name}
+          description="Applies to every folder in this workspace without its own policy"
+          policy={workspacePolicy}
+          onSave={(limits) => save({ workspaceId: currentWorkspace.id }, limits)}
+          onRemove={remove}
+        />
+        {currentFolder && (
+          <PolicyEditor
+            title={currentFolder.name}
+            description="Replaces the workspace policy for this folder"
+            policy={folderPolicy}
+            onSave={(limits) => save({ folderId: currentFolder.id }, limits)}
+            onRemove={remove}
+          />
+        )}
+      </section>
+
+      <section className="space-y-2">
+        <div className="flex items-center justify-between px-1">
+          <h3 className="section-header">Due for Deletion</h3>
+          <Button size="sm" variant="secondary" loading={previewing} onClick={handlePreview}>
+            Preview
+          </Button>
+        </div>
+        {preview &&
+          (preview.length === 0 ? (
+            <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] text-center">
+              <p className="text-[13px] text-[var(--muted-foreground)]">Nothing is due</p>
+            </div>
+          ) : (
+            <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-1">
+              {preview.map((item) => (
+                <p key={`${item.sessionId}-${item.dataType}`} className="text-[12px]">
+                  {DATA_LABELS[item.dataType]} of {item.sessionTitle || 'Untitled'}
+                  <span className="text-[var(--muted-foreground)]">
+                    {' · '}recorded {formatDate(item.sessionCreatedAt)}, {item.retentionDays}-day
+                    limit
+                  </span>
+                </p>
+              ))}
+            </div>
+          ))}
+      </section>
+
+      <section className="space-y-2">
+        <h3 className="section-header">Deletion Log</h3>
+        {log.length === 0 ? (
+          <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] text-center">
+            <p className="text-[13px] text-[var(--muted-foreground)]">No data deleted yet</p>
+          </div>
+        ) : (
+          <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-1">
+            {log.map((entry) => (
+              <p key={entry.id} className="text-[12px]">
+                {formatDate(entry.purgedAt)} · {DATA_LABELS[entry.dataType]}
+                <span className="text-[var(--muted-foreground)]">
+                  {' · '}session {entry.sessionId.slice(0, 8)} recorded{' '}
+                  {formatDate(entry.sessionCreatedAt)}, {entry.retentionDays}-day limit
+                </span>
+              </p>
+            ))}
+          </div>
+        )}
+      </section>
+
+      {error && <p className="text-[11px] text-[var(--destructive)] px-1">{error}</p>}
+    </div>
+  );
+}
@@ -31,7 +31,7 @@ vi.mock('@tauri-apps/api/core', () => ({
       return Promise.resolve(['whisper-small']);
     }
     if (command === 'get_loaded_whisper_model') {
-      return Promise.resolve('whisper-small');
+      return Promise.resolve({ modelId: 'whisper-small', backend: 'whispercpp' });
     }
     if (command === 'get_loaded_llm_model') {
       return Promise.resolve(null);
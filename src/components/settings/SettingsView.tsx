@@ -2,6 +2,14 @@ import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../../stores/appStore';
 import { ModelManager } from './ModelManager';
+import { EncryptionSettings } from './EncryptionSettings';
+import { TrashSettings } from './TrashSettings';
+import { BackupSettings } from './BackupSettings';
+import { LibraryIntegritySettings } from './LibraryIntegritySettings';
+import { RetentionSettings } from './RetentionSettings';
+import { AuditLogSettings } from './AuditLogSettings';
+import { WorkspaceTypesSettings } from './WorkspaceTypesSettings';
+import { TagsSettings } from './TagsSettings';
 import { Button, Card, Switch, StatusDot } from '../ui';
 import { logger, type LogEntry, type LogLevel } from '../../lib/logger';
 import { useTheme, type Theme } from '../../hooks/useTheme';
@@ -15,7 +23,15 @@ import {
   Monitor,
 } from 'lucide-react';
 
-type Tab = 'models' | 'general' | 'storage' | 'logs' | 'about';
+type Tab =
+  | 'models'
+  | 'general'
+  | 'storage'
+  | 'trash'
+  | 'retention'
+  | 'security'
+  | 'logs'
+  | 'about';
 
 export function SettingsView() {
   const { setView } = useAppStore();
@@ -52,6 +68,15 @@ export function SettingsView() {
           <TabButton active={activeTab === 'storage'} onClick={() => setActiveTab('storage')}>
             Storage
           </TabButton>
+          <TabButton active={activeTab === 'trash'} onClick={() => setActiveTab('trash')}>
+            Trash
+          </TabButton>
+          <TabButton active={activeTab === 'retention'} onClick={() => setActiveTab('retention')}>
+            Retention
+          </TabButton>
+          <TabButton active={activeTab === 'security'} onClick={() => setActiveTab('security')}>
+            Security
+          </TabButton>
           <TabButton active={activeTab === 'logs'} onClick={() => setActiveTab('logs')}>
             Logs
           </TabButton>
@@ -66,6 +91,14 @@ export function SettingsView() {
         {activeTab === 'models' && <ModelManager />}
         {activeTab === 'general' && <GeneralSettings />}
         {activeTab === 'storage' && <StorageSection />}
+        {activeTab === 'trash' && <TrashSettings />}
+        {activeTab === 'retention' && <RetentionSettings />}
+        {activeTab === 'security' && (
+          <div className="p-4 max-w-xl space-y-4">
+            <EncryptionSettings />
+            <AuditLogSettings />
+          </div>
+        )}
         {activeTab === 'logs' && <LogsSection />}
         {activeTab === 'about' && <AboutSection />}
       </div>
@@ -344,6 +377,10 @@ function GeneralSettings() {
         </Card>
       </section>
 
+      <WorkspaceTypesSettings />
+
+      <TagsSettings />
+
       {/* Debug Settings */}
       <section className="space-y-2">
         <h3 className="section-header">Developer</h3>
@@ -513,6 +550,10 @@ function StorageSection() {
         </div>
       </section>
 
+      <BackupSettings />
+
+      <LibraryIntegritySettings />
+
       {/* Tips */}
       <section className="space-y-2">
         <h3 className="text-[11px] font-medium text-[var(--muted-foreground)] uppercase tracking-wide px-1">
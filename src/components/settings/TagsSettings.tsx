@@ -0,0 +1,96 @@
+import { useState, useEffect } from 'react';
+import { Button, Input } from '../ui';
+import { useTagStore } from '../../stores/useTagStore';
+
+export function TagsSettings() {
+  const tags = useTagStore((state) => state.tags);
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [name, setName] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    useTagStore
+      .getState()
+      .loadTags()
+      .catch((e) => setError(String(e)));
+  }, []);
+
+  const handleRename = async (id: string) => {
+    setError(null);
+    try {
+      await useTagStore.getState().renameTag(id, name.trim());
+      setEditingId(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    setError(null);
+    try {
+      await useTagStore.getState().deleteTag(id);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <h3 className="section-header">Tags</h3>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-1">
+        {tags.length === 0 && (
+          <p className="text-[11px] text-[var(--muted-foreground)]">
+            No tags yet. Add them to a session from its header.
+          </p>
+        )}
+        {tags.map((tag) =>
+          editingId === tag.id ? (
+            <div key={tag.id} className="flex items-end gap-2 py-1">
+              <Input
+                id={`tag-name-${tag.id}`}
+                aria-label="Tag name"
+                value={name}
+                onChange={(e) => setName(e.target.value)}
+                onKeyDown={(e) => e.key === 'Enter' && name.trim() && handleRename(tag.id)}
+              />
+              <Button size="sm" variant="ghost" onClick={() => setEditingId(null)}>
+                Cancel
+              </Button>
+              <Button size="sm" disabled={!name.trim()} onClick={() => handleRename(tag.id)}>
+                Save
+              </Button>
+            </div>
+          ) : (
+            <div key={tag.id} className="flex items-center justify-between gap-3 py-1">
+              <p className="text-[13px]">
+                {tag.name}
+                <span className="text-[11px] text-[var(--muted-foreground)]">
+                  {' · '}
+                  {tag.sessionCount === 1 ? '1 session' : `${tag.sessionCount} sessions`}
+                </span>
+              </p>
+              <div className="flex gap-1">
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  onClick={() => {
+                    setName(tag.name);
+                    setEditingId(tag.id);
+                  }}
+                >
+                  Rename
+                </Button>
+                <Button size="sm" variant="ghost" onClick={() => handleDelete(tag.id)}>
+                  Delete
+                </Button>
+              </div>
+            </div>
+          )
+        )}
+      </div>
+
+      {error && <p className="text-[11px] text-[var(--destructive)] px-1">{error}</p>}
+    </section>
+  );
+}
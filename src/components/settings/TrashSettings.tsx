@@ -0,0 +1,191 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Button, Input } from '../ui';
+import { listTrash, restoreSession, restoreFolder, emptyTrash } from '../../lib/tauri';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import { useSessionStore } from '../../stores/useSessionStore';
+import type { AppSettings, TrashItem } from '../../types';
+
+function formatDate(timestamp: number) {
+  return new Date(timestamp * 1000).toLocaleDateString(undefined, {
+    month: 'short',
+    day: 'numeric',
+    year: 'numeric',
+  });
+}
+
+export function TrashSettings() {
+  const [items, setItems] = useState<TrashItem[]>([]);
+  const [retentionDays, setRetentionDays] = useState('30');
+  const [loading, setLoading] = useState(true);
+  const [working, setWorking] = useState<string | null>(null);
+  const [confirmEmpty, setConfirmEmpty] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async () => {
+    setError(null);
+    try {
+      const [trash, settings] = await Promise.all([
+        listTrash(),
+        invoke<AppSettings>('get_settings'),
+      ]);
+      setItems(trash ?? []);
+      if (settings) setRetentionDays(String(settings.trashRetentionDays));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  // Restored items should show up in the sidebar and session list right away
+  const refreshLists = async () => {
+    const { currentWorkspace, currentFolder, loadFolders } = useWorkspaceStore.getState();
+    if (currentWorkspace) await loadFolders(currentWorkspace.id);
+    if (currentFolder) await useSessionStore.getState().loadSessions(currentFolder.id);
+  };
+
+  const handleRestore = async (item: TrashItem) => {
+    setWorking(item.id);
+    setError(null);
+    try {
+      if (item.kind === 'folder') {
+        await restoreFolder(item.id);
+      } else {
+        await restoreSession(item.id);
+      }
+      await load();
+      await refreshLists();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setWorking(null);
+    }
+  };
+
+  const handleEmpty = async () => {
+    setWorking('empty');
+    setError(null);
+    try {
+      await emptyTrash();
+      setConfirmEmpty(false);
+      await load();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setWorking(null);
+    }
+  };
+
+  const saveRetention = async () => {
+    const days = Number(retentionDays);
+    if (!Number.isInteger(days) || days < 1) {
+      setError('Retention must be a whole number of days, at least 1');
+      return;
+    }
+    setError(null);
+    try {
+      await invoke('update_settings', { request: { trashRetentionDays: days } });
+      await load();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="p-4 max-w-xl space-y-4">
+      <section className="space-y-2">
+        <h3 className="section-header">Retention</h3>
+        <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-2">
+          <Input
+            type="number"
+            min={1}
+            label="Keep deleted items for (days)"
+            value={retentionDays}
+            onChange={(e) => setRetentionDays(e.target.value)}
+            onBlur={saveRetention}
+          />
+          <p className="text-[11px] text-[var(--muted-foreground)]">
+            Deleted sessions and folders can be restored until then. After that they and their
+            recordings are deleted permanently.
+          </p>
+        </div>
+      </section>
+
+      <section className="space-y-2">
+        <div className="flex items-center justify-between px-1">
+          <h3 className="section-header">Trash</h3>
+          {items.length > 0 && !confirmEmpty && (
+            <Button size="sm" variant="ghost" onClick={() => setConfirmEmpty(true)}>
+              Empty Trash
+            </Button>
+          )}
+        </div>
+
+        {confirmEmpty && (
+          <div className="p-3 rounded-lg border border-[var(--destructive)] bg-[var(--destructive)]/10 space-y-2">
+            <p className="text-[12px]">
+              Permanently delete {items.length} {items.length === 1 ? 'item' : 'items'} and their
+              recordings? This cannot be undone.
+            </p>
+            <div className="flex gap-2 justify-end">
+              <Button size="sm" variant="ghost" onClick={() => setConfirmEmpty(false)}>
+                Cancel
+              </Button>
+              <Button
+                size="sm"
+                variant="destructive"
+                loading={working === 'empty'}
+                onClick={handleEmpty}
+              >
+                Delete Permanently
+              </Button>
+            </div>
+          </div>
+        )}
+
+        {loading ? (
+          <p className="text-[13px] text-[var(--muted-foreground)] px-1">Loading...</p>
+        ) : items.length === 0 ? (
+          <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] text-center">
+            <p className="text-[13px] text-[var(--muted-foreground)]">Trash is empty</p>
+          </div>
+        ) : (
+          <div className="space-y-2">
+            {items.map((item) => (
+              <div
+                key={`${item.kind}-${item.id}`}
+                className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] flex items-center justify-between gap-3"
+              >
+                <div className="min-w-0">
+                  <p className="text-[13px] font-medium truncate">{item.name || 'Untitled'}</p>
+                  <p className="text-[11px] text-[var(--muted-foreground)]">
+                    {item.kind === 'folder'
+                      ? `Folder · ${item.sessionCount} ${item.sessionCount === 1 ? 'session' : 'sessions'}`
+                      : `Session in ${item.folderName ?? 'unknown folder'}`}
+                    {' · '}Deleted {formatDate(item.deletedAt)} · Removed {formatDate(item.purgeAt)}
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="secondary"
+                  loading={working === item.id}
+                  disabled={working !== null && working !== item.id}
+                  onClick={() => handleRestore(item)}
+                >
+                  Restore
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {error && <p className="text-[11px] text-[var(--destructive)] px-1">{error}</p>}
+      </section>
+    </div>
+  );
+}
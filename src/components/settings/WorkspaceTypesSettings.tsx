@@ -0,0 +1,163 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Button, Input } from '../ui';
+import { createWorkspaceType, deleteWorkspaceType } from '../../lib/tauri';
+import { metadataFields } from '../../lib/utils';
+import { useWorkspaceStore } from '../../stores/useWorkspaceStore';
+import type { Template } from '../../types';
+
+export function WorkspaceTypesSettings() {
+  const workspaceTypes = useWorkspaceStore((state) => state.workspaceTypes);
+  const [templates, setTemplates] = useState<Template[]>([]);
+  const [name, setName] = useState('');
+  const [folderLabel, setFolderLabel] = useState('');
+  const [icon, setIcon] = useState('');
+  const [defaultTemplateId, setDefaultTemplateId] = useState('');
+  const [metadataSchema, setMetadataSchema] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const { loadWorkspaceTypes } = useWorkspaceStore.getState();
+    Promise.all([loadWorkspaceTypes(), invoke<Template[]>('get_templates')])
+      .then(([, allTemplates]) => setTemplates(allTemplates ?? []))
+      .catch((e) => setError(String(e)));
+  }, []);
+
+  const handleAdd = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      await createWorkspaceType({
+        name: name.trim(),
+        folderLabel: folderLabel.trim(),
+        icon: icon.trim() || undefined,
+        defaultTemplateId: defaultTemplateId || undefined,
+        metadataSchema: metadataSchema.trim() || undefined,
+      });
+      setName('');
+      setFolderLabel('');
+      setIcon('');
+      setDefaultTemplateId('');
+      setMetadataSchema('');
+      await useWorkspaceStore.getState().loadWorkspaceTypes();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    setError(null);
+    try {
+      await deleteWorkspaceType(id);
+      await useWorkspaceStore.getState().loadWorkspaceTypes();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <section className="space-y-2">
+      <h3 className="section-header">Workspace Types</h3>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-1">
+        {workspaceTypes.map((type) => (
+          <div key={type.id} className="flex items-center justify-between gap-3 py-1">
+            <p className="text-[13px]">
+              {type.icon ?? '📝'} {type.name}
+              <span className="text-[11px] text-[var(--muted-foreground)]">
+                {' · '}folders are {type.folderLabel.toLowerCase()}s
+                {metadataFields(type.metadataSchema).length > 0 &&
+                  ` · ${metadataFields(type.metadataSchema).map((f) => f.title).join(', ')}`}
+                {type.isSystem && ' · built-in'}
+              </span>
+            </p>
+            {!type.isSystem && (
+              <Button size="sm" variant="ghost" onClick={() => handleDelete(type.id)}>
+                Delete
+              </Button>
+            )}
+          </div>
+        ))}
+      </div>
+
+      <div className="p-3 rounded-lg border border-[var(--border)] bg-[var(--card)] space-y-3">
+        <p className="text-[11px] text-[var(--muted-foreground)]">
+          Add a type for work the built-in ones don't fit, such as coaching or HR interviews.
+        </p>
+        <div className="grid grid-cols-2 gap-2">
+          <Input
+            id="type-name"
+            label="Name"
+            placeholder="Coaching"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+          />
+          <Input
+            id="type-folder-label"
+            label="Folder label"
+            placeholder="Coachee"
+            hint="Singular"
+            value={folderLabel}
+            onChange={(e) => setFolderLabel(e.target.value)}
+          />
+          <Input
+            id="type-icon"
+            label="Icon"
+            placeholder="🎯"
+            value={icon}
+            onChange={(e) => setIcon(e.target.value)}
+          />
+          <div className="space-y-1">
+            <label htmlFor="type-default-template" className="block text-[12px] font-medium text-[var(--foreground)]">
+              Default template
+            </label>
+            <select
+              id="type-default-template"
+              value={defaultTemplateId}
+              onChange={(e) => setDefaultTemplateId(e.target.value)}
+              className="w-full h-7 px-2 rounded-md border border-[var(--border)] bg-[var(--card)] text-[13px]"
+            >
+              <option value="">None</option>
+              {templates.map((template) => (
+                <option key={template.id} value={template.id}>
+                  {template.name}
+                </option>
+              ))}
+            </select>
+          </div>
+        </div>
+        <div className="space-y-1">
+          <label htmlFor="type-metadata-schema" className="block text-[12px] font-medium text-[var(--foreground)]">
+            Folder fields
+          </label>
+          <textarea
+            id="type-metadata-schema"
+            rows={4}
+            placeholder={'{"type": "object", "properties": {"goal": {"type": "string"}}}'}
+            value={metadataSchema}
+            onChange={(e) => setMetadataSchema(e.target.value)}
+            className="w-full px-2 py-1 rounded-md border border-[var(--border)] bg-[var(--card)] text-[12px] font-mono"
+          />
+          <p className="text-[11px] text-[var(--muted-foreground)]">
+            Optional JSON schema for folder details. Templates can use them as {'{folder.goal}'}.
+          </p>
+        </div>
+        <div className="flex justify-end">
+          <Button
+            size="sm"
+            loading={saving}
+            disabled={!name.trim() || !folderLabel.trim()}
+            onClick={handleAdd}
+          >
+            Add Type
+          </Button>
+        </div>
+      </div>
+
+      {error && <p className="text-[11px] text-[var(--destructive)] px-1">{error}</p>}
+    </section>
+  );
+}
@@ -1,4 +1,33 @@
 import { invoke } from '@tauri-apps/api/core';
+import type {
+  AuditEntry,
+  AuditLogQuery,
+  AuditVerification,
+  BackupInfo,
+  EncryptionStatus,
+  EnqueueJobRequest,
+  Folder,
+  IntegrityIssue,
+  InterruptedSession,
+  IntegrityReport,
+  Job,
+  JobStatus,
+  RestoreMode,
+  RetentionCandidate,
+  RetentionLimits,
+  RetentionLogEntry,
+  RetentionPolicy,
+  RepairReport,
+  RestoreSummary,
+  Session,
+  SessionListQuery,
+  SessionPage,
+  Tag,
+  Template,
+  TrashItem,
+  WorkspaceType,
+  WorkspaceTypeInfo,
+} from '../types';
 
 // Re-export invoke for convenience
 export { invoke };
@@ -20,22 +49,271 @@ export async function getFolders(workspaceId: string) {
   return invoke('get_folders', { workspaceId });
 }
 
+// Metadata is a JSON object string when the workspace type has a schema
+export async function updateFolder(request: { id: string; name?: string; metadata?: string }) {
+  return invoke<Folder>('update_folder', { request });
+}
+
+// Folders whose metadata field equals the value, in one workspace or all
+export async function findFoldersByMetadata(
+  field: string,
+  value: string | number | boolean,
+  workspaceId?: string
+) {
+  return invoke<Folder[]>('find_folders_by_metadata', { workspaceId, field, value });
+}
+
+// A null parent moves the folder to the top level
+export async function moveFolder(id: string, parentId: string | null) {
+  return invoke<Folder>('move_folder', { id, parentId });
+}
+
+export async function moveSession(id: string, folderId: string) {
+  return invoke<Session>('move_session', { id, folderId });
+}
+
 export async function createSession(folderId: string, audioPath: string, title?: string) {
   return invoke('create_session', { request: { folder_id: folderId, audio_path: audioPath, title } });
 }
 
-export async function getSessions(folderId: string) {
-  return invoke('get_sessions', { folderId });
+// Create the session for a live-transcribed recording as it starts, so
+// confirmed segments are saved while recording
+export async function beginLiveSession(sessionId: string, folderId: string, title?: string) {
+  return invoke('begin_live_session', { sessionId, folderId, title });
+}
+
+export async function finishLiveSession(sessionId: string, audioPath: string, audioDuration?: number) {
+  return invoke<Session>('finish_live_session', { sessionId, audioPath, audioDuration });
+}
+
+// Live sessions left behind when the app or worker stopped mid-recording
+export async function getInterruptedSessions() {
+  return invoke<InterruptedSession[]>('get_interrupted_sessions');
+}
+
+export async function recoverLiveSession(sessionId: string) {
+  return invoke<Session>('recover_live_session', { sessionId });
+}
+
+// Deletes the session and any partial recording for good
+export async function discardLiveSession(sessionId: string) {
+  return invoke('discard_live_session', { sessionId });
+}
+
+// With tags, only sessions carrying all of them
+export async function getSessions(folderId: string, tags?: string[]) {
+  return invoke('get_sessions', { folderId, tags });
+}
+
+// A page of session summaries; pass nextCursor back as cursor for the next
+export async function listSessions(query: SessionListQuery) {
+  return invoke<SessionPage>('list_sessions', { query });
+}
+
+export async function getTags() {
+  return invoke<Tag[]>('get_tags');
+}
+
+export async function renameTag(id: string, name: string) {
+  return invoke<Tag>('rename_tag', { id, name });
+}
+
+export async function deleteTag(id: string) {
+  return invoke('delete_tag', { id });
+}
+
+// Creates the tag when no tag has that name yet
+export async function addSessionTag(sessionId: string, name: string) {
+  return invoke<Session>('add_session_tag', { sessionId, name });
+}
+
+export async function removeSessionTag(sessionId: string, name: string) {
+  return invoke<Session>('remove_session_tag', { sessionId, name });
 }
 
 export async function transcribeAudio(sessionId: string, audioPath: string) {
   return invoke('transcribe_audio', { sessionId, audioPath });
 }
 
+export async function cancelTranscription(sessionId: string) {
+  return invoke<boolean>('cancel_transcription', { sessionId });
+}
+
+export async function cancelGeneration(sessionId: string) {
+  return invoke<boolean>('cancel_generation', { sessionId });
+}
+
+// Error returned by transcription/generation commands when the user cancels
+export const CANCELLED_ERROR = 'Cancelled by user';
+
+export function isCancelledError(error: unknown) {
+  return String(error).includes(CANCELLED_ERROR);
+}
+
 export async function generateNote(transcript: string, templateId: string) {
   return invoke('generate_note', { transcript, templateId });
 }
 
+export async function enqueueJob(request: EnqueueJobRequest) {
+  return invoke<Job>('enqueue_job', { request });
+}
+
+export async function listJobs(status?: JobStatus) {
+  return invoke<Job[]>('list_jobs', { status });
+}
+
+export async function setJobPriority(jobId: string, priority: number) {
+  return invoke<Job>('set_job_priority', { jobId, priority });
+}
+
+export async function cancelJob(jobId: string) {
+  return invoke<Job>('cancel_job', { jobId });
+}
+
+export async function getEncryptionStatus() {
+  return invoke<EncryptionStatus>('get_encryption_status');
+}
+
+export async function unlockDatabase(passphrase: string) {
+  return invoke('unlock_database', { passphrase });
+}
+
+export async function setEncryptionPassphrase(passphrase: string) {
+  return invoke('set_encryption_passphrase', { passphrase });
+}
+
+export async function changeEncryptionPassphrase(currentPassphrase: string, newPassphrase: string) {
+  return invoke('change_encryption_passphrase', { currentPassphrase, newPassphrase });
+}
+
+export async function removeEncryptionPassphrase(currentPassphrase: string) {
+  return invoke('remove_encryption_passphrase', { currentPassphrase });
+}
+
+export async function listTrash(workspaceId?: string) {
+  return invoke<TrashItem[]>('list_trash', { workspaceId });
+}
+
+export async function restoreSession(id: string) {
+  return invoke('restore_session', { id });
+}
+
+export async function restoreFolder(id: string) {
+  return invoke('restore_folder', { id });
+}
+
+export async function emptyTrash() {
+  return invoke<number>('empty_trash');
+}
+
+// Reports problems without changing anything
+export async function checkLibraryIntegrity() {
+  return invoke<IntegrityReport>('check_library_integrity');
+}
+
+// Fixes only the given issues, and only if they are still found as reviewed
+export async function repairLibrary(issues: IntegrityIssue[]) {
+  return invoke<RepairReport>('repair_library', { issues });
+}
+
+export async function createBackup(includeSettings: boolean) {
+  return invoke<BackupInfo>('create_backup', { includeSettings });
+}
+
+export async function restoreBackup(
+  path: string,
+  mode: RestoreMode,
+  restoreSettings: boolean,
+  passphrase?: string
+) {
+  return invoke<RestoreSummary>('restore_backup', {
+    request: { path, mode, restoreSettings, passphrase: passphrase || undefined },
+  });
+}
+
+export async function getRetentionPolicies() {
+  return invoke<RetentionPolicy[]>('get_retention_policies');
+}
+
+// Exactly one of workspaceId and folderId; null limits keep that data indefinitely
+export async function setRetentionPolicy(
+  scope: { workspaceId: string } | { folderId: string },
+  limits: RetentionLimits
+) {
+  return invoke<RetentionPolicy>('set_retention_policy', { request: { ...scope, ...limits } });
+}
+
+export async function deleteRetentionPolicy(id: string) {
+  return invoke('delete_retention_policy', { id });
+}
+
+export async function previewRetention(workspaceId?: string) {
+  return invoke<RetentionCandidate[]>('preview_retention', { workspaceId });
+}
+
+export async function getRetentionLog(workspaceId?: string, limit?: number) {
+  return invoke<RetentionLogEntry[]>('get_retention_log', { workspaceId, limit });
+}
+
+export async function queryAuditLog(query: AuditLogQuery = {}) {
+  return invoke<AuditEntry[]>('query_audit_log', { query });
+}
+
+export async function verifyAuditLog() {
+  return invoke<AuditVerification>('verify_audit_log');
+}
+
+export async function getWorkspaceTypes() {
+  return invoke<WorkspaceTypeInfo[]>('get_workspace_types');
+}
+
+export async function createWorkspaceType(request: {
+  name: string;
+  folderLabel: string;
+  description?: string;
+  icon?: string;
+  defaultTemplateId?: string;
+  metadataSchema?: string;
+}) {
+  return invoke<WorkspaceTypeInfo>('create_workspace_type', { request });
+}
+
+// Omitted fields are unchanged; an empty string clears an optional one
+export async function updateWorkspaceType(request: {
+  id: string;
+  name?: string;
+  folderLabel?: string;
+  description?: string;
+  icon?: string;
+  defaultTemplateId?: string;
+  metadataSchema?: string;
+}) {
+  return invoke<WorkspaceTypeInfo>('update_workspace_type', { request });
+}
+
+export async function deleteWorkspaceType(id: string) {
+  return invoke('delete_workspace_type', { id });
+}
+
+export async function createTemplate(request: {
+  name: string;
+  workspaceType: WorkspaceType;
+  description?: string;
+  prompt: string;
+  outputFormat?: string;
+}) {
+  return invoke<Template>('create_template', { request });
+}
+
+export async function deleteTemplate(id: string) {
+  return invoke('delete_template', { id });
+}
+
+// Recording bytes for playback, decrypted if encryption is on
+export async function readAudioFile(audioPath: string) {
+  return invoke<ArrayBuffer>('read_audio_file', { audioPath });
+}
+
 export async function checkOllamaStatus() {
   return invoke('check_ollama_status');
 }
@@ -1,5 +1,16 @@
 import { describe, it, expect } from 'vitest';
-import { cn, formatDuration, formatDate, formatDateTime, truncate } from './utils';
+import {
+  cn,
+  formatDuration,
+  formatDate,
+  formatDateTime,
+  truncate,
+  folderTree,
+  folderSubtreeIds,
+  metadataFields,
+  parseMetadata,
+} from './utils';
+import type { Folder } from '../types';
 
 describe('cn (className utility)', () => {
   it('should merge class names', () => {
@@ -99,3 +110,71 @@ describe('truncate', () => {
     expect(truncate('hello', 0)).toBe('...');
   });
 });
+
+describe('folder tree', () => {
+  const folder = (id: string, parentId: string | null = null): Folder => ({
+    id,
+    workspaceId: 'ws',
+    parentId,
+    name: id,
+    createdAt: 0,
+    updatedAt: 0,
+    sessionCount: 0,
+  });
+  const folders = [
+    folder('witness', 'depositions'),
+    folder('other'),
+    folder('depositions', 'case'),
+    folder('case'),
+    folder('orphan', 'trashed'),
+  ];
+
+  it('should list parents before their subfolders', () => {
+    expect(folderTree(folders).map(({ folder, depth }) => [folder.id, depth])).toEqual([
+      ['other', 0],
+      ['case', 0],
+      ['depositions', 1],
+      ['witness', 2],
+      ['orphan', 0],
+    ]);
+  });
+
+  it('should collect a folder and everything nested in it', () => {
+    expect([...folderSubtreeIds(folders, 'case')].sort()).toEqual(['case', 'depositions', 'witness']);
+    expect([...folderSubtreeIds(folders, 'other')]).toEqual(['other']);
+  });
+});
+
+describe('metadataFields', () => {
+  it('should list schema fields in order', () => {
+    const schema = JSON.stringify({
+      type: 'object',
+      properties: {
+        docket_number: { type: 'string', title: 'Docket number' },
+        filed_on: { type: 'string', format: 'date' },
+        court: { type: 'string', enum: ['district', 'appeals'] },
+      },
+      required: ['docket_number'],
+    });
+    expect(metadataFields(schema)).toEqual([
+      { name: 'docket_number', title: 'Docket number', type: 'string', required: true, date: false, options: undefined },
+      { name: 'filed_on', title: 'filed_on', type: 'string', required: false, date: true, options: undefined },
+      { name: 'court', title: 'court', type: 'string', required: false, date: false, options: ['district', 'appeals'] },
+    ]);
+  });
+
+  it('should return no fields without a readable schema', () => {
+    expect(metadataFields(null)).toEqual([]);
+    expect(metadataFields('{"type":"object"}')).toEqual([]);
+    expect(metadataFields('not json')).toEqual([]);
+  });
+});
+
+describe('parseMetadata', () => {
+  it('should parse object metadata only', () => {
+    expect(parseMetadata('{"court":"district"}')).toEqual({ court: 'district' });
+    expect(parseMetadata('free text')).toEqual({});
+    expect(parseMetadata('[1]')).toEqual({});
+    expect(parseMetadata(undefined)).toEqual({});
+  });
+});
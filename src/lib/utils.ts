@@ -1,4 +1,5 @@
 import { clsx, type ClassValue } from 'clsx';
+import type { Folder, MetadataField } from '../types';
 
 export function cn(...inputs: ClassValue[]) {
   return clsx(inputs);
@@ -37,3 +38,77 @@ export function truncate(str: string, length: number): string {
   if (str.length <= length) return str;
   return str.slice(0, length) + '...';
 }
+
+/** Drag data type for a session dragged onto a folder */
+export const SESSION_DRAG_TYPE = 'application/x-transcript-session';
+
+/** Folders in tree order, each parent followed by its subfolders, with their depth */
+export function folderTree(folders: Folder[]): { folder: Folder; depth: number }[] {
+  const ids = new Set(folders.map((f) => f.id));
+  const children = new Map<string | null, Folder[]>();
+  for (const folder of folders) {
+    // A folder whose parent isn't listed is shown at the top level
+    const parent = folder.parentId && ids.has(folder.parentId) ? folder.parentId : null;
+    children.set(parent, [...(children.get(parent) ?? []), folder]);
+  }
+
+  const ordered: { folder: Folder; depth: number }[] = [];
+  const visit = (parent: string | null, depth: number) => {
+    for (const folder of children.get(parent) ?? []) {
+      ordered.push({ folder, depth });
+      visit(folder.id, depth + 1);
+    }
+  };
+  visit(null, 0);
+  return ordered;
+}
+
+/** Ids of a folder and everything nested in it */
+export function folderSubtreeIds(folders: Folder[], id: string): Set<string> {
+  const subtree = new Set([id]);
+  let grew = true;
+  while (grew) {
+    grew = false;
+    for (const folder of folders) {
+      if (folder.parentId && subtree.has(folder.parentId) && !subtree.has(folder.id)) {
+        subtree.add(folder.id);
+        grew = true;
+      }
+    }
+  }
+  return subtree;
+}
+
+/** Fields a workspace type's metadata schema declares, in schema order */
+export function metadataFields(schema: string | null | undefined): MetadataField[] {
+  if (!schema) return [];
+  try {
+    const parsed = JSON.parse(schema);
+    const required: string[] = Array.isArray(parsed.required) ? parsed.required : [];
+    return Object.entries(parsed.properties ?? {}).map(([name, spec]) => {
+      const field = spec as { type?: MetadataField['type']; title?: string; format?: string; enum?: (string | number)[] };
+      return {
+        name,
+        title: field.title ?? name,
+        type: field.type ?? 'string',
+        required: required.includes(name),
+        date: field.format === 'date',
+        options: field.enum,
+      };
+    });
+  } catch {
+    // The backend rejects schemas it can't read; show no fields rather than fail
+    return [];
+  }
+}
+
+/** A folder's metadata as an object; empty when missing or not JSON */
+export function parseMetadata(metadata: string | null | undefined): Record<string, unknown> {
+  if (!metadata) return {};
+  try {
+    const parsed = JSON.parse(metadata);
+    return parsed && typeof parsed === 'object' && !Array.isArray(parsed) ? parsed : {};
+  } catch {
+    return {};
+  }
+}
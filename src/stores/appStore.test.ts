@@ -87,7 +87,7 @@ describe('AppStore', () => {
   });
 
   describe('selectSession', () => {
-    it('should select a session and change view', () => {
+    it('should select a session and change view', async () => {
       const mockSession = {
         id: 'session-1',
         folderId: 'folder-1',
@@ -98,8 +98,10 @@ describe('AppStore', () => {
         updatedAt: Date.now(),
       };
 
+      vi.mocked(invoke).mockResolvedValueOnce(mockSession); // get_session
+
       const { selectSession } = useAppStore.getState();
-      selectSession(mockSession as any);
+      await selectSession(mockSession as any);
 
       const state = useAppStore.getState();
       expect(state.currentSession).toEqual(mockSession);
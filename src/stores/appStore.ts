@@ -51,15 +51,17 @@ interface AppState {
   selectWorkspace: (workspace: Workspace) => Promise<void>;
 
   // Folder actions
-  createFolder: (name: string) => Promise<Folder>;
+  createFolder: (name: string, parentId?: string, metadata?: string) => Promise<Folder>;
   selectFolder: (folder: Folder) => Promise<void>;
   deleteFolder: (id: string) => Promise<void>;
 
   // Session actions
   createSession: (audioPath: string, title?: string) => Promise<Session>;
-  selectSession: (session: Session) => void;
+  finishLiveSession: (id: string, audioPath: string, audioDuration?: number) => Promise<Session>;
+  selectSession: (session: Session) => Promise<void>;
   updateSession: (id: string, updates: Partial<Session>) => Promise<void>;
   deleteSession: (id: string) => Promise<void>;
+  moveSession: (id: string, folderId: string) => Promise<void>;
 
   // View actions
   setView: (view: ViewType) => void;
@@ -137,7 +139,8 @@ export const useAppStore = create<AppState>((set) => {
       useUIStore.getState().setView('list');
     },
 
-    createFolder: (name) => useWorkspaceStore.getState().createFolder(name),
+    createFolder: (name, parentId, metadata) =>
+      useWorkspaceStore.getState().createFolder(name, parentId, metadata),
 
     selectFolder: async (folder) => {
       useWorkspaceStore.getState().selectFolder(folder);
@@ -159,8 +162,11 @@ export const useAppStore = create<AppState>((set) => {
       return useSessionStore.getState().createSession(currentFolder.id, audioPath, title);
     },
 
-    selectSession: (session) => {
-      useSessionStore.getState().selectSession(session);
+    finishLiveSession: (id, audioPath, audioDuration) =>
+      useSessionStore.getState().finishLiveSession(id, audioPath, audioDuration),
+
+    selectSession: async (session) => {
+      await useSessionStore.getState().selectSession(session);
       useUIStore.getState().setView('session');
     },
 
@@ -174,6 +180,17 @@ export const useAppStore = create<AppState>((set) => {
       }
     },
 
+    moveSession: async (id, folderId) => {
+      await useSessionStore.getState().moveSession(id, folderId);
+      // Refresh session counts along both folder paths
+      const { currentWorkspace, currentFolder } = useWorkspaceStore.getState();
+      if (currentWorkspace) {
+        const folders = await useWorkspaceStore.getState().loadFolders(currentWorkspace.id);
+        const moved = folders.find((f) => f.id === currentFolder?.id);
+        if (moved) useWorkspaceStore.setState({ currentFolder: moved });
+      }
+    },
+
     setView: (view) => useUIStore.getState().setView(view),
 
     loadTemplates: (workspaceType) => useTemplateStore.getState().loadTemplates(workspaceType),
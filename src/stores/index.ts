@@ -5,6 +5,7 @@ export { useWorkspaceStore } from './useWorkspaceStore';
 export { useSessionStore } from './useSessionStore';
 export { useTemplateStore } from './useTemplateStore';
 export { useChatStore } from './useChatStore';
+export { useTagStore } from './useTagStore';
 
 // Cross-store subscriptions
 import { useWorkspaceStore } from './useWorkspaceStore';
@@ -6,12 +6,20 @@ import type { Session } from '../types';
 interface SessionState {
   sessions: Session[];
   currentSession: Session | null;
+  /** Only sessions carrying all of these tags are listed */
+  tagFilter: string[];
 
   loadSessions: (folderId: string) => Promise<void>;
+  setTagFilter: (folderId: string, tags: string[]) => Promise<void>;
   createSession: (folderId: string, audioPath: string, title?: string) => Promise<Session>;
-  selectSession: (session: Session) => void;
+  finishLiveSession: (id: string, audioPath: string, audioDuration?: number) => Promise<Session>;
+  /** Open a session, loading its content (the backend audits the view) */
+  selectSession: (session: Session) => Promise<void>;
   updateSession: (id: string, updates: Partial<Session>) => Promise<void>;
   deleteSession: (id: string) => Promise<void>;
+  moveSession: (id: string, folderId: string) => Promise<Session>;
+  addTag: (id: string, name: string) => Promise<void>;
+  removeTag: (id: string, name: string) => Promise<void>;
   clearSessions: () => void;
 }
 
@@ -19,12 +27,22 @@ export const useSessionStore = create<SessionState>()(
   subscribeWithSelector((set, get) => ({
     sessions: [],
     currentSession: null,
+    tagFilter: [],
 
     loadSessions: async (folderId) => {
-      const sessions = await invoke<Session[]>('get_sessions', { folderId });
+      const { tagFilter } = get();
+      const sessions = await invoke<Session[]>('get_sessions', {
+        folderId,
+        ...(tagFilter.length > 0 && { tags: tagFilter }),
+      });
       set({ sessions, currentSession: null });
     },
 
+    setTagFilter: async (folderId, tags) => {
+      set({ tagFilter: tags });
+      await get().loadSessions(folderId);
+    },
+
     createSession: async (folderId, audioPath, title) => {
       const session = await invoke<Session>('create_session', {
         request: {
@@ -40,8 +58,24 @@ export const useSessionStore = create<SessionState>()(
       return session;
     },
 
-    selectSession: (session) => {
-      set({ currentSession: session });
+    finishLiveSession: async (id, audioPath, audioDuration) => {
+      const session = await invoke<Session>('finish_live_session', {
+        sessionId: id,
+        audioPath,
+        ...(audioDuration !== undefined && { audioDuration }),
+      });
+      // The live session may already be listed from while it was recording
+      set((state) => ({
+        sessions: [session, ...state.sessions.filter((s) => s.id !== id)],
+        currentSession: session,
+      }));
+      return session;
+    },
+
+    selectSession: async (session) => {
+      // Listed sessions come without their transcript and note
+      const full = await invoke<Session>('get_session', { id: session.id });
+      set({ currentSession: full });
     },
 
     updateSession: async (id, updates) => {
@@ -66,6 +100,32 @@ export const useSessionStore = create<SessionState>()(
       }));
     },
 
+    moveSession: async (id, folderId) => {
+      const session = await invoke<Session>('move_session', { id, folderId });
+      // The open list shows one folder, so the session leaves it
+      set((state) => ({
+        sessions: state.sessions.filter((s) => s.id !== id),
+        currentSession: state.currentSession?.id === id ? session : state.currentSession,
+      }));
+      return session;
+    },
+
+    addTag: async (id, name) => {
+      const session = await invoke<Session>('add_session_tag', { sessionId: id, name });
+      set((state) => ({
+        sessions: state.sessions.map((s) => (s.id === id ? session : s)),
+        currentSession: state.currentSession?.id === id ? session : state.currentSession,
+      }));
+    },
+
+    removeTag: async (id, name) => {
+      const session = await invoke<Session>('remove_session_tag', { sessionId: id, name });
+      set((state) => ({
+        sessions: state.sessions.map((s) => (s.id === id ? session : s)),
+        currentSession: state.currentSession?.id === id ? session : state.currentSession,
+      }));
+    },
+
     clearSessions: () => set({ sessions: [], currentSession: null }),
   }))
 );
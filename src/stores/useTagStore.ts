@@ -0,0 +1,50 @@
+import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
+import type { Session, Tag } from '../types';
+import { useSessionStore } from './useSessionStore';
+
+interface TagState {
+  tags: Tag[];
+
+  loadTags: () => Promise<Tag[]>;
+  renameTag: (id: string, name: string) => Promise<void>;
+  deleteTag: (id: string) => Promise<void>;
+}
+
+/** Apply a tag rename, or a removal when `to` is null, to loaded sessions */
+function retagSessions(from: string, to: string | null) {
+  const retag = (names: string[]) =>
+    names.flatMap((n) => (n.toLowerCase() === from.toLowerCase() ? (to ? [to] : []) : [n]));
+  const retagSession = (session: Session) =>
+    session.tags ? { ...session, tags: retag(session.tags) } : session;
+
+  useSessionStore.setState((state) => ({
+    sessions: state.sessions.map(retagSession),
+    currentSession: state.currentSession && retagSession(state.currentSession),
+    tagFilter: retag(state.tagFilter),
+  }));
+}
+
+export const useTagStore = create<TagState>()((set, get) => ({
+  tags: [],
+
+  loadTags: async () => {
+    const tags = (await invoke<Tag[]>('get_tags')) ?? [];
+    set({ tags });
+    return tags;
+  },
+
+  renameTag: async (id, name) => {
+    const previous = get().tags.find((t) => t.id === id);
+    const tag = await invoke<Tag>('rename_tag', { id, name });
+    if (previous) retagSessions(previous.name, tag.name);
+    await get().loadTags();
+  },
+
+  deleteTag: async (id) => {
+    const previous = get().tags.find((t) => t.id === id);
+    await invoke('delete_tag', { id });
+    if (previous) retagSessions(previous.name, null);
+    set((state) => ({ tags: state.tags.filter((t) => t.id !== id) }));
+  },
+}));
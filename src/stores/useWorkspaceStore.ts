@@ -1,22 +1,27 @@
 import { create } from 'zustand';
 import { subscribeWithSelector } from 'zustand/middleware';
 import { invoke } from '@tauri-apps/api/core';
-import type { Workspace, Folder, WorkspaceType } from '../types';
+import type { Workspace, Folder, WorkspaceType, WorkspaceTypeInfo } from '../types';
 import { logger } from '../lib/logger';
+import { folderSubtreeIds } from '../lib/utils';
 
 interface WorkspaceState {
   workspaces: Workspace[];
   currentWorkspace: Workspace | null;
   folders: Folder[];
   currentFolder: Folder | null;
+  workspaceTypes: WorkspaceTypeInfo[];
 
   loadWorkspaces: () => Promise<Workspace[]>;
   createWorkspace: (name: string, type: WorkspaceType) => Promise<Workspace>;
   selectWorkspace: (workspace: Workspace) => Promise<Folder[]>;
   setCurrentWorkspace: (workspace: Workspace | null) => void;
+  loadWorkspaceTypes: () => Promise<WorkspaceTypeInfo[]>;
 
   loadFolders: (workspaceId: string) => Promise<Folder[]>;
-  createFolder: (name: string) => Promise<Folder>;
+  createFolder: (name: string, parentId?: string, metadata?: string) => Promise<Folder>;
+  updateFolder: (id: string, changes: { name?: string; metadata?: string }) => Promise<Folder>;
+  moveFolder: (id: string, parentId: string | null) => Promise<void>;
   selectFolder: (folder: Folder) => void;
   deleteFolder: (id: string) => Promise<void>;
   clearFolderSelection: () => void;
@@ -28,6 +33,7 @@ export const useWorkspaceStore = create<WorkspaceState>()(
     currentWorkspace: null,
     folders: [],
     currentFolder: null,
+    workspaceTypes: [],
 
     loadWorkspaces: async () => {
       const workspaces = await invoke<Workspace[]>('get_workspaces');
@@ -62,23 +68,48 @@ export const useWorkspaceStore = create<WorkspaceState>()(
 
     setCurrentWorkspace: (workspace) => set({ currentWorkspace: workspace }),
 
+    loadWorkspaceTypes: async () => {
+      const workspaceTypes = await invoke<WorkspaceTypeInfo[]>('get_workspace_types');
+      set({ workspaceTypes });
+      return workspaceTypes;
+    },
+
     loadFolders: async (workspaceId) => {
       const folders = await invoke<Folder[]>('get_folders', { workspaceId });
       set({ folders });
       return folders;
     },
 
-    createFolder: async (name) => {
+    createFolder: async (name, parentId, metadata) => {
       const { currentWorkspace } = get();
       if (!currentWorkspace) throw new Error('No workspace selected');
 
       const folder = await invoke<Folder>('create_folder', {
-        request: { workspaceId: currentWorkspace.id, name },
+        request: { workspaceId: currentWorkspace.id, name, parentId, metadata },
       });
       set((state) => ({ folders: [folder, ...state.folders] }));
       return folder;
     },
 
+    updateFolder: async (id, changes) => {
+      const folder = await invoke<Folder>('update_folder', { request: { id, ...changes } });
+      set((state) => ({
+        folders: state.folders.map((f) => (f.id === id ? folder : f)),
+        currentFolder: state.currentFolder?.id === id ? folder : state.currentFolder,
+      }));
+      return folder;
+    },
+
+    moveFolder: async (id, parentId) => {
+      const { currentWorkspace, currentFolder } = get();
+      await invoke<Folder>('move_folder', { id, parentId });
+      // Session counts of the old and new ancestors change too
+      if (currentWorkspace) {
+        const folders = await get().loadFolders(currentWorkspace.id);
+        set({ currentFolder: folders.find((f) => f.id === currentFolder?.id) ?? currentFolder });
+      }
+    },
+
     selectFolder: (folder) => {
       set({ currentFolder: folder });
     },
@@ -94,11 +125,13 @@ export const useWorkspaceStore = create<WorkspaceState>()(
       }
       const { currentFolder } = get();
       set((state) => {
-        const newFolders = state.folders.filter((f) => f.id !== id);
+        // Subfolders go to the trash with their parent
+        const trashed = folderSubtreeIds(state.folders, id);
+        const newFolders = state.folders.filter((f) => !trashed.has(f.id));
         logger.debug(`Folders updated: ${state.folders.length} -> ${newFolders.length}`, { context: 'WorkspaceStore' });
         return {
           folders: newFolders,
-          currentFolder: currentFolder?.id === id ? null : currentFolder,
+          currentFolder: currentFolder && trashed.has(currentFolder.id) ? null : currentFolder,
         };
       });
     },
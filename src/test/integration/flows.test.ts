@@ -60,6 +60,8 @@ const mockSettings: AppSettings = {
   ollamaEndpoint: 'http://localhost:11434',
   exportFormat: 'markdown',
   autoSave: true,
+  transcriptionBackend: 'whisperkit',
+  trashRetentionDays: 30,
 };
 
 // Reset all stores before each test
@@ -374,11 +374,12 @@ describe('Integration: Session Selection', () => {
     vi.clearAllMocks();
   });
 
-  it('should select session and switch view', () => {
+  it('should select session and switch view', async () => {
     const session = createMockSession();
     useSessionStore.setState({ sessions: [session] });
+    vi.mocked(invoke).mockResolvedValueOnce(session); // get_session
 
-    useSessionStore.getState().selectSession(session);
+    await useSessionStore.getState().selectSession(session);
     useUIStore.getState().setView('session');
 
     expect(useSessionStore.getState().currentSession).toEqual(session);
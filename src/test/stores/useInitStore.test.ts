@@ -40,6 +40,8 @@ const mockSettings: AppSettings = {
   ollamaEndpoint: 'http://localhost:11434',
   exportFormat: 'markdown',
   autoSave: true,
+  transcriptionBackend: 'whisperkit',
+  trashRetentionDays: 30,
 };
 
 describe('useInitStore', () => {
@@ -21,6 +21,7 @@ describe('useSessionStore', () => {
     useSessionStore.setState({
       sessions: [],
       currentSession: null,
+      tagFilter: [],
     });
     vi.clearAllMocks();
   });
@@ -81,14 +82,40 @@ describe('useSessionStore', () => {
     });
   });
 
-  describe('selectSession', () => {
-    it('should update currentSession', () => {
-      useSessionStore.getState().selectSession(mockSession);
+  describe('finishLiveSession', () => {
+    it('should replace the live session in the list', async () => {
+      const live = { ...mockSession, audioPath: '', status: 'transcribing' as const };
+      const other = { ...mockSession, id: 'session-0' };
+      useSessionStore.setState({ sessions: [other, live] });
+      vi.mocked(invoke).mockResolvedValueOnce(mockSession);
+
+      const session = await useSessionStore
+        .getState()
+        .finishLiveSession('session-1', '/path/to/audio.wav', 3600);
 
+      expect(invoke).toHaveBeenCalledWith('finish_live_session', {
+        sessionId: 'session-1',
+        audioPath: '/path/to/audio.wav',
+        audioDuration: 3600,
+      });
+      expect(session).toEqual(mockSession);
+      expect(useSessionStore.getState().sessions).toEqual([mockSession, other]);
       expect(useSessionStore.getState().currentSession).toEqual(mockSession);
     });
   });
 
+  describe('selectSession', () => {
+    it('should load the full session through get_session', async () => {
+      const fullSession = { ...mockSession, transcript: 'Full transcript' };
+      vi.mocked(invoke).mockResolvedValueOnce(fullSession);
+
+      await useSessionStore.getState().selectSession(mockSession);
+
+      expect(invoke).toHaveBeenCalledWith('get_session', { id: mockSession.id });
+      expect(useSessionStore.getState().currentSession).toEqual(fullSession);
+    });
+  });
+
   describe('updateSession', () => {
     it('should update session and refresh from backend', async () => {
       const updatedSession = { ...mockSession, title: 'Updated Title' };
@@ -145,6 +172,29 @@ describe('useSessionStore', () => {
     });
   });
 
+  describe('tags', () => {
+    it('should reload sessions filtered by tags', async () => {
+      vi.mocked(invoke).mockResolvedValueOnce([mockSession]);
+
+      await useSessionStore.getState().setTagFilter('folder-1', ['intake']);
+
+      expect(invoke).toHaveBeenCalledWith('get_sessions', { folderId: 'folder-1', tags: ['intake'] });
+      expect(useSessionStore.getState().tagFilter).toEqual(['intake']);
+    });
+
+    it('should replace the tagged session in state', async () => {
+      const tagged = { ...mockSession, tags: ['intake'] };
+      useSessionStore.setState({ sessions: [mockSession], currentSession: mockSession });
+      vi.mocked(invoke).mockResolvedValueOnce(tagged);
+
+      await useSessionStore.getState().addTag('session-1', 'intake');
+
+      expect(invoke).toHaveBeenCalledWith('add_session_tag', { sessionId: 'session-1', name: 'intake' });
+      expect(useSessionStore.getState().sessions).toEqual([tagged]);
+      expect(useSessionStore.getState().currentSession).toEqual(tagged);
+    });
+  });
+
   describe('clearSessions', () => {
     it('should clear all sessions and currentSession', () => {
       useSessionStore.setState({ sessions: [mockSession], currentSession: mockSession });
@@ -21,6 +21,8 @@ const mockSettings: AppSettings = {
   ollamaEndpoint: 'http://localhost:11434',
   exportFormat: 'markdown',
   autoSave: true,
+  transcriptionBackend: 'whisperkit',
+  trashRetentionDays: 30,
 };
 
 describe('useTemplateStore', () => {
@@ -116,6 +116,22 @@ describe('useWorkspaceStore', () => {
     });
   });
 
+  describe('updateFolder', () => {
+    it('should replace the folder and the selection', async () => {
+      useWorkspaceStore.setState({ folders: [mockFolder], currentFolder: mockFolder });
+      const updated = { ...mockFolder, metadata: '{"date_of_birth":"1990-04-02"}' };
+      vi.mocked(invoke).mockResolvedValueOnce(updated);
+
+      await useWorkspaceStore.getState().updateFolder('folder-1', { metadata: updated.metadata });
+
+      expect(invoke).toHaveBeenCalledWith('update_folder', {
+        request: { id: 'folder-1', metadata: '{"date_of_birth":"1990-04-02"}' },
+      });
+      expect(useWorkspaceStore.getState().folders).toEqual([updated]);
+      expect(useWorkspaceStore.getState().currentFolder).toEqual(updated);
+    });
+  });
+
   describe('selectFolder', () => {
     it('should update currentFolder', () => {
       useWorkspaceStore.getState().selectFolder(mockFolder);
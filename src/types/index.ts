@@ -1,4 +1,21 @@
-export type WorkspaceType = 'therapy' | 'legal' | 'research' | 'general';
+export type BuiltInWorkspaceType = 'therapy' | 'legal' | 'research' | 'general';
+
+// Id of a row in workspace_types: a built-in type or a custom slug
+export type WorkspaceType = BuiltInWorkspaceType | (string & {});
+
+export interface WorkspaceTypeInfo {
+  id: WorkspaceType;
+  name: string;
+  // Singular: "Client", "Case"
+  folderLabel: string;
+  description: string | null;
+  icon: string | null;
+  defaultTemplateId: string | null;
+  metadataSchema: string | null;
+  isSystem: boolean;
+  createdAt: number;
+  updatedAt: number;
+}
 
 export interface Workspace {
   id: string;
@@ -11,13 +28,28 @@ export interface Workspace {
 export interface Folder {
   id: string;
   workspaceId: string;
+  /** Enclosing folder; null or missing at the top level */
+  parentId?: string | null;
   name: string;
   metadata?: string;
   createdAt: number;
   updatedAt: number;
+  /** Sessions in this folder and all its subfolders */
   sessionCount: number;
 }
 
+/** A folder metadata field declared by a workspace type's schema */
+export interface MetadataField {
+  name: string;
+  /** The schema's title, or the field name */
+  title: string;
+  type: 'string' | 'number' | 'integer' | 'boolean';
+  required: boolean;
+  /** `format: "date"`: a YYYY-MM-DD string */
+  date: boolean;
+  options?: (string | number)[];
+}
+
 export type SessionStatus = 'pending' | 'transcribing' | 'generating' | 'complete' | 'error';
 
 export interface Session {
@@ -35,6 +67,77 @@ export interface Session {
   errorMessage?: string;
   createdAt: number;
   updatedAt: number;
+  /** Tag names, alphabetically */
+  tags?: string[];
+}
+
+/** A session for lists, without its transcript, segments and note */
+export interface SessionSummary {
+  id: string;
+  folderId: string;
+  title?: string;
+  audioDuration?: number;
+  templateId?: string;
+  status: SessionStatus;
+  errorMessage?: string;
+  /** The start of the transcript, if there is one */
+  transcriptPreview?: string;
+  hasNote: boolean;
+  createdAt: number;
+  updatedAt: number;
+  tags: string[];
+}
+
+export type SessionSort = 'created' | 'duration' | 'title' | 'status';
+
+export interface SessionListQuery {
+  folderId: string;
+  sort?: SessionSort;
+  /** Newest, longest and A to Z come first by default */
+  order?: 'asc' | 'desc';
+  status?: SessionStatus;
+  since?: number;
+  until?: number;
+  templateId?: string;
+  hasNote?: boolean;
+  tags?: string[];
+  /** nextCursor from the previous page */
+  cursor?: string;
+  limit?: number;
+}
+
+export interface SessionPage {
+  sessions: SessionSummary[];
+  /** Absent on the last page */
+  nextCursor?: string;
+}
+
+/** A session label shared by all workspaces, such as "intake" */
+export interface Tag {
+  id: string;
+  name: string;
+  /** Live sessions carrying the tag */
+  sessionCount: number;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export type SearchField = 'title' | 'transcript' | 'note';
+
+export interface SnippetPart {
+  text: string;
+  highlighted: boolean;
+}
+
+export interface SearchSnippet {
+  field: SearchField;
+  parts: SnippetPart[];
+}
+
+export interface SessionSearchResult extends Session {
+  score: number;
+  matchedFields: SearchField[];
+  snippets: SearchSnippet[];
 }
 
 export interface TranscriptSegment {
@@ -42,9 +145,59 @@ export interface TranscriptSegment {
   end: number;
   text: string;
   speaker?: string;
+  avgLogprob?: number;
+  words?: TranscriptWord[];
   favorited?: boolean;
 }
 
+export interface TranscriptWord {
+  text: string;
+  start: number;
+  end: number;
+  probability: number;
+}
+
+export interface LowConfidenceSpan {
+  segmentIndex: number;
+  wordStartIndex?: number;
+  wordEndIndex?: number;
+  start: number;
+  end: number;
+  text: string;
+  minProbability: number;
+}
+
+export type JobKind = 'transcribe' | 'generate' | 'index';
+
+export type JobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface Job {
+  id: string;
+  sessionId: string;
+  kind: JobKind;
+  status: JobStatus;
+  priority: number;
+  attempts: number;
+  maxAttempts: number;
+  templateId?: string;
+  chain: boolean;
+  progress: number;
+  error?: string;
+  runAfter: number;
+  createdAt: number;
+  updatedAt: number;
+  startedAt?: number;
+  finishedAt?: number;
+}
+
+export interface EnqueueJobRequest {
+  sessionId: string;
+  kind: JobKind;
+  templateId?: string;
+  chain?: boolean;
+  priority?: number;
+}
+
 export interface Template {
   id: string;
   name: string;
@@ -70,6 +223,15 @@ export interface AppSettings {
   autoSave: boolean;
   bundledWhisperModel?: string;
   bundledLlmModel?: string;
+  transcriptionBackend: TranscriptionBackend;
+  trashRetentionDays: number;
+}
+
+export type TranscriptionBackend = 'whisperkit' | 'whispercpp';
+
+export interface LoadedWhisperModel {
+  modelId: string;
+  backend: TranscriptionBackend;
 }
 
 export type ModelType = 'whisper' | 'llm';
@@ -101,7 +263,7 @@ export interface OllamaStatus {
   error?: string;
 }
 
-export const WORKSPACE_CONFIG: Record<WorkspaceType, {
+export const WORKSPACE_CONFIG: Record<BuiltInWorkspaceType, {
   label: string;
   folderLabel: string;
   sessionLabel: string;
@@ -138,6 +300,21 @@ export const WORKSPACE_CONFIG: Record<WorkspaceType, {
   },
 };
 
+// Display config for any workspace type; custom types and edited built-in
+// ones come from the workspace_types table
+export function getWorkspaceConfig(workspaceType: WorkspaceType, workspaceTypes: WorkspaceTypeInfo[] = []) {
+  const builtIn = WORKSPACE_CONFIG[workspaceType as BuiltInWorkspaceType];
+  const info = workspaceTypes.find((t) => t.id === workspaceType);
+  if (!info) return builtIn;
+  return {
+    label: info.name,
+    folderLabel: `${info.folderLabel}s`,
+    sessionLabel: builtIn?.sessionLabel ?? 'Recordings',
+    icon: info.icon ?? builtIn?.icon ?? '📝',
+    description: info.description ?? builtIn?.description ?? '',
+  };
+}
+
 // System audio types
 export interface AudioDevice {
   id: string;
@@ -189,6 +366,8 @@ export interface LiveTranscriptionConfig {
   language?: string;
   useVad?: boolean;
   confirmationThreshold?: number;
+  // Sample format for binary audio frames to workers that support them
+  audioEncoding?: 'f32' | 'i16';
 }
 
 export interface LiveTranscriptionEvent {
@@ -209,7 +388,209 @@ export interface TranscriptionErrorEvent {
   code: string;
 }
 
+// Sent when the live transcription worker exits or hangs and is being restarted
+export interface WorkerRestartingEvent {
+  // The live session that resumes once the worker is back
+  sessionId: string | null;
+  reason: string;
+  attempt: number;
+  maxAttempts: number;
+  delayMs: number;
+}
+
+// Returned by get_streaming_worker_state
+export interface StreamingStatus {
+  state: string;
+  // Worker processes, including one kept idle for the next session
+  workers: number;
+  sessions: LiveSessionStatus[];
+}
+
+export interface LiveSessionStatus {
+  sessionId: string;
+  workerState: string;
+  config: LiveTranscriptionConfig;
+  audioSeconds: number;
+  confirmedSegments: number;
+  stopping: boolean;
+  restartAttempts: number;
+}
+
+// A live-transcribed recording that stopped before it was saved
+export interface InterruptedSession {
+  id: string;
+  folderId: string;
+  title: string | null;
+  startedAt: number;
+  segmentCount: number;
+  transcribedSeconds: number;
+  transcriptPreview: string | null;
+  // A partial recording found in the audio folder
+  audioPath: string | null;
+}
+
 export interface WorkerReadyEvent {
   model: string;
   modelPath: string | null;
 }
+
+export interface EncryptionStatus {
+  enabled: boolean;
+  unlocked: boolean;
+}
+
+export interface TrashItem {
+  kind: 'session' | 'folder';
+  id: string;
+  name: string;
+  workspaceId: string;
+  folderId: string | null;
+  folderName: string | null;
+  sessionCount: number;
+  deletedAt: number;
+  purgeAt: number;
+}
+
+export type IntegrityIssueKind =
+  | 'orphaned_audio'
+  | 'missing_audio'
+  | 'orphaned_chunks'
+  | 'stale_chunks'
+  | 'stale_index';
+
+export interface IntegrityIssue {
+  kind: IntegrityIssueKind;
+  sessionId: string | null;
+  path: string | null;
+  description: string;
+  /** What repairing will do */
+  repair: string;
+}
+
+export interface IntegrityReport {
+  checkedAt: number;
+  sessionsChecked: number;
+  audioFilesChecked: number;
+  issues: IntegrityIssue[];
+}
+
+export interface RepairReport {
+  repaired: IntegrityIssue[];
+  /** Reviewed issues no longer found as reviewed, left alone */
+  skipped: number;
+  /** Sessions queued for indexing again */
+  reindex: string[];
+  errors: string[];
+}
+
+export interface BackupFile {
+  path: string;
+  size: number;
+  sha256: string;
+}
+
+export interface BackupManifest {
+  formatVersion: number;
+  appVersion: string;
+  createdAt: number;
+  schemaVersion: number;
+  encrypted: boolean;
+  includesSettings: boolean;
+  sessionCount: number;
+  files: BackupFile[];
+}
+
+export interface BackupInfo {
+  path: string;
+  manifest: BackupManifest;
+}
+
+export type RestoreMode = 'merge' | 'replace';
+
+export interface RestoreSummary {
+  mode: RestoreMode;
+  sessionsRestored: number;
+  audioFilesRestored: number;
+}
+
+export interface RetentionLimits {
+  audioDays: number | null;
+  transcriptDays: number | null;
+  noteDays: number | null;
+  chunksDays: number | null;
+}
+
+export interface RetentionPolicy extends RetentionLimits {
+  id: string;
+  workspaceId: string | null;
+  folderId: string | null;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export type RetainedData = 'audio' | 'transcript' | 'note' | 'chunks';
+
+export interface RetentionCandidate {
+  sessionId: string;
+  sessionTitle: string | null;
+  folderId: string;
+  workspaceId: string;
+  policyId: string;
+  dataType: RetainedData;
+  retentionDays: number;
+  sessionCreatedAt: number;
+  /** When the data was finished; its age counts from here */
+  anchoredAt: number;
+  dueAt: number;
+}
+
+export interface RetentionLogEntry {
+  id: string;
+  sessionId: string;
+  folderId: string;
+  workspaceId: string;
+  policyId: string;
+  dataType: RetainedData;
+  retentionDays: number;
+  sessionCreatedAt: number;
+  purgedAt: number;
+}
+
+export type AuditAction =
+  | 'view'
+  | 'update'
+  | 'export'
+  | 'delete'
+  | 'restore'
+  | 'purge'
+  | 'cloud_llm';
+
+export interface AuditEntry {
+  seq: number;
+  id: string;
+  occurredAt: number;
+  actor: string;
+  action: AuditAction;
+  entityType: string;
+  entityId: string | null;
+  details: string | null;
+  prevHash: string;
+  hash: string;
+}
+
+export interface AuditLogQuery {
+  entityType?: string;
+  entityId?: string;
+  action?: AuditAction;
+  since?: number;
+  until?: number;
+  beforeSeq?: number;
+  limit?: number;
+}
+
+export interface AuditVerification {
+  valid: boolean;
+  entriesChecked: number;
+  firstInvalidSeq: number | null;
+  error: string | null;
+}
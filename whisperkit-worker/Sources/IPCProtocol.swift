@@ -2,12 +2,15 @@ import Foundation
 
 // MARK: - Commands (Rust -> Swift via stdin)
 
-/// Commands received from the Rust backend via stdin as NDJSON
+/// Commands received from the Rust backend via stdin as NDJSON, plus binary
+/// audio frames from protocol version 2
 enum Command: Decodable {
     case initialize(model: String?, language: String?)
     case start(sessionId: String, useVAD: Bool, confirmationThreshold: Int)
     case audio(sessionId: String, samples: String, sampleCount: Int)
+    case audioFrame(sessionId: String, samples: [Float])
     case stop(sessionId: String)
+    case status
     case shutdown
 
     private enum CodingKeys: String, CodingKey {
@@ -47,6 +50,9 @@ enum Command: Decodable {
             let sessionId = try container.decode(String.self, forKey: .sessionId)
             self = .stop(sessionId: sessionId)
 
+        case "status":
+            self = .status
+
         case "shutdown":
             self = .shutdown
 
@@ -64,7 +70,7 @@ enum Command: Decodable {
 
 /// Events sent to the Rust backend via stdout as NDJSON
 enum Event: Encodable {
-    case ready(model: String, modelPath: String?)
+    case ready(model: String, modelPath: String?, protocolVersion: Int)
     case tentative(sessionId: String, text: String, timestamp: Double)
     case confirmed(sessionId: String, text: String, startTime: Double, endTime: Double)
     case complete(sessionId: String, fullText: String)
@@ -75,6 +81,7 @@ enum Event: Encodable {
         case type
         case model
         case modelPath
+        case protocolVersion
         case sessionId
         case text
         case timestamp
@@ -90,10 +97,11 @@ enum Event: Encodable {
         var container = encoder.container(keyedBy: CodingKeys.self)
 
         switch self {
-        case .ready(let model, let modelPath):
+        case .ready(let model, let modelPath, let protocolVersion):
             try container.encode("ready", forKey: .type)
             try container.encode(model, forKey: .model)
             try container.encodeIfPresent(modelPath, forKey: .modelPath)
+            try container.encode(protocolVersion, forKey: .protocolVersion)
 
         case .tentative(let sessionId, let text, let timestamp):
             try container.encode("tentative", forKey: .type)
@@ -139,10 +147,68 @@ enum ErrorCode: String {
     case internalError = "INTERNAL_ERROR"
 }
 
+// MARK: - Stdin Reader
+
+/// Buffered stdin, which carries NDJSON command lines and binary audio frames
+/// between them
+final class StdinReader {
+    private let handle = FileHandle.standardInput
+    private var buffer = Data()
+
+    /// Buffer at least `count` bytes; false at end of input
+    private func fill(_ count: Int) -> Bool {
+        while buffer.count < count {
+            let chunk = handle.availableData
+            if chunk.isEmpty {
+                return false
+            }
+            buffer.append(chunk)
+        }
+        return true
+    }
+
+    /// The next byte, without consuming it
+    func peekByte() -> UInt8? {
+        guard fill(1) else { return nil }
+        return buffer[buffer.startIndex]
+    }
+
+    /// Exactly `count` bytes, or nil at end of input
+    func read(_ count: Int) -> Data? {
+        guard fill(count) else { return nil }
+        let data = Data(buffer.prefix(count))
+        buffer.removeFirst(count)
+        return data
+    }
+
+    /// The next line without its newline, or nil at end of input
+    func readLine() -> String? {
+        while true {
+            if let newline = buffer.firstIndex(of: UInt8(ascii: "\n")) {
+                let line = Data(buffer[buffer.startIndex..<newline])
+                buffer.removeSubrange(buffer.startIndex...newline)
+                return String(decoding: line, as: UTF8.self)
+            }
+            guard fill(buffer.count + 1) else {
+                return nil
+            }
+        }
+    }
+}
+
 // MARK: - IPC Helper Functions
 
 /// Namespace for IPC helper functions
 enum IPC {
+    /// Protocol version reported in the ready event. Version 2 accepts binary
+    /// audio frames alongside the JSON audio command.
+    static let protocolVersion = 2
+
+    /// First byte of a binary audio frame; a JSON line never starts with it
+    static let audioFrameMarker: UInt8 = 0x00
+
+    private static let stdinReader = StdinReader()
+
     /// Send an event to stdout as NDJSON
     static func emit(_ event: Event) {
         let encoder = JSONEncoder()
@@ -161,9 +227,17 @@ enum IPC {
         }
     }
 
-    /// Read a command from stdin as NDJSON
+    /// Read a command from stdin, either an NDJSON line or an audio frame
     static func readCommand() -> Command? {
-        guard let line = readLine() else {
+        guard let first = stdinReader.peekByte() else {
+            return nil
+        }
+
+        if first == audioFrameMarker {
+            return readAudioFrame()
+        }
+
+        guard let line = stdinReader.readLine() else {
             return nil
         }
 
@@ -183,6 +257,57 @@ enum IPC {
         }
     }
 
+    /// Read a binary audio frame: the marker byte, the encoding (0 = float32,
+    /// 1 = int16), the session id prefixed by its UInt16 length, the UInt32
+    /// sample count and then the samples, all little-endian
+    private static func readAudioFrame() -> Command? {
+        guard let header = stdinReader.read(4) else {
+            return nil
+        }
+        let encoding = header[header.startIndex + 1]
+        let idLength = Int(header[header.startIndex + 2]) | Int(header[header.startIndex + 3]) << 8
+
+        guard let idData = stdinReader.read(idLength),
+              let countData = stdinReader.read(4) else {
+            return nil
+        }
+        let sessionId = String(decoding: idData, as: UTF8.self)
+        let sampleCount = countData.enumerated().reduce(0) { total, byte in
+            total | Int(byte.element) << (8 * byte.offset)
+        }
+
+        let sampleSize: Int
+        switch encoding {
+        case 0: sampleSize = 4
+        case 1: sampleSize = 2
+        default:
+            // The payload size is unknown, so the rest of stdin can't be read
+            emit(.error(sessionId: sessionId, message: "Unknown audio frame encoding: \(encoding)", code: ErrorCode.audioDecodeFailed.rawValue))
+            return nil
+        }
+
+        guard let payload = stdinReader.read(sampleCount * sampleSize) else {
+            return nil
+        }
+
+        var samples = [Float](repeating: 0, count: sampleCount)
+        if encoding == 0 {
+            _ = samples.withUnsafeMutableBytes { buffer in
+                payload.copyBytes(to: buffer)
+            }
+        } else {
+            var values = [Int16](repeating: 0, count: sampleCount)
+            _ = values.withUnsafeMutableBytes { buffer in
+                payload.copyBytes(to: buffer)
+            }
+            for (index, value) in values.enumerated() {
+                samples[index] = Float(Int16(littleEndian: value)) / Float(Int16.max)
+            }
+        }
+
+        return .audioFrame(sessionId: sessionId, samples: samples)
+    }
+
     /// Decode base64-encoded float32 samples
     static func decodeSamples(base64: String, expectedCount: Int) -> [Float]? {
         guard let data = Data(base64Encoded: base64) else {
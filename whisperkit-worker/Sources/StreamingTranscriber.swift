@@ -24,6 +24,9 @@ actor StreamingTranscriber {
 
     // Audio buffer for samples received via IPC
     private var audioBuffer: [Float] = []
+    // Samples dropped from the front of audioBuffer, so segment times count
+    // from the start of the session rather than the start of the buffer
+    private var bufferStartSample: Int = 0
 
     // Transcription state
     private var confirmedSegments: [TranscriptionSegment] = []
@@ -53,7 +56,7 @@ actor StreamingTranscriber {
             isInitialized = true
 
             let modelPath = whisperKit?.modelFolder?.path
-            IPC.emit(.ready(model: model ?? "default", modelPath: modelPath))
+            IPC.emit(.ready(model: model ?? "default", modelPath: modelPath, protocolVersion: IPC.protocolVersion))
             fputs("[StreamingTranscriber] Initialized successfully\n", stderr)
         } catch {
             fputs("[StreamingTranscriber] Failed to initialize: \(error)\n", stderr)
@@ -81,6 +84,7 @@ actor StreamingTranscriber {
 
         // Reset state
         audioBuffer = []
+        bufferStartSample = 0
         confirmedSegments = []
         lastTranscribedSampleCount = 0
         confirmedText = ""
@@ -178,6 +182,10 @@ actor StreamingTranscriber {
             return
         }
 
+        // Where the window starts in the session, taken now because more
+        // audio can arrive while WhisperKit runs
+        let windowStart = Double(bufferStartSample + audioBuffer.count - samples.count) / 16000.0
+
         fputs("[StreamingTranscriber] transcribeCurrentBuffer: \(samples.count) samples (buffer: \(audioBuffer.count)), isFinal=\(isFinal)\n", stderr)
 
         // Optional VAD check
@@ -235,13 +243,14 @@ actor StreamingTranscriber {
             if audioBuffer.count > keepSamples {
                 let removeCount = audioBuffer.count - keepSamples
                 audioBuffer.removeFirst(removeCount)
+                bufferStartSample += removeCount
                 lastTranscribedSampleCount = 0
                 fputs("[StreamingTranscriber] Cleared \(removeCount) old samples from buffer\n", stderr)
             } else {
                 lastTranscribedSampleCount = audioBuffer.count
             }
 
-            processResults(results, sessionId: sessionId, isFinal: isFinal)
+            processResults(results, sessionId: sessionId, isFinal: isFinal, windowStart: windowStart)
             fputs("[StreamingTranscriber] transcribeCurrentBuffer: completed successfully\n", stderr)
 
         } catch {
@@ -254,7 +263,7 @@ actor StreamingTranscriber {
         }
     }
 
-    private func processResults(_ results: [TranscriptionResult], sessionId: String, isFinal: Bool) {
+    private func processResults(_ results: [TranscriptionResult], sessionId: String, isFinal: Bool, windowStart: Double) {
         fputs("[StreamingTranscriber] processResults: entering\n", stderr)
 
         guard !results.isEmpty else {
@@ -299,8 +308,8 @@ actor StreamingTranscriber {
                     IPC.emit(.confirmed(
                         sessionId: sessionId,
                         text: text,
-                        startTime: Double(segment.start),
-                        endTime: Double(segment.end)
+                        startTime: windowStart + Double(segment.start),
+                        endTime: windowStart + Double(segment.end)
                     ))
                 }
             }
@@ -336,7 +345,7 @@ actor StreamingTranscriber {
         if tentativeText != lastTentativeText {
             lastTentativeText = tentativeText
             if !tentativeText.isEmpty {
-                let timestamp = Double(tentativeSegments.first?.start ?? 0.0)
+                let timestamp = windowStart + Double(tentativeSegments.first?.start ?? 0.0)
                 fputs("[StreamingTranscriber] processResults: emitting tentative: \(tentativeText.prefix(50))\n", stderr)
                 IPC.emit(.tentative(sessionId: sessionId, text: tentativeText, timestamp: timestamp))
             }
@@ -352,8 +361,8 @@ actor StreamingTranscriber {
                     IPC.emit(.confirmed(
                         sessionId: sessionId,
                         text: text,
-                        startTime: Double(segment.start),
-                        endTime: Double(segment.end)
+                        startTime: windowStart + Double(segment.start),
+                        endTime: windowStart + Double(segment.end)
                     ))
                 }
             }
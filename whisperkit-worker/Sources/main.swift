@@ -31,6 +31,9 @@ struct Transcribe: AsyncParsableCommand {
     @Option(name: .long, help: "Language code (e.g., 'en', 'es'). Auto-detect if not specified")
     var language: String?
 
+    @Flag(name: .long, help: "Output timestamped segments as a JSON array instead of plain text")
+    var json: Bool = false
+
     func run() async throws {
         // Verify audio file exists
         guard FileManager.default.fileExists(atPath: audioPath) else {
@@ -56,6 +59,15 @@ struct Transcribe: AsyncParsableCommand {
 
             fputs("Transcribing audio...\n", stderr)
 
+            // Report progress on stderr so the app can show real progress and an ETA
+            let progressTask = Task {
+                while !Task.isCancelled {
+                    fputs("PROGRESS \(whisperKit.progress.fractionCompleted)\n", stderr)
+                    try? await Task.sleep(nanoseconds: 500_000_000)
+                }
+            }
+            defer { progressTask.cancel() }
+
             // Set up decoding options
             let decodingOptions = DecodingOptions(
                 verbose: false,
@@ -66,7 +78,8 @@ struct Transcribe: AsyncParsableCommand {
                 usePrefillCache: false,
                 detectLanguage: language == nil,
                 skipSpecialTokens: true,
-                withoutTimestamps: true
+                withoutTimestamps: !json,
+                wordTimestamps: json
             )
 
             // Transcribe the audio
@@ -86,8 +99,29 @@ struct Transcribe: AsyncParsableCommand {
                 fputs("Detected language: \(detectedLanguage)\n", stderr)
             }
 
-            let fullText = results.map { $0.text }.joined(separator: " ")
-            print(fullText.trimmingCharacters(in: .whitespacesAndNewlines))
+            if json {
+                let segments = results.flatMap { $0.segments }.map { segment in
+                    BatchSegment(
+                        start: Double(segment.start),
+                        end: Double(segment.end),
+                        text: segment.text.trimmingCharacters(in: .whitespacesAndNewlines),
+                        avgLogprob: Double(segment.avgLogprob),
+                        words: segment.words?.map { word in
+                            BatchWord(
+                                text: word.word.trimmingCharacters(in: .whitespacesAndNewlines),
+                                start: Double(word.start),
+                                end: Double(word.end),
+                                probability: Double(word.probability)
+                            )
+                        }
+                    )
+                }
+                let data = try JSONEncoder().encode(segments)
+                print(String(data: data, encoding: .utf8) ?? "[]")
+            } else {
+                let fullText = results.map { $0.text }.joined(separator: " ")
+                print(fullText.trimmingCharacters(in: .whitespacesAndNewlines))
+            }
 
         } catch let error as ExitCode {
             throw error
@@ -98,6 +132,23 @@ struct Transcribe: AsyncParsableCommand {
     }
 }
 
+/// Segment printed by `transcribe --json` (matches `TranscriptSegment` on the Rust side)
+struct BatchSegment: Codable {
+    let start: Double
+    let end: Double
+    let text: String
+    let avgLogprob: Double
+    let words: [BatchWord]?
+}
+
+/// Word timing printed inside a `BatchSegment` (matches `TranscriptWord` on the Rust side)
+struct BatchWord: Codable {
+    let text: String
+    let start: Double
+    let end: Double
+    let probability: Double
+}
+
 // MARK: - Stream Subcommand (Real-time Mode)
 
 struct Stream: AsyncParsableCommand {
@@ -146,13 +197,8 @@ struct Stream: AsyncParsableCommand {
                 case .audio(let sessionId, let samplesBase64, let sampleCount):
                     // Decode and feed audio samples
                     if let samples = IPC.decodeSamples(base64: samplesBase64, expectedCount: sampleCount) {
-                        // Log sample statistics periodically (every 50th chunk = ~5 seconds)
                         audioChunkCount += 1
-                        if audioChunkCount % 50 == 1 {
-                            let energy = samples.map { $0 * $0 }.reduce(0, +) / Float(samples.count)
-                            fputs("[Stream] Audio chunk #\(audioChunkCount): energy=\(energy)\n", stderr)
-                        }
-                        await transcriber.feedAudio(samples: samples)
+                        await feed(samples, chunk: audioChunkCount, to: transcriber)
                     } else {
                         fputs("[Stream] Failed to decode audio!\n", stderr)
                         IPC.emit(.error(
@@ -162,10 +208,20 @@ struct Stream: AsyncParsableCommand {
                         ))
                     }
 
+                case .audioFrame(_, let samples):
+                    // Binary frames arrive already decoded
+                    audioChunkCount += 1
+                    await feed(samples, chunk: audioChunkCount, to: transcriber)
+
                 case .stop(let sessionId):
                     fputs("[Stream] Received stop command for session: \(sessionId)\n", stderr)
                     _ = await transcriber.stopSession()
 
+                case .status:
+                    // Heartbeat from the backend; answering shows the loop is alive
+                    let status = await transcriber.getStatus()
+                    IPC.emit(.status(state: status.state, sessionId: status.sessionId))
+
                 case .shutdown:
                     fputs("[Stream] Received shutdown command\n", stderr)
                     _ = await transcriber.stopSession()
@@ -183,4 +239,14 @@ struct Stream: AsyncParsableCommand {
 
         fputs("[Stream] Exiting streaming mode\n", stderr)
     }
+
+    /// Feed decoded audio to the transcriber
+    private func feed(_ samples: [Float], chunk: Int, to transcriber: StreamingTranscriber) async {
+        // Log sample statistics periodically (every 50th chunk = ~5 seconds)
+        if chunk % 50 == 1 && !samples.isEmpty {
+            let energy = samples.map { $0 * $0 }.reduce(0, +) / Float(samples.count)
+            fputs("[Stream] Audio chunk #\(chunk): energy=\(energy)\n", stderr)
+        }
+        await transcriber.feedAudio(samples: samples)
+    }
 }